@@ -0,0 +1,77 @@
+-- The schema from before migrations, so databases created then upgrade in place
+CREATE TABLE IF NOT EXISTS campaigns (
+    address TEXT PRIMARY KEY,
+    creator TEXT NOT NULL,
+    seeds TEXT NOT NULL,
+    keywords TEXT NOT NULL,
+    platform SMALLINT NOT NULL,
+    reward_amount BIGINT NOT NULL,
+    max_claimers BIGINT NOT NULL,
+    claimers_count BIGINT NOT NULL,
+    total_paid BIGINT NOT NULL,
+    active BOOLEAN NOT NULL,
+    closed BOOLEAN NOT NULL DEFAULT FALSE,
+    created_slot BIGINT NOT NULL,
+    updated_slot BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS campaigns_creator ON campaigns (creator);
+
+CREATE TABLE IF NOT EXISTS verifications (
+    address TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    post_url TEXT NOT NULL,
+    status TEXT NOT NULL,
+    failure_reason INTEGER NOT NULL,
+    failed_checks BIGINT NOT NULL,
+    author_did_hash TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    updated_slot BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS verifications_campaign ON verifications (campaign);
+CREATE INDEX IF NOT EXISTS verifications_verifier ON verifications (verifier);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    signature TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    payout TEXT NOT NULL,
+    author_did_hash TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    claimer_share BIGINT NOT NULL,
+    creator_share BIGINT NOT NULL,
+    platform_share BIGINT NOT NULL,
+    referrer TEXT,
+    referrer_share BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS payouts_campaign ON payouts (campaign);
+CREATE INDEX IF NOT EXISTS payouts_verifier ON payouts (verifier);
+
+CREATE TABLE IF NOT EXISTS requests (
+    execution_request TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    expiry_slot BIGINT NOT NULL,
+    completed_slot BIGINT,
+    expired BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX IF NOT EXISTS requests_open ON requests (campaign, verifier)
+    WHERE completed_slot IS NULL;
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id BIGSERIAL PRIMARY KEY,
+    creator TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    UNIQUE (creator, url)
+);
+
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
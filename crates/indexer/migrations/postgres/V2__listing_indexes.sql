@@ -0,0 +1,9 @@
+-- Listings filter on one column and page newest first
+DROP INDEX IF EXISTS verifications_campaign;
+DROP INDEX IF EXISTS verifications_verifier;
+DROP INDEX IF EXISTS payouts_campaign;
+DROP INDEX IF EXISTS payouts_verifier;
+CREATE INDEX verifications_campaign_slot ON verifications (campaign, slot DESC);
+CREATE INDEX verifications_verifier_slot ON verifications (verifier, slot DESC);
+CREATE INDEX payouts_campaign_slot ON payouts (campaign, slot DESC);
+CREATE INDEX payouts_verifier_slot ON payouts (verifier, slot DESC);
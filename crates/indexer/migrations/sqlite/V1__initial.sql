@@ -0,0 +1,77 @@
+-- The schema from before migrations, so databases created then upgrade in place
+CREATE TABLE IF NOT EXISTS campaigns (
+    address TEXT PRIMARY KEY,
+    creator TEXT NOT NULL,
+    seeds TEXT NOT NULL,
+    keywords TEXT NOT NULL,
+    platform INTEGER NOT NULL,
+    reward_amount INTEGER NOT NULL,
+    max_claimers INTEGER NOT NULL,
+    claimers_count INTEGER NOT NULL,
+    total_paid INTEGER NOT NULL,
+    active INTEGER NOT NULL,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_slot INTEGER NOT NULL,
+    updated_slot INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS campaigns_creator ON campaigns (creator);
+
+CREATE TABLE IF NOT EXISTS verifications (
+    address TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    post_url TEXT NOT NULL,
+    status TEXT NOT NULL,
+    failure_reason INTEGER NOT NULL,
+    failed_checks INTEGER NOT NULL,
+    author_did_hash TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    updated_slot INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS verifications_campaign ON verifications (campaign);
+CREATE INDEX IF NOT EXISTS verifications_verifier ON verifications (verifier);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    payout TEXT NOT NULL,
+    author_did_hash TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    claimer_share INTEGER NOT NULL,
+    creator_share INTEGER NOT NULL,
+    platform_share INTEGER NOT NULL,
+    referrer TEXT,
+    referrer_share INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS payouts_campaign ON payouts (campaign);
+CREATE INDEX IF NOT EXISTS payouts_verifier ON payouts (verifier);
+
+CREATE TABLE IF NOT EXISTS requests (
+    execution_request TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    expiry_slot INTEGER NOT NULL,
+    completed_slot INTEGER,
+    expired INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS requests_open ON requests (campaign, verifier)
+    WHERE completed_slot IS NULL;
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    creator TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    UNIQUE (creator, url)
+);
+
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
@@ -0,0 +1,104 @@
+//! Decoding the program's Anchor events from transaction logs
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use proof_of_post::{
+    CampaignExhausted, CampaignFinalized, RewardPaid, VerificationCompleted, VerificationRequested,
+};
+
+/// Prefix `emit!` logs an event's base64 encoding with
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// The events the indexer stores or notifies about
+pub enum Event {
+    RewardPaid(RewardPaid),
+    CampaignFinalized(CampaignFinalized),
+    VerificationRequested(VerificationRequested),
+    VerificationCompleted(VerificationCompleted),
+    CampaignExhausted(CampaignExhausted),
+}
+
+/// Events `program_id` emitted in a transaction with these logs, in order. Only data
+/// logged while the program itself was executing counts, so another program in the
+/// transaction can't pass off a payout of its own.
+pub fn program_events(program_id: &Pubkey, logs: &[String]) -> Vec<Event> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            if stack.last() == Some(&program_id.as_str()) {
+                events.extend(STANDARD.decode(data).ok().and_then(|data| decode(&data)));
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => stack.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+fn decode(data: &[u8]) -> Option<Event> {
+    decode_as(data)
+        .map(Event::RewardPaid)
+        .or_else(|| decode_as(data).map(Event::CampaignFinalized))
+        .or_else(|| decode_as(data).map(Event::VerificationRequested))
+        .or_else(|| decode_as(data).map(Event::VerificationCompleted))
+        .or_else(|| decode_as(data).map(Event::CampaignExhausted))
+}
+
+fn decode_as<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let mut body = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    fn program_data(event: &CampaignFinalized) -> String {
+        let mut data = CampaignFinalized::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("{PROGRAM_DATA}{}", STANDARD.encode(data))
+    }
+
+    #[test]
+    fn only_events_the_program_logged_count() {
+        let program = proof_of_post::ID;
+        let other = Pubkey::new_unique();
+        let event = CampaignFinalized {
+            config: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            leftover: 42,
+            donated: false,
+        };
+        let logs = [
+            format!("Program {other} invoke [1]"),
+            program_data(&event),
+            format!("Program {program} invoke [2]"),
+            String::from("Program log: Instruction: CloseConfig"),
+            program_data(&event),
+            format!("Program {program} consumed 5000 of 200000 compute units"),
+            format!("Program {program} success"),
+            program_data(&event),
+            format!("Program {other} success"),
+        ];
+
+        let events = program_events(&program, &logs);
+        let [Event::CampaignFinalized(decoded)] = events.as_slice() else {
+            panic!("expected the one event the program logged");
+        };
+        assert_eq!(decoded.config, event.config);
+        assert_eq!(decoded.leftover, 42);
+    }
+}
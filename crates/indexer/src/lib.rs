@@ -0,0 +1,481 @@
+//! Keeps a copy of the program's campaigns, verifications and payouts in a database,
+//! so explorers and analytics query it instead of paginating `getProgramAccounts` on
+//! every load. [`Storage`] is the read side for the REST layer, and [`open_store`]
+//! opens SQLite, Postgres or memory by URL.
+//!
+//! Account state comes from a `programSubscribe` feed and payouts and closed campaigns
+//! from the program's events in a `logsSubscribe` feed. On start the indexer reads
+//! every program account once and replays the events of transactions since the last
+//! one it indexed, so it catches up on whatever happened while it was down.
+//!
+//! Creators can register webhooks, which the indexer calls on their campaigns'
+//! `VerificationCompleted` and `CampaignExhausted` events; see [`webhooks`]. What it
+//! observes is also counted in [`Metrics`] for Prometheus.
+//!
+//! Transactions are applied in `tracing` spans carrying their `signature`, and the
+//! requests and callbacks in them are logged with their `config`, so a claim can be
+//! followed from the relayer's logs to the indexer's.
+//!
+//! With a [`Retention`] policy the indexer also pseudonymizes or purges the post URLs
+//! of verifications older than it, as they identify their authors.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use futures::StreamExt;
+use proof_of_post::{PostProofConfig, PostVerificationLog};
+use proof_of_post_client::PopError;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig, RpcTransactionLogsConfig,
+    RpcTransactionLogsFilter,
+};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, oneshot};
+use webhooks::Dispatcher;
+
+mod events;
+mod memory;
+mod metrics;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod sqlite;
+mod store;
+pub mod webhooks;
+
+pub use events::{program_events, Event};
+pub use memory::MemoryStore;
+pub use metrics::Metrics;
+// `self::`, as the `postgres` crate has the same name
+#[cfg(feature = "postgres")]
+pub use self::postgres::PostgresStore;
+pub use rusqlite;
+pub use sqlite::SqliteStore;
+pub use store::{
+    open_store, Campaign, CampaignFilter, Page, Payout, Retention, RetentionMode, Stats, Storage,
+    StoreError, Verification, VerificationStatus,
+};
+
+/// How often requests past their expiry are counted
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+/// How often the retention policy is applied
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(3_600);
+
+/// An update from one of the feeds
+enum Update {
+    Account {
+        address: Pubkey,
+        data: Vec<u8>,
+        slot: u64,
+    },
+    Transaction {
+        signature: String,
+        logs: Vec<String>,
+        /// Whether its events are news: webhooks fire and metrics count for them
+        notify: bool,
+    },
+}
+
+pub struct Indexer {
+    rpc: RpcClient,
+    websocket_url: String,
+    program_id: Pubkey,
+    store: Box<dyn Storage>,
+    webhooks: Dispatcher,
+    metrics: Arc<Metrics>,
+    retention: Option<Retention>,
+}
+
+impl Indexer {
+    /// Needs a Tokio runtime, which delivers the webhooks
+    pub fn new(
+        rpc: RpcClient,
+        websocket_url: String,
+        program_id: Pubkey,
+        store: Box<dyn Storage>,
+    ) -> Self {
+        Self {
+            rpc,
+            websocket_url,
+            program_id,
+            store,
+            webhooks: Dispatcher::spawn(),
+            metrics: Arc::new(Metrics::new()),
+            retention: None,
+        }
+    }
+
+    /// Applies `retention` to the indexed post URLs as they age
+    pub fn with_retention(mut self, retention: Retention) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    pub fn store(&self) -> &dyn Storage {
+        &*self.store
+    }
+
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    /// Catches up, then indexes updates as they arrive until a feed ends or fails.
+    /// The feeds are subscribed before catching up, so nothing falls in between; run
+    /// it again to resume.
+    pub async fn run(&self) -> Result<(), PopError> {
+        let (tx, mut updates) = mpsc::unbounded_channel();
+        let (subscribed_tx, subscribed) = oneshot::channel();
+        let feeds = tokio::spawn(feeds(
+            self.websocket_url.clone(),
+            self.program_id,
+            self.rpc.commitment(),
+            tx,
+            subscribed_tx,
+        ));
+        match subscribed.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(PopError::PubSub(e)),
+            Err(_) => return Err(PopError::FeedClosed),
+        }
+
+        let result = match self.catch_up().await {
+            Ok(()) => {
+                self.metrics.set_live(true);
+                let mut sweep = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+                let mut retention_sweep = tokio::time::interval(RETENTION_SWEEP_INTERVAL);
+                loop {
+                    let result = tokio::select! {
+                        update = updates.recv() => match update {
+                            Some(update) => self.apply(update),
+                            None => Err(PopError::FeedClosed),
+                        },
+                        _ = sweep.tick() => self.sweep_expired().await,
+                        _ = retention_sweep.tick(), if self.retention.is_some() => {
+                            self.apply_retention()
+                        }
+                    };
+                    if let Err(e) = result {
+                        break Err(e);
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        };
+        self.metrics.set_live(false);
+        feeds.abort();
+        result
+    }
+
+    /// Reads every program account and replays the events since the cursor
+    async fn catch_up(&self) -> Result<(), PopError> {
+        // The accounts are at least as new as this slot
+        let slot = self.rpc.get_slot().await?;
+        let config = RpcProgramAccountsConfig {
+            account_config: account_config(self.rpc.commitment()),
+            ..Default::default()
+        };
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?;
+        for (address, account) in accounts {
+            self.put_account(&address, &account.data, slot)?;
+        }
+
+        // Signatures come newest first, a page at a time. Webhooks fire for events
+        // missed while down, but not for the whole history on the first run.
+        let until = self
+            .store
+            .cursor()?
+            .and_then(|s| s.parse::<Signature>().ok());
+        let notify = until.is_some();
+        let mut before = None;
+        let mut signatures = Vec::new();
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(self.rpc.commitment()),
+            };
+            let page = self
+                .rpc
+                .get_signatures_for_address_with_config(&self.program_id, config)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = last.signature.parse().ok();
+            signatures.extend(
+                page.into_iter()
+                    .filter(|status| status.err.is_none())
+                    .map(|status| status.signature),
+            );
+            if before.is_none() {
+                break;
+            }
+        }
+        for signature in signatures.into_iter().rev() {
+            let Ok(parsed) = signature.parse::<Signature>() else {
+                continue;
+            };
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                max_supported_transaction_version: Some(0),
+            };
+            let transaction = self
+                .rpc
+                .get_transaction_with_config(&parsed, config)
+                .await?;
+            let logs: Option<Vec<String>> = transaction
+                .transaction
+                .meta
+                .and_then(|meta| meta.log_messages.into());
+            self.apply(Update::Transaction {
+                signature,
+                logs: logs.unwrap_or_default(),
+                notify,
+            })?;
+        }
+        if !notify {
+            // Nor do requests that expired long ago count as timeouts now
+            self.store.expire_requests(slot)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, update: Update) -> Result<(), PopError> {
+        match update {
+            Update::Account {
+                address,
+                data,
+                slot,
+            } => {
+                tracing::debug!(%address, slot, "account updated");
+                self.metrics.updates.with_label_values(&["account"]).inc();
+                self.metrics.saw_slot(slot);
+                self.put_account(&address, &data, slot)?
+            }
+            Update::Transaction {
+                signature,
+                logs,
+                notify,
+            } => {
+                let _span = tracing::info_span!("transaction", %signature).entered();
+                if notify {
+                    self.metrics
+                        .updates
+                        .with_label_values(&["transaction"])
+                        .inc();
+                }
+                for (index, event) in program_events(&self.program_id, &logs)
+                    .into_iter()
+                    .enumerate()
+                {
+                    match event {
+                        Event::RewardPaid(event) => {
+                            self.store.put_payout(&signature, index, &event)?;
+                            if notify {
+                                tracing::info!(
+                                    config = %event.config,
+                                    verifier = %event.verifier,
+                                    amount = event.amount,
+                                    "reward paid"
+                                );
+                                self.metrics.payout_lamports.inc_by(event.amount);
+                            }
+                        }
+                        Event::CampaignFinalized(event) => {
+                            self.store.close_campaign(&event.config)?
+                        }
+                        Event::VerificationRequested(event) => {
+                            self.store.put_request(&event)?;
+                            if notify {
+                                tracing::info!(
+                                    config = %event.config,
+                                    verifier = %event.verifier,
+                                    execution_request = %event.execution_request,
+                                    "verification requested"
+                                );
+                                self.metrics.requested.inc();
+                            }
+                        }
+                        Event::VerificationCompleted(event) => {
+                            let requested_slot = self.store.complete_request(
+                                &event.config,
+                                &event.verifier,
+                                event.slot,
+                            )?;
+                            if notify {
+                                let result = if event.passed { "passed" } else { "failed" };
+                                tracing::info!(
+                                    config = %event.config,
+                                    verifier = %event.verifier,
+                                    result,
+                                    "verification completed"
+                                );
+                                self.metrics.completed.with_label_values(&[result]).inc();
+                                if let Some(requested_slot) = requested_slot {
+                                    let latency = event.slot.saturating_sub(requested_slot);
+                                    self.metrics.callback_latency.observe(latency as f64);
+                                }
+                                self.notify(
+                                    &event.creator,
+                                    webhooks::verification_completed(&event, &signature),
+                                )?;
+                            }
+                        }
+                        Event::CampaignExhausted(event) if notify => self.notify(
+                            &event.creator,
+                            webhooks::campaign_exhausted(&event, &signature),
+                        )?,
+                        Event::CampaignExhausted(_) => {}
+                    }
+                }
+                self.store.set_cursor(&signature)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the requests that expired without a callback since the last sweep
+    async fn sweep_expired(&self) -> Result<(), PopError> {
+        let slot = self.rpc.get_slot().await?;
+        self.metrics.saw_slot(slot);
+        let expired = self.store.expire_requests(slot)?;
+        self.metrics.expired.inc_by(expired as u64);
+        Ok(())
+    }
+
+    /// Ages post URLs out by the wall clock, which settled verifications are stamped with
+    fn apply_retention(&self) -> Result<(), PopError> {
+        let Some(retention) = self.retention else {
+            return Ok(());
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        self.store.apply_retention(retention, now)?;
+        Ok(())
+    }
+
+    fn notify(&self, creator: &Pubkey, body: String) -> Result<(), StoreError> {
+        for webhook in self.store.webhooks(creator)? {
+            self.webhooks.send(&webhook, body.clone());
+        }
+        Ok(())
+    }
+
+    /// Stores campaigns and verification logs; other accounts are skipped, as are
+    /// accounts still in a layout from before a program upgrade
+    fn put_account(&self, address: &Pubkey, data: &[u8], slot: u64) -> Result<(), StoreError> {
+        if data.starts_with(PostProofConfig::DISCRIMINATOR) {
+            if let Ok(config) = PostProofConfig::try_deserialize(&mut &data[..]) {
+                self.store.put_campaign(address, &config, slot)?;
+            }
+        } else if data.starts_with(PostVerificationLog::DISCRIMINATOR) {
+            if let Ok(log) = PostVerificationLog::try_deserialize(&mut &data[..]) {
+                self.store.put_verification(address, &log, slot)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn account_config(commitment: CommitmentConfig) -> RpcAccountInfoConfig {
+    RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..Default::default()
+    }
+}
+
+/// Forwards both feeds into `tx` from their own task, as the update streams borrow
+/// the client. Reports on `subscribed` once both are up, and returns when either
+/// ends or the receiver is dropped.
+async fn feeds(
+    url: String,
+    program_id: Pubkey,
+    commitment: CommitmentConfig,
+    tx: mpsc::UnboundedSender<Update>,
+    subscribed: oneshot::Sender<Result<(), PubsubClientError>>,
+) {
+    let pubsub = match PubsubClient::new(&url).await {
+        Ok(pubsub) => pubsub,
+        Err(e) => {
+            let _ = subscribed.send(Err(e));
+            return;
+        }
+    };
+    let accounts = pubsub
+        .program_subscribe(
+            &program_id,
+            Some(RpcProgramAccountsConfig {
+                account_config: account_config(commitment),
+                ..Default::default()
+            }),
+        )
+        .await;
+    let logs = pubsub
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(commitment),
+            },
+        )
+        .await;
+    let ((mut accounts, unsubscribe_accounts), (mut logs, unsubscribe_logs)) =
+        match (accounts, logs) {
+            (Ok(accounts), Ok(logs)) => (accounts, logs),
+            (Err(e), _) | (_, Err(e)) => {
+                let _ = subscribed.send(Err(e));
+                return;
+            }
+        };
+    if subscribed.send(Ok(())).is_err() {
+        return;
+    }
+
+    loop {
+        let update = tokio::select! {
+            update = accounts.next() => update.map(|response| {
+                let address = response.value.pubkey.parse().ok();
+                let account: Option<Account> = response.value.account.decode();
+                address.zip(account).map(|(address, account)| Update::Account {
+                    address,
+                    data: account.data,
+                    slot: response.context.slot,
+                })
+            }),
+            update = logs.next() => update.map(|response| {
+                // Failed transactions changed nothing, whatever they logged
+                response.value.err.is_none().then(|| Update::Transaction {
+                    signature: response.value.signature,
+                    logs: response.value.logs,
+                    notify: true,
+                })
+            }),
+            _ = tx.closed() => break,
+        };
+        match update {
+            None => break,
+            Some(None) => continue,
+            Some(Some(update)) => {
+                if tx.send(update).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    drop((accounts, logs));
+    unsubscribe_accounts().await;
+    unsubscribe_logs().await;
+}
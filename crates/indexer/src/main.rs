@@ -0,0 +1,118 @@
+//! Runs the indexer against an RPC node, restarting it when a feed drops.
+//!
+//! `indexer --url https://api.devnet.solana.com --database pop.db`, or
+//! `--database postgres://indexer@localhost/pop` in builds with the `postgres` feature.
+//!
+//! Serves Prometheus metrics at `/metrics` and readiness at `/health` on
+//! `--metrics-listen`. `--retention-days` pseudonymizes, or with
+//! `--retention-mode purge` blanks, post URLs settled longer ago than that. `RUST_LOG`
+//! sets the log verbosity, `info` by default.
+
+use anchor_lang::prelude::Pubkey;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use clap::Parser;
+use indexer::{open_store, Indexer, Metrics, Retention, RetentionMode};
+use proof_of_post_client::websocket_url;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+/// Wait before resuming after the indexer stopped on an error
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Parser)]
+#[command(name = "indexer", version, about)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        env = "POP_RPC_URL",
+        default_value = "https://api.devnet.solana.com"
+    )]
+    url: String,
+    /// PubSub endpoint, by default the one next to the RPC endpoint
+    #[arg(long, env = "POP_WS_URL")]
+    ws_url: Option<String>,
+    /// Database to write: a SQLite path, created if missing, a `postgres://` URL in
+    /// builds with the `postgres` feature, or `:memory:` to keep nothing
+    #[arg(long, env = "POP_DATABASE", default_value = "proof-of-post.db")]
+    database: String,
+    #[arg(long, env = "POP_PROGRAM_ID", default_value_t = proof_of_post::ID)]
+    program_id: Pubkey,
+    /// Where to serve `/metrics` and `/health`
+    #[arg(long, env = "POP_METRICS_LISTEN", default_value = "127.0.0.1:9464")]
+    metrics_listen: SocketAddr,
+    /// Days to keep the post URLs of settled verifications; kept indefinitely if unset
+    #[arg(long, env = "POP_RETENTION_DAYS")]
+    retention_days: Option<u32>,
+    /// What happens to post URLs past the retention period
+    #[arg(
+        long,
+        env = "POP_RETENTION_MODE",
+        value_enum,
+        default_value = "pseudonymize"
+    )]
+    retention_mode: RetentionMode,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    let store = open_store(&cli.database).map_err(|e| format!("opening {}: {e}", cli.database))?;
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let ws_url = cli.ws_url.unwrap_or_else(|| websocket_url(&cli.url));
+    let mut indexer = Indexer::new(rpc, ws_url, cli.program_id, store);
+    if let Some(days) = cli.retention_days {
+        indexer = indexer.with_retention(Retention {
+            max_age_secs: i64::from(days) * 86_400,
+            mode: cli.retention_mode,
+        });
+    }
+
+    let listener = tokio::net::TcpListener::bind(cli.metrics_listen).await?;
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .route("/health", get(health))
+        .with_state(indexer.metrics());
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "metrics server stopped");
+        }
+    });
+
+    loop {
+        if let Err(e) = indexer.run().await {
+            tracing::warn!(
+                error = %e,
+                "indexer stopped; resuming in {}s",
+                RETRY_DELAY.as_secs()
+            );
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+async fn metrics(State(metrics): State<Arc<Metrics>>) -> String {
+    metrics.encode()
+}
+
+/// Ready once caught up and following the feeds
+async fn health(State(metrics): State<Arc<Metrics>>) -> StatusCode {
+    if metrics.is_live() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
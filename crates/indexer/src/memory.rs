@@ -0,0 +1,455 @@
+//! A backend keeping everything in memory, for tests and throwaway runs. Nothing
+//! survives the process, and other processes can't read it.
+
+use anchor_lang::prelude::Pubkey;
+use proof_of_post::{PostProofConfig, PostVerificationLog, RewardPaid, VerificationRequested};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::store::{
+    hex, pseudonym, Campaign, CampaignFilter, Page, Payout, Retention, RetentionMode, Stats,
+    Storage, StoreError, Verification, VerificationStatus, PSEUDONYM_PREFIX,
+};
+use crate::webhooks::Webhook;
+
+struct Request {
+    campaign: String,
+    verifier: String,
+    slot: u64,
+    expiry_slot: u64,
+    completed_slot: Option<u64>,
+    expired: bool,
+}
+
+/// The rows of each table, with the slot account rows were last read at
+#[derive(Default)]
+struct Tables {
+    campaigns: BTreeMap<String, (Campaign, u64)>,
+    verifications: BTreeMap<String, (Verification, u64)>,
+    payouts: BTreeMap<(String, usize), Payout>,
+    requests: BTreeMap<String, Request>,
+    webhooks: BTreeMap<i64, Webhook>,
+    /// Ids aren't reused, as with SQLite's AUTOINCREMENT
+    last_webhook_id: i64,
+    cursor: Option<String>,
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    tables: Mutex<Tables>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The rows in `page` of `rows`
+fn paged<T>(rows: impl Iterator<Item = T>, page: Page) -> Vec<T> {
+    rows.skip(page.offset as usize)
+        .take(page.limit as usize)
+        .collect()
+}
+
+fn verifications_by(
+    tables: &Tables,
+    matches: impl Fn(&Verification) -> bool,
+    status: Option<VerificationStatus>,
+    page: Page,
+) -> Vec<Verification> {
+    let mut rows: Vec<_> = tables
+        .verifications
+        .values()
+        .map(|(verification, _)| verification)
+        .filter(|verification| matches(verification))
+        .filter(|verification| status.map_or(true, |status| verification.status == status))
+        .cloned()
+        .collect();
+    rows.sort_by(|a, b| b.slot.cmp(&a.slot).then_with(|| a.address.cmp(&b.address)));
+    paged(rows.into_iter(), page)
+}
+
+fn payouts_by(tables: &Tables, matches: impl Fn(&Payout) -> bool, page: Page) -> Vec<Payout> {
+    // Keys already sort by signature and event index
+    let mut rows: Vec<_> = tables
+        .payouts
+        .values()
+        .filter(|payout| matches(payout))
+        .cloned()
+        .collect();
+    rows.sort_by(|a, b| b.slot.cmp(&a.slot));
+    paged(rows.into_iter(), page)
+}
+
+fn stats_of(tables: &Tables, campaign: Option<&str>) -> Stats {
+    let in_scope = |address: &str| campaign.map_or(true, |campaign| campaign == address);
+    let campaigns: Vec<_> = tables
+        .campaigns
+        .values()
+        .map(|(campaign, _)| campaign)
+        .filter(|campaign| in_scope(&campaign.address))
+        .collect();
+    let verifications: Vec<_> = tables
+        .verifications
+        .values()
+        .map(|(verification, _)| verification)
+        .filter(|verification| in_scope(&verification.campaign))
+        .collect();
+    let payouts: Vec<_> = tables
+        .payouts
+        .values()
+        .filter(|payout| in_scope(&payout.campaign))
+        .collect();
+    let count = |status| {
+        verifications
+            .iter()
+            .filter(|verification| verification.status == status)
+            .count() as u64
+    };
+    Stats {
+        campaigns: campaigns.len() as u64,
+        active_campaigns: campaigns.iter().filter(|campaign| campaign.active).count() as u64,
+        verifications: verifications.len() as u64,
+        verified: count(VerificationStatus::Verified),
+        failed: count(VerificationStatus::Failed),
+        pending: count(VerificationStatus::Pending),
+        verifiers: verifications
+            .iter()
+            .map(|verification| &verification.verifier)
+            .collect::<BTreeSet<_>>()
+            .len() as u64,
+        payouts: payouts.len() as u64,
+        total_paid: payouts.iter().map(|payout| payout.amount).sum(),
+    }
+}
+
+impl Storage for MemoryStore {
+    fn put_campaign(
+        &self,
+        address: &Pubkey,
+        config: &PostProofConfig,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        let mut tables = self.tables();
+        let (row, updated_slot) =
+            tables
+                .campaigns
+                .entry(address.to_string())
+                .or_insert_with(|| {
+                    let campaign = Campaign {
+                        address: address.to_string(),
+                        creator: String::new(),
+                        seeds: config.seeds.clone(),
+                        keywords: config.keywords.clone(),
+                        platform: config.platform,
+                        reward_amount: 0,
+                        max_claimers: 0,
+                        claimers_count: 0,
+                        total_paid: 0,
+                        active: false,
+                        closed: false,
+                        created_slot: config.created_slot,
+                    };
+                    (campaign, 0)
+                });
+        if slot >= *updated_slot {
+            row.creator = config.creator.to_string();
+            row.reward_amount = config.reward_amount;
+            row.max_claimers = config.max_claimers;
+            row.claimers_count = config.claimers_count;
+            row.total_paid = config.total_paid;
+            row.active = config.active;
+            *updated_slot = slot;
+        }
+        Ok(())
+    }
+
+    fn close_campaign(&self, address: &Pubkey) -> Result<(), StoreError> {
+        if let Some((campaign, _)) = self.tables().campaigns.get_mut(&address.to_string()) {
+            campaign.closed = true;
+            campaign.active = false;
+        }
+        Ok(())
+    }
+
+    fn put_verification(
+        &self,
+        address: &Pubkey,
+        log: &PostVerificationLog,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        let verification = Verification {
+            address: address.to_string(),
+            campaign: log.config.to_string(),
+            verifier: log.verifier.to_string(),
+            post_url: log.post_url.clone(),
+            status: VerificationStatus::of(log),
+            failure_reason: log.failure_reason,
+            failed_checks: log.failed_checks,
+            author_did_hash: hex(&log.author_did_hash),
+            slot: log.slot,
+            timestamp: log.timestamp,
+        };
+        let mut tables = self.tables();
+        let Some((row, updated_slot)) = tables.verifications.get_mut(&verification.address) else {
+            tables
+                .verifications
+                .insert(verification.address.clone(), (verification, slot));
+            return Ok(());
+        };
+        if slot < *updated_slot {
+            return Ok(());
+        }
+        // The same settled verification keeps the URL retention replaced
+        let retained = verification.status != VerificationStatus::Pending
+            && verification.timestamp == row.timestamp
+            && (row.post_url.is_empty() || row.post_url.starts_with(PSEUDONYM_PREFIX));
+        let post_url = if retained {
+            row.post_url.clone()
+        } else {
+            verification.post_url
+        };
+        *row = Verification {
+            campaign: row.campaign.clone(),
+            verifier: row.verifier.clone(),
+            post_url,
+            ..verification
+        };
+        *updated_slot = slot;
+        Ok(())
+    }
+
+    fn apply_retention(&self, retention: Retention, now: i64) -> Result<usize, StoreError> {
+        let cutoff = now.saturating_sub(retention.max_age_secs);
+        let mut changed = 0;
+        for (verification, _) in self.tables().verifications.values_mut() {
+            if verification.status == VerificationStatus::Pending
+                || verification.timestamp >= cutoff
+                || verification.post_url.is_empty()
+                || verification.post_url.starts_with(PSEUDONYM_PREFIX)
+            {
+                continue;
+            }
+            verification.post_url = match retention.mode {
+                RetentionMode::Pseudonymize => pseudonym(&verification.post_url),
+                RetentionMode::Purge => String::new(),
+            };
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    fn put_payout(
+        &self,
+        signature: &str,
+        index: usize,
+        event: &RewardPaid,
+    ) -> Result<(), StoreError> {
+        self.tables()
+            .payouts
+            .entry((signature.to_string(), index))
+            .or_insert_with(|| Payout {
+                signature: signature.to_string(),
+                campaign: event.config.to_string(),
+                verifier: event.verifier.to_string(),
+                payout: event.payout.to_string(),
+                author_did_hash: hex(&event.author_did_hash),
+                amount: event.amount,
+                claimer_share: event.claimer_share,
+                creator_share: event.creator_share,
+                platform_share: event.platform_share,
+                referrer: event.referrer.map(|referrer| referrer.to_string()),
+                referrer_share: event.referrer_share,
+                slot: event.slot,
+            });
+        Ok(())
+    }
+
+    fn cursor(&self) -> Result<Option<String>, StoreError> {
+        Ok(self.tables().cursor.clone())
+    }
+
+    fn set_cursor(&self, signature: &str) -> Result<(), StoreError> {
+        self.tables().cursor = Some(signature.to_string());
+        Ok(())
+    }
+
+    fn campaigns(&self, filter: &CampaignFilter, page: Page) -> Result<Vec<Campaign>, StoreError> {
+        let creator = filter.creator.map(|creator| creator.to_string());
+        let tables = self.tables();
+        let mut rows: Vec<_> = tables
+            .campaigns
+            .values()
+            .map(|(campaign, _)| campaign)
+            .filter(|campaign| creator.as_ref().map_or(true, |c| *c == campaign.creator))
+            .filter(|campaign| {
+                filter
+                    .active
+                    .map_or(true, |active| active == campaign.active)
+            })
+            .filter(|campaign| filter.platform.map_or(true, |p| p == campaign.platform))
+            .cloned()
+            .collect();
+        rows.sort_by(|a, b| {
+            b.created_slot
+                .cmp(&a.created_slot)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        Ok(paged(rows.into_iter(), page))
+    }
+
+    fn campaign(&self, address: &Pubkey) -> Result<Option<Campaign>, StoreError> {
+        Ok(self
+            .tables()
+            .campaigns
+            .get(&address.to_string())
+            .map(|(campaign, _)| campaign.clone()))
+    }
+
+    fn verifications(
+        &self,
+        campaign: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        let campaign = campaign.to_string();
+        let matches = |verification: &Verification| verification.campaign == campaign;
+        Ok(verifications_by(&self.tables(), matches, status, page))
+    }
+
+    fn verifier_history(
+        &self,
+        verifier: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        let verifier = verifier.to_string();
+        let matches = |verification: &Verification| verification.verifier == verifier;
+        Ok(verifications_by(&self.tables(), matches, status, page))
+    }
+
+    fn payouts(&self, campaign: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        let campaign = campaign.to_string();
+        let matches = |payout: &Payout| payout.campaign == campaign;
+        Ok(payouts_by(&self.tables(), matches, page))
+    }
+
+    fn verifier_payouts(&self, verifier: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        let verifier = verifier.to_string();
+        let matches = |payout: &Payout| payout.verifier == verifier;
+        Ok(payouts_by(&self.tables(), matches, page))
+    }
+
+    fn put_request(&self, event: &VerificationRequested) -> Result<(), StoreError> {
+        self.tables()
+            .requests
+            .entry(event.execution_request.to_string())
+            .or_insert_with(|| Request {
+                campaign: event.config.to_string(),
+                verifier: event.verifier.to_string(),
+                slot: event.slot,
+                expiry_slot: event.expiry_slot,
+                completed_slot: None,
+                expired: false,
+            });
+        Ok(())
+    }
+
+    fn complete_request(
+        &self,
+        campaign: &Pubkey,
+        verifier: &Pubkey,
+        slot: u64,
+    ) -> Result<Option<u64>, StoreError> {
+        let (campaign, verifier) = (campaign.to_string(), verifier.to_string());
+        let mut tables = self.tables();
+        let open = tables
+            .requests
+            .values_mut()
+            .filter(|request| {
+                request.campaign == campaign
+                    && request.verifier == verifier
+                    && request.completed_slot.is_none()
+            })
+            .max_by_key(|request| request.slot);
+        Ok(open.map(|request| {
+            request.completed_slot = Some(slot);
+            request.slot
+        }))
+    }
+
+    fn expire_requests(&self, slot: u64) -> Result<usize, StoreError> {
+        let mut expired = 0;
+        for request in self.tables().requests.values_mut() {
+            if request.completed_slot.is_none() && !request.expired && request.expiry_slot < slot {
+                request.expired = true;
+                expired += 1;
+            }
+        }
+        Ok(expired)
+    }
+
+    fn add_webhook(
+        &self,
+        creator: &Pubkey,
+        url: &str,
+        secret: &str,
+    ) -> Result<Option<i64>, StoreError> {
+        let mut tables = self.tables();
+        let registered = tables
+            .webhooks
+            .values()
+            .any(|webhook| webhook.creator == *creator && webhook.url == url);
+        if registered {
+            return Ok(None);
+        }
+        tables.last_webhook_id += 1;
+        let id = tables.last_webhook_id;
+        tables.webhooks.insert(
+            id,
+            Webhook {
+                id,
+                creator: *creator,
+                url: url.to_string(),
+                secret: secret.to_string(),
+            },
+        );
+        Ok(Some(id))
+    }
+
+    fn remove_webhook(&self, creator: &Pubkey, id: i64) -> Result<bool, StoreError> {
+        let mut tables = self.tables();
+        if tables
+            .webhooks
+            .get(&id)
+            .is_some_and(|webhook| webhook.creator == *creator)
+        {
+            tables.webhooks.remove(&id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn webhooks(&self, creator: &Pubkey) -> Result<Vec<Webhook>, StoreError> {
+        Ok(self
+            .tables()
+            .webhooks
+            .values()
+            .filter(|webhook| webhook.creator == *creator)
+            .cloned()
+            .collect())
+    }
+
+    fn stats(&self) -> Result<Stats, StoreError> {
+        Ok(stats_of(&self.tables(), None))
+    }
+
+    fn campaign_stats(&self, campaign: &Pubkey) -> Result<Stats, StoreError> {
+        Ok(stats_of(&self.tables(), Some(&campaign.to_string())))
+    }
+}
@@ -0,0 +1,142 @@
+//! Prometheus metrics of what the indexer observes on-chain, for ops dashboards.
+//! Counters count events seen live or caught up on since the process started, not
+//! the history replayed by a first sync. The prover timeout rate is
+//! `pop_requests_expired_total / pop_verifications_requested_total`.
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Slots from request to callback. Bonsol drops requests after 50k slots.
+const LATENCY_BUCKETS: &[f64] = &[
+    10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0, 5_000.0, 10_000.0, 50_000.0,
+];
+
+pub struct Metrics {
+    registry: Registry,
+    pub requested: IntCounter,
+    /// By `result`, `passed` or `failed`
+    pub completed: IntCounterVec,
+    pub payout_lamports: IntCounter,
+    pub callback_latency: Histogram,
+    /// Requests that passed their expiry without a callback
+    pub expired: IntCounter,
+    /// Account and transaction notifications, by `kind`
+    pub updates: IntCounterVec,
+    pub last_slot: IntGauge,
+    /// Caught up and following the feeds
+    live: AtomicBool,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let metrics = Self {
+            requested: IntCounter::new(
+                "pop_verifications_requested_total",
+                "Verification requests made with verify_post",
+            )
+            .unwrap(),
+            completed: IntCounterVec::new(
+                Opts::new(
+                    "pop_verifications_completed_total",
+                    "Verification requests settled by the callback",
+                ),
+                &["result"],
+            )
+            .unwrap(),
+            payout_lamports: IntCounter::new(
+                "pop_payout_lamports_total",
+                "Lamports paid out, all shares included",
+            )
+            .unwrap(),
+            callback_latency: Histogram::with_opts(
+                HistogramOpts::new(
+                    "pop_callback_latency_slots",
+                    "Slots from a verification request to its callback",
+                )
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            )
+            .unwrap(),
+            expired: IntCounter::new(
+                "pop_requests_expired_total",
+                "Verification requests no prover delivered before expiry",
+            )
+            .unwrap(),
+            updates: IntCounterVec::new(
+                Opts::new("pop_indexer_updates_total", "Feed notifications indexed"),
+                &["kind"],
+            )
+            .unwrap(),
+            last_slot: IntGauge::new("pop_indexer_last_slot", "Latest slot the indexer saw")
+                .unwrap(),
+            live: AtomicBool::new(false),
+            registry,
+        };
+        for collector in [
+            Box::new(metrics.requested.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(metrics.completed.clone()),
+            Box::new(metrics.payout_lamports.clone()),
+            Box::new(metrics.callback_latency.clone()),
+            Box::new(metrics.expired.clone()),
+            Box::new(metrics.updates.clone()),
+            Box::new(metrics.last_slot.clone()),
+        ] {
+            metrics.registry.register(collector).unwrap();
+        }
+        metrics
+    }
+
+    /// Everything in the Prometheus text format
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("encoding metrics");
+        String::from_utf8(buffer).expect("metrics are utf-8")
+    }
+
+    /// Whether the indexer is caught up and following the feeds
+    pub fn is_live(&self) -> bool {
+        self.live.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_live(&self, live: bool) {
+        self.live.store(live, Ordering::Relaxed);
+    }
+
+    pub(crate) fn saw_slot(&self, slot: u64) {
+        if slot as i64 > self.last_slot.get() {
+            self.last_slot.set(slot as i64);
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_every_metric() {
+        let metrics = Metrics::new();
+        metrics.requested.inc();
+        metrics.completed.with_label_values(&["passed"]).inc();
+        metrics.callback_latency.observe(42.0);
+        metrics.saw_slot(7);
+        metrics.saw_slot(5);
+
+        let text = metrics.encode();
+        assert!(text.contains("pop_verifications_requested_total 1"));
+        assert!(text.contains("pop_verifications_completed_total{result=\"passed\"} 1"));
+        assert!(text.contains("pop_callback_latency_slots_bucket{le=\"50\"} 1"));
+        assert!(text.contains("pop_indexer_last_slot 7"));
+    }
+}
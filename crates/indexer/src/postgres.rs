@@ -0,0 +1,481 @@
+//! The Postgres backend, for deployments where the indexer and its readers run on
+//! different hosts or the history outgrows a single file. Amounts and slots are
+//! stored as `BIGINT`, as SQLite stores them.
+
+use anchor_lang::prelude::Pubkey;
+use postgres::{Client, NoTls, Row};
+use proof_of_post::{PostProofConfig, PostVerificationLog, RewardPaid, VerificationRequested};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::store::{
+    hex, pseudonym, Campaign, CampaignFilter, Page, Payout, Retention, RetentionMode, Stats,
+    Storage, StoreError, Verification, VerificationStatus,
+};
+use crate::webhooks::Webhook;
+
+mod embedded {
+    refinery::embed_migrations!("migrations/postgres");
+}
+
+/// `postgres::Client` needs `&mut` for every query, while [`Storage`] takes `&self`
+pub struct PostgresStore {
+    client: Mutex<Client>,
+}
+
+impl PostgresStore {
+    /// Connects to the database at `url` and migrates it to the current schema
+    pub fn connect(url: &str) -> Result<Self, StoreError> {
+        let mut client = Client::connect(url, NoTls)?;
+        embedded::migrations::runner().run(&mut client)?;
+        Ok(Self {
+            client: Mutex::new(client),
+        })
+    }
+
+    fn client(&self) -> MutexGuard<'_, Client> {
+        self.client.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// `column` is one of ours, never user input
+    fn query_verifications(
+        &self,
+        column: &str,
+        key: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        let rows = self.client().query(
+            &format!(
+                "SELECT * FROM verifications
+                WHERE {column} = $1 AND ($2::TEXT IS NULL OR status = $2)
+                ORDER BY slot DESC, address LIMIT $3 OFFSET $4"
+            ),
+            &[
+                &key.to_string(),
+                &status.map(VerificationStatus::as_str),
+                &i64::from(page.limit),
+                &i64::from(page.offset),
+            ],
+        )?;
+        Ok(rows.iter().map(verification).collect::<Result<_, _>>()?)
+    }
+
+    /// `column` is one of ours, never user input
+    fn query_payouts(
+        &self,
+        column: &str,
+        key: &Pubkey,
+        page: Page,
+    ) -> Result<Vec<Payout>, StoreError> {
+        let rows = self.client().query(
+            &format!(
+                "SELECT * FROM payouts WHERE {column} = $1
+                ORDER BY slot DESC, signature, event_index LIMIT $2 OFFSET $3"
+            ),
+            &[
+                &key.to_string(),
+                &i64::from(page.limit),
+                &i64::from(page.offset),
+            ],
+        )?;
+        Ok(rows.iter().map(payout).collect::<Result<_, _>>()?)
+    }
+
+    fn query_stats(&self, campaign: Option<&Pubkey>) -> Result<Stats, StoreError> {
+        let row = self.client().query_one(
+            "SELECT
+                (SELECT COUNT(*) FROM campaigns WHERE $1::TEXT IS NULL OR address = $1),
+                (SELECT COUNT(*) FROM campaigns WHERE active AND ($1::TEXT IS NULL OR address = $1)),
+                (SELECT COUNT(*) FROM verifications WHERE $1::TEXT IS NULL OR campaign = $1),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'verified' AND ($1::TEXT IS NULL OR campaign = $1)),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'failed' AND ($1::TEXT IS NULL OR campaign = $1)),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'pending' AND ($1::TEXT IS NULL OR campaign = $1)),
+                (SELECT COUNT(DISTINCT verifier) FROM verifications
+                    WHERE $1::TEXT IS NULL OR campaign = $1),
+                (SELECT COUNT(*) FROM payouts WHERE $1::TEXT IS NULL OR campaign = $1),
+                (SELECT COALESCE(SUM(amount), 0)::BIGINT FROM payouts
+                    WHERE $1::TEXT IS NULL OR campaign = $1)",
+            &[&campaign.map(|campaign| campaign.to_string())],
+        )?;
+        let count = |index| row.try_get::<_, i64>(index).map(|n| n as u64);
+        Ok(Stats {
+            campaigns: count(0)?,
+            active_campaigns: count(1)?,
+            verifications: count(2)?,
+            verified: count(3)?,
+            failed: count(4)?,
+            pending: count(5)?,
+            verifiers: count(6)?,
+            payouts: count(7)?,
+            total_paid: count(8)?,
+        })
+    }
+}
+
+impl Storage for PostgresStore {
+    fn put_campaign(
+        &self,
+        address: &Pubkey,
+        config: &PostProofConfig,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        self.client().execute(
+            "INSERT INTO campaigns (address, creator, seeds, keywords, platform, reward_amount,
+                max_claimers, claimers_count, total_paid, active, created_slot, updated_slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (address) DO UPDATE SET
+                creator = excluded.creator,
+                reward_amount = excluded.reward_amount,
+                max_claimers = excluded.max_claimers,
+                claimers_count = excluded.claimers_count,
+                total_paid = excluded.total_paid,
+                active = excluded.active,
+                updated_slot = excluded.updated_slot
+            WHERE excluded.updated_slot >= campaigns.updated_slot",
+            &[
+                &address.to_string(),
+                &config.creator.to_string(),
+                &config.seeds,
+                // Keywords can't contain commas
+                &config.keywords.join(","),
+                &i16::from(config.platform),
+                &(config.reward_amount as i64),
+                &(config.max_claimers as i64),
+                &(config.claimers_count as i64),
+                &(config.total_paid as i64),
+                &config.active,
+                &(config.created_slot as i64),
+                &(slot as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn close_campaign(&self, address: &Pubkey) -> Result<(), StoreError> {
+        self.client().execute(
+            "UPDATE campaigns SET closed = TRUE, active = FALSE WHERE address = $1",
+            &[&address.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn put_verification(
+        &self,
+        address: &Pubkey,
+        log: &PostVerificationLog,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        self.client().execute(
+            "INSERT INTO verifications (address, campaign, verifier, post_url, status,
+                failure_reason, failed_checks, author_did_hash, slot, timestamp, updated_slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (address) DO UPDATE SET
+                post_url = CASE
+                    WHEN excluded.status <> 'pending' AND excluded.timestamp = verifications.timestamp
+                        AND (verifications.post_url = '' OR verifications.post_url LIKE 'sha256:%')
+                    THEN verifications.post_url
+                    ELSE excluded.post_url
+                END,
+                status = excluded.status,
+                failure_reason = excluded.failure_reason,
+                failed_checks = excluded.failed_checks,
+                author_did_hash = excluded.author_did_hash,
+                slot = excluded.slot,
+                timestamp = excluded.timestamp,
+                updated_slot = excluded.updated_slot
+            WHERE excluded.updated_slot >= verifications.updated_slot",
+            &[
+                &address.to_string(),
+                &log.config.to_string(),
+                &log.verifier.to_string(),
+                &log.post_url,
+                &VerificationStatus::of(log).as_str(),
+                &i32::from(log.failure_reason),
+                &i64::from(log.failed_checks),
+                &hex(&log.author_did_hash),
+                &(log.slot as i64),
+                &log.timestamp,
+                &(slot as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn apply_retention(&self, retention: Retention, now: i64) -> Result<usize, StoreError> {
+        let cutoff = now.saturating_sub(retention.max_age_secs);
+        let mut client = self.client();
+        let mut tx = client.transaction()?;
+        let expired = tx.query(
+            "SELECT address, post_url FROM verifications
+            WHERE status <> 'pending' AND timestamp < $1
+                AND post_url <> '' AND post_url NOT LIKE 'sha256:%'
+            FOR UPDATE",
+            &[&cutoff],
+        )?;
+        for row in &expired {
+            let (address, post_url): (String, String) = (row.try_get(0)?, row.try_get(1)?);
+            // Hashed here rather than in SQL, so it matches the other backends exactly
+            let replacement = match retention.mode {
+                RetentionMode::Pseudonymize => pseudonym(&post_url),
+                RetentionMode::Purge => String::new(),
+            };
+            tx.execute(
+                "UPDATE verifications SET post_url = $2 WHERE address = $1",
+                &[&address, &replacement],
+            )?;
+        }
+        tx.commit()?;
+        Ok(expired.len())
+    }
+
+    fn put_payout(
+        &self,
+        signature: &str,
+        index: usize,
+        event: &RewardPaid,
+    ) -> Result<(), StoreError> {
+        self.client().execute(
+            "INSERT INTO payouts (signature, event_index, campaign, verifier, payout,
+                author_did_hash, amount, claimer_share, creator_share, platform_share, referrer,
+                referrer_share, slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT DO NOTHING",
+            &[
+                &signature,
+                &(index as i64),
+                &event.config.to_string(),
+                &event.verifier.to_string(),
+                &event.payout.to_string(),
+                &hex(&event.author_did_hash),
+                &(event.amount as i64),
+                &(event.claimer_share as i64),
+                &(event.creator_share as i64),
+                &(event.platform_share as i64),
+                &event.referrer.map(|referrer| referrer.to_string()),
+                &(event.referrer_share as i64),
+                &(event.slot as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn cursor(&self) -> Result<Option<String>, StoreError> {
+        let row = self
+            .client()
+            .query_opt("SELECT signature FROM cursor WHERE id = 0", &[])?;
+        Ok(row.map(|row| row.try_get(0)).transpose()?)
+    }
+
+    fn set_cursor(&self, signature: &str) -> Result<(), StoreError> {
+        self.client().execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, $1)
+            ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            &[&signature],
+        )?;
+        Ok(())
+    }
+
+    fn campaigns(&self, filter: &CampaignFilter, page: Page) -> Result<Vec<Campaign>, StoreError> {
+        let rows = self.client().query(
+            "SELECT * FROM campaigns
+            WHERE ($1::TEXT IS NULL OR creator = $1) AND ($2::BOOLEAN IS NULL OR active = $2)
+                AND ($3::SMALLINT IS NULL OR platform = $3)
+            ORDER BY created_slot DESC, address LIMIT $4 OFFSET $5",
+            &[
+                &filter.creator.map(|creator| creator.to_string()),
+                &filter.active,
+                &filter.platform.map(i16::from),
+                &i64::from(page.limit),
+                &i64::from(page.offset),
+            ],
+        )?;
+        Ok(rows.iter().map(campaign).collect::<Result<_, _>>()?)
+    }
+
+    fn campaign(&self, address: &Pubkey) -> Result<Option<Campaign>, StoreError> {
+        let row = self.client().query_opt(
+            "SELECT * FROM campaigns WHERE address = $1",
+            &[&address.to_string()],
+        )?;
+        Ok(row.as_ref().map(campaign).transpose()?)
+    }
+
+    fn verifications(
+        &self,
+        campaign: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        self.query_verifications("campaign", campaign, status, page)
+    }
+
+    fn verifier_history(
+        &self,
+        verifier: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        self.query_verifications("verifier", verifier, status, page)
+    }
+
+    fn payouts(&self, campaign: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        self.query_payouts("campaign", campaign, page)
+    }
+
+    fn verifier_payouts(&self, verifier: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        self.query_payouts("verifier", verifier, page)
+    }
+
+    fn put_request(&self, event: &VerificationRequested) -> Result<(), StoreError> {
+        self.client().execute(
+            "INSERT INTO requests (execution_request, campaign, verifier, slot, expiry_slot)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING",
+            &[
+                &event.execution_request.to_string(),
+                &event.config.to_string(),
+                &event.verifier.to_string(),
+                &(event.slot as i64),
+                &(event.expiry_slot as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn complete_request(
+        &self,
+        campaign: &Pubkey,
+        verifier: &Pubkey,
+        slot: u64,
+    ) -> Result<Option<u64>, StoreError> {
+        let row = self.client().query_opt(
+            "UPDATE requests SET completed_slot = $3
+            WHERE execution_request = (
+                SELECT execution_request FROM requests
+                WHERE campaign = $1 AND verifier = $2 AND completed_slot IS NULL
+                ORDER BY slot DESC LIMIT 1
+            )
+            RETURNING slot",
+            &[&campaign.to_string(), &verifier.to_string(), &(slot as i64)],
+        )?;
+        let requested_slot = row.map(|row| row.try_get::<_, i64>(0)).transpose()?;
+        Ok(requested_slot.map(|slot| slot as u64))
+    }
+
+    fn expire_requests(&self, slot: u64) -> Result<usize, StoreError> {
+        let expired = self.client().execute(
+            "UPDATE requests SET expired = TRUE
+            WHERE completed_slot IS NULL AND NOT expired AND expiry_slot < $1",
+            &[&(slot as i64)],
+        )?;
+        Ok(expired as usize)
+    }
+
+    fn add_webhook(
+        &self,
+        creator: &Pubkey,
+        url: &str,
+        secret: &str,
+    ) -> Result<Option<i64>, StoreError> {
+        let row = self.client().query_opt(
+            "INSERT INTO webhooks (creator, url, secret) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            RETURNING id",
+            &[&creator.to_string(), &url, &secret],
+        )?;
+        Ok(row.map(|row| row.try_get(0)).transpose()?)
+    }
+
+    fn remove_webhook(&self, creator: &Pubkey, id: i64) -> Result<bool, StoreError> {
+        let removed = self.client().execute(
+            "DELETE FROM webhooks WHERE id = $1 AND creator = $2",
+            &[&id, &creator.to_string()],
+        )?;
+        Ok(removed == 1)
+    }
+
+    fn webhooks(&self, creator: &Pubkey) -> Result<Vec<Webhook>, StoreError> {
+        let rows = self.client().query(
+            "SELECT id, url, secret FROM webhooks WHERE creator = $1 ORDER BY id",
+            &[&creator.to_string()],
+        )?;
+        let webhooks = rows
+            .iter()
+            .map(|row| {
+                Ok(Webhook {
+                    id: row.try_get(0)?,
+                    creator: *creator,
+                    url: row.try_get(1)?,
+                    secret: row.try_get(2)?,
+                })
+            })
+            .collect::<Result<_, postgres::Error>>()?;
+        Ok(webhooks)
+    }
+
+    fn stats(&self) -> Result<Stats, StoreError> {
+        self.query_stats(None)
+    }
+
+    fn campaign_stats(&self, campaign: &Pubkey) -> Result<Stats, StoreError> {
+        self.query_stats(Some(campaign))
+    }
+}
+
+fn campaign(row: &Row) -> Result<Campaign, postgres::Error> {
+    let keywords: String = row.try_get("keywords")?;
+    Ok(Campaign {
+        address: row.try_get("address")?,
+        creator: row.try_get("creator")?,
+        seeds: row.try_get("seeds")?,
+        keywords: keywords
+            .split(',')
+            .filter(|keyword| !keyword.is_empty())
+            .map(String::from)
+            .collect(),
+        platform: row.try_get::<_, i16>("platform")? as u8,
+        reward_amount: row.try_get::<_, i64>("reward_amount")? as u64,
+        max_claimers: row.try_get::<_, i64>("max_claimers")? as u64,
+        claimers_count: row.try_get::<_, i64>("claimers_count")? as u64,
+        total_paid: row.try_get::<_, i64>("total_paid")? as u64,
+        active: row.try_get("active")?,
+        closed: row.try_get("closed")?,
+        created_slot: row.try_get::<_, i64>("created_slot")? as u64,
+    })
+}
+
+fn verification(row: &Row) -> Result<Verification, postgres::Error> {
+    let status: String = row.try_get("status")?;
+    Ok(Verification {
+        address: row.try_get("address")?,
+        campaign: row.try_get("campaign")?,
+        verifier: row.try_get("verifier")?,
+        post_url: row.try_get("post_url")?,
+        status: VerificationStatus::parse(&status),
+        failure_reason: row.try_get::<_, i32>("failure_reason")? as u16,
+        failed_checks: row.try_get::<_, i64>("failed_checks")? as u32,
+        author_did_hash: row.try_get("author_did_hash")?,
+        slot: row.try_get::<_, i64>("slot")? as u64,
+        timestamp: row.try_get("timestamp")?,
+    })
+}
+
+fn payout(row: &Row) -> Result<Payout, postgres::Error> {
+    Ok(Payout {
+        signature: row.try_get("signature")?,
+        campaign: row.try_get("campaign")?,
+        verifier: row.try_get("verifier")?,
+        payout: row.try_get("payout")?,
+        author_did_hash: row.try_get("author_did_hash")?,
+        amount: row.try_get::<_, i64>("amount")? as u64,
+        claimer_share: row.try_get::<_, i64>("claimer_share")? as u64,
+        creator_share: row.try_get::<_, i64>("creator_share")? as u64,
+        platform_share: row.try_get::<_, i64>("platform_share")? as u64,
+        referrer: row.try_get("referrer")?,
+        referrer_share: row.try_get::<_, i64>("referrer_share")? as u64,
+        slot: row.try_get::<_, i64>("slot")? as u64,
+    })
+}
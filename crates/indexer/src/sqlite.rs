@@ -0,0 +1,524 @@
+//! The embedded SQLite backend, in WAL mode so the REST layer can read the file the
+//! indexer writes
+
+use anchor_lang::prelude::Pubkey;
+use proof_of_post::{PostProofConfig, PostVerificationLog, RewardPaid, VerificationRequested};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::path::Path;
+
+use crate::store::{
+    hex, pseudonym, Campaign, CampaignFilter, Page, Payout, Retention, RetentionMode, Stats,
+    Storage, StoreError, Verification, VerificationStatus,
+};
+use crate::webhooks::Webhook;
+
+mod embedded {
+    refinery::embed_migrations!("migrations/sqlite");
+}
+
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if needed and migrating it to the
+    /// current schema. The database is in WAL mode, so readers don't block the indexer.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self, StoreError> {
+        embedded::migrations::runner().run(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// `column` is one of ours, never user input
+    fn query_verifications(
+        &self,
+        column: &str,
+        key: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT * FROM verifications WHERE {column} = ?1 AND (?2 IS NULL OR status = ?2)
+            ORDER BY slot DESC, address LIMIT ?3 OFFSET ?4"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                key.to_string(),
+                status.map(VerificationStatus::as_str),
+                page.limit,
+                page.offset,
+            ],
+            verification,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// `column` is one of ours, never user input
+    fn query_payouts(
+        &self,
+        column: &str,
+        key: &Pubkey,
+        page: Page,
+    ) -> Result<Vec<Payout>, StoreError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT * FROM payouts WHERE {column} = ?1
+            ORDER BY slot DESC, signature, event_index LIMIT ?2 OFFSET ?3"
+        ))?;
+        let rows = stmt.query_map(params![key.to_string(), page.limit, page.offset], payout)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn query_stats(&self, campaign: Option<&Pubkey>) -> Result<Stats, StoreError> {
+        let stats = self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM campaigns WHERE ?1 IS NULL OR address = ?1),
+                (SELECT COUNT(*) FROM campaigns WHERE active = 1 AND (?1 IS NULL OR address = ?1)),
+                (SELECT COUNT(*) FROM verifications WHERE ?1 IS NULL OR campaign = ?1),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'verified' AND (?1 IS NULL OR campaign = ?1)),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'failed' AND (?1 IS NULL OR campaign = ?1)),
+                (SELECT COUNT(*) FROM verifications
+                    WHERE status = 'pending' AND (?1 IS NULL OR campaign = ?1)),
+                (SELECT COUNT(DISTINCT verifier) FROM verifications
+                    WHERE ?1 IS NULL OR campaign = ?1),
+                (SELECT COUNT(*) FROM payouts WHERE ?1 IS NULL OR campaign = ?1),
+                (SELECT COALESCE(SUM(amount), 0) FROM payouts WHERE ?1 IS NULL OR campaign = ?1)",
+            params![campaign.map(|campaign| campaign.to_string())],
+            |row| {
+                let count = |index| row.get::<_, i64>(index).map(|n| n as u64);
+                Ok(Stats {
+                    campaigns: count(0)?,
+                    active_campaigns: count(1)?,
+                    verifications: count(2)?,
+                    verified: count(3)?,
+                    failed: count(4)?,
+                    pending: count(5)?,
+                    verifiers: count(6)?,
+                    payouts: count(7)?,
+                    total_paid: count(8)?,
+                })
+            },
+        )?;
+        Ok(stats)
+    }
+}
+
+impl Storage for SqliteStore {
+    fn put_campaign(
+        &self,
+        address: &Pubkey,
+        config: &PostProofConfig,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT INTO campaigns (address, creator, seeds, keywords, platform, reward_amount,
+                max_claimers, claimers_count, total_paid, active, created_slot, updated_slot)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            ON CONFLICT (address) DO UPDATE SET
+                creator = excluded.creator,
+                reward_amount = excluded.reward_amount,
+                max_claimers = excluded.max_claimers,
+                claimers_count = excluded.claimers_count,
+                total_paid = excluded.total_paid,
+                active = excluded.active,
+                updated_slot = excluded.updated_slot
+            WHERE excluded.updated_slot >= campaigns.updated_slot",
+            params![
+                address.to_string(),
+                config.creator.to_string(),
+                config.seeds,
+                // Keywords can't contain commas
+                config.keywords.join(","),
+                config.platform,
+                config.reward_amount as i64,
+                config.max_claimers as i64,
+                config.claimers_count as i64,
+                config.total_paid as i64,
+                config.active,
+                config.created_slot as i64,
+                slot as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn close_campaign(&self, address: &Pubkey) -> Result<(), StoreError> {
+        self.conn.execute(
+            "UPDATE campaigns SET closed = 1, active = 0 WHERE address = ?1",
+            params![address.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn put_verification(
+        &self,
+        address: &Pubkey,
+        log: &PostVerificationLog,
+        slot: u64,
+    ) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT INTO verifications (address, campaign, verifier, post_url, status,
+                failure_reason, failed_checks, author_did_hash, slot, timestamp, updated_slot)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            ON CONFLICT (address) DO UPDATE SET
+                post_url = CASE
+                    WHEN excluded.status <> 'pending' AND excluded.timestamp = verifications.timestamp
+                        AND (verifications.post_url = '' OR verifications.post_url LIKE 'sha256:%')
+                    THEN verifications.post_url
+                    ELSE excluded.post_url
+                END,
+                status = excluded.status,
+                failure_reason = excluded.failure_reason,
+                failed_checks = excluded.failed_checks,
+                author_did_hash = excluded.author_did_hash,
+                slot = excluded.slot,
+                timestamp = excluded.timestamp,
+                updated_slot = excluded.updated_slot
+            WHERE excluded.updated_slot >= verifications.updated_slot",
+            params![
+                address.to_string(),
+                log.config.to_string(),
+                log.verifier.to_string(),
+                log.post_url,
+                VerificationStatus::of(log).as_str(),
+                log.failure_reason,
+                log.failed_checks,
+                hex(&log.author_did_hash),
+                log.slot as i64,
+                log.timestamp,
+                slot as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn apply_retention(&self, retention: Retention, now: i64) -> Result<usize, StoreError> {
+        let cutoff = now.saturating_sub(retention.max_age_secs);
+        let tx = self.conn.unchecked_transaction()?;
+        let expired = {
+            let mut stmt = tx.prepare(
+                "SELECT address, post_url FROM verifications
+                WHERE status <> 'pending' AND timestamp < ?1
+                    AND post_url <> '' AND post_url NOT LIKE 'sha256:%'",
+            )?;
+            let rows = stmt.query_map(params![cutoff], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        for (address, post_url) in &expired {
+            let replacement = match retention.mode {
+                RetentionMode::Pseudonymize => pseudonym(post_url),
+                RetentionMode::Purge => String::new(),
+            };
+            tx.execute(
+                "UPDATE verifications SET post_url = ?2 WHERE address = ?1",
+                params![address, replacement],
+            )?;
+        }
+        tx.commit()?;
+        Ok(expired.len())
+    }
+
+    fn put_payout(
+        &self,
+        signature: &str,
+        index: usize,
+        event: &RewardPaid,
+    ) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO payouts (signature, event_index, campaign, verifier, payout,
+                author_did_hash, amount, claimer_share, creator_share, platform_share, referrer,
+                referrer_share, slot)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                signature,
+                index as i64,
+                event.config.to_string(),
+                event.verifier.to_string(),
+                event.payout.to_string(),
+                hex(&event.author_did_hash),
+                event.amount as i64,
+                event.claimer_share as i64,
+                event.creator_share as i64,
+                event.platform_share as i64,
+                event.referrer.map(|referrer| referrer.to_string()),
+                event.referrer_share as i64,
+                event.slot as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn cursor(&self) -> Result<Option<String>, StoreError> {
+        self.conn
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(StoreError::from)
+    }
+
+    fn set_cursor(&self, signature: &str) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, ?1)
+            ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            params![signature],
+        )?;
+        Ok(())
+    }
+
+    fn campaigns(&self, filter: &CampaignFilter, page: Page) -> Result<Vec<Campaign>, StoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM campaigns
+            WHERE (?1 IS NULL OR creator = ?1) AND (?2 IS NULL OR active = ?2)
+                AND (?3 IS NULL OR platform = ?3)
+            ORDER BY created_slot DESC, address LIMIT ?4 OFFSET ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                filter.creator.map(|creator| creator.to_string()),
+                filter.active,
+                filter.platform,
+                page.limit,
+                page.offset,
+            ],
+            campaign,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn campaign(&self, address: &Pubkey) -> Result<Option<Campaign>, StoreError> {
+        self.conn
+            .query_row(
+                "SELECT * FROM campaigns WHERE address = ?1",
+                params![address.to_string()],
+                campaign,
+            )
+            .optional()
+            .map_err(StoreError::from)
+    }
+
+    fn verifications(
+        &self,
+        campaign: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        self.query_verifications("campaign", campaign, status, page)
+    }
+
+    fn verifier_history(
+        &self,
+        verifier: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError> {
+        self.query_verifications("verifier", verifier, status, page)
+    }
+
+    fn payouts(&self, campaign: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        self.query_payouts("campaign", campaign, page)
+    }
+
+    fn verifier_payouts(&self, verifier: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError> {
+        self.query_payouts("verifier", verifier, page)
+    }
+
+    fn put_request(&self, event: &VerificationRequested) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO requests (execution_request, campaign, verifier, slot,
+                expiry_slot)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                event.execution_request.to_string(),
+                event.config.to_string(),
+                event.verifier.to_string(),
+                event.slot as i64,
+                event.expiry_slot as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn complete_request(
+        &self,
+        campaign: &Pubkey,
+        verifier: &Pubkey,
+        slot: u64,
+    ) -> Result<Option<u64>, StoreError> {
+        let requested_slot = self
+            .conn
+            .query_row(
+                "UPDATE requests SET completed_slot = ?3
+                WHERE execution_request = (
+                    SELECT execution_request FROM requests
+                    WHERE campaign = ?1 AND verifier = ?2 AND completed_slot IS NULL
+                    ORDER BY slot DESC LIMIT 1
+                )
+                RETURNING slot",
+                params![campaign.to_string(), verifier.to_string(), slot as i64],
+                |row| row.get::<_, i64>(0).map(|slot| slot as u64),
+            )
+            .optional()?;
+        Ok(requested_slot)
+    }
+
+    fn expire_requests(&self, slot: u64) -> Result<usize, StoreError> {
+        let expired = self.conn.execute(
+            "UPDATE requests SET expired = 1
+            WHERE completed_slot IS NULL AND expired = 0 AND expiry_slot < ?1",
+            params![slot as i64],
+        )?;
+        Ok(expired)
+    }
+
+    fn add_webhook(
+        &self,
+        creator: &Pubkey,
+        url: &str,
+        secret: &str,
+    ) -> Result<Option<i64>, StoreError> {
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO webhooks (creator, url, secret) VALUES (?1, ?2, ?3)",
+            params![creator.to_string(), url, secret],
+        )?;
+        Ok((added == 1).then(|| self.conn.last_insert_rowid()))
+    }
+
+    fn remove_webhook(&self, creator: &Pubkey, id: i64) -> Result<bool, StoreError> {
+        let removed = self.conn.execute(
+            "DELETE FROM webhooks WHERE id = ?1 AND creator = ?2",
+            params![id, creator.to_string()],
+        )?;
+        Ok(removed == 1)
+    }
+
+    fn webhooks(&self, creator: &Pubkey) -> Result<Vec<Webhook>, StoreError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, url, secret FROM webhooks WHERE creator = ?1 ORDER BY id")?;
+        let rows = stmt.query_map(params![creator.to_string()], |row| {
+            Ok(Webhook {
+                id: row.get(0)?,
+                creator: *creator,
+                url: row.get(1)?,
+                secret: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn stats(&self) -> Result<Stats, StoreError> {
+        self.query_stats(None)
+    }
+
+    fn campaign_stats(&self, campaign: &Pubkey) -> Result<Stats, StoreError> {
+        self.query_stats(Some(campaign))
+    }
+}
+
+fn campaign(row: &Row) -> rusqlite::Result<Campaign> {
+    let keywords: String = row.get("keywords")?;
+    Ok(Campaign {
+        address: row.get("address")?,
+        creator: row.get("creator")?,
+        seeds: row.get("seeds")?,
+        keywords: keywords
+            .split(',')
+            .filter(|keyword| !keyword.is_empty())
+            .map(String::from)
+            .collect(),
+        platform: row.get("platform")?,
+        reward_amount: row.get::<_, i64>("reward_amount")? as u64,
+        max_claimers: row.get::<_, i64>("max_claimers")? as u64,
+        claimers_count: row.get::<_, i64>("claimers_count")? as u64,
+        total_paid: row.get::<_, i64>("total_paid")? as u64,
+        active: row.get("active")?,
+        closed: row.get("closed")?,
+        created_slot: row.get::<_, i64>("created_slot")? as u64,
+    })
+}
+
+fn verification(row: &Row) -> rusqlite::Result<Verification> {
+    let status: String = row.get("status")?;
+    Ok(Verification {
+        address: row.get("address")?,
+        campaign: row.get("campaign")?,
+        verifier: row.get("verifier")?,
+        post_url: row.get("post_url")?,
+        status: VerificationStatus::parse(&status),
+        failure_reason: row.get("failure_reason")?,
+        failed_checks: row.get("failed_checks")?,
+        author_did_hash: row.get("author_did_hash")?,
+        slot: row.get::<_, i64>("slot")? as u64,
+        timestamp: row.get("timestamp")?,
+    })
+}
+
+fn payout(row: &Row) -> rusqlite::Result<Payout> {
+    Ok(Payout {
+        signature: row.get("signature")?,
+        campaign: row.get("campaign")?,
+        verifier: row.get("verifier")?,
+        payout: row.get("payout")?,
+        author_did_hash: row.get("author_did_hash")?,
+        amount: row.get::<_, i64>("amount")? as u64,
+        claimer_share: row.get::<_, i64>("claimer_share")? as u64,
+        creator_share: row.get::<_, i64>("creator_share")? as u64,
+        platform_share: row.get::<_, i64>("platform_share")? as u64,
+        referrer: row.get("referrer")?,
+        referrer_share: row.get::<_, i64>("referrer_share")? as u64,
+        slot: row.get::<_, i64>("slot")? as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_a_database_from_before_migrations() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../migrations/sqlite/V1__initial.sql"))
+            .unwrap();
+        conn.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, 'sig')",
+            params![],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO webhooks (creator, url, secret) VALUES (?1, 'https://example.com', 's')",
+            params![Pubkey::default().to_string()],
+        )
+        .unwrap();
+
+        let store = SqliteStore::init(conn).unwrap();
+        assert_eq!(store.cursor().unwrap().as_deref(), Some("sig"));
+        assert_eq!(store.webhooks(&Pubkey::default()).unwrap().len(), 1);
+        let indexes: Vec<String> = store
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'payouts'")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert!(indexes.contains(&String::from("payouts_campaign_slot")));
+        assert!(!indexes.contains(&String::from("payouts_campaign")));
+
+        // Migrating again finds nothing to do
+        let store = SqliteStore::init(store.conn).unwrap();
+        assert_eq!(store.cursor().unwrap().as_deref(), Some("sig"));
+    }
+}
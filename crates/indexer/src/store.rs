@@ -0,0 +1,601 @@
+//! What the indexer writes and the REST layer reads, behind the [`Storage`] trait:
+//! embedded SQLite by default, Postgres for larger deployments with the `postgres`
+//! feature, or memory for tests and throwaway runs. [`open_store`] picks the backend
+//! from the database URL.
+//!
+//! Addresses are stored base58 and hashes hex, as they are shown to users. Account rows
+//! carry the slot they were read at, so a late notification never overwrites newer
+//! state.
+//!
+//! Each backend's schema is versioned by the migrations embedded from `migrations/`,
+//! which run on open, so upgrading the indexer keeps the history it indexed. Databases
+//! from before migrations start at the first one, which is the schema they have.
+//!
+//! Post URLs name their author, so a [`Retention`] policy can pseudonymize or purge
+//! them once settled verifications are old enough; hashes and totals stay.
+
+use anchor_lang::prelude::Pubkey;
+use proof_of_post::{PostProofConfig, PostVerificationLog, RewardPaid, VerificationRequested};
+use proof_of_post_client::PopError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use crate::memory::MemoryStore;
+#[cfg(feature = "postgres")]
+use crate::postgres::PostgresStore;
+use crate::sqlite::SqliteStore;
+use crate::webhooks::Webhook;
+
+/// Marks a post URL replaced by its hash, `sha256:<hex>`
+pub(crate) const PSEUDONYM_PREFIX: &str = "sha256:";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Campaign {
+    pub address: String,
+    pub creator: String,
+    pub seeds: String,
+    pub keywords: Vec<String>,
+    /// `PLATFORM_*` id
+    pub platform: u8,
+    pub reward_amount: u64,
+    pub max_claimers: u64,
+    pub claimers_count: u64,
+    pub total_paid: u64,
+    pub active: bool,
+    /// The creator closed the campaign and the account is gone
+    pub closed: bool,
+    pub created_slot: u64,
+}
+
+/// Where a verifier's latest request on a campaign stands, as far as its log tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// Waiting for its callback, or expired without one; the log can't tell which
+    Pending,
+    Verified,
+    Failed,
+}
+
+impl VerificationStatus {
+    pub(crate) fn of(log: &PostVerificationLog) -> Self {
+        match (log.current_execution_account, log.is_verified) {
+            (Some(_), _) => Self::Pending,
+            (None, true) => Self::Verified,
+            (None, false) => Self::Failed,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Verified => "verified",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub(crate) fn parse(status: &str) -> Self {
+        match status {
+            "verified" => Self::Verified,
+            "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verification {
+    /// Address of the verification log
+    pub address: String,
+    pub campaign: String,
+    pub verifier: String,
+    pub post_url: String,
+    pub status: VerificationStatus,
+    /// `REASON_*` code of a failed verification
+    pub failure_reason: u16,
+    pub failed_checks: u32,
+    pub author_did_hash: String,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Payout {
+    /// Transaction the callback paid in
+    pub signature: String,
+    pub campaign: String,
+    pub verifier: String,
+    /// Where the claimer's share went
+    pub payout: String,
+    pub author_did_hash: String,
+    pub amount: u64,
+    pub claimer_share: u64,
+    pub creator_share: u64,
+    pub platform_share: u64,
+    pub referrer: Option<String>,
+    pub referrer_share: u64,
+    pub slot: u64,
+}
+
+/// Which campaigns [`Storage::campaigns`] returns; unset fields match any
+#[derive(Debug, Clone, Default)]
+pub struct CampaignFilter {
+    pub creator: Option<Pubkey>,
+    pub active: Option<bool>,
+    /// `PLATFORM_*` id
+    pub platform: Option<u8>,
+}
+
+/// A window into a listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub campaigns: u64,
+    pub active_campaigns: u64,
+    /// Verification logs, one per verifier and campaign
+    pub verifications: u64,
+    pub verified: u64,
+    pub failed: u64,
+    pub pending: u64,
+    /// Distinct verifiers
+    pub verifiers: u64,
+    pub payouts: u64,
+    /// Lamports paid out, all shares included
+    pub total_paid: u64,
+}
+
+/// What [`Storage::apply_retention`] does with a post URL past its retention period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RetentionMode {
+    /// Replaces it with `sha256:<hex>` of the URL, so it can still be matched
+    Pseudonymize,
+    /// Blanks it
+    Purge,
+}
+
+/// How long post URLs are kept once their verification settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    pub max_age_secs: i64,
+    pub mode: RetentionMode,
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Sqlite(rusqlite::Error),
+    #[cfg(feature = "postgres")]
+    Postgres(postgres::Error),
+    /// Migrating the database to the current schema failed
+    Migration(refinery::Error),
+    /// The database URL names a backend this build leaves out
+    Unsupported(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sqlite(e) => write!(f, "sqlite: {e}"),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(e) => write!(f, "postgres: {e}"),
+            Self::Migration(e) => write!(f, "migrating: {e}"),
+            Self::Unsupported(url) => write!(f, "no storage backend for {url} in this build"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Sqlite(e)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for StoreError {
+    fn from(e: postgres::Error) -> Self {
+        Self::Postgres(e)
+    }
+}
+
+impl From<StoreError> for PopError {
+    fn from(e: StoreError) -> Self {
+        Self::Storage(Box::new(e))
+    }
+}
+
+impl From<refinery::Error> for StoreError {
+    fn from(e: refinery::Error) -> Self {
+        Self::Migration(e)
+    }
+}
+
+/// The indexer's database. Backends keep the same ordering and upsert rules, so the
+/// indexer and the REST layer behave the same on any of them.
+pub trait Storage: Send {
+    /// Records `config` as the campaign's state at `slot`
+    fn put_campaign(
+        &self,
+        address: &Pubkey,
+        config: &PostProofConfig,
+        slot: u64,
+    ) -> Result<(), StoreError>;
+
+    /// Marks the campaign closed. Its row stays, with the state it had last.
+    fn close_campaign(&self, address: &Pubkey) -> Result<(), StoreError>;
+
+    /// Records `log` as the verification log's state at `slot`
+    fn put_verification(
+        &self,
+        address: &Pubkey,
+        log: &PostVerificationLog,
+        slot: u64,
+    ) -> Result<(), StoreError>;
+
+    /// Pseudonymizes or purges the post URLs of verifications settled more than
+    /// `retention.max_age_secs` before `now`, returning how many changed. Pending
+    /// requests keep theirs until they settle. Re-reading a log whose verification
+    /// hasn't changed since doesn't bring its URL back.
+    fn apply_retention(&self, retention: Retention, now: i64) -> Result<usize, StoreError>;
+
+    /// Records the payout event at `index` among the program's events in transaction
+    /// `signature`. Recording it again is a no-op.
+    fn put_payout(
+        &self,
+        signature: &str,
+        index: usize,
+        event: &RewardPaid,
+    ) -> Result<(), StoreError>;
+
+    /// Newest transaction whose events are indexed
+    fn cursor(&self) -> Result<Option<String>, StoreError>;
+
+    fn set_cursor(&self, signature: &str) -> Result<(), StoreError>;
+
+    /// Campaigns matching `filter`, newest first
+    fn campaigns(&self, filter: &CampaignFilter, page: Page) -> Result<Vec<Campaign>, StoreError>;
+
+    fn campaign(&self, address: &Pubkey) -> Result<Option<Campaign>, StoreError>;
+
+    /// Every verifier's latest request on `campaign`, newest first, optionally only
+    /// those in `status`
+    fn verifications(
+        &self,
+        campaign: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError>;
+
+    /// `verifier`'s latest request on each campaign, newest first, optionally only
+    /// those in `status`
+    fn verifier_history(
+        &self,
+        verifier: &Pubkey,
+        status: Option<VerificationStatus>,
+        page: Page,
+    ) -> Result<Vec<Verification>, StoreError>;
+
+    /// Payouts from `campaign`, newest first
+    fn payouts(&self, campaign: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError>;
+
+    /// Payouts to `verifier`, newest first
+    fn verifier_payouts(&self, verifier: &Pubkey, page: Page) -> Result<Vec<Payout>, StoreError>;
+
+    /// Records a verification request, for timing its callback
+    fn put_request(&self, event: &VerificationRequested) -> Result<(), StoreError>;
+
+    /// Marks `verifier`'s open request on `campaign` completed at `slot`, returning the
+    /// slot it was requested at, or `None` if no request was open, e.g. because this
+    /// completion was recorded already
+    fn complete_request(
+        &self,
+        campaign: &Pubkey,
+        verifier: &Pubkey,
+        slot: u64,
+    ) -> Result<Option<u64>, StoreError>;
+
+    /// Marks open requests past their expiry at `slot` expired, returning how many
+    /// newly expired
+    fn expire_requests(&self, slot: u64) -> Result<usize, StoreError>;
+
+    /// Registers `url` for `creator`'s campaigns, returning its id, or `None` if it is
+    /// registered already; the secret it was registered with stays
+    fn add_webhook(
+        &self,
+        creator: &Pubkey,
+        url: &str,
+        secret: &str,
+    ) -> Result<Option<i64>, StoreError>;
+
+    /// Removes `creator`'s webhook `id`, returning whether there was one
+    fn remove_webhook(&self, creator: &Pubkey, id: i64) -> Result<bool, StoreError>;
+
+    fn webhooks(&self, creator: &Pubkey) -> Result<Vec<Webhook>, StoreError>;
+
+    /// Totals across everything indexed
+    fn stats(&self) -> Result<Stats, StoreError>;
+
+    /// Totals for `campaign` alone
+    fn campaign_stats(&self, campaign: &Pubkey) -> Result<Stats, StoreError>;
+}
+
+/// Opens the storage `url` names: a `postgres://` or `postgresql://` URL with the
+/// `postgres` feature, `:memory:` for a [`MemoryStore`], and otherwise the path of a
+/// SQLite database, created if missing
+pub fn open_store(url: &str) -> Result<Box<dyn Storage>, StoreError> {
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Box::new(PostgresStore::connect(url)?));
+        #[cfg(not(feature = "postgres"))]
+        return Err(StoreError::Unsupported(String::from("postgres")));
+    }
+    if url == ":memory:" {
+        return Ok(Box::new(MemoryStore::new()));
+    }
+    Ok(Box::new(SqliteStore::open(url)?))
+}
+
+pub(crate) fn pseudonym(post_url: &str) -> String {
+    format!(
+        "{PSEUDONYM_PREFIX}{}",
+        hex(&Sha256::digest(post_url.as_bytes()))
+    )
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryStore, SqliteStore};
+
+    /// Every backend that runs without a server
+    fn backends() -> Vec<Box<dyn Storage>> {
+        vec![
+            Box::new(SqliteStore::open_in_memory().unwrap()),
+            Box::new(MemoryStore::new()),
+        ]
+    }
+
+    fn log(is_verified: bool, slot: u64) -> PostVerificationLog {
+        PostVerificationLog {
+            verifier: Pubkey::new_from_array([1u8; 32]),
+            config: Pubkey::new_from_array([2u8; 32]),
+            post_url: String::from("https://bsky.app/profile/bonsol.bsky.social/post/1"),
+            slot,
+            is_verified,
+            current_execution_account: None,
+            referrer: None,
+            timestamp: 0,
+            input_hash: None,
+            payout: None,
+            author_did_hash: [3u8; 32],
+            terms_signature: None,
+            failure_reason: 0,
+            failed_checks: 0,
+            bump: 0,
+            version: PostVerificationLog::VERSION,
+            held_reward: 0,
+            held_segment: 0,
+            claimable_slot: 0,
+            challenger: None,
+            challenge_bond: 0,
+            requested_slot: 0,
+        }
+    }
+
+    #[test]
+    fn older_state_does_not_overwrite_newer() {
+        for store in backends() {
+            let address = Pubkey::new_unique();
+            store
+                .put_verification(&address, &log(true, 100), 100)
+                .unwrap();
+            store
+                .put_verification(&address, &log(false, 90), 90)
+                .unwrap();
+
+            let campaign = Pubkey::new_from_array([2u8; 32]);
+            let verifications = store
+                .verifications(&campaign, None, Page::default())
+                .unwrap();
+            assert_eq!(verifications.len(), 1);
+            assert_eq!(verifications[0].status, VerificationStatus::Verified);
+            assert_eq!(verifications[0].slot, 100);
+            assert_eq!(verifications[0].author_did_hash, "03".repeat(32));
+            let pending = store
+                .verifications(
+                    &campaign,
+                    Some(VerificationStatus::Pending),
+                    Page::default(),
+                )
+                .unwrap();
+            assert!(pending.is_empty());
+        }
+    }
+
+    #[test]
+    fn retention_pseudonymizes_settled_post_urls() {
+        for store in backends() {
+            let retention = Retention {
+                max_age_secs: 100,
+                mode: RetentionMode::Pseudonymize,
+            };
+            let (settled, pending) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let settled_log = PostVerificationLog {
+                timestamp: 1_000,
+                ..log(true, 100)
+            };
+            store.put_verification(&settled, &settled_log, 100).unwrap();
+            let pending_log = PostVerificationLog {
+                current_execution_account: Some(Pubkey::new_unique()),
+                ..log(false, 100)
+            };
+            store.put_verification(&pending, &pending_log, 100).unwrap();
+
+            assert_eq!(store.apply_retention(retention, 1_100).unwrap(), 0);
+            assert_eq!(store.apply_retention(retention, 1_101).unwrap(), 1);
+            let campaign = Pubkey::new_from_array([2u8; 32]);
+            let post_url = |address: &Pubkey| {
+                store
+                    .verifications(&campaign, None, Page::default())
+                    .unwrap()
+                    .into_iter()
+                    .find(|verification| verification.address == address.to_string())
+                    .unwrap()
+                    .post_url
+            };
+            assert_eq!(post_url(&settled), pseudonym(&settled_log.post_url));
+            assert_eq!(post_url(&pending), pending_log.post_url);
+            assert_eq!(store.stats().unwrap().verified, 1);
+
+            // Reading the same log again doesn't restore it, a new request does
+            store.put_verification(&settled, &settled_log, 200).unwrap();
+            assert_eq!(post_url(&settled), pseudonym(&settled_log.post_url));
+            let retried = PostVerificationLog {
+                current_execution_account: Some(Pubkey::new_unique()),
+                ..settled_log.clone()
+            };
+            store.put_verification(&settled, &retried, 300).unwrap();
+            assert_eq!(post_url(&settled), settled_log.post_url);
+        }
+    }
+
+    #[test]
+    fn retention_can_purge() {
+        for store in backends() {
+            let address = Pubkey::new_unique();
+            let settled = PostVerificationLog {
+                timestamp: 1_000,
+                ..log(false, 100)
+            };
+            store.put_verification(&address, &settled, 100).unwrap();
+            let retention = Retention {
+                max_age_secs: 0,
+                mode: RetentionMode::Purge,
+            };
+            assert_eq!(store.apply_retention(retention, 2_000).unwrap(), 1);
+            assert_eq!(store.apply_retention(retention, 2_000).unwrap(), 0);
+            let verifications = store
+                .verifier_history(&settled.verifier, None, Page::default())
+                .unwrap();
+            assert_eq!(verifications[0].post_url, "");
+            assert_eq!(verifications[0].author_did_hash, "03".repeat(32));
+        }
+    }
+
+    #[test]
+    fn store_errors_keep_their_source_in_the_shared_error() {
+        let e = PopError::from(StoreError::Unsupported(String::from("postgres")));
+        assert!(matches!(e, PopError::Storage(_)));
+        let source = std::error::Error::source(&e).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "no storage backend for postgres in this build"
+        );
+    }
+
+    #[test]
+    fn payouts_are_recorded_once() {
+        for store in backends() {
+            let event = RewardPaid {
+                config: Pubkey::new_unique(),
+                verifier: Pubkey::new_unique(),
+                payout: Pubkey::new_unique(),
+                author_did_hash: [0u8; 32],
+                amount: 1_000,
+                claimer_share: 900,
+                creator_share: 100,
+                platform_share: 0,
+                referrer: None,
+                referrer_share: 0,
+                slot: 5,
+            };
+            store.put_payout("sig", 0, &event).unwrap();
+            store.put_payout("sig", 0, &event).unwrap();
+
+            let payouts = store.payouts(&event.config, Page::default()).unwrap();
+            assert_eq!(payouts.len(), 1);
+            assert_eq!(payouts[0].amount, 1_000);
+            assert_eq!(payouts[0].referrer, None);
+            assert_eq!(
+                store
+                    .verifier_payouts(&event.verifier, Page::default())
+                    .unwrap(),
+                payouts
+            );
+            let stats = store.stats().unwrap();
+            assert_eq!((stats.payouts, stats.total_paid), (1, 1_000));
+            let stats = store.campaign_stats(&event.config).unwrap();
+            assert_eq!((stats.payouts, stats.total_paid), (1, 1_000));
+            let stats = store.campaign_stats(&Pubkey::new_unique()).unwrap();
+            assert_eq!((stats.payouts, stats.total_paid), (0, 0));
+        }
+    }
+
+    #[test]
+    fn webhooks_belong_to_their_creator() {
+        for store in backends() {
+            let (creator, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let id = store
+                .add_webhook(&creator, "https://example.com/hook", "secret")
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                store
+                    .add_webhook(&creator, "https://example.com/hook", "new")
+                    .unwrap(),
+                None
+            );
+            assert_eq!(store.webhooks(&creator).unwrap()[0].secret, "secret");
+
+            assert!(!store.remove_webhook(&other, id).unwrap());
+            assert!(store.remove_webhook(&creator, id).unwrap());
+            assert!(store.webhooks(&creator).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn requests_complete_once_or_expire() {
+        for store in backends() {
+            let request = VerificationRequested {
+                config: Pubkey::new_unique(),
+                verifier: Pubkey::new_unique(),
+                execution_request: Pubkey::new_unique(),
+                slot: 100,
+                expiry_slot: 50_100,
+            };
+            store.put_request(&request).unwrap();
+            let complete = || store.complete_request(&request.config, &request.verifier, 160);
+            assert_eq!(complete().unwrap(), Some(100));
+            assert_eq!(complete().unwrap(), None);
+
+            let stale = VerificationRequested {
+                execution_request: Pubkey::new_unique(),
+                verifier: Pubkey::new_unique(),
+                ..request
+            };
+            store.put_request(&stale).unwrap();
+            assert_eq!(store.expire_requests(50_100).unwrap(), 0);
+            assert_eq!(store.expire_requests(50_101).unwrap(), 1);
+            assert_eq!(store.expire_requests(60_000).unwrap(), 0);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Webhooks campaign creators register to hear about their campaigns' verifications
+//! and exhaustion as the indexer sees them.
+//!
+//! Each delivery is a JSON POST carrying `X-Pop-Timestamp` (unix seconds) and
+//! `X-Pop-Signature: sha256=<hex>`, the HMAC-SHA256 of `<timestamp>.<body>` under the
+//! secret handed out at registration. Receivers should recompute it and reject stale
+//! timestamps.
+//!
+//! Registering and removing a webhook is authorized by the creator's wallet signing
+//! [`registration_message`] or [`removal_message`].
+
+use anchor_lang::prelude::Pubkey;
+use hmac::{Hmac, Mac};
+use proof_of_post::{CampaignExhausted, VerificationCompleted, EXHAUSTED_MAX_CLAIMERS};
+use rand::distributions::{Alphanumeric, DistString};
+use serde_json::json;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+pub const TIMESTAMP_HEADER: &str = "X-Pop-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Pop-Signature";
+/// How far a registration's timestamp may be from the server's clock, in seconds
+pub const REGISTRATION_WINDOW_SECS: u64 = 300;
+/// Attempts per delivery, doubling the wait between them from one second
+const ATTEMPTS: u32 = 4;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What the creator signs to register `url`, at unix time `timestamp`
+pub fn registration_message(url: &str, timestamp: u64) -> Vec<u8> {
+    format!("proof-of-post webhook registration:{timestamp}:{url}").into_bytes()
+}
+
+/// What the creator signs to remove the webhook with `id`
+pub fn removal_message(id: i64) -> Vec<u8> {
+    format!("proof-of-post webhook removal:{id}").into_bytes()
+}
+
+/// A new random secret for signing deliveries
+pub fn new_secret() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), 32)
+}
+
+/// `X-Pop-Signature` of `body` sent at `timestamp`
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes keys of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    format!("sha256={digest}")
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// The body sent for a completed verification in transaction `signature`
+pub fn verification_completed(event: &VerificationCompleted, signature: &str) -> String {
+    json!({
+        "event": "verification_completed",
+        "campaign": event.config.to_string(),
+        "verifier": event.verifier.to_string(),
+        "post_url": event.post_url,
+        "passed": event.passed,
+        "reason_code": event.reason_code,
+        "failed_checks": event.failed_checks,
+        "slot": event.slot,
+        "signature": signature,
+    })
+    .to_string()
+}
+
+/// The body sent for an exhausted campaign in transaction `signature`
+pub fn campaign_exhausted(event: &CampaignExhausted, signature: &str) -> String {
+    let reason = if event.reason == EXHAUSTED_MAX_CLAIMERS {
+        "max_claimers"
+    } else {
+        "budget"
+    };
+    json!({
+        "event": "campaign_exhausted",
+        "campaign": event.config.to_string(),
+        "reason": reason,
+        "claimers_count": event.claimers_count,
+        "remaining": event.remaining,
+        "signature": signature,
+    })
+    .to_string()
+}
+
+/// A registered endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: i64,
+    pub creator: Pubkey,
+    pub url: String,
+    pub secret: String,
+}
+
+struct Delivery {
+    url: String,
+    secret: String,
+    body: String,
+}
+
+/// Sends deliveries from a background task, so a slow endpoint never holds up
+/// indexing. Deliveries still queued are lost when the process exits.
+pub struct Dispatcher {
+    tx: mpsc::UnboundedSender<Delivery>,
+}
+
+impl Dispatcher {
+    /// Needs a Tokio runtime
+    pub fn spawn() -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Delivery>();
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("building the http client");
+        tokio::spawn(async move {
+            while let Some(delivery) = rx.recv().await {
+                // Each delivery retries on its own, so one dead endpoint doesn't queue
+                // everyone else's behind its backoff
+                tokio::spawn(deliver(http.clone(), delivery));
+            }
+        });
+        Self { tx }
+    }
+
+    pub fn send(&self, webhook: &Webhook, body: String) {
+        let _ = self.tx.send(Delivery {
+            url: webhook.url.clone(),
+            secret: webhook.secret.clone(),
+            body,
+        });
+    }
+}
+
+async fn deliver(http: reqwest::Client, delivery: Delivery) {
+    let mut wait = Duration::from_secs(1);
+    for attempt in 1..=ATTEMPTS {
+        let timestamp = now();
+        let result = http
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                sign(&delivery.secret, timestamp, &delivery.body),
+            )
+            .body(delivery.body.clone())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => return,
+            Err(e) if attempt == ATTEMPTS => {
+                tracing::warn!(url = %delivery.url, error = %e, "webhook failed, giving up");
+            }
+            Err(_) => {
+                tokio::time::sleep(wait).await;
+                wait *= 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", 1_700_000_000, r#"{"event":"x"}"#);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("secret", 1_700_000_000, r#"{"event":"x"}"#));
+        assert_ne!(signature, sign("secret", 1_700_000_001, r#"{"event":"x"}"#));
+        assert_ne!(signature, sign("secret", 1_700_000_000, r#"{"event":"y"}"#));
+        assert_ne!(signature, sign("other", 1_700_000_000, r#"{"event":"x"}"#));
+    }
+}
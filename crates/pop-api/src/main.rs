@@ -0,0 +1,554 @@
+//! Serves the indexer's database as a JSON API, so web frontends integrate without RPC
+//! access. Run it on the database the indexer writes: next to it on the same SQLite
+//! file, or anywhere for a `postgres://` URL in builds with the `postgres` feature.
+//!
+//! `pop-api --database proof-of-post.db --listen 0.0.0.0:8080`
+//!
+//! - `GET /campaigns?creator=&active=&platform=`
+//! - `GET /campaigns/:id`
+//! - `GET /campaigns/:id/verifications?status=`
+//! - `GET /campaigns/:id/claims`, the campaign's payouts
+//! - `GET /verifiers/:pubkey/history?status=`
+//! - `GET /verifiers/:pubkey/payouts`
+//! - `GET /stats`
+//! - `POST /webhooks` with `{"creator", "url", "timestamp", "signature"}`
+//! - `DELETE /webhooks/:id` with `{"creator", "signature"}`
+//! - `GET /health`, 200 while the database answers
+//! - `GET /metrics`, Prometheus text: requests served by route and status, and the
+//!   database's totals
+//!
+//! Requests are logged in `tracing` spans naming their route, and a campaign's or
+//! verifier's routes carry its `config` or `verifier` address; `RUST_LOG` sets the
+//! verbosity, `info` by default.
+//!
+//! Listings come newest first as `{"items": [...], "next_offset": n}` and take `limit`
+//! and `offset`; `next_offset` is left out on the last page. `status` is one of
+//! `pending`, `verified` or `failed`.
+//!
+//! Webhook calls are authorized by the creator's wallet: `signature` is its base58
+//! signature over `indexer::webhooks::registration_message(url, timestamp)` or
+//! `removal_message(id)`, and registrations must be timestamped within five minutes.
+//! Registering returns the webhook's `id` and the `secret` its deliveries are signed
+//! with; the URL must be https.
+
+use axum::extract::{MatchedPath, Path, Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Extension, Json, Router};
+use clap::Parser;
+use indexer::{
+    open_store, webhooks, Campaign, CampaignFilter, Page, Stats, Storage, StoreError,
+    VerificationStatus,
+};
+use prometheus::{Encoder, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::error::Error;
+use std::sync::{Arc, Mutex, PoisonError};
+use tower_http::cors::CorsLayer;
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+#[derive(Parser)]
+#[command(name = "pop-api", version, about)]
+struct Cli {
+    /// Database the indexer writes: a SQLite path or a `postgres://` URL
+    #[arg(long, env = "POP_DATABASE", default_value = "proof-of-post.db")]
+    database: String,
+    #[arg(long, env = "POP_API_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: String,
+}
+
+type Db = Arc<Mutex<Box<dyn Storage>>>;
+
+/// What `/metrics` reports. The gauges are refreshed from the database on each scrape.
+struct Metrics {
+    registry: Registry,
+    /// By `route` pattern and `status` code
+    requests: IntCounterVec,
+    campaigns: IntGaugeVec,
+    verifications: IntGaugeVec,
+    paid_lamports: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let requests = IntCounterVec::new(
+            Opts::new("pop_api_requests_total", "Requests served"),
+            &["route", "status"],
+        )
+        .unwrap();
+        let campaigns =
+            IntGaugeVec::new(Opts::new("pop_campaigns", "Indexed campaigns"), &["state"]).unwrap();
+        let verifications = IntGaugeVec::new(
+            Opts::new("pop_verifications", "Indexed verification logs"),
+            &["status"],
+        )
+        .unwrap();
+        let paid_lamports = IntGauge::new(
+            "pop_paid_lamports",
+            "Lamports paid out, all shares included",
+        )
+        .unwrap();
+        registry.register(Box::new(requests.clone())).unwrap();
+        registry.register(Box::new(campaigns.clone())).unwrap();
+        registry.register(Box::new(verifications.clone())).unwrap();
+        registry.register(Box::new(paid_lamports.clone())).unwrap();
+        Self {
+            registry,
+            requests,
+            campaigns,
+            verifications,
+            paid_lamports,
+        }
+    }
+
+    fn update(&self, stats: &Stats) {
+        let active = stats.active_campaigns as i64;
+        self.campaigns.with_label_values(&["active"]).set(active);
+        self.campaigns
+            .with_label_values(&["closed"])
+            .set(stats.campaigns as i64 - active);
+        for (status, count) in [
+            ("pending", stats.pending),
+            ("verified", stats.verified),
+            ("failed", stats.failed),
+        ] {
+            self.verifications
+                .with_label_values(&[status])
+                .set(count as i64);
+        }
+        self.paid_lamports.set(stats.total_paid as i64);
+    }
+}
+
+enum ApiError {
+    BadRequest(String),
+    /// The signature doesn't check out against the claimed creator
+    Unauthorized,
+    NotFound,
+    Conflict(String),
+    Internal,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, String::from("bad signature")),
+            Self::NotFound => (StatusCode::NOT_FOUND, String::from("not found")),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message),
+            Self::Internal => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("internal error"),
+            ),
+        };
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct Listing<T> {
+    items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<u32>,
+}
+
+impl<T> Listing<T> {
+    fn new(items: Vec<T>, page: Page) -> Json<Self> {
+        let next_offset = (items.len() == page.limit as usize).then(|| page.offset + page.limit);
+        Json(Self { items, next_offset })
+    }
+}
+
+// The query structs repeat `limit` and `offset`: serde's flatten would hand them over
+// as strings, which don't parse as numbers from a query string
+
+#[derive(Deserialize)]
+struct PageQuery {
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct CampaignsQuery {
+    creator: Option<String>,
+    active: Option<bool>,
+    platform: Option<u8>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct VerificationsQuery {
+    status: Option<VerificationStatus>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct Registration {
+    creator: String,
+    url: String,
+    timestamp: u64,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct Removal {
+    creator: String,
+    signature: String,
+}
+
+fn page(limit: Option<u32>, offset: Option<u32>) -> Page {
+    Page {
+        limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        offset: offset.unwrap_or(0),
+    }
+}
+
+fn pubkey(value: &str) -> Result<Pubkey, ApiError> {
+    value
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("invalid address {value:?}")))
+}
+
+/// Checks `signature`, base58, is `signer`'s over `message`
+fn verify_signature(signer: &Pubkey, signature: &str, message: &[u8]) -> Result<(), ApiError> {
+    let signature: Signature = signature
+        .parse()
+        .map_err(|_| ApiError::BadRequest(String::from("invalid signature")))?;
+    if !signature.verify(signer.as_ref(), message) {
+        return Err(ApiError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Runs `f` on the store off the async workers, as database calls block
+async fn query<T: Send + 'static>(
+    db: &Db,
+    f: impl FnOnce(&dyn Storage) -> Result<T, StoreError> + Send + 'static,
+) -> Result<T, ApiError> {
+    let db = db.clone();
+    tokio::task::spawn_blocking(move || f(&**db.lock().unwrap_or_else(PoisonError::into_inner)))
+        .await
+        .map_err(|_| ApiError::Internal)?
+        .map_err(|e| {
+            tracing::error!(error = %e, "query failed");
+            ApiError::Internal
+        })
+}
+
+async fn campaigns(
+    State(db): State<Db>,
+    Query(params): Query<CampaignsQuery>,
+) -> Result<Json<Listing<Campaign>>, ApiError> {
+    let filter = CampaignFilter {
+        creator: params.creator.as_deref().map(pubkey).transpose()?,
+        active: params.active,
+        platform: params.platform,
+    };
+    let page = page(params.limit, params.offset);
+    let items = query(&db, move |store| store.campaigns(&filter, page)).await?;
+    Ok(Listing::new(items, page))
+}
+
+#[tracing::instrument(skip_all, fields(config = %id))]
+async fn campaign(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+) -> Result<Json<Campaign>, ApiError> {
+    let address = pubkey(&id)?;
+    query(&db, move |store| store.campaign(&address))
+        .await?
+        .map(Json)
+        .ok_or(ApiError::NotFound)
+}
+
+#[tracing::instrument(skip_all, fields(config = %id))]
+async fn campaign_verifications(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    Query(params): Query<VerificationsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let address = pubkey(&id)?;
+    let page = page(params.limit, params.offset);
+    let items = query(&db, move |store| {
+        store.verifications(&address, params.status, page)
+    })
+    .await?;
+    Ok(Listing::new(items, page))
+}
+
+#[tracing::instrument(skip_all, fields(config = %id))]
+async fn campaign_claims(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    Query(params): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let address = pubkey(&id)?;
+    let page = page(params.limit, params.offset);
+    let items = query(&db, move |store| store.payouts(&address, page)).await?;
+    Ok(Listing::new(items, page))
+}
+
+#[tracing::instrument(skip_all, fields(verifier = %verifier))]
+async fn verifier_history(
+    State(db): State<Db>,
+    Path(verifier): Path<String>,
+    Query(params): Query<VerificationsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let verifier = pubkey(&verifier)?;
+    let page = page(params.limit, params.offset);
+    let items = query(&db, move |store| {
+        store.verifier_history(&verifier, params.status, page)
+    })
+    .await?;
+    Ok(Listing::new(items, page))
+}
+
+#[tracing::instrument(skip_all, fields(verifier = %verifier))]
+async fn verifier_payouts(
+    State(db): State<Db>,
+    Path(verifier): Path<String>,
+    Query(params): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let verifier = pubkey(&verifier)?;
+    let page = page(params.limit, params.offset);
+    let items = query(&db, move |store| store.verifier_payouts(&verifier, page)).await?;
+    Ok(Listing::new(items, page))
+}
+
+async fn stats(State(db): State<Db>) -> Result<Json<Stats>, ApiError> {
+    query(&db, |store| store.stats()).await.map(Json)
+}
+
+async fn health(State(db): State<Db>) -> Result<StatusCode, ApiError> {
+    query(&db, |store| store.cursor())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+async fn export_metrics(
+    State(db): State<Db>,
+    Extension(metrics): Extension<Arc<Metrics>>,
+) -> Result<String, ApiError> {
+    metrics.update(&query(&db, |store| store.stats()).await?);
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metrics.registry.gather(), &mut buffer)
+        .map_err(|_| ApiError::Internal)?;
+    String::from_utf8(buffer).map_err(|_| ApiError::Internal)
+}
+
+/// Counts each request under its route pattern, so ids don't each get a series, and
+/// handles it in a span naming the route
+async fn count_requests(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request.extensions().get::<MatchedPath>().map_or_else(
+        || String::from("unmatched"),
+        |path| path.as_str().to_owned(),
+    );
+    let span = tracing::info_span!("request", method = %request.method(), route = %route);
+    let response = next.run(request).instrument(span).await;
+    tracing::debug!(%route, status = %response.status(), "served");
+    metrics
+        .requests
+        .with_label_values(&[&route, response.status().as_str()])
+        .inc();
+    response
+}
+
+async fn add_webhook(
+    State(db): State<Db>,
+    Json(registration): Json<Registration>,
+) -> Result<impl IntoResponse, ApiError> {
+    let creator = pubkey(&registration.creator)?;
+    if !registration.url.starts_with("https://") {
+        return Err(ApiError::BadRequest(String::from(
+            "webhook url must be https",
+        )));
+    }
+    if webhooks::now().abs_diff(registration.timestamp) > webhooks::REGISTRATION_WINDOW_SECS {
+        return Err(ApiError::BadRequest(String::from("stale timestamp")));
+    }
+    let message = webhooks::registration_message(&registration.url, registration.timestamp);
+    verify_signature(&creator, &registration.signature, &message)?;
+
+    let secret = webhooks::new_secret();
+    let (url, stored_secret) = (registration.url, secret.clone());
+    let id = query(&db, move |store| {
+        store.add_webhook(&creator, &url, &stored_secret)
+    })
+    .await?
+    .ok_or_else(|| ApiError::Conflict(String::from("webhook already registered")))?;
+    let body = serde_json::json!({ "id": id, "secret": secret });
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+async fn remove_webhook(
+    State(db): State<Db>,
+    Path(id): Path<i64>,
+    Json(removal): Json<Removal>,
+) -> Result<StatusCode, ApiError> {
+    let creator = pubkey(&removal.creator)?;
+    verify_signature(&creator, &removal.signature, &webhooks::removal_message(id))?;
+    if query(&db, move |store| store.remove_webhook(&creator, id)).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+fn router(db: Db) -> Router {
+    let metrics = Arc::new(Metrics::new());
+    Router::new()
+        .route("/campaigns", get(campaigns))
+        .route("/campaigns/:id", get(campaign))
+        .route("/campaigns/:id/verifications", get(campaign_verifications))
+        .route("/campaigns/:id/claims", get(campaign_claims))
+        .route("/verifiers/:pubkey/history", get(verifier_history))
+        .route("/verifiers/:pubkey/payouts", get(verifier_payouts))
+        .route("/stats", get(stats))
+        .route("/webhooks", post(add_webhook))
+        .route("/webhooks/:id", delete(remove_webhook))
+        .route("/health", get(health))
+        .route("/metrics", get(export_metrics))
+        .layer(middleware::from_fn_with_state(
+            metrics.clone(),
+            count_requests,
+        ))
+        .layer(Extension(metrics))
+        // Everything served is public on-chain data, and writes are signed
+        .layer(CorsLayer::permissive())
+        .with_state(db)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    let store = open_store(&cli.database).map_err(|e| format!("opening {}: {e}", cli.database))?;
+    let listener = tokio::net::TcpListener::bind(&cli.listen).await?;
+    tracing::info!(listen = %cli.listen, "serving");
+    axum::serve(listener, router(Arc::new(Mutex::new(store)))).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn db() -> Db {
+        let store: Box<dyn Storage> = Box::new(indexer::SqliteStore::open_in_memory().unwrap());
+        Arc::new(Mutex::new(store))
+    }
+
+    async fn call(db: &Db, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = router(db.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    async fn get_json(path: &str) -> (StatusCode, serde_json::Value) {
+        call(&db(), Request::get(path).body(Body::empty()).unwrap()).await
+    }
+
+    fn post_json(path: &str, body: serde_json::Value) -> Request<Body> {
+        Request::post(path)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn listings_page_and_reject_bad_input() {
+        let (status, body) = get_json("/campaigns?active=true&limit=1000").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({ "items": [] }));
+
+        let (status, _) = get_json("/verifiers/not-a-key/history").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(&format!("/campaigns/{}", Pubkey::new_unique())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = get_json("/stats").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["campaigns"], 0);
+    }
+
+    #[tokio::test]
+    async fn metrics_count_requests_by_route() {
+        let db = db();
+        let router = router(db.clone());
+        for path in ["/health", "/campaigns/not-a-key", "/metrics"] {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            router.clone().oneshot(request).await.unwrap();
+        }
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains(r#"pop_api_requests_total{route="/health",status="200"} 1"#));
+        assert!(text.contains(r#"pop_api_requests_total{route="/campaigns/:id",status="400"} 1"#));
+        assert!(text.contains(r#"pop_campaigns{state="active"} 0"#));
+    }
+
+    #[tokio::test]
+    async fn webhooks_need_the_creators_signature() {
+        use solana_sdk::signature::{Keypair, Signer};
+
+        let db = db();
+        let creator = Keypair::new();
+        let url = "https://example.com/hook";
+        let timestamp = webhooks::now();
+        let message = webhooks::registration_message(url, timestamp);
+        let registration = |signer: &Keypair| {
+            serde_json::json!({
+                "creator": creator.pubkey().to_string(),
+                "url": url,
+                "timestamp": timestamp,
+                "signature": signer.sign_message(&message).to_string(),
+            })
+        };
+
+        let forged = post_json("/webhooks", registration(&Keypair::new()));
+        assert_eq!(call(&db, forged).await.0, StatusCode::UNAUTHORIZED);
+        let (status, body) = call(&db, post_json("/webhooks", registration(&creator))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["secret"].as_str().unwrap().len(), 32);
+        let again = post_json("/webhooks", registration(&creator));
+        assert_eq!(call(&db, again).await.0, StatusCode::CONFLICT);
+
+        let id = body["id"].as_i64().unwrap();
+        let removal = serde_json::json!({
+            "creator": creator.pubkey().to_string(),
+            "signature": creator.sign_message(&webhooks::removal_message(id)).to_string(),
+        });
+        let request = Request::delete(format!("/webhooks/{id}"))
+            .header("content-type", "application/json")
+            .body(Body::from(removal.to_string()))
+            .unwrap();
+        assert_eq!(call(&db, request).await.0, StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,192 @@
+//! `dev prove`: the whole verify→callback flow on a local validator, with the guest run
+//! on this machine in dev mode instead of by a Bonsol prover. The validator runs the
+//! program and bonsol-mock at Bonsol's address:
+//!
+//! ```text
+//! cargo build-sbf --manifest-path programs/bonsol-mock/Cargo.toml
+//! solana-test-validator --reset \
+//!     --bpf-program BoNsHRcyLLNdtnoDf8hiCNZpyehMC4FDMxs6NTxFi3ew target/deploy/bonsol_mock.so \
+//!     --upgradeable-program 5MQLTq2D5ZhUAc6TDoAMXfnMeA32bo5DUxYco5LDMKAA \
+//!         target/deploy/proof_of_post.so ~/.config/solana/id.json
+//! pop-cli --url http://localhost:8899 dev prove --post-file post.json --keyword bonsol
+//! ```
+
+use bonsol_schema::root_as_execution_request_v1;
+use proof_of_post::{
+    InitProtocolArgs, JournalBounds, VerifyPostArgs, DEFAULT_EXECUTION_EXPIRY_SLOTS,
+    DEFAULT_SIGNATURE_FEE_LAMPORTS, DEFAULT_VERIFY_COOLDOWN_SECS, DEFAULT_VERIFY_COOLDOWN_SLOTS,
+};
+use proof_of_post_client::{instructions, TipMode, TipPolicy};
+use proof_of_post_common::{decode_public_input, ENCODING_IDENTITY};
+use prover_harness::{open_campaign, Harness, Request};
+use solana_sdk::signature::Signer;
+use std::error::Error;
+use std::path::PathBuf;
+
+use crate::{print_state, App, Platform};
+
+#[derive(clap::Subcommand)]
+pub enum DevCommand {
+    /// Verify a saved API response end to end against a fresh campaign
+    Prove(Prove),
+}
+
+#[derive(clap::Args)]
+pub struct Prove {
+    /// The platform API response for the post, as the prover would fetch it
+    #[arg(long)]
+    post_file: PathBuf,
+    /// Term the campaign requires; repeat for several
+    #[arg(long = "keyword", required = true)]
+    keywords: Vec<String>,
+    #[arg(long, value_enum, default_value_t = Platform::Bluesky)]
+    platform: Platform,
+    /// URL the request is made for; the response is never fetched from it
+    #[arg(long, default_value = "https://localhost/post.json")]
+    post_url: String,
+    /// Lamports the campaign pays for the post
+    #[arg(long, default_value_t = 1_000_000)]
+    reward: u64,
+}
+
+pub async fn run(app: &App, command: DevCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        DevCommand::Prove(prove) => self::prove(app, prove).await,
+    }
+}
+
+async fn prove(app: &App, prove: Prove) -> Result<(), Box<dyn Error>> {
+    let response = std::fs::read(&prove.post_file)
+        .map_err(|e| format!("reading {}: {e}", prove.post_file.display()))?;
+    let harness = Harness::from_env()?;
+    let program_id = *app.program_id();
+    let verifier = app.payer.pubkey();
+    let rpc = app.client.rpc();
+
+    let protocol = instructions::protocol_config_address(&program_id);
+    if rpc
+        .get_account_with_commitment(&protocol, rpc.commitment())
+        .await?
+        .value
+        .is_none()
+    {
+        println!("Initializing the protocol config");
+        let args = InitProtocolArgs {
+            feature_flags: 0,
+            guardian: verifier,
+            breaker_window_slots: 0,
+            breaker_max_velocity_multiple: 0,
+            breaker_min_norm_lamports: 0,
+            min_tip: 0,
+            max_tip: 0,
+            max_post_size: 0,
+            journal_bounds: JournalBounds::default(),
+            verify_cooldown_slots: DEFAULT_VERIFY_COOLDOWN_SLOTS,
+            verify_cooldown_secs: DEFAULT_VERIFY_COOLDOWN_SECS,
+            execution_expiry_slots: DEFAULT_EXECUTION_EXPIRY_SLOTS,
+            signature_fee_lamports: DEFAULT_SIGNATURE_FEE_LAMPORTS,
+        };
+        app.send(&[instructions::init_protocol(&program_id, &verifier, args)])
+            .await?;
+    }
+
+    // A fresh campaign per run, so the author's claim from an earlier run can't get in
+    // the way
+    let keywords: Vec<&str> = prove.keywords.iter().map(String::as_str).collect();
+    let seeds = format!("dev{}", rpc.get_slot().await? % 10_000_000);
+    let mut args = instructions::open_campaign_args(&seeds, &keywords, prove.reward, 1);
+    args.platform = prove.platform.id();
+    let campaign = instructions::config_address(&program_id, &verifier, &seeds);
+    app.send(&[
+        instructions::create_config(&program_id, &verifier, args),
+        instructions::top_up(&verifier, &campaign, prove.reward),
+    ])
+    .await?;
+    println!("Campaign {campaign}");
+
+    // The request declares the post's author up front; parse it from the response the
+    // way the guest will
+    let probe = Request::new(
+        open_campaign(prove.platform.id(), &keywords),
+        response.clone(),
+    );
+    let author_did_hash = post_verification_core::run(probe.stdin().as_slice())
+        .output
+        .author_did_hash;
+
+    let config = app.client.get_campaign(&campaign).await?;
+    let post_size = response.len() as u64;
+    let (current_req_id, nonce) = app
+        .client
+        .new_request_id(&verifier, &campaign, &prove.post_url)
+        .await?;
+    let tip = app
+        .client
+        .recommend_tip(post_size, TipMode::Conservative, &TipPolicy::default())
+        .await?;
+    let args = VerifyPostArgs {
+        current_req_id: current_req_id.clone(),
+        post_url: prove.post_url,
+        post_size,
+        tip,
+        referrer: None,
+        input_hash: None,
+        payout: None,
+        author_did_hash,
+        terms_signature: None,
+        response_signature: None,
+        response_encoding: ENCODING_IDENTITY,
+        request_nonce: Some(nonce),
+    };
+    app.send(&[instructions::verify_post(
+        &program_id,
+        &app.image_id,
+        &verifier,
+        &campaign,
+        &config,
+        args,
+    )])
+    .await?;
+
+    // Prove exactly the public input verify_post handed to Bonsol
+    let log_address = instructions::verification_log_address(&program_id, &verifier, &campaign);
+    let log = app.client.get_verification_log(&log_address).await?;
+    let execution_request = log
+        .current_execution_account
+        .ok_or("verify_post recorded no execution request")?;
+    let request_account = rpc.get_account(&execution_request).await?;
+    let request = root_as_execution_request_v1(&request_account.data)
+        .map_err(|e| format!("decoding execution request {execution_request}: {e}"))?;
+    let public_input = request
+        .input()
+        .and_then(|inputs| inputs.iter().next())
+        .and_then(|input| input.data())
+        .ok_or("execution request has no public input")?;
+    let input = decode_public_input(&mut public_input.bytes())
+        .map_err(|e| format!("decoding the public input: {e:?}"))?;
+
+    println!("Running the guest in dev mode");
+    let journal = tokio::task::block_in_place(|| harness.run(&Request::new(input, response)))?;
+    println!("{:#?}", journal.output);
+    let journal_bytes = [journal.input_digest.as_slice(), &journal.output.encode()].concat();
+
+    let deliver = bonsol_mock::deliver_instruction(
+        &request_account.owner,
+        &execution_request,
+        &verifier,
+        &app.payer.pubkey(),
+        &program_id,
+        instructions::callback_accounts(
+            &program_id,
+            &campaign,
+            &config,
+            &log_address,
+            &log,
+            &current_req_id,
+        ),
+        &bonsol_mock::callback_payload(&journal_bytes),
+    );
+    app.send(&[deliver]).await?;
+    print_state(&app.client.verification_state(&log_address).await?);
+    Ok(())
+}
@@ -0,0 +1,647 @@
+//! Operates proof-of-post campaigns from the command line: creating, funding, updating
+//! and closing them, submitting posts for verification and following their proofs.
+//!
+//! `pop-cli campaign create launch --keyword bonsol --reward 1000000 --max-claimers 100 --budget 100000000`
+//!
+//! `pop-cli stats <campaign> --chart` charts a campaign's claims and spend from the
+//! indexer's database.
+//!
+//! Built with the `dev` feature, `pop-cli dev prove` runs a verification end to end on a
+//! local validator without provers; see the `dev` module for the validator setup.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use proof_of_post::{
+    terms_message, FundSponsorshipArgs, PostProofConfig, UpdateConfigArgs, VerifyPostArgs,
+    POST_VERIFICATION_IMAGE_ID,
+};
+use proof_of_post_client::{
+    instructions, websocket_url, CampaignClient, CampaignFilter, PopError, TipMode, TipPolicy,
+    VerificationState,
+};
+use proof_of_post_common::*;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction_with_signature;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use std::error::Error;
+
+#[cfg(feature = "dev")]
+mod dev;
+mod post;
+mod stats;
+
+#[derive(Parser)]
+#[command(name = "pop-cli", version, about)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        env = "POP_RPC_URL",
+        default_value = "https://api.devnet.solana.com"
+    )]
+    url: String,
+    /// Keypair that signs and pays for transactions
+    #[arg(long, env = "POP_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[arg(long, env = "POP_PROGRAM_ID", default_value_t = proof_of_post::ID)]
+    program_id: Pubkey,
+    /// Bonsol image ID of the post verification guest
+    #[arg(long, env = "POP_IMAGE_ID", default_value = POST_VERIFICATION_IMAGE_ID)]
+    image_id: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create, fund, update, close and list campaigns
+    #[command(subcommand)]
+    Campaign(CampaignCommand),
+    /// Submit posts for verification and follow their proofs
+    #[command(subcommand)]
+    Verify(VerifyCommand),
+    /// Show whether an author's reward on a campaign was claimed, and by whom
+    Claim {
+        campaign: Pubkey,
+        /// The author's DID, e.g. did:plc:abc
+        author_did: String,
+    },
+    /// Summarize a campaign from the indexer's database
+    Stats {
+        campaign: Pubkey,
+        /// Database the indexer writes: a SQLite path or a `postgres://` URL
+        #[arg(long, env = "POP_DATABASE", default_value = "proof-of-post.db")]
+        database: String,
+        /// Chart claims and spend over time and the verifications' outcomes
+        #[arg(long)]
+        chart: bool,
+        /// Time ranges the charts split the campaign into
+        #[arg(long, default_value_t = 20)]
+        buckets: usize,
+        /// Also write claims and spend per time range to this CSV file
+        #[arg(long)]
+        csv: Option<String>,
+    },
+    /// Local end-to-end runs against a validator with a mocked Bonsol
+    #[cfg(feature = "dev")]
+    #[command(subcommand)]
+    Dev(dev::DevCommand),
+}
+
+#[derive(Subcommand)]
+enum CampaignCommand {
+    /// Create a campaign owned by the keypair
+    Create(CreateCampaign),
+    Update {
+        campaign: Pubkey,
+        /// Pause (false) or resume (true) verifications
+        #[arg(long)]
+        active: Option<bool>,
+        /// Lamports per rewarded post
+        #[arg(long)]
+        reward: Option<u64>,
+        #[arg(long)]
+        max_claimers: Option<u64>,
+        /// Emit BudgetLow below this share of the full budget, in basis points
+        #[arg(long)]
+        budget_alert_bps: Option<u16>,
+        #[arg(long)]
+        budget_alert_lamports: Option<u64>,
+    },
+    /// Close a campaign, returning the unclaimed budget
+    Close { campaign: Pubkey },
+    /// Upgrade a campaign created by an earlier program version to the current layout
+    Migrate { campaign: Pubkey },
+    /// Offer a campaign to another creator, who takes over once it accepts
+    Transfer {
+        campaign: Pubkey,
+        /// The new creator; leave out to withdraw a pending offer
+        new_creator: Option<Pubkey>,
+    },
+    /// Take over a campaign offered to the keypair
+    Accept { campaign: Pubkey },
+    /// Add lamports to a campaign's budget
+    TopUp { campaign: Pubkey, lamports: u64 },
+    /// Set aside lamports for reimbursing relayers of gasless verifications
+    Sponsor {
+        campaign: Pubkey,
+        /// Lamports added to the sponsorship budget
+        lamports: u64,
+        /// Most one sponsored request may be reimbursed, tip and rent included
+        #[arg(long)]
+        max_per_request: u64,
+    },
+    List {
+        /// Only campaigns created by this key
+        #[arg(long, conflicts_with = "mine")]
+        creator: Option<Pubkey>,
+        /// Only campaigns created by the keypair
+        #[arg(long)]
+        mine: bool,
+        /// Only campaigns accepting verifications
+        #[arg(long)]
+        active: bool,
+        #[arg(long, value_enum)]
+        platform: Option<Platform>,
+    },
+}
+
+#[derive(clap::Args)]
+struct CreateCampaign {
+    /// Name telling the campaign apart from the creator's others, up to 10 characters
+    seeds: String,
+    /// Term posts must contain; repeat for several
+    #[arg(long = "keyword", required = true)]
+    keywords: Vec<String>,
+    /// "all", "any", or how many of the keywords must match
+    #[arg(long = "match", default_value = "all")]
+    match_mode: String,
+    /// Term that disqualifies a post; repeat for several
+    #[arg(long = "blocked")]
+    blocked_keywords: Vec<String>,
+    /// `#hashtag` or `@handle` posts must carry as a facet; repeat for several
+    #[arg(long = "facet")]
+    required_facets: Vec<String>,
+    /// Lamports per rewarded post
+    #[arg(long)]
+    reward: u64,
+    #[arg(long)]
+    max_claimers: u64,
+    #[arg(long, value_enum, default_value_t = Platform::Bluesky)]
+    platform: Platform,
+    /// Posts must contain the verifier's wallet address
+    #[arg(long)]
+    require_authorship: bool,
+    /// Pay claimers' prover tips and request rent from the budget
+    #[arg(long)]
+    sponsor_costs: bool,
+    /// Host post URLs must be on, e.g. public.api.bsky.app; repeat for several
+    #[arg(long = "post-host")]
+    post_hosts: Vec<String>,
+    /// Slots rewards are held for after verification, open to challenges
+    #[arg(long, default_value_t = 0)]
+    dispute_window: u64,
+    /// Lamports a challenger must bond, required with a dispute window
+    #[arg(long, default_value_t = 0)]
+    challenge_bond: u64,
+    /// Basis points of a voided reward paid to the challenger
+    #[arg(long, default_value_t = 0)]
+    challenger_share_bps: u16,
+    /// Prover allowed to deliver the campaign's proofs; repeat for several, none
+    /// accepts any
+    #[arg(long = "prover")]
+    allowed_provers: Vec<Pubkey>,
+    /// Lamports to fund the campaign with in the same transaction
+    #[arg(long)]
+    budget: Option<u64>,
+}
+
+#[derive(Subcommand)]
+enum VerifyCommand {
+    /// Request a proof that the post meets a campaign's rules
+    Submit {
+        /// bsky.app URL, AT-URI, or the platform API URL for other platforms
+        post_url: String,
+        #[arg(long)]
+        campaign: Pubkey,
+        /// Prover tip in lamports; recommended from recent requests when left out
+        #[arg(long)]
+        tip: Option<u64>,
+        /// Outbid other requests for a quicker proof when recommending the tip
+        #[arg(long)]
+        fast: bool,
+        #[arg(long)]
+        referrer: Option<Pubkey>,
+        /// Wallet receiving the reward instead of the keypair
+        #[arg(long)]
+        payout: Option<Pubkey>,
+        /// Return once submitted instead of following the proof
+        #[arg(long)]
+        no_watch: bool,
+    },
+    /// Show where the latest request on a campaign stands
+    Status {
+        campaign: Pubkey,
+        /// Whose request; the keypair's by default
+        #[arg(long)]
+        verifier: Option<Pubkey>,
+        /// Follow the request until it settles
+        #[arg(long)]
+        watch: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Platform {
+    Bluesky,
+    Twitter,
+    Mastodon,
+    Farcaster,
+    Lens,
+    Reddit,
+    Youtube,
+}
+
+impl Platform {
+    fn id(self) -> u8 {
+        match self {
+            Self::Bluesky => PLATFORM_BLUESKY,
+            Self::Twitter => PLATFORM_TWITTER,
+            Self::Mastodon => PLATFORM_MASTODON,
+            Self::Farcaster => PLATFORM_FARCASTER,
+            Self::Lens => PLATFORM_LENS,
+            Self::Reddit => PLATFORM_REDDIT,
+            Self::Youtube => PLATFORM_YOUTUBE,
+        }
+    }
+}
+
+struct App {
+    client: CampaignClient,
+    payer: Keypair,
+    image_id: String,
+    url: String,
+}
+
+impl App {
+    fn program_id(&self) -> &Pubkey {
+        self.client.program_id()
+    }
+
+    async fn send(&self, instructions: &[Instruction]) -> Result<Signature, Box<dyn Error>> {
+        let rpc = self.client.rpc();
+        let blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+        println!("{}", explorer_url(&self.url, &signature));
+        Ok(signature)
+    }
+
+    async fn campaign(&self, command: CampaignCommand) -> Result<(), Box<dyn Error>> {
+        let creator = self.payer.pubkey();
+        match command {
+            CampaignCommand::Create(create) => {
+                let keywords: Vec<&str> = create.keywords.iter().map(String::as_str).collect();
+                let mut args = instructions::open_campaign_args(
+                    &create.seeds,
+                    &keywords,
+                    create.reward,
+                    create.max_claimers,
+                );
+                (args.match_mode, args.match_threshold) = match create.match_mode.as_str() {
+                    "all" => (MATCH_ALL, 0),
+                    "any" => (MATCH_ANY, 0),
+                    n => (MATCH_AT_LEAST, n.parse()?),
+                };
+                args.blocked_keywords = create.blocked_keywords;
+                args.required_facets = create.required_facets;
+                args.platform = create.platform.id();
+                args.require_authorship = create.require_authorship;
+                args.sponsor_costs = create.sponsor_costs;
+                args.allowed_post_hosts = create.post_hosts;
+                args.dispute_window_slots = create.dispute_window;
+                args.challenge_bond = create.challenge_bond;
+                args.challenger_share_bps = create.challenger_share_bps;
+                args.allowed_provers = create.allowed_provers;
+
+                let config =
+                    instructions::config_address(self.program_id(), &creator, &create.seeds);
+                let mut ixs = vec![instructions::create_config(
+                    self.program_id(),
+                    &creator,
+                    args,
+                )];
+                if let Some(budget) = create.budget {
+                    ixs.push(instructions::top_up(&creator, &config, budget));
+                }
+                self.send(&ixs).await?;
+                println!("Campaign {config}");
+            }
+            CampaignCommand::Update {
+                campaign,
+                active,
+                reward,
+                max_claimers,
+                budget_alert_bps,
+                budget_alert_lamports,
+            } => {
+                let args = UpdateConfigArgs {
+                    active,
+                    max_claimers,
+                    reward_amount: reward,
+                    callback_version: None,
+                    budget_alert_bps,
+                    budget_alert_lamports,
+                };
+                let ix = instructions::update_config(self.program_id(), &creator, &campaign, args);
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::Close { campaign } => {
+                let config = self.client.get_campaign(&campaign).await?;
+                let ix = instructions::close_config(
+                    self.program_id(),
+                    &creator,
+                    &campaign,
+                    config.leftover_recipient,
+                );
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::Transfer {
+                campaign,
+                new_creator,
+            } => {
+                let ix = instructions::propose_transfer(
+                    self.program_id(),
+                    &creator,
+                    &campaign,
+                    new_creator,
+                );
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::Accept { campaign } => {
+                let ix = instructions::accept_transfer(self.program_id(), &creator, &campaign);
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::Migrate { campaign } => {
+                let ix = instructions::migrate_config(self.program_id(), &creator, &campaign);
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::TopUp { campaign, lamports } => {
+                self.send(&[instructions::top_up(&creator, &campaign, lamports)])
+                    .await?;
+            }
+            CampaignCommand::Sponsor {
+                campaign,
+                lamports,
+                max_per_request,
+            } => {
+                let args = FundSponsorshipArgs {
+                    deposit: lamports,
+                    max_per_request,
+                };
+                let ix =
+                    instructions::fund_sponsorship(self.program_id(), &creator, &campaign, args);
+                self.send(&[ix]).await?;
+            }
+            CampaignCommand::List {
+                creator,
+                mine,
+                active,
+                platform,
+            } => {
+                let filter = CampaignFilter {
+                    creator: if mine {
+                        Some(self.payer.pubkey())
+                    } else {
+                        creator
+                    },
+                    active_only: active,
+                    platform: platform.map(Platform::id),
+                };
+                for (address, config) in self.client.list_campaigns(&filter).await? {
+                    print_campaign(&address, &config);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn verify(&self, command: VerifyCommand) -> Result<(), Box<dyn Error>> {
+        let verifier = self.payer.pubkey();
+        match command {
+            VerifyCommand::Submit {
+                post_url,
+                campaign,
+                tip,
+                fast,
+                referrer,
+                payout,
+                no_watch,
+            } => {
+                let config = self.client.get_campaign(&campaign).await?;
+                if config.response_signer.is_some() {
+                    return Err("campaigns with a response signer need the notary's \
+                                signature, which pop-cli doesn't fetch"
+                        .into());
+                }
+
+                let http = reqwest::Client::new();
+                let api_url = post::api_url(&http, &post_url, config.min_thread_posts > 0).await?;
+                let post_size = post::response_size(&http, &api_url).await?;
+                let (current_req_id, nonce) = self
+                    .client
+                    .new_request_id(&verifier, &campaign, &api_url)
+                    .await?;
+                let mode = if fast {
+                    TipMode::Aggressive
+                } else {
+                    TipMode::Conservative
+                };
+                let tip = match tip {
+                    Some(tip) => tip,
+                    None => {
+                        self.client
+                            .recommend_tip(post_size, mode, &TipPolicy::default())
+                            .await?
+                    }
+                };
+                println!("{api_url}: {post_size} bytes, tip {tip} lamports");
+
+                // Campaigns with terms need the verifier's signed acceptance, checked by
+                // an Ed25519 instruction right before verify_post
+                let mut ixs = Vec::new();
+                let mut terms_signature = None;
+                if config.requires_terms() {
+                    let message = terms_message(&campaign, &config.terms_hash);
+                    let signature: [u8; 64] =
+                        self.payer.sign_message(&message).as_ref().try_into()?;
+                    ixs.push(new_ed25519_instruction_with_signature(
+                        &message,
+                        &signature,
+                        &verifier.to_bytes(),
+                    ));
+                    terms_signature = Some(signature);
+                }
+
+                let args = VerifyPostArgs {
+                    current_req_id,
+                    author_did_hash: post::author_did_hash(&api_url),
+                    post_url: api_url,
+                    post_size,
+                    tip,
+                    referrer,
+                    input_hash: None,
+                    payout,
+                    terms_signature,
+                    response_signature: None,
+                    response_encoding: ENCODING_IDENTITY,
+                    request_nonce: Some(nonce),
+                };
+                ixs.push(instructions::verify_post(
+                    self.program_id(),
+                    &self.image_id,
+                    &verifier,
+                    &campaign,
+                    &config,
+                    args,
+                ));
+                self.send(&ixs).await?;
+
+                if !no_watch {
+                    self.watch(&verifier, &campaign).await?;
+                }
+            }
+            VerifyCommand::Status {
+                campaign,
+                verifier: other,
+                watch,
+            } => {
+                let verifier = other.unwrap_or(verifier);
+                if watch {
+                    self.watch(&verifier, &campaign).await?;
+                } else {
+                    let log = instructions::verification_log_address(
+                        self.program_id(),
+                        &verifier,
+                        &campaign,
+                    );
+                    print_state(&self.client.verification_state(&log).await?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn watch(&self, verifier: &Pubkey, campaign: &Pubkey) -> Result<(), Box<dyn Error>> {
+        let log = instructions::verification_log_address(self.program_id(), verifier, campaign);
+        let mut states = Box::pin(self.client.watch_verification(log));
+        while let Some(state) = states.next().await {
+            match state {
+                Ok(state) => print_state(&state),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        Ok(())
+    }
+
+    async fn claim(&self, campaign: Pubkey, author_did: &str) -> Result<(), Box<dyn Error>> {
+        let author_did_hash = hash(author_did.as_bytes()).to_bytes();
+        match self
+            .client
+            .get_author_claim(&campaign, &author_did_hash)
+            .await
+        {
+            Ok(claim) if claim.claimed => {
+                println!("Claimed by {} at slot {}", claim.claimer, claim.slot)
+            }
+            Ok(_) | Err(PopError::NotFound(_)) => println!("Not claimed"),
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+}
+
+fn print_campaign(address: &Pubkey, config: &PostProofConfig) {
+    println!(
+        "{address}  {:<10}  {}  {}/{} claimed  {} lamports each  keywords: {}",
+        config.seeds,
+        if config.active {
+            "active  "
+        } else {
+            "inactive"
+        },
+        config.claimers_count,
+        config.max_claimers,
+        config.reward_amount,
+        config.keywords.join(", "),
+    );
+}
+
+fn print_state(state: &VerificationState) {
+    match state {
+        VerificationState::Requested { execution_account } => {
+            println!("Requested, waiting for a prover ({execution_account})")
+        }
+        VerificationState::Proving { execution_account } => {
+            println!("Proving ({execution_account})")
+        }
+        VerificationState::Verified { slot } => println!("Verified at slot {slot}"),
+        VerificationState::Failed {
+            reason_code,
+            failed_checks,
+        } => println!("Failed: reason {reason_code}, failed checks {failed_checks:#b}"),
+        VerificationState::Expired { execution_account } => {
+            println!("Expired without a proof ({execution_account})")
+        }
+    }
+}
+
+/// Solana Explorer link for `signature` on the cluster behind `rpc_url`
+fn explorer_url(rpc_url: &str, signature: &Signature) -> String {
+    let cluster = if rpc_url.contains("devnet") {
+        String::from("?cluster=devnet")
+    } else if rpc_url.contains("testnet") {
+        String::from("?cluster=testnet")
+    } else if rpc_url.contains("mainnet") {
+        String::new()
+    } else {
+        format!("?cluster=custom&customUrl={rpc_url}")
+    };
+    format!("https://explorer.solana.com/tx/{signature}{cluster}")
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    // Reads the indexer's database only, so needs no keypair or RPC
+    if let Command::Stats {
+        campaign,
+        database,
+        chart,
+        buckets,
+        csv,
+    } = &cli.command
+    {
+        return stats::run(database, campaign, *chart, *buckets, csv.as_deref());
+    }
+    let payer = read_keypair_file(expand_home(&cli.keypair))
+        .map_err(|e| format!("reading keypair {}: {e}", cli.keypair))?;
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let client = CampaignClient::with_program_id(rpc, cli.program_id)
+        .with_websocket_url(websocket_url(&cli.url));
+    let app = App {
+        client,
+        payer,
+        image_id: cli.image_id,
+        url: cli.url,
+    };
+
+    match cli.command {
+        Command::Campaign(command) => app.campaign(command).await,
+        Command::Verify(command) => app.verify(command).await,
+        Command::Claim {
+            campaign,
+            author_did,
+        } => app.claim(campaign, &author_did).await,
+        Command::Stats { .. } => unreachable!("handled before connecting"),
+        #[cfg(feature = "dev")]
+        Command::Dev(command) => dev::run(&app, command).await,
+    }
+}
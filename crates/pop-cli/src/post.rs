@@ -0,0 +1,83 @@
+//! From the post a user points at to the API URL the prover fetches
+
+use anchor_lang::solana_program::hash::hash;
+use std::error::Error;
+
+const BSKY_XRPC: &str = "https://public.api.bsky.app/xrpc/";
+
+/// The Bluesky API URL for a bsky.app post URL or an AT-URI. Anything else is taken to
+/// be the platform API URL already. Thread campaigns fetch the post with its replies
+/// through getPostThread instead of getPosts.
+pub async fn api_url(
+    http: &reqwest::Client,
+    post: &str,
+    thread: bool,
+) -> Result<String, Box<dyn Error>> {
+    let endpoint = if thread {
+        "app.bsky.feed.getPostThread?uri="
+    } else {
+        "app.bsky.feed.getPosts?uris="
+    };
+    if post.starts_with("at://") {
+        return Ok(format!("{BSKY_XRPC}{endpoint}{post}"));
+    }
+    if let Some(path) = post.strip_prefix("https://bsky.app/profile/") {
+        let mut parts = path.split('/');
+        if let (Some(handle), Some("post"), Some(rkey)) = (parts.next(), parts.next(), parts.next())
+        {
+            let did = if handle.starts_with("did:") {
+                handle.to_string()
+            } else {
+                resolve_handle(http, handle).await?
+            };
+            return Ok(format!(
+                "{BSKY_XRPC}{endpoint}at://{did}/app.bsky.feed.post/{rkey}"
+            ));
+        }
+    }
+    Ok(post.to_string())
+}
+
+async fn resolve_handle(http: &reqwest::Client, handle: &str) -> Result<String, Box<dyn Error>> {
+    let resolved: serde_json::Value = http
+        .get(format!("{BSKY_XRPC}com.atproto.identity.resolveHandle"))
+        .query(&[("handle", handle)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    match resolved["did"].as_str() {
+        Some(did) => Ok(did.to_string()),
+        None => Err(format!("couldn't resolve the handle {handle}").into()),
+    }
+}
+
+/// Size of the response at `url`, which verify_post takes as `post_size`
+pub async fn response_size(http: &reqwest::Client, url: &str) -> Result<u64, Box<dyn Error>> {
+    let body = http
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    Ok(body.len() as u64)
+}
+
+/// sha256 of the author DID in a Bluesky API URL's AT-URI. Other platforms' URLs carry
+/// no DID and hash the empty string.
+pub fn author_did_hash(api_url: &str) -> [u8; 32] {
+    let query = api_url.split_once('?').map_or("", |(_, query)| query);
+    let at_uri = query
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| *key == "uris" || *key == "uri")
+        .map_or("", |(_, value)| value);
+    let did = at_uri
+        .trim_start_matches("at://")
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    hash(did.as_bytes()).to_bytes()
+}
@@ -0,0 +1,154 @@
+//! A campaign's post-mortem from the indexer's database: totals, and with `--chart`
+//! claims and spend over time and how its verifications turned out
+
+use anchor_lang::prelude::Pubkey;
+use indexer::{open_store, Page, Payout, StoreError, Verification, VerificationStatus};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write as _;
+
+/// Widest a chart's bar gets, in characters
+const BAR_WIDTH: u64 = 50;
+/// Rows read from the database at a time
+const PAGE_SIZE: u32 = 500;
+
+/// Claims and lamports paid in a slot range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bucket {
+    start_slot: u64,
+    claims: u64,
+    lamports: u64,
+}
+
+pub fn run(
+    database: &str,
+    campaign: &Pubkey,
+    chart: bool,
+    buckets: usize,
+    csv: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let store = open_store(database).map_err(|e| format!("opening {database}: {e}"))?;
+    let Some(summary) = store.campaign(campaign)? else {
+        return Err(format!("{campaign} isn't indexed in {database}").into());
+    };
+    let stats = store.campaign_stats(campaign)?;
+    println!(
+        "{campaign}  {}  {}/{} claimed  {} lamports each",
+        summary.seeds, summary.claimers_count, summary.max_claimers, summary.reward_amount
+    );
+    println!(
+        "{} verifiers: {} verified, {} failed, {} pending",
+        stats.verifiers, stats.verified, stats.failed, stats.pending
+    );
+    println!(
+        "{} payouts, {} lamports paid",
+        stats.payouts, stats.total_paid
+    );
+
+    if !chart && csv.is_none() {
+        return Ok(());
+    }
+    let payouts = all(|page| store.payouts(campaign, page))?;
+    let series = bucket(&payouts, buckets.max(1));
+    if chart {
+        let claims: Vec<_> = series
+            .iter()
+            .map(|bucket| (format!("slot {}", bucket.start_slot), bucket.claims))
+            .collect();
+        print!("{}", bar_chart("Claims over time", &claims));
+        let mut spent = 0;
+        let spend: Vec<_> = series
+            .iter()
+            .map(|bucket| {
+                spent += bucket.lamports;
+                (format!("slot {}", bucket.start_slot), spent)
+            })
+            .collect();
+        print!("{}", bar_chart("Lamports spent", &spend));
+        // The indexer doesn't keep engagement counts, so outcomes stand in for them
+        let verifications = all(|page| store.verifications(campaign, None, page))?;
+        print!("{}", bar_chart("Outcomes", &outcomes(&verifications)));
+    }
+    if let Some(path) = csv {
+        let mut out = String::from("start_slot,claims,lamports\n");
+        for bucket in &series {
+            writeln!(
+                out,
+                "{},{},{}",
+                bucket.start_slot, bucket.claims, bucket.lamports
+            )?;
+        }
+        std::fs::write(path, out).map_err(|e| format!("writing {path}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Every row of a paged listing
+fn all<T>(mut query: impl FnMut(Page) -> Result<Vec<T>, StoreError>) -> Result<Vec<T>, StoreError> {
+    let mut rows = Vec::new();
+    loop {
+        let page = query(Page {
+            limit: PAGE_SIZE,
+            offset: rows.len() as u32,
+        })?;
+        let done = page.len() < PAGE_SIZE as usize;
+        rows.extend(page);
+        if done {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Splits the slots from the first payout to the last into `count` equal ranges
+fn bucket(payouts: &[Payout], count: usize) -> Vec<Bucket> {
+    let (Some(first), Some(last)) = (
+        payouts.iter().map(|payout| payout.slot).min(),
+        payouts.iter().map(|payout| payout.slot).max(),
+    ) else {
+        return Vec::new();
+    };
+    let width = ((last - first) / count as u64 + 1).max(1);
+    let mut buckets: Vec<Bucket> = (0..count as u64)
+        .map(|index| Bucket {
+            start_slot: first + index * width,
+            claims: 0,
+            lamports: 0,
+        })
+        .collect();
+    for payout in payouts {
+        let bucket = &mut buckets[((payout.slot - first) / width) as usize];
+        bucket.claims += 1;
+        bucket.lamports += payout.amount;
+    }
+    buckets
+}
+
+/// Verifications by status, failures by `REASON_*` code
+fn outcomes(verifications: &[Verification]) -> Vec<(String, u64)> {
+    let mut counts = BTreeMap::new();
+    for verification in verifications {
+        let label = match verification.status {
+            VerificationStatus::Verified => String::from("verified"),
+            VerificationStatus::Pending => String::from("pending"),
+            VerificationStatus::Failed => format!("failed ({})", verification.failure_reason),
+        };
+        *counts.entry(label).or_insert(0) += 1;
+    }
+    counts.into_iter().collect()
+}
+
+fn bar_chart(title: &str, rows: &[(String, u64)]) -> String {
+    let mut chart = format!("\n{title}\n");
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let max = rows
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (label, value) in rows {
+        let bar = "#".repeat((value * BAR_WIDTH / max) as usize);
+        chart.push_str(&format!("{label:>label_width$} | {bar} {value}\n"));
+    }
+    chart
+}
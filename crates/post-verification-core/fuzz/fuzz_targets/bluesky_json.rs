@@ -0,0 +1,79 @@
+//! Bluesky responses of any shape: parsing never panics, and neither does evaluating
+//! whatever parses against a campaign with every check switched on.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use post_verification_core::evaluate;
+use post_verification_core::platform::{Bluesky, BlueskyThread, PlatformAdapter};
+use proof_of_post_common::*;
+
+fn campaign() -> (PublicInput, RulesBundle) {
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let input = PublicInput {
+        post_size: 0,
+        keywords: Vec::new(),
+        blocked_keywords: Vec::new(),
+        required_facets: Vec::new(),
+        match_mode: MatchMode::AtLeast(1),
+        match_flags: MATCH_FLAG_WHOLE_WORD | MATCH_FLAG_FOLD_DIACRITICS,
+        require_authorship: true,
+        verifier: [7u8; 32],
+        engagement: EngagementThresholds {
+            min_likes: 1,
+            min_reposts: 1,
+            min_replies: 1,
+            min_views: 0,
+        },
+        recency: RecencyWindow {
+            not_before_ts: 1_700_000_000,
+            not_after_ts: 1_800_000_000,
+        },
+        required_langs: strings(&["en"]),
+        content: ContentRequirements {
+            min_chars: 10,
+            min_words: 3,
+        },
+        media: MediaRequirement {
+            min_images: 1,
+            external_domain: String::from("bonsol.sh"),
+        },
+        allowed_link_domains: strings(&["bonsol.sh/launch"]),
+        reply_to_uri: String::from("at://did:plc:abc/app.bsky.feed.post/1"),
+        author: AuthorRequirements {
+            min_account_age_days: 30,
+            min_followers: 10,
+            as_of_ts: 1_750_000_000,
+        },
+        blocked_labels: strings(&["spam"]),
+        platform: PLATFORM_BLUESKY,
+        required_communities: Vec::new(),
+        disclosure_tags: strings(&["#ad"]),
+        allowed_author_ids: Vec::new(),
+        segments: vec![SegmentRule {
+            kind: SEGMENT_HANDLE_SUFFIX,
+            value: String::from(".bsky.social"),
+        }],
+        response_signer: [0u8; 32],
+        post_url_hash: [0u8; 32],
+        config: [0u8; 32],
+        response_encoding: ENCODING_IDENTITY,
+        min_thread_posts: 2,
+        rules_bundle: None,
+    };
+    let rules = RulesBundle {
+        keywords: strings(&["bonsol", "zero knowledge", "🚀"]),
+        blocked_keywords: strings(&["scam"]),
+        required_facets: strings(&["#bonsol", "@bonsol.bsky.social"]),
+    };
+    (input, rules)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (input, rules) = campaign();
+    let adapters: [&dyn PlatformAdapter; 2] = [&Bluesky, &BlueskyThread];
+    for adapter in adapters {
+        let parsed = adapter.parse(&mut &data[..]);
+        evaluate(&input, &rules, parsed, false);
+    }
+});
@@ -0,0 +1,14 @@
+//! The guest's whole input stream as the prover supplies it: whatever the bytes, the
+//! guest ends in a journal, never a panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use post_verification_core::run;
+use proof_of_post_common::PostVerificationOutput;
+
+fuzz_target!(|data: &[u8]| {
+    let journal = run(data);
+    let encoded = journal.output.encode();
+    assert_eq!(PostVerificationOutput::decode(&encoded), Ok(journal.output));
+});
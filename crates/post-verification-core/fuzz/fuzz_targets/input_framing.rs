@@ -0,0 +1,20 @@
+//! Public input and rule bundle decoding over arbitrary bytes: never panics, and
+//! whatever decodes survives an encode/decode round trip unchanged.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proof_of_post_common::{
+    decode_public_input, decode_rules_bundle, encode_public_input, encode_rules_bundle,
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = decode_public_input(&mut &data[..]) {
+        let encoded = encode_public_input(&input);
+        assert_eq!(decode_public_input(&mut encoded.as_slice()), Ok(input));
+    }
+    if let Ok(bundle) = decode_rules_bundle(data) {
+        let encoded = encode_rules_bundle(&bundle);
+        assert_eq!(decode_rules_bundle(&encoded), Ok(bundle));
+    }
+});
@@ -0,0 +1,111 @@
+use curve25519_dalek::{edwards::CompressedEdwardsY, scalar::Scalar, EdwardsPoint};
+use proof_of_post_common::response_signature_message;
+use sha2::{Digest, Sha512};
+
+/// Whether `signature` is the campaign's response signer vouching for the response with
+/// SHA-256 `response_hash` at the URL hashing to `post_url_hash`. The signer (a notary
+/// or signing proxy that fetched the URL itself) signs [`response_signature_message`],
+/// so a prover can't swap in a fabricated body, or one signed for another post.
+pub fn is_authentic_response(
+    signer: &[u8; 32],
+    post_url_hash: &[u8; 32],
+    response_hash: &[u8; 32],
+    signature: &[u8; 64],
+) -> bool {
+    verify_ed25519(
+        signer,
+        &response_signature_message(post_url_hash, response_hash),
+        signature,
+    )
+}
+
+/// RFC 8032 Ed25519 verification. Non-canonical `S` values and small-order keys are
+/// rejected, since either would let a signature be forged without the secret key.
+fn verify_ed25519(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let Some(key) = CompressedEdwardsY(*public_key).decompress() else {
+        return false;
+    };
+    if key.is_small_order() {
+        return false;
+    }
+    let mut r_bytes = [0u8; 32];
+    r_bytes.copy_from_slice(&signature[..32]);
+    let mut s_bytes = [0u8; 32];
+    s_bytes.copy_from_slice(&signature[32..]);
+    let Some(s) = Option::<Scalar>::from(Scalar::from_canonical_bytes(s_bytes)) else {
+        return false;
+    };
+
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(
+        &Sha512::new()
+            .chain_update(r_bytes)
+            .chain_update(public_key)
+            .chain_update(message)
+            .finalize(),
+    );
+    let k = Scalar::from_bytes_mod_order_wide(&wide);
+
+    // R == [S]B - [k]A
+    let r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-key, &s);
+    r.compress().to_bytes() == r_bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    // RFC 8032 section 7.1, tests 1 and 2
+    const KEY_1: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const SIG_1: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+    const KEY_2: &str = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+    const SIG_2: &str = "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00";
+
+    #[test]
+    fn rfc8032_vectors_verify() {
+        assert!(verify_ed25519(&hex(KEY_1), b"", &hex(SIG_1)));
+        assert!(verify_ed25519(&hex(KEY_2), &[0x72], &hex(SIG_2)));
+    }
+
+    #[test]
+    fn tampered_message_is_rejected() {
+        assert!(!verify_ed25519(&hex(KEY_2), &[0x73], &hex(SIG_2)));
+        assert!(!verify_ed25519(&hex(KEY_1), &[0x72], &hex(SIG_2)));
+    }
+
+    #[test]
+    fn non_canonical_s_is_rejected() {
+        let mut signature = hex::<64>(SIG_1);
+        signature[63] |= 0xf0;
+        assert!(!verify_ed25519(&hex(KEY_1), b"", &signature));
+    }
+
+    #[test]
+    fn small_order_key_is_rejected() {
+        // The identity point: with S = 0 and R = identity, any message would verify
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let mut signature = [0u8; 64];
+        signature[0] = 1;
+        assert!(!verify_ed25519(&identity, b"anything", &signature));
+    }
+
+    #[test]
+    fn response_signature_covers_the_hash() {
+        let signature = hex::<64>(SIG_1);
+        assert!(!is_authentic_response(
+            &hex(KEY_1),
+            &[0u8; 32],
+            &[0u8; 32],
+            &signature
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+/// Aho-Corasick automaton over byte strings: finds every occurrence of every pattern in
+/// one pass over the haystack, so the work grows with the text rather than with
+/// keywords x text
+pub struct Automaton {
+    nodes: Vec<Node>,
+    pattern_lens: Vec<usize>,
+}
+
+const ROOT: u32 = 0;
+
+#[derive(Default)]
+struct Node {
+    /// Trie edges, sorted by byte
+    next: Vec<(u8, u32)>,
+    /// Longest proper suffix of this node's path that is also a trie path
+    fail: u32,
+    /// Patterns ending here, including those ending at a suffix
+    outputs: Vec<usize>,
+}
+
+impl Node {
+    fn child(&self, byte: u8) -> Option<u32> {
+        self.next
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.next[i].1)
+    }
+}
+
+impl Automaton {
+    /// Empty patterns are kept for indexing but never match
+    pub fn new<'a>(patterns: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut pattern_lens = Vec::new();
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            pattern_lens.push(pattern.len());
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = ROOT;
+            for &byte in pattern {
+                state = match nodes[state as usize].child(byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = nodes.len() as u32;
+                        nodes.push(Node::default());
+                        let edges = &mut nodes[state as usize].next;
+                        let at = edges.partition_point(|&(b, _)| b < byte);
+                        edges.insert(at, (byte, next));
+                        next
+                    }
+                };
+            }
+            nodes[state as usize].outputs.push(index);
+        }
+
+        // Breadth-first, so every node's fail target is final before its children's
+        let mut queue = std::collections::VecDeque::from([ROOT]);
+        while let Some(state) = queue.pop_front() {
+            for i in 0..nodes[state as usize].next.len() {
+                let (byte, child) = nodes[state as usize].next[i];
+                let fail = if state == ROOT {
+                    ROOT
+                } else {
+                    Self::advance(&nodes, nodes[state as usize].fail, byte).0
+                };
+                nodes[child as usize].fail = fail;
+                let inherited = nodes[fail as usize].outputs.clone();
+                nodes[child as usize].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Self {
+            nodes,
+            pattern_lens,
+        }
+    }
+
+    /// Follows `byte` from `state`, falling back along fail links. Returns the new state
+    /// and the number of transitions taken.
+    fn advance(nodes: &[Node], mut state: u32, byte: u8) -> (u32, usize) {
+        let mut transitions = 1;
+        loop {
+            if let Some(next) = nodes[state as usize].child(byte) {
+                return (next, transitions);
+            }
+            if state == ROOT {
+                return (ROOT, transitions);
+            }
+            state = nodes[state as usize].fail;
+            transitions += 1;
+        }
+    }
+
+    /// Calls `on_match(pattern_index, start)` for every occurrence, overlapping ones
+    /// included, in order of where they end. Returns the transitions taken, which the
+    /// proving cycles of a search scale with.
+    pub fn for_each_match(&self, haystack: &[u8], mut on_match: impl FnMut(usize, usize)) -> usize {
+        let mut state = ROOT;
+        let mut total = 0;
+        for (end, &byte) in haystack.iter().enumerate() {
+            let (next, transitions) = Self::advance(&self.nodes, state, byte);
+            state = next;
+            total += transitions;
+            for &index in &self.nodes[state as usize].outputs {
+                on_match(index, end + 1 - self.pattern_lens[index]);
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(patterns: &[&str], haystack: &str) -> Vec<(usize, usize)> {
+        let automaton = Automaton::new(patterns.iter().map(|p| p.as_bytes()));
+        let mut found = Vec::new();
+        automaton.for_each_match(haystack.as_bytes(), |index, start| {
+            found.push((index, start))
+        });
+        found
+    }
+
+    #[test]
+    fn finds_overlapping_patterns() {
+        assert_eq!(
+            matches(&["he", "she", "his", "hers"], "ushers"),
+            [(1, 1), (0, 2), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn reports_every_occurrence() {
+        assert_eq!(matches(&["aa"], "aaaa"), [(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn duplicate_and_empty_patterns() {
+        assert_eq!(matches(&["sol", "", "sol"], "gm sol"), [(0, 3), (2, 3)]);
+        assert!(matches(&[""], "anything").is_empty());
+    }
+
+    #[test]
+    fn multibyte_patterns_start_on_char_boundaries() {
+        let haystack = "café 🚀 bonsol";
+        let found = matches(&["🚀", "é"], haystack);
+        assert_eq!(found, [(1, 3), (0, 6)]);
+        assert!(found
+            .iter()
+            .all(|&(_, start)| haystack.is_char_boundary(start)));
+    }
+
+    #[test]
+    fn transitions_stay_linear_in_text_length() {
+        // 20 keywords near the on-chain 50-byte cap, sharing long prefixes so the
+        // search keeps falling back
+        let patterns: Vec<String> = (0..20)
+            .map(|i| format!("{}{}", "ab".repeat(24), char::from(b'c' + i)))
+            .collect();
+        let automaton = Automaton::new(patterns.iter().map(String::as_bytes));
+        let haystack = "ab".repeat(1000) + "v";
+        let mut hits = 0;
+        let transitions = automaton.for_each_match(haystack.as_bytes(), |_, _| hits += 1);
+        assert_eq!(hits, 1);
+        // A per-keyword scan would be on the order of keywords x text
+        assert!(
+            transitions <= 2 * haystack.len(),
+            "{transitions} transitions"
+        );
+    }
+}
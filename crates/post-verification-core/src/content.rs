@@ -0,0 +1,52 @@
+/// Substantive length of a post: characters and words left after dropping hashtags,
+/// mentions and URLs, so keyword-only spam doesn't count as content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentStats {
+    pub chars: usize,
+    pub words: usize,
+}
+
+pub fn content_stats(text: &str) -> ContentStats {
+    text.split_whitespace()
+        .filter(|token| !is_tag_or_link(token))
+        .fold(ContentStats { chars: 0, words: 0 }, |stats, token| {
+            ContentStats {
+                chars: stats.chars + token.chars().count(),
+                words: stats.words + 1,
+            }
+        })
+}
+
+fn is_tag_or_link(token: &str) -> bool {
+    token.starts_with('#')
+        || token.starts_with('@')
+        || token.contains("://")
+        || token.starts_with("www.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_plain_words() {
+        assert_eq!(
+            content_stats("Proving posts  with\nBonsol"),
+            ContentStats {
+                chars: 22,
+                words: 4
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_hashtags_mentions_and_urls() {
+        let stats = content_stats("#bonsol #zk @bonsol.bsky.social https://bonsol.sh www.x.com gm");
+        assert_eq!(stats, ContentStats { chars: 2, words: 1 });
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        assert_eq!(content_stats("café"), ContentStats { chars: 4, words: 1 });
+    }
+}
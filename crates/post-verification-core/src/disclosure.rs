@@ -0,0 +1,53 @@
+/// Whether the post carries one of the accepted disclosure hashtags ("#ad",
+/// "#sponsored"), compared case-insensitively. Hashtag annotations count, and so do
+/// hashtags written in the text: a visible `#ad` discloses the promotion just as well on
+/// platforms without annotations. An empty list accepts any post.
+pub fn has_disclosure(tags: &[String], text: &str, accepted: &[String]) -> bool {
+    let text_tags: Vec<&str> = text
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| tag.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_')))
+        .collect();
+    accepted.is_empty()
+        || accepted
+            .iter()
+            .filter_map(|disclosure| disclosure.strip_prefix('#'))
+            .any(|disclosure| {
+                tags.iter()
+                    .map(String::as_str)
+                    .chain(text_tags.iter().copied())
+                    .any(|tag| tag.eq_ignore_ascii_case(disclosure))
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn annotated_hashtag_discloses() {
+        let accepted = list(&["#ad", "#sponsored"]);
+        assert!(has_disclosure(
+            &list(&["Sponsored"]),
+            "loving bonsol",
+            &accepted
+        ));
+    }
+
+    #[test]
+    fn written_hashtag_discloses() {
+        let accepted = list(&["#ad"]);
+        assert!(has_disclosure(&[], "loving bonsol #AD.", &accepted));
+        assert!(!has_disclosure(&[], "loving bonsol #adventure", &accepted));
+        assert!(!has_disclosure(&[], "this is an ad", &accepted));
+    }
+
+    #[test]
+    fn no_requirement_accepts_any_post() {
+        assert!(has_disclosure(&[], "gm", &[]));
+    }
+}
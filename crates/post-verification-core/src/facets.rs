@@ -0,0 +1,157 @@
+use serde::Deserialize;
+
+/// Rich-text annotation on a Bluesky post record
+#[derive(Debug, Deserialize)]
+pub struct Facet {
+    pub index: FacetIndex,
+    #[serde(default)]
+    pub features: Vec<FacetFeature>,
+}
+
+/// UTF-8 byte range of the annotated text
+#[derive(Debug, Deserialize)]
+pub struct FacetIndex {
+    #[serde(rename = "byteStart")]
+    pub byte_start: usize,
+    #[serde(rename = "byteEnd")]
+    pub byte_end: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "$type")]
+pub enum FacetFeature {
+    #[serde(rename = "app.bsky.richtext.facet#tag")]
+    Tag { tag: String },
+    #[serde(rename = "app.bsky.richtext.facet#mention")]
+    Mention { did: String },
+    #[serde(rename = "app.bsky.richtext.facet#link")]
+    Link { uri: String },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Hashtags carried by tag facets, without the leading `#`
+pub fn facet_tags(facets: &[Facet]) -> Vec<String> {
+    facets
+        .iter()
+        .flat_map(|f| &f.features)
+        .filter_map(|feature| match feature {
+            FacetFeature::Tag { tag } => Some(tag.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Handles covered by mention facets, without the leading `@`. Facets whose byte range
+/// doesn't fall on the text are ignored.
+pub fn facet_mentions(text: &str, facets: &[Facet]) -> Vec<String> {
+    facets
+        .iter()
+        .filter(|facet| {
+            facet
+                .features
+                .iter()
+                .any(|feature| matches!(feature, FacetFeature::Mention { .. }))
+        })
+        .filter_map(|facet| {
+            text.get(facet.index.byte_start..facet.index.byte_end)
+                .and_then(|s| s.strip_prefix('@'))
+                .map(String::from)
+        })
+        .collect()
+}
+
+/// Link targets of link facets
+pub fn facet_links(facets: &[Facet]) -> Vec<String> {
+    facets
+        .iter()
+        .flat_map(|f| &f.features)
+        .filter_map(|feature| match feature {
+            FacetFeature::Link { uri } => Some(uri.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the post carries a real annotation for `requirement`: `#tag` needs a tagged
+/// hashtag and `@handle` a mention of that handle. Plain text lookalikes don't count.
+pub fn has_facet(tags: &[String], mentions: &[String], requirement: &str) -> bool {
+    if let Some(tag) = requirement.strip_prefix('#') {
+        tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    } else if let Some(handle) = requirement.strip_prefix('@') {
+        mentions.iter().any(|m| m.eq_ignore_ascii_case(handle))
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "gm @bonsol.bsky.social #Bonsol #zk";
+
+    fn facets() -> Vec<Facet> {
+        serde_json::from_str(
+            r##"[
+                {
+                    "index": { "byteStart": 3, "byteEnd": 22 },
+                    "features": [{ "$type": "app.bsky.richtext.facet#mention", "did": "did:plc:abc" }]
+                },
+                {
+                    "index": { "byteStart": 23, "byteEnd": 30 },
+                    "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "Bonsol" }]
+                },
+                {
+                    "index": { "byteStart": 31, "byteEnd": 34 },
+                    "features": [{ "$type": "app.bsky.richtext.facet#unknownThing" }]
+                }
+            ]"##,
+        )
+        .unwrap()
+    }
+
+    fn check(text: &str, facets: &[Facet], requirement: &str) -> bool {
+        has_facet(
+            &facet_tags(facets),
+            &facet_mentions(text, facets),
+            requirement,
+        )
+    }
+
+    #[test]
+    fn hashtag_requires_tag_facet() {
+        assert!(check(TEXT, &facets(), "#bonsol"));
+        // "#zk" is in the text but has no tag facet
+        assert!(!check(TEXT, &facets(), "#zk"));
+    }
+
+    #[test]
+    fn mention_requires_mention_facet_over_handle() {
+        assert!(check(TEXT, &facets(), "@bonsol.bsky.social"));
+        assert!(!check(TEXT, &facets(), "@someone.bsky.social"));
+    }
+
+    #[test]
+    fn plain_text_mention_does_not_count() {
+        let text = "gm @bonsol.bsky.social";
+        assert!(!check(text, &[], "@bonsol.bsky.social"));
+    }
+
+    #[test]
+    fn out_of_range_facet_index_is_ignored() {
+        let facets: Vec<Facet> = serde_json::from_str(
+            r##"[{
+                "index": { "byteStart": 3, "byteEnd": 400 },
+                "features": [{ "$type": "app.bsky.richtext.facet#mention", "did": "did:plc:abc" }]
+            }]"##,
+        )
+        .unwrap();
+        assert!(!check(TEXT, &facets, "@bonsol.bsky.social"));
+    }
+
+    #[test]
+    fn requirement_without_prefix_never_matches() {
+        assert!(!check(TEXT, &facets(), "bonsol"));
+    }
+}
@@ -0,0 +1,139 @@
+/// Plain text of an HTML fragment such as a Mastodon status `content`: tags are
+/// dropped, paragraphs and line breaks become newlines and entities are decoded
+pub fn strip_html(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&decode_entities(&rest[..start]));
+        let Some(len) = rest[start..].find('>') else {
+            // An unterminated tag swallows the rest, as in a browser
+            rest = "";
+            break;
+        };
+        if matches!(tag_name(&rest[start + 1..start + len]).as_str(), "br" | "p")
+            && !out.is_empty()
+            && !out.ends_with('\n')
+        {
+            out.push('\n');
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(&decode_entities(rest));
+    out.trim_end().to_string()
+}
+
+/// `href` targets of the fragment's links, skipping mention and hashtag links, which
+/// Mastodon marks with a `mention` class
+pub fn html_links(html: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        if tag_name(tag) == "a" && !tag.starts_with('/') {
+            let is_mention = attribute(tag, "class")
+                .is_some_and(|class| class.split_whitespace().any(|c| c == "mention"));
+            if let Some(href) = attribute(tag, "href").filter(|_| !is_mention) {
+                links.push(decode_entities(href));
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    links
+}
+
+/// Lowercased element name of the text between `<` and `>`, for opening and closing tags
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Value of a double-quoted attribute inside a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {name}=\"");
+    let start = tag.find(&pattern)? + pattern.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    let code = match entity {
+        "amp" => return Some('&'),
+        "lt" => return Some('<'),
+        "gt" => return Some('>'),
+        "quot" => return Some('"'),
+        "apos" => return Some('\''),
+        "nbsp" => return Some(' '),
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            }
+        }
+    };
+    char::from_u32(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"<p>gm <a href="https://mastodon.social/tags/bonsol" class="mention hashtag" rel="tag">#<span>bonsol</span></a> &amp; <span class="h-card"><a href="https://bonsol.social/@team" class="u-url mention">@<span>team</span></a></span></p><p>read <a href="https://bonsol.sh/launch?a=1&amp;b=2" rel="nofollow noopener" target="_blank"><span class="invisible">https://</span><span class="">bonsol.sh/launch?a=1&amp;b=2</span><span class="invisible"></span></a><br />it&#39;s live</p>"#;
+
+    #[test]
+    fn strips_tags_and_keeps_line_structure() {
+        assert_eq!(
+            strip_html(CONTENT),
+            "gm #bonsol & @team\nread https://bonsol.sh/launch?a=1&b=2\nit's live"
+        );
+    }
+
+    #[test]
+    fn collects_links_but_not_mentions_or_hashtags() {
+        assert_eq!(html_links(CONTENT), ["https://bonsol.sh/launch?a=1&b=2"]);
+    }
+
+    #[test]
+    fn decodes_numeric_entities_and_keeps_stray_ampersands() {
+        assert_eq!(
+            decode_entities("&#x1F680; &#233; AT&T &bogus;"),
+            "🚀 é AT&T &bogus;"
+        );
+    }
+
+    #[test]
+    fn unterminated_tag_is_dropped() {
+        assert_eq!(strip_html("gm <a href="), "gm");
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Deserialize;
+
+/// Moderation label on a post or account view
+#[derive(Debug, Deserialize)]
+pub struct Label {
+    /// DID of the labeler, or of the account itself for self-labels
+    #[serde(default)]
+    pub src: String,
+    pub val: String,
+    /// Negation labels retract an earlier label with the same source and value
+    #[serde(default)]
+    pub neg: bool,
+}
+
+/// Values of the labels in effect: a label retracted by a negation from the same source
+/// doesn't count
+pub fn labels_in_effect(labels: &[Label]) -> impl Iterator<Item = &str> {
+    labels
+        .iter()
+        .filter(|label| !label.neg)
+        .filter(|label| {
+            !labels
+                .iter()
+                .any(|other| other.neg && other.src == label.src && other.val == label.val)
+        })
+        .map(|label| label.val.as_str())
+}
+
+/// The first of `labels` that is blocked, compared case-insensitively
+pub fn find_blocked_label<'a>(labels: &'a [String], blocked: &[String]) -> Option<&'a str> {
+    labels
+        .iter()
+        .map(String::as_str)
+        .find(|val| blocked.iter().any(|b| b.eq_ignore_ascii_case(val)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(vals: &[&str]) -> Vec<String> {
+        vals.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn labels(json: &str) -> Vec<String> {
+        let labels: Vec<Label> = serde_json::from_str(json).unwrap();
+        labels_in_effect(&labels).map(String::from).collect()
+    }
+
+    #[test]
+    fn finds_blocked_label() {
+        let labels = labels(
+            r#"[
+                { "src": "did:plc:mod", "val": "sexual" },
+                { "src": "did:plc:mod", "val": "Spam", "cts": "2024-01-01T00:00:00Z" }
+            ]"#,
+        );
+        assert_eq!(
+            find_blocked_label(&labels, &blocked(&["spam", "porn"])),
+            Some("Spam")
+        );
+        assert_eq!(find_blocked_label(&labels, &blocked(&["porn"])), None);
+        assert_eq!(find_blocked_label(&labels, &[]), None);
+    }
+
+    #[test]
+    fn negated_labels_are_not_in_effect() {
+        let labels = labels(
+            r#"[
+                { "src": "did:plc:mod", "val": "spam" },
+                { "src": "did:plc:mod", "val": "spam", "neg": true }
+            ]"#,
+        );
+        assert_eq!(find_blocked_label(&labels, &blocked(&["spam"])), None);
+    }
+
+    #[test]
+    fn negation_only_retracts_its_own_source() {
+        let labels = labels(
+            r#"[
+                { "src": "did:plc:mod", "val": "spam" },
+                { "src": "did:plc:other", "val": "spam", "neg": true }
+            ]"#,
+        );
+        assert_eq!(
+            find_blocked_label(&labels, &blocked(&["spam"])),
+            Some("spam")
+        );
+    }
+}
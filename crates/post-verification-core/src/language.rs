@@ -0,0 +1,39 @@
+/// Whether any of the post's `langs` tags is one of the accepted languages. Tags match on
+/// their primary subtag, case-insensitively, so "en-US" satisfies "en".
+pub fn has_accepted_language(langs: &[String], accepted: &[String]) -> bool {
+    accepted.is_empty()
+        || langs.iter().any(|lang| {
+            let primary = lang.split(['-', '_']).next().unwrap_or_default();
+            accepted
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(lang) || a.eq_ignore_ascii_case(primary))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn langs(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_primary_subtag() {
+        assert!(has_accepted_language(&langs(&["en-US"]), &langs(&["en"])));
+        assert!(has_accepted_language(&langs(&["PT"]), &langs(&["en", "pt"])));
+        assert!(!has_accepted_language(&langs(&["es"]), &langs(&["en", "pt"])));
+    }
+
+    #[test]
+    fn regional_requirement_needs_exact_tag() {
+        assert!(has_accepted_language(&langs(&["pt-BR"]), &langs(&["pt-br"])));
+        assert!(!has_accepted_language(&langs(&["pt"]), &langs(&["pt-BR"])));
+    }
+
+    #[test]
+    fn posts_without_langs_fail_a_requirement() {
+        assert!(!has_accepted_language(&[], &langs(&["en"])));
+        assert!(has_accepted_language(&[], &[]));
+    }
+}
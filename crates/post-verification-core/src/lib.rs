@@ -0,0 +1,35 @@
+//! Everything the post verification guest does between reading its inputs and
+//! committing the journal: decoding and hashing the input stream, parsing platform
+//! responses and evaluating campaign rules. The guest binary only does zkVM I/O, so
+//! all of this runs and is tested on the host.
+
+/// `println!` with the `debug-log` feature, nothing without it: the guest's console
+/// output would otherwise leak its private inputs and costs cycles
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "debug-log") {
+            println!($($arg)*)
+        }
+    };
+}
+
+pub mod authenticity;
+pub mod automaton;
+pub mod content;
+pub mod disclosure;
+pub mod facets;
+pub mod html;
+pub mod labels;
+pub mod language;
+pub mod links;
+pub mod matching;
+pub mod media;
+pub mod platform;
+pub mod run;
+pub mod segments;
+pub mod stream;
+pub mod time;
+pub mod verify;
+
+pub use run::{run, Journal};
+pub use verify::evaluate;
@@ -0,0 +1,96 @@
+/// Lowercased host of an http(s) URL
+pub fn url_host(url: &str) -> Option<String> {
+    let (host, _) = split_url(url)?;
+    Some(host)
+}
+
+/// `host` is `domain` or one of its subdomains
+pub fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.to_ascii_lowercase();
+    host == domain
+        || host
+            .strip_suffix(domain.as_str())
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// First allowlist entry that one of `uris` links to. Entries are a domain with an
+/// optional path prefix ("example.com/launch"); subdomains of the domain match.
+pub fn find_allowed_link<'a, U: AsRef<str>>(
+    uris: &[U],
+    allowlist: &'a [String],
+) -> Option<&'a str> {
+    allowlist
+        .iter()
+        .find(|entry| uris.iter().any(|uri| link_matches(uri.as_ref(), entry)))
+        .map(String::as_str)
+}
+
+fn link_matches(uri: &str, entry: &str) -> bool {
+    let (domain, prefix) = match entry.find('/') {
+        Some(i) => entry.split_at(i),
+        None => (entry, ""),
+    };
+    let Some((host, path)) = split_url(uri) else {
+        return false;
+    };
+    domain_matches(&host, domain)
+        && (prefix.is_empty()
+            || path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#'])))
+}
+
+/// Lowercased host and the path (with query) of an http(s) URL
+fn split_url(url: &str) -> Option<(String, &str)> {
+    let rest = strip_prefix_ignore_case(url, "https://")
+        .or_else(|| strip_prefix_ignore_case(url, "http://"))?;
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| (host.to_ascii_lowercase(), path))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn domain_matching_rejects_lookalikes() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches("www.example.com", "Example.com"));
+        assert!(!domain_matches("badexample.com", "example.com"));
+        assert_eq!(
+            url_host("https://user@Example.com:443/path").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(url_host("ftp://example.com"), None);
+    }
+
+    #[test]
+    fn finds_first_matching_entry() {
+        let list = allowlist(&["other.com", "example.com"]);
+        let uris = ["https://bsky.app", "https://www.example.com/post"];
+        assert_eq!(find_allowed_link(&uris, &list), Some("example.com"));
+        assert_eq!(find_allowed_link(&["https://nope.com"], &list), None);
+    }
+
+    #[test]
+    fn path_prefixes_match_whole_segments() {
+        let list = allowlist(&["example.com/launch"]);
+        assert!(find_allowed_link(&["https://example.com/launch"], &list).is_some());
+        assert!(find_allowed_link(&["https://example.com/launch/day-1?ref=x"], &list).is_some());
+        assert!(find_allowed_link(&["HTTPS://EXAMPLE.com/launch#top"], &list).is_some());
+        assert!(find_allowed_link(&["https://example.com/launchpad"], &list).is_none());
+        assert!(find_allowed_link(&["https://example.com/"], &list).is_none());
+    }
+}
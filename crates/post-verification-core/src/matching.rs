@@ -0,0 +1,330 @@
+use proof_of_post_common::{
+    MATCH_FLAG_CASE_SENSITIVE, MATCH_FLAG_EXACT_PHRASE, MATCH_FLAG_FOLD_DIACRITICS,
+    MATCH_FLAG_REGEX, MATCH_FLAG_WHOLE_WORD, MAX_REGEX_PATTERN_LEN,
+};
+use regex_automata::{meta::Regex, util::syntax};
+
+use crate::automaton::Automaton;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Matches campaign keywords against post text according to the campaign's match flags
+pub struct KeywordMatcher {
+    flags: u8,
+    text: String,
+}
+
+impl KeywordMatcher {
+    pub fn new(text: &str, flags: u8) -> Self {
+        Self {
+            flags,
+            text: normalize(text, flags),
+        }
+    }
+
+    /// The post text after case folding and whitespace normalization
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Byte offset of the first acceptable match of `keyword` in [`Self::text`]
+    pub fn find(&self, keyword: &str) -> Option<usize> {
+        self.find_all(&[keyword])[0]
+    }
+
+    /// [`Self::find`] for each keyword, in order. Plain keywords are searched together
+    /// in a single pass over the text, so large keyword sets don't multiply the work;
+    /// regex keywords are searched one by one.
+    pub fn find_all<K: AsRef<str>>(&self, keywords: &[K]) -> Vec<Option<usize>> {
+        if self.flags & MATCH_FLAG_REGEX != 0 {
+            return keywords
+                .iter()
+                .map(|keyword| {
+                    let regex = compile_pattern(keyword.as_ref(), self.flags)?;
+                    regex.find(self.text.as_str()).map(|m| m.start())
+                })
+                .collect();
+        }
+        let needles: Vec<String> = keywords
+            .iter()
+            .map(|keyword| normalize(keyword.as_ref(), self.flags))
+            .collect();
+        let automaton = Automaton::new(needles.iter().map(String::as_bytes));
+        let mut found = vec![None; keywords.len()];
+        automaton.for_each_match(self.text.as_bytes(), |index, start| {
+            // Occurrences of one keyword arrive in text order, so the first kept is the
+            // first acceptable one
+            if found[index].is_none()
+                && (self.flags & MATCH_FLAG_WHOLE_WORD == 0
+                    || is_word_boundary(&self.text, start, start + needles[index].len()))
+            {
+                found[index] = Some(start);
+            }
+        });
+        found
+    }
+
+    pub fn contains(&self, keyword: &str) -> bool {
+        self.find(keyword).is_some()
+    }
+
+    /// Up to `radius` bytes of text around a match, widened to char boundaries
+    pub fn context(&self, pos: usize, len: usize, radius: usize) -> &str {
+        let mut start = pos.saturating_sub(radius);
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (pos + len + radius).min(self.text.len());
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+        &self.text[start..end]
+    }
+}
+
+/// Compile a regex keyword. Case folding follows the campaign's case-sensitivity flag;
+/// patterns express word boundaries themselves with `\b`.
+/// Returns `None` for invalid patterns, and for patterns over the on-chain length cap,
+/// which rule bundles bypass.
+pub fn compile_pattern(pattern: &str, flags: u8) -> Option<Regex> {
+    if pattern.len() > MAX_REGEX_PATTERN_LEN {
+        return None;
+    }
+    Regex::builder()
+        .syntax(syntax::Config::new().case_insensitive(flags & MATCH_FLAG_CASE_SENSITIVE == 0))
+        .build(pattern)
+        .ok()
+}
+
+/// Folding scheme applied to both post text and keywords:
+/// 1. NFKC, so composed/decomposed accents and compatibility forms (full-width letters,
+///    ligatures) compare equal
+/// 2. drop zero-width characters and emoji variation selectors, which are invisible but
+///    split words and emoji
+/// 3. optionally strip diacritics ("Bónsol" matches "bonsol")
+/// 4. lowercase unless case-sensitive, then collapse whitespace unless exact-phrase
+fn normalize(text: &str, flags: u8) -> String {
+    let text: String = text.nfkc().filter(|&c| !is_invisible(c)).collect();
+    let text = if flags & MATCH_FLAG_FOLD_DIACRITICS != 0 {
+        text.nfd()
+            .filter(|&c| !is_combining_mark(c))
+            .nfc()
+            .collect()
+    } else {
+        text
+    };
+    let text = if flags & MATCH_FLAG_CASE_SENSITIVE != 0 {
+        text
+    } else {
+        text.to_lowercase()
+    };
+    if flags & MATCH_FLAG_EXACT_PHRASE != 0 {
+        text
+    } else {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}' // zero width space
+            | '\u{200C}' // zero width non-joiner
+            | '\u{200D}' // zero width joiner
+            | '\u{2060}' // word joiner
+            | '\u{FEFF}' // zero width no-break space
+            | '\u{FE0E}' // text presentation selector
+            | '\u{FE0F}' // emoji presentation selector
+    )
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `text[start..end]` is not glued to word characters on either side. Like regex
+/// `\b`, an edge that is itself a non-word character (`#`, emoji) needs no boundary.
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let matched = &text[start..end];
+    let first = matched.chars().next();
+    let last = matched.chars().next_back();
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let glued_before = first.is_some_and(is_word_char) && before.is_some_and(is_word_char);
+    let glued_after = last.is_some_and(is_word_char) && after.is_some_and(is_word_char);
+    !glued_before && !glued_after
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substring_matching_is_the_default() {
+        let matcher = KeywordMatcher::new("Building a Solution on Solana", 0);
+        assert!(matcher.contains("sol"));
+        assert!(matcher.contains("SOLANA"));
+    }
+
+    #[test]
+    fn whole_word_rejects_partial_words() {
+        let matcher = KeywordMatcher::new("Building a solution", MATCH_FLAG_WHOLE_WORD);
+        assert!(!matcher.contains("sol"));
+        assert!(matcher.contains("solution"));
+    }
+
+    #[test]
+    fn whole_word_accepts_punctuation_boundaries() {
+        let matcher = KeywordMatcher::new("gm (sol), #zk!", MATCH_FLAG_WHOLE_WORD);
+        assert!(matcher.contains("sol"));
+        assert!(matcher.contains("#zk"));
+        assert!(!matcher.contains("zk!s"));
+    }
+
+    #[test]
+    fn whole_word_checks_every_occurrence() {
+        let matcher = KeywordMatcher::new("solution then sol", MATCH_FLAG_WHOLE_WORD);
+        assert_eq!(matcher.find("sol"), Some(14));
+    }
+
+    #[test]
+    fn whitespace_is_collapsed_unless_exact_phrase() {
+        let loose = KeywordMatcher::new("proof  of\npost", 0);
+        assert!(loose.contains("proof of post"));
+
+        let exact = KeywordMatcher::new("proof  of\npost", MATCH_FLAG_EXACT_PHRASE);
+        assert!(!exact.contains("proof of post"));
+        assert!(exact.contains("proof  of\npost"));
+    }
+
+    #[test]
+    fn case_sensitive_matching() {
+        let matcher = KeywordMatcher::new("Bonsol ships", MATCH_FLAG_CASE_SENSITIVE);
+        assert!(matcher.contains("Bonsol"));
+        assert!(!matcher.contains("bonsol"));
+    }
+
+    #[test]
+    fn combined_flags() {
+        let flags = MATCH_FLAG_WHOLE_WORD | MATCH_FLAG_CASE_SENSITIVE;
+        let matcher = KeywordMatcher::new("SOLve it with SOL", flags);
+        assert_eq!(matcher.find("SOL"), Some(14));
+        assert!(!matcher.contains("sol"));
+    }
+
+    #[test]
+    fn word_boundaries_handle_multibyte_text() {
+        let matcher = KeywordMatcher::new("café sol über", MATCH_FLAG_WHOLE_WORD);
+        assert!(matcher.contains("sol"));
+        assert!(!matcher.contains("caf"));
+    }
+
+    #[test]
+    fn context_respects_char_boundaries() {
+        let matcher = KeywordMatcher::new("ééééé sol ééééé", 0);
+        let pos = matcher.find("sol").unwrap();
+        assert_eq!(matcher.context(pos, 3, 3), "é sol é");
+    }
+
+    #[test]
+    fn regex_matches_coupon_codes() {
+        let matcher = KeywordMatcher::new("use code BONSOL-2024 today", MATCH_FLAG_REGEX);
+        assert_eq!(matcher.find(r"bonsol-\d{4}"), Some(9));
+        assert!(!matcher.contains(r"bonsol-\d{5}"));
+    }
+
+    #[test]
+    fn regex_respects_case_sensitivity() {
+        let flags = MATCH_FLAG_REGEX | MATCH_FLAG_CASE_SENSITIVE;
+        let matcher = KeywordMatcher::new("Launch at example.com/launch", flags);
+        assert!(matcher.contains(r"example\.com/\w+"));
+        assert!(!matcher.contains(r"^launch"));
+    }
+
+    #[test]
+    fn regex_word_boundaries() {
+        let matcher = KeywordMatcher::new("solution sol", MATCH_FLAG_REGEX);
+        assert_eq!(matcher.find(r"\bsol\b"), Some(9));
+    }
+
+    #[test]
+    fn invalid_regex_never_matches() {
+        let matcher = KeywordMatcher::new("anything (", MATCH_FLAG_REGEX);
+        assert!(compile_pattern("(", MATCH_FLAG_REGEX).is_none());
+        assert!(!matcher.contains("("));
+    }
+
+    #[test]
+    fn overlong_regex_is_rejected() {
+        let pattern = "a".repeat(MAX_REGEX_PATTERN_LEN + 1);
+        assert!(compile_pattern(&pattern, MATCH_FLAG_REGEX).is_none());
+        assert!(compile_pattern(&pattern[1..], MATCH_FLAG_REGEX).is_some());
+    }
+
+    #[test]
+    fn composed_and_decomposed_accents_match() {
+        let matcher = KeywordMatcher::new("Building on Bo\u{301}nsol", 0);
+        assert!(matcher.contains("Bónsol"));
+    }
+
+    #[test]
+    fn diacritics_fold_only_when_enabled() {
+        let strict = KeywordMatcher::new("Bónsol rocks", 0);
+        assert!(!strict.contains("bonsol"));
+
+        let folded = KeywordMatcher::new("Bónsol rocks", MATCH_FLAG_FOLD_DIACRITICS);
+        assert!(folded.contains("bonsol"));
+        assert!(folded.contains("BÓNSOL"));
+    }
+
+    #[test]
+    fn compatibility_forms_are_folded() {
+        let matcher = KeywordMatcher::new("ｂｏｎｓｏｌ", MATCH_FLAG_WHOLE_WORD);
+        assert!(matcher.contains("bonsol"));
+    }
+
+    #[test]
+    fn zero_width_characters_are_ignored() {
+        let matcher = KeywordMatcher::new("bon\u{200B}sol and zk\u{FEFF}vm", MATCH_FLAG_WHOLE_WORD);
+        assert!(matcher.contains("bonsol"));
+        assert!(matcher.contains("zkvm"));
+    }
+
+    #[test]
+    fn emoji_keywords_ignore_variation_selectors() {
+        let matcher = KeywordMatcher::new("we \u{2764}\u{FE0F} bonsol", 0);
+        assert!(matcher.contains("\u{2764}"));
+        assert!(matcher.contains("\u{2764}\u{FE0F} bonsol"));
+    }
+
+    #[test]
+    fn emoji_adjacent_keywords_are_whole_words() {
+        let matcher = KeywordMatcher::new("🚀bonsol🚀 launch", MATCH_FLAG_WHOLE_WORD);
+        assert!(matcher.contains("bonsol"));
+        assert!(matcher.contains("🚀"));
+    }
+
+    #[test]
+    fn find_all_matches_each_keyword_like_find() {
+        let matcher =
+            KeywordMatcher::new("solution then sol, #zk and Bonsol", MATCH_FLAG_WHOLE_WORD);
+        let keywords = ["sol", "bonsol", "missing", "#zk", "sol"];
+        let found = matcher.find_all(&keywords);
+        assert_eq!(found, [Some(14), Some(27), None, Some(19), Some(14)]);
+        for (keyword, found) in keywords.iter().zip(&found) {
+            assert_eq!(matcher.find(keyword), *found);
+        }
+    }
+
+    #[test]
+    fn whole_word_finds_overlapping_occurrences() {
+        // The only standalone "ab ab" overlaps an earlier, glued one
+        let matcher = KeywordMatcher::new("xab ab ab", MATCH_FLAG_WHOLE_WORD);
+        assert_eq!(matcher.find("ab ab"), Some(4));
+    }
+
+    #[test]
+    fn empty_keyword_never_matches() {
+        let matcher = KeywordMatcher::new("anything", 0);
+        assert!(!matcher.contains("   "));
+    }
+}
@@ -0,0 +1,115 @@
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::links::{domain_matches, url_host};
+
+/// Embed on the author's post record
+#[derive(Debug, Deserialize)]
+#[serde(tag = "$type")]
+pub enum RecordEmbed {
+    #[serde(rename = "app.bsky.embed.images")]
+    Images { images: Vec<IgnoredAny> },
+    #[serde(rename = "app.bsky.embed.external")]
+    External { external: ExternalEmbed },
+    #[serde(rename = "app.bsky.embed.recordWithMedia")]
+    RecordWithMedia { media: Box<RecordEmbed> },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalEmbed {
+    pub uri: String,
+}
+
+/// Platform-neutral view of the media attached to a post
+#[derive(Debug, Default)]
+pub struct MediaSummary {
+    pub images: usize,
+    /// Target of the link card, if the post has one
+    pub external_uri: Option<String>,
+}
+
+impl RecordEmbed {
+    /// The embedded media, looking through quote posts that carry media
+    fn media(&self) -> &RecordEmbed {
+        match self {
+            RecordEmbed::RecordWithMedia { media } => media.media(),
+            other => other,
+        }
+    }
+
+    pub fn summary(&self) -> MediaSummary {
+        match self.media() {
+            RecordEmbed::Images { images } => MediaSummary {
+                images: images.len(),
+                external_uri: None,
+            },
+            RecordEmbed::External { external } => MediaSummary {
+                images: 0,
+                external_uri: Some(external.uri.clone()),
+            },
+            _ => MediaSummary::default(),
+        }
+    }
+}
+
+/// Whether the post's media satisfies the campaign's media requirement: at least
+/// `min_images` images, or a link card on `external_domain`. With neither configured,
+/// any post passes.
+pub fn has_required_media(media: &MediaSummary, min_images: u8, external_domain: &str) -> bool {
+    if min_images == 0 && external_domain.is_empty() {
+        return true;
+    }
+    let images_ok = min_images > 0 && media.images >= min_images as usize;
+    let external_ok = !external_domain.is_empty()
+        && media
+            .external_uri
+            .as_deref()
+            .and_then(url_host)
+            .is_some_and(|host| domain_matches(&host, external_domain));
+    images_ok || external_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embed(json: &str) -> MediaSummary {
+        serde_json::from_str::<RecordEmbed>(json).unwrap().summary()
+    }
+
+    #[test]
+    fn counts_images() {
+        let images =
+            embed(r#"{"$type": "app.bsky.embed.images", "images": [{"alt": "a"}, {"alt": "b"}]}"#);
+        assert!(has_required_media(&images, 2, ""));
+        assert!(!has_required_media(&images, 3, ""));
+        assert!(!has_required_media(&MediaSummary::default(), 1, ""));
+    }
+
+    #[test]
+    fn external_embed_must_be_on_domain() {
+        let external = embed(
+            r#"{"$type": "app.bsky.embed.external", "external": {"uri": "https://blog.Example.com/launch", "title": "t"}}"#,
+        );
+        assert!(has_required_media(&external, 0, "example.com"));
+        assert!(!has_required_media(&external, 0, "other.com"));
+        assert!(!has_required_media(&external, 1, ""));
+    }
+
+    #[test]
+    fn media_inside_quote_posts_counts() {
+        let quote = embed(
+            r#"{"$type": "app.bsky.embed.recordWithMedia", "record": {}, "media": {"$type": "app.bsky.embed.images", "images": [{}]}}"#,
+        );
+        assert!(has_required_media(&quote, 1, ""));
+    }
+
+    #[test]
+    fn unknown_embeds_fail_a_requirement() {
+        let video = embed(r#"{"$type": "app.bsky.embed.video", "video": {}}"#);
+        assert!(!has_required_media(&video, 1, ""));
+        assert!(has_required_media(&video, 0, ""));
+    }
+}
@@ -0,0 +1,303 @@
+use proof_of_post_common::PostMetrics;
+use serde::Deserialize;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::facets::{facet_links, facet_mentions, facet_tags, Facet};
+use crate::labels::{labels_in_effect, Label};
+use crate::media::RecordEmbed;
+use crate::time::parse_rfc3339;
+
+/// `app.bsky.feed.getPosts` responses
+pub struct Bluesky;
+
+/// `app.bsky.feed.getPostThread` responses, for thread campaigns. The anchor post is
+/// normalized like a single post, and the author's own replies continuing it become
+/// [`NormalizedPost::thread`].
+pub struct BlueskyThread;
+
+#[derive(Debug, Deserialize)]
+struct GetPostsResponse {
+    #[serde(deserialize_with = "super::first")]
+    posts: Option<PostView>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetPostThreadResponse {
+    thread: ThreadNode,
+}
+
+/// A `threadViewPost`. Not-found and blocked entries carry no `post`.
+#[derive(Debug, Deserialize)]
+struct ThreadNode {
+    #[serde(default)]
+    post: Option<PostView>,
+    #[serde(default)]
+    replies: Vec<ThreadNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostView {
+    uri: String,
+    author: Author,
+    record: PostRecord,
+    #[serde(rename = "replyCount", default)]
+    reply_count: u64,
+    #[serde(rename = "repostCount", default)]
+    repost_count: u64,
+    #[serde(rename = "likeCount", default)]
+    like_count: u64,
+    #[serde(rename = "quoteCount", default)]
+    quote_count: u64,
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    did: String,
+    handle: String,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostRecord {
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    text: String,
+    #[serde(default)]
+    langs: Vec<String>,
+    #[serde(default)]
+    embed: Option<RecordEmbed>,
+    #[serde(default)]
+    facets: Vec<Facet>,
+    #[serde(default)]
+    reply: Option<ReplyRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplyRef {
+    parent: StrongRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct StrongRef {
+    uri: String,
+}
+
+impl PlatformAdapter for Bluesky {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: GetPostsResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let post = response.posts.ok_or(ParseError::NoPost)?;
+        Ok(normalize(post))
+    }
+}
+
+impl PlatformAdapter for BlueskyThread {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: GetPostThreadResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let ThreadNode { post, replies } = response.thread;
+        let post = post.ok_or(ParseError::NoPost)?;
+        let mut thread = Vec::new();
+        collect_author_replies(&post.author.did, replies, &mut thread);
+        Ok(NormalizedPost {
+            thread,
+            ..normalize(post)
+        })
+    }
+}
+
+/// Texts of the posts continuing a thread: replies by `author` to the author's own
+/// posts, depth first. The author's answers to other people's replies don't count.
+fn collect_author_replies(author: &str, replies: Vec<ThreadNode>, thread: &mut Vec<String>) {
+    for reply in replies {
+        let Some(post) = reply.post else {
+            continue;
+        };
+        if post.author.did == author {
+            thread.push(post.record.text);
+            collect_author_replies(author, reply.replies, thread);
+        }
+    }
+}
+
+fn normalize(post: PostView) -> NormalizedPost {
+    let record = post.record;
+    // Links come from rich-text link facets and the external link card
+    let media = record
+        .embed
+        .as_ref()
+        .map(RecordEmbed::summary)
+        .unwrap_or_default();
+    let mut links = facet_links(&record.facets);
+    if let Some(RecordEmbed::External { external }) = &record.embed {
+        links.push(external.uri.clone());
+    }
+    let labels = labels_in_effect(&post.labels)
+        .chain(labels_in_effect(&post.author.labels))
+        .map(String::from)
+        .collect();
+
+    NormalizedPost {
+        uri: post.uri,
+        author_id: post.author.did,
+        author_handle: post.author.handle,
+        // getPosts only carries a ProfileViewBasic, which has no bio, so authorship
+        // binds through the post text alone
+        author_bio: String::new(),
+        author_created_at: post.author.created_at.as_deref().and_then(parse_rfc3339),
+        // Nor a follower count, so campaigns can't require one
+        author_followers: None,
+        tags: facet_tags(&record.facets),
+        mentions: facet_mentions(&record.text, &record.facets),
+        links,
+        media,
+        created_at: parse_rfc3339(&record.created_at),
+        langs: record.langs,
+        parent_uri: record.reply.map(|reply| reply.parent.uri),
+        community: None,
+        labels,
+        metrics: PostMetrics {
+            like_count: post.like_count,
+            repost_count: post.repost_count,
+            reply_count: post.reply_count,
+            quote_count: post.quote_count,
+            view_count: 0,
+        },
+        text: record.text,
+        thread: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{"posts": [{
+        "uri": "at://did:plc:abc/app.bsky.feed.post/1",
+        "cid": "bafy",
+        "author": {
+            "did": "did:plc:abc",
+            "handle": "bonsol.bsky.social",
+            "displayName": "Bonsol",
+            "avatar": "https://cdn.bsky.app/img/avatar/plain/did:plc:abc/bafkrei@jpeg",
+            "associated": { "chat": { "allowIncoming": "following" } },
+            "viewer": { "muted": false, "blockedBy": false },
+            "labels": [{ "src": "did:plc:abc", "val": "!no-unauthenticated" }],
+            "createdAt": "2023-01-01T00:00:00Z"
+        },
+        "record": {
+            "$type": "app.bsky.feed.post",
+            "createdAt": "2024-06-01T12:00:00Z",
+            "text": "gm #bonsol",
+            "langs": ["en"],
+            "facets": [{
+                "index": { "byteStart": 3, "byteEnd": 10 },
+                "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "bonsol" }]
+            }],
+            "embed": {
+                "$type": "app.bsky.embed.external",
+                "external": { "uri": "https://bonsol.sh/launch" }
+            },
+            "reply": {
+                "parent": { "uri": "at://did:plc:xyz/app.bsky.feed.post/9", "cid": "p" },
+                "root": { "uri": "at://did:plc:xyz/app.bsky.feed.post/9", "cid": "p" }
+            }
+        },
+        "likeCount": 7,
+        "replyCount": 1,
+        "indexedAt": "2024-06-01T12:00:01Z"
+    }]}"##;
+
+    #[test]
+    fn normalizes_get_posts_response() {
+        let post = Bluesky.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "at://did:plc:abc/app.bsky.feed.post/1");
+        assert_eq!(post.author_id, "did:plc:abc");
+        assert_eq!(post.author_handle, "bonsol.bsky.social");
+        assert!(post.author_bio.is_empty());
+        assert_eq!(post.author_followers, None);
+        assert_eq!(post.author_created_at, Some(1_672_531_200));
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.tags, ["bonsol"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(
+            post.media.external_uri.as_deref(),
+            Some("https://bonsol.sh/launch")
+        );
+        assert_eq!(
+            post.parent_uri.as_deref(),
+            Some("at://did:plc:xyz/app.bsky.feed.post/9")
+        );
+        assert_eq!(post.labels, ["!no-unauthenticated"]);
+        assert_eq!(post.metrics.like_count, 7);
+        assert_eq!(post.metrics.reply_count, 1);
+    }
+
+    #[test]
+    fn posts_after_the_first_are_skipped() {
+        // Later entries are only scanned past, so their shape doesn't matter
+        let response = RESPONSE.replacen("}]}", r#"}, {"uri": 1}, [null]]}"#, 1);
+        let post = Bluesky.parse(&mut response.as_bytes()).unwrap();
+        assert_eq!(post.uri, "at://did:plc:abc/app.bsky.feed.post/1");
+    }
+
+    #[test]
+    fn empty_response_has_no_post() {
+        let result = Bluesky.parse(&mut r#"{"posts": []}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+
+    #[test]
+    fn other_shapes_are_malformed() {
+        let result = Bluesky.parse(&mut r#"{"data": {}}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::Malformed);
+    }
+
+    fn thread_post(did: &str, n: u32, text: &str, replies: &str) -> String {
+        format!(
+            r#"{{"$type": "app.bsky.feed.defs#threadViewPost", "post": {{
+                "uri": "at://{did}/app.bsky.feed.post/{n}",
+                "author": {{ "did": "{did}", "handle": "h" }},
+                "record": {{ "createdAt": "2024-06-01T12:00:00Z", "text": "{text}" }}
+            }}, "replies": [{replies}]}}"#
+        )
+    }
+
+    #[test]
+    fn thread_collects_the_authors_own_continuation() {
+        let author = "did:plc:abc";
+        let other = "did:plc:xyz";
+        let answer_to_other = thread_post(author, 5, "thanks!", "");
+        let replies = [
+            thread_post(
+                author,
+                2,
+                "2/ proofs",
+                &thread_post(author, 3, "3/ bonsol", ""),
+            ),
+            thread_post(other, 4, "nice", &answer_to_other),
+            r#"{"$type": "app.bsky.feed.defs#notFoundPost", "notFound": true}"#.into(),
+        ]
+        .join(",");
+        let response = format!(
+            r#"{{"thread": {}}}"#,
+            thread_post(author, 1, "1/ gm", &replies)
+        );
+        let post = BlueskyThread.parse(&mut response.as_bytes()).unwrap();
+        assert_eq!(post.text, "1/ gm");
+        assert_eq!(post.thread, ["2/ proofs", "3/ bonsol"]);
+    }
+
+    #[test]
+    fn missing_thread_anchor_has_no_post() {
+        let response = r#"{"thread": {"$type": "app.bsky.feed.defs#notFoundPost"}}"#;
+        let result = BlueskyThread.parse(&mut response.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
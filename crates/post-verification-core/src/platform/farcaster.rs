@@ -0,0 +1,225 @@
+use proof_of_post_common::PostMetrics;
+use serde::Deserialize;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::media::MediaSummary;
+use crate::time::parse_rfc3339;
+
+/// Neynar `GET /v2/farcaster/cast` responses. Raw hub messages carry no reaction
+/// counts, so casts are read through Neynar's hydrated view.
+pub struct Farcaster;
+
+/// Casts are identified by their hash, the same shape for the cast itself and the cast
+/// it replies to
+const CAST_URI_PREFIX: &str = "farcaster://casts/";
+
+#[derive(Debug, Deserialize)]
+struct CastResponse {
+    cast: Option<Cast>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cast {
+    hash: String,
+    #[serde(default)]
+    parent_hash: Option<String>,
+    author: User,
+    text: String,
+    timestamp: String,
+    #[serde(default)]
+    embeds: Vec<Embed>,
+    #[serde(default)]
+    reactions: Reactions,
+    #[serde(default)]
+    replies: Replies,
+    #[serde(default)]
+    mentioned_profiles: Vec<MentionedProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    fid: u64,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    profile: Option<Profile>,
+    #[serde(default)]
+    follower_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Profile {
+    #[serde(default)]
+    bio: Option<Bio>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bio {
+    #[serde(default)]
+    text: String,
+}
+
+/// URL embeds; cast embeds (quotes) have no `url` and are skipped
+#[derive(Debug, Deserialize)]
+struct Embed {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    metadata: Option<EmbedMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedMetadata {
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Reactions {
+    #[serde(default)]
+    likes_count: u64,
+    #[serde(default)]
+    recasts_count: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Replies {
+    #[serde(default)]
+    count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MentionedProfile {
+    username: String,
+}
+
+impl Embed {
+    fn is_image(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.content_type.as_deref())
+            .is_some_and(|content_type| content_type.starts_with("image/"))
+    }
+}
+
+fn cast_uri(hash: &str) -> String {
+    format!("{CAST_URI_PREFIX}{hash}")
+}
+
+impl PlatformAdapter for Farcaster {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: CastResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let cast = response.cast.ok_or(ParseError::NoPost)?;
+
+        let url_embeds = cast.embeds.iter().filter(|embed| embed.url.is_some());
+        let images = url_embeds.clone().filter(|embed| embed.is_image()).count();
+        // The first non-image URL embed renders as the cast's link card
+        let external_uri = url_embeds
+            .clone()
+            .find(|embed| !embed.is_image())
+            .and_then(|embed| embed.url.clone());
+        let links = url_embeds
+            .filter(|embed| !embed.is_image())
+            .filter_map(|embed| embed.url.clone())
+            .collect();
+
+        Ok(NormalizedPost {
+            uri: cast_uri(&cast.hash),
+            author_id: cast.author.fid.to_string(),
+            author_handle: cast.author.username,
+            author_bio: cast
+                .author
+                .profile
+                .and_then(|profile| profile.bio)
+                .map(|bio| bio.text)
+                .unwrap_or_default(),
+            // Neither hubs nor Neynar expose account creation time
+            author_created_at: None,
+            author_followers: cast.author.follower_count,
+            text: cast.text,
+            thread: Vec::new(),
+            created_at: parse_rfc3339(&cast.timestamp),
+            langs: Vec::new(),
+            tags: Vec::new(),
+            mentions: cast
+                .mentioned_profiles
+                .into_iter()
+                .map(|profile| profile.username)
+                .collect(),
+            links,
+            media: MediaSummary {
+                images,
+                external_uri,
+            },
+            parent_uri: cast.parent_hash.as_deref().map(cast_uri),
+            community: None,
+            labels: Vec::new(),
+            metrics: PostMetrics {
+                like_count: cast.reactions.likes_count,
+                repost_count: cast.reactions.recasts_count,
+                reply_count: cast.replies.count,
+                quote_count: 0,
+                view_count: 0,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{"cast": {
+        "object": "cast",
+        "hash": "0xabc",
+        "parent_hash": "0xdef",
+        "parent_url": null,
+        "author": {
+            "object": "user",
+            "fid": 3,
+            "username": "poster",
+            "profile": { "bio": { "text": "wallet in bio" } },
+            "follower_count": 120
+        },
+        "text": "gm @bonsol https://bonsol.sh/launch",
+        "timestamp": "2024-06-01T12:00:00.000Z",
+        "embeds": [
+            { "url": "https://bonsol.sh/launch", "metadata": { "content_type": "text/html" } },
+            { "url": "https://imagedelivery.net/x.png", "metadata": { "content_type": "image/png" } },
+            { "cast_id": { "fid": 9, "hash": "0x123" } }
+        ],
+        "reactions": { "likes_count": 7, "recasts_count": 2, "likes": [] },
+        "replies": { "count": 1 },
+        "mentioned_profiles": [{ "fid": 5, "username": "bonsol" }]
+    }}"##;
+
+    #[test]
+    fn normalizes_cast() {
+        let post = Farcaster.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "farcaster://casts/0xabc");
+        assert_eq!(post.author_id, "3");
+        assert_eq!(post.author_handle, "poster");
+        assert_eq!(post.author_bio, "wallet in bio");
+        assert_eq!(post.author_followers, Some(120));
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.mentions, ["bonsol"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.media.images, 1);
+        assert_eq!(
+            post.media.external_uri.as_deref(),
+            Some("https://bonsol.sh/launch")
+        );
+        assert_eq!(post.parent_uri.as_deref(), Some("farcaster://casts/0xdef"));
+        assert_eq!(post.metrics.like_count, 7);
+        assert_eq!(post.metrics.repost_count, 2);
+        assert_eq!(post.metrics.reply_count, 1);
+    }
+
+    #[test]
+    fn missing_cast_has_no_post() {
+        let result = Farcaster.parse(&mut r#"{"cast": null}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
@@ -0,0 +1,259 @@
+use proof_of_post_common::PostMetrics;
+use serde::Deserialize;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::media::MediaSummary;
+use crate::time::parse_rfc3339;
+
+/// Lens API v2 `publication` query responses, selecting `id`, `createdAt`,
+/// `by { id handle { fullHandle } createdAt metadata { bio } stats { followers } }`,
+/// `metadata { content tags locale asset attachments }`, `profilesMentioned`,
+/// `commentOn { id }` and `stats { reactions mirrors comments quotes }`
+pub struct Lens;
+
+/// Publications are identified by their publication id, the same shape for the
+/// publication itself and the one it comments on
+const PUBLICATION_URI_PREFIX: &str = "lens://publications/";
+
+/// Media type names Lens uses for images in `asset` and `attachments`
+const IMAGE_TYPENAME: &str = "PublicationMetadataMediaImage";
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse {
+    #[serde(default)]
+    data: Option<PublicationData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicationData {
+    publication: Option<Publication>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Publication {
+    id: String,
+    by: Profile,
+    created_at: String,
+    metadata: PublicationMetadata,
+    #[serde(default)]
+    stats: PublicationStats,
+    #[serde(default)]
+    profiles_mentioned: Vec<ProfileMentioned>,
+    #[serde(default)]
+    comment_on: Option<PublicationRef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    id: String,
+    #[serde(default)]
+    handle: Option<Handle>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    metadata: Option<ProfileMetadata>,
+    #[serde(default)]
+    stats: Option<ProfileStats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Handle {
+    full_handle: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileMetadata {
+    #[serde(default)]
+    bio: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileStats {
+    followers: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicationMetadata {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    asset: Option<Media>,
+    #[serde(default)]
+    attachments: Option<Vec<Media>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Media {
+    #[serde(rename = "__typename")]
+    typename: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PublicationStats {
+    #[serde(default)]
+    reactions: u64,
+    #[serde(default)]
+    mirrors: u64,
+    #[serde(default)]
+    comments: u64,
+    #[serde(default)]
+    quotes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileMentioned {
+    profile: MentionedProfile,
+}
+
+#[derive(Debug, Deserialize)]
+struct MentionedProfile {
+    #[serde(default)]
+    handle: Option<Handle>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicationRef {
+    id: String,
+}
+
+fn publication_uri(id: &str) -> String {
+    format!("{PUBLICATION_URI_PREFIX}{id}")
+}
+
+impl PlatformAdapter for Lens {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: GraphqlResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let publication = response
+            .data
+            .and_then(|data| data.publication)
+            .ok_or(ParseError::NoPost)?;
+
+        let metadata = publication.metadata;
+        let images = metadata
+            .asset
+            .iter()
+            .chain(metadata.attachments.iter().flatten())
+            .filter(|media| media.typename == IMAGE_TYPENAME)
+            .count();
+        // Lens has no link annotations, so links are the URLs written in the content
+        let links = metadata
+            .content
+            .split_whitespace()
+            .filter(|token| token.starts_with("https://") || token.starts_with("http://"))
+            .map(String::from)
+            .collect();
+        let author = publication.by;
+
+        Ok(NormalizedPost {
+            uri: publication_uri(&publication.id),
+            author_id: author.id,
+            author_handle: author
+                .handle
+                .map(|handle| handle.full_handle)
+                .unwrap_or_default(),
+            author_bio: author
+                .metadata
+                .and_then(|metadata| metadata.bio)
+                .unwrap_or_default(),
+            author_created_at: author.created_at.as_deref().and_then(parse_rfc3339),
+            author_followers: author.stats.map(|stats| stats.followers),
+            created_at: parse_rfc3339(&publication.created_at),
+            langs: metadata.locale.into_iter().collect(),
+            tags: metadata.tags.unwrap_or_default(),
+            mentions: publication
+                .profiles_mentioned
+                .into_iter()
+                .filter_map(|mentioned| mentioned.profile.handle)
+                .map(|handle| handle.full_handle)
+                .collect(),
+            links,
+            media: MediaSummary {
+                images,
+                external_uri: None,
+            },
+            parent_uri: publication
+                .comment_on
+                .map(|parent| publication_uri(&parent.id)),
+            community: None,
+            labels: Vec::new(),
+            metrics: PostMetrics {
+                like_count: publication.stats.reactions,
+                repost_count: publication.stats.mirrors,
+                reply_count: publication.stats.comments,
+                quote_count: publication.stats.quotes,
+                view_count: 0,
+            },
+            text: metadata.content,
+            thread: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{"data": {"publication": {
+        "__typename": "Comment",
+        "id": "0x01-0x2a",
+        "createdAt": "2024-06-01T12:00:00.000Z",
+        "by": {
+            "id": "0x01",
+            "handle": { "fullHandle": "lens/poster" },
+            "createdAt": "2023-01-01T00:00:00.000Z",
+            "metadata": { "bio": "wallet in bio" },
+            "stats": { "followers": 120 }
+        },
+        "metadata": {
+            "__typename": "ImageMetadataV3",
+            "content": "gm #bonsol https://bonsol.sh/launch",
+            "tags": ["bonsol"],
+            "locale": "en",
+            "asset": { "__typename": "PublicationMetadataMediaImage" },
+            "attachments": [{ "__typename": "PublicationMetadataMediaVideo" }]
+        },
+        "profilesMentioned": [{ "profile": { "handle": { "fullHandle": "lens/bonsol" } } }],
+        "commentOn": { "id": "0x02-0x01" },
+        "stats": { "reactions": 7, "mirrors": 2, "comments": 1, "quotes": 0 }
+    }}}"##;
+
+    #[test]
+    fn normalizes_publication() {
+        let post = Lens.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "lens://publications/0x01-0x2a");
+        assert_eq!(post.author_id, "0x01");
+        assert_eq!(post.author_handle, "lens/poster");
+        assert_eq!(post.author_bio, "wallet in bio");
+        assert_eq!(post.author_followers, Some(120));
+        assert_eq!(post.author_created_at, Some(1_672_531_200));
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.langs, ["en"]);
+        assert_eq!(post.tags, ["bonsol"]);
+        assert_eq!(post.mentions, ["lens/bonsol"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.media.images, 1);
+        assert_eq!(
+            post.parent_uri.as_deref(),
+            Some("lens://publications/0x02-0x01")
+        );
+        assert_eq!(post.metrics.like_count, 7);
+        assert_eq!(post.metrics.repost_count, 2);
+    }
+
+    #[test]
+    fn missing_publication_has_no_post() {
+        let result = Lens.parse(&mut r#"{"data": {"publication": null}}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+        let result = Lens.parse(&mut r#"{"errors": [{"message": "bad id"}]}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
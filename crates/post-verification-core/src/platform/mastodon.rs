@@ -0,0 +1,206 @@
+use proof_of_post_common::PostMetrics;
+use serde::Deserialize;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::html::{html_links, strip_html};
+use crate::links::url_host;
+use crate::media::MediaSummary;
+use crate::time::parse_rfc3339;
+
+/// Mastodon `GET /api/v1/statuses/:id` responses. The API only gives the local id of
+/// the status replied to, so reply campaigns aren't supported.
+pub struct Mastodon;
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    /// ActivityPub id, stable across instances
+    uri: String,
+    created_at: String,
+    content: String,
+    #[serde(default)]
+    language: Option<String>,
+    account: Account,
+    #[serde(default)]
+    replies_count: u64,
+    #[serde(default)]
+    reblogs_count: u64,
+    #[serde(default)]
+    favourites_count: u64,
+    #[serde(default)]
+    media_attachments: Vec<MediaAttachment>,
+    #[serde(default)]
+    mentions: Vec<Mention>,
+    #[serde(default)]
+    tags: Vec<Tag>,
+    #[serde(default)]
+    card: Option<Card>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    /// `user` for accounts on the queried instance, `user@domain` otherwise
+    acct: String,
+    /// Profile URL on the account's home instance
+    url: String,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    followers_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaAttachment {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Mention {
+    acct: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Card {
+    url: String,
+}
+
+impl Account {
+    /// `user@domain`, whichever instance served the status
+    fn qualified_acct(&self) -> String {
+        match url_host(&self.url) {
+            Some(host) if !self.acct.contains('@') => format!("{}@{}", self.acct, host),
+            _ => self.acct.clone(),
+        }
+    }
+}
+
+impl PlatformAdapter for Mastodon {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let status: Status =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+
+        let images = status
+            .media_attachments
+            .iter()
+            .filter(|media| media.kind == "image")
+            .count();
+        let external_uri = status.card.map(|card| card.url);
+        // Links come from the status HTML and the preview card
+        let mut links = html_links(&status.content);
+        links.extend(external_uri.iter().cloned());
+
+        Ok(NormalizedPost {
+            uri: status.uri,
+            author_id: status.account.qualified_acct(),
+            author_handle: status.account.qualified_acct(),
+            author_bio: strip_html(&status.account.note),
+            author_created_at: status.account.created_at.as_deref().and_then(parse_rfc3339),
+            author_followers: status.account.followers_count,
+            // Keywords match the visible text, not the markup
+            text: strip_html(&status.content),
+            thread: Vec::new(),
+            created_at: parse_rfc3339(&status.created_at),
+            langs: status.language.into_iter().collect(),
+            tags: status.tags.into_iter().map(|tag| tag.name).collect(),
+            mentions: status
+                .mentions
+                .into_iter()
+                .map(|mention| mention.acct)
+                .collect(),
+            links,
+            media: MediaSummary {
+                images,
+                external_uri,
+            },
+            parent_uri: None,
+            community: None,
+            labels: Vec::new(),
+            metrics: PostMetrics {
+                like_count: status.favourites_count,
+                repost_count: status.reblogs_count,
+                reply_count: status.replies_count,
+                quote_count: 0,
+                view_count: 0,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{
+        "id": "112",
+        "uri": "https://mastodon.social/users/poster/statuses/112",
+        "url": "https://mastodon.social/@poster/112",
+        "created_at": "2024-06-01T12:00:00.000Z",
+        "in_reply_to_id": null,
+        "sensitive": false,
+        "language": "en",
+        "content": "<p>gm <a href=\"https://mastodon.social/tags/bonsol\" class=\"mention hashtag\" rel=\"tag\">#<span>bonsol</span></a> <a href=\"https://bonsol.sh/launch\" rel=\"nofollow\">bonsol.sh/launch</a></p>",
+        "account": {
+            "id": "1",
+            "username": "poster",
+            "acct": "poster",
+            "url": "https://mastodon.social/@poster",
+            "note": "<p>wallet &amp; bio</p>",
+            "created_at": "2023-01-01T00:00:00.000Z",
+            "followers_count": 120
+        },
+        "replies_count": 1,
+        "reblogs_count": 2,
+        "favourites_count": 7,
+        "media_attachments": [{ "type": "image" }, { "type": "video" }],
+        "mentions": [{ "id": "9", "username": "team", "acct": "team@bonsol.social" }],
+        "tags": [{ "name": "bonsol", "url": "https://mastodon.social/tags/bonsol" }],
+        "card": null
+    }"##;
+
+    #[test]
+    fn normalizes_status() {
+        let post = Mastodon.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(
+            post.uri,
+            "https://mastodon.social/users/poster/statuses/112"
+        );
+        assert_eq!(post.author_id, "poster@mastodon.social");
+        assert_eq!(post.author_bio, "wallet & bio");
+        assert_eq!(post.author_followers, Some(120));
+        assert_eq!(post.text, "gm #bonsol bonsol.sh/launch");
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.langs, ["en"]);
+        assert_eq!(post.tags, ["bonsol"]);
+        assert_eq!(post.mentions, ["team@bonsol.social"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.media.images, 1);
+        assert_eq!(post.metrics.like_count, 7);
+        assert_eq!(post.metrics.repost_count, 2);
+    }
+
+    #[test]
+    fn remote_accounts_keep_their_domain() {
+        let account = Account {
+            acct: String::from("poster@other.example"),
+            url: String::from("https://other.example/@poster"),
+            note: String::new(),
+            created_at: None,
+            followers_count: None,
+        };
+        assert_eq!(account.qualified_acct(), "poster@other.example");
+    }
+
+    #[test]
+    fn error_responses_are_malformed() {
+        let result = Mastodon.parse(&mut r#"{"error": "Record not found"}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::Malformed);
+    }
+}
@@ -0,0 +1,131 @@
+use proof_of_post_common::{
+    PostMetrics, PLATFORM_BLUESKY, PLATFORM_FARCASTER, PLATFORM_LENS, PLATFORM_MASTODON,
+    PLATFORM_REDDIT, PLATFORM_TWITTER, PLATFORM_YOUTUBE,
+};
+use serde::de::{IgnoredAny, SeqAccess, Visitor};
+use serde::Deserializer;
+use std::fmt;
+use std::io::Read;
+
+use crate::media::MediaSummary;
+
+mod bluesky;
+mod farcaster;
+mod lens;
+mod mastodon;
+mod reddit;
+mod twitter;
+mod youtube;
+
+pub use bluesky::{Bluesky, BlueskyThread};
+pub use farcaster::Farcaster;
+pub use lens::Lens;
+pub use mastodon::Mastodon;
+pub use reddit::Reddit;
+pub use twitter::Twitter;
+pub use youtube::YouTube;
+
+/// A post as every campaign check sees it, whatever platform it came from
+#[derive(Debug, Default)]
+pub struct NormalizedPost {
+    /// Stable post identifier: the AT URI on Bluesky, the status URL on X, the
+    /// ActivityPub id on Mastodon, `farcaster://casts/<hash>` on Farcaster,
+    /// `lens://publications/<id>` on Lens, `reddit://<fullname>` on Reddit, the watch
+    /// URL on YouTube
+    pub uri: String,
+    /// Stable author identifier: the DID on Bluesky, the numeric user id on X,
+    /// `user@domain` on Mastodon, the fid on Farcaster, the profile id on Lens, the
+    /// account fullname on Reddit, the channel id on YouTube
+    pub author_id: String,
+    /// Human-readable handle, which can change hands; empty when the response has none
+    pub author_handle: String,
+    /// Profile bio, which authorship binding also searches; empty when the response
+    /// carries no bio
+    pub author_bio: String,
+    /// Unix time the author's account was created, when the response includes it
+    pub author_created_at: Option<i64>,
+    pub author_followers: Option<u64>,
+    pub text: String,
+    /// Unix creation time; `None` when missing or unparseable
+    pub created_at: Option<i64>,
+    pub langs: Vec<String>,
+    /// Hashtags carried as structured annotations, without the leading `#`
+    pub tags: Vec<String>,
+    /// Mentioned handles carried as structured annotations, without the leading `@`
+    pub mentions: Vec<String>,
+    /// Link targets from annotations and link cards
+    pub links: Vec<String>,
+    pub media: MediaSummary,
+    /// Identifier of the post this one directly replies to
+    pub parent_uri: Option<String>,
+    /// Community the post was made in, on platforms that have them (the subreddit)
+    pub community: Option<String>,
+    /// Moderation labels in effect on the post or its author
+    pub labels: Vec<String>,
+    pub metrics: PostMetrics,
+    /// In thread mode, texts of the author's own posts continuing the thread below this
+    /// one; empty otherwise
+    pub thread: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The response isn't the platform's API shape
+    Malformed,
+    /// The response parsed but holds no post
+    NoPost,
+}
+
+/// Turns a platform's API response into a [`NormalizedPost`]
+pub trait PlatformAdapter {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError>;
+}
+
+/// Adapter for a `PLATFORM_*` id from the public input
+pub fn adapter(platform: u8) -> Option<&'static dyn PlatformAdapter> {
+    match platform {
+        PLATFORM_BLUESKY => Some(&Bluesky),
+        PLATFORM_TWITTER => Some(&Twitter),
+        PLATFORM_MASTODON => Some(&Mastodon),
+        PLATFORM_FARCASTER => Some(&Farcaster),
+        PLATFORM_LENS => Some(&Lens),
+        PLATFORM_REDDIT => Some(&Reddit),
+        PLATFORM_YOUTUBE => Some(&YouTube),
+        _ => None,
+    }
+}
+
+/// Adapter reading a whole thread in thread mode, for platforms that support it
+pub fn thread_adapter(platform: u8) -> Option<&'static dyn PlatformAdapter> {
+    match platform {
+        PLATFORM_BLUESKY => Some(&BlueskyThread),
+        _ => None,
+    }
+}
+
+/// Deserializes only the first element of a JSON array, skipping the rest without
+/// allocating them. Responses are lists holding the one verified post first, so the
+/// guest never pays to build the others.
+fn first<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    struct First<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T: serde::Deserialize<'de>> Visitor<'de> for First<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an array")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let first = seq.next_element()?;
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            Ok(first)
+        }
+    }
+
+    deserializer.deserialize_seq(First(std::marker::PhantomData))
+}
@@ -0,0 +1,279 @@
+use proof_of_post_common::PostMetrics;
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::media::MediaSummary;
+
+/// Reddit `.json` responses: a post permalink (`/comments/<id>.json`, the post listing
+/// followed by its comments) or `/api/info.json?id=t1_<id>` for a single comment. Either
+/// way the verified item is the first child of the first listing.
+pub struct Reddit;
+
+/// Items are identified by their fullname (`t3_` posts, `t1_` comments), which is also
+/// what a comment's `parent_id` holds
+const ITEM_URI_PREFIX: &str = "reddit://";
+
+/// Reddit has no label system; NSFW-marked items carry this label value instead
+const NSFW_LABEL: &str = "nsfw";
+
+/// The listing holding the verified item: the first of a thread's listings or the lone
+/// listing of an info lookup. Decoded in a single pass, so a thread's comment listing is
+/// skipped rather than buffered the way an untagged enum would.
+struct Response(Option<Listing>);
+
+impl<'de> Deserialize<'de> for Response {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ResponseVisitor;
+
+        impl<'de> Visitor<'de> for ResponseVisitor {
+            type Value = Response;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a listing or an array of listings")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Response, A::Error> {
+                let first = seq.next_element()?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(Response(first))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Response, A::Error> {
+                Listing::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+                    .map(|listing| Response(Some(listing)))
+            }
+        }
+
+        deserializer.deserialize_any(ResponseVisitor)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingData {
+    #[serde(default, deserialize_with = "super::first")]
+    children: Option<Thing>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thing {
+    data: Item,
+}
+
+/// Fields shared by posts (`t3`) and comments (`t1`); each kind leaves the other's empty
+#[derive(Debug, Deserialize)]
+struct Item {
+    /// Fullname, e.g. `t3_1abcde`
+    name: String,
+    author: String,
+    /// Account fullname, missing for deleted accounts
+    #[serde(default)]
+    author_fullname: Option<String>,
+    subreddit: String,
+    created_utc: f64,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    over_18: bool,
+    // Posts
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    selftext: String,
+    #[serde(default)]
+    is_self: bool,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    post_hint: Option<String>,
+    #[serde(default)]
+    gallery_data: Option<GalleryData>,
+    #[serde(default)]
+    num_comments: u64,
+    #[serde(default)]
+    num_crossposts: u64,
+    // Comments
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GalleryData {
+    items: Vec<IgnoredAny>,
+}
+
+fn item_uri(fullname: &str) -> String {
+    format!("{ITEM_URI_PREFIX}{fullname}")
+}
+
+/// http(s) URLs written in markdown text, with markdown link syntax trimmed
+fn text_links(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| c.is_whitespace() || c == '(' || c == '[')
+        .filter(|token| token.starts_with("https://") || token.starts_with("http://"))
+        .map(|token| token.trim_end_matches([')', ']', '.', ',']).to_string())
+}
+
+impl PlatformAdapter for Reddit {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: Response =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let item = response
+            .0
+            .and_then(|listing| listing.data.children)
+            .map(|thing| thing.data)
+            .ok_or(ParseError::NoPost)?;
+
+        let is_comment = item.name.starts_with("t1_");
+        // Keywords match the title and body of posts, the body of comments
+        let text = if is_comment {
+            item.body
+        } else if item.selftext.is_empty() {
+            item.title
+        } else {
+            format!("{}\n\n{}", item.title, item.selftext)
+        };
+        let images = match (&item.gallery_data, item.post_hint.as_deref()) {
+            (Some(gallery), _) => gallery.items.len(),
+            (None, Some("image")) => 1,
+            _ => 0,
+        };
+        // Link posts point at their url; image and gallery urls are Reddit's own media
+        let external_uri = item
+            .url
+            .filter(|_| !is_comment && !item.is_self && images == 0);
+        let mut links: Vec<String> = text_links(&text).collect();
+        links.extend(external_uri.iter().cloned());
+
+        Ok(NormalizedPost {
+            uri: item_uri(&item.name),
+            author_id: item.author_fullname.unwrap_or_default(),
+            author_handle: item.author,
+            author_bio: String::new(),
+            author_created_at: None,
+            author_followers: None,
+            created_at: Some(item.created_utc as i64),
+            langs: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
+            links,
+            media: MediaSummary {
+                images,
+                external_uri,
+            },
+            parent_uri: item.parent_id.as_deref().map(item_uri),
+            community: Some(item.subreddit),
+            labels: if item.over_18 {
+                vec![String::from(NSFW_LABEL)]
+            } else {
+                Vec::new()
+            },
+            metrics: PostMetrics {
+                like_count: item.score.max(0) as u64,
+                repost_count: item.num_crossposts,
+                reply_count: item.num_comments,
+                quote_count: 0,
+                view_count: 0,
+            },
+            text,
+            thread: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POST_RESPONSE: &str = r##"[
+        {"kind": "Listing", "data": {"children": [{"kind": "t3", "data": {
+            "name": "t3_1abcde",
+            "author": "poster",
+            "author_fullname": "t2_xyz",
+            "subreddit": "solana",
+            "created_utc": 1717243200.0,
+            "score": 42,
+            "over_18": false,
+            "title": "Shipping on Bonsol",
+            "selftext": "Read the [launch post](https://bonsol.sh/launch).",
+            "is_self": true,
+            "url": "https://www.reddit.com/r/solana/comments/1abcde/shipping_on_bonsol/",
+            "num_comments": 5,
+            "num_crossposts": 1
+        }}]}},
+        {"kind": "Listing", "data": {"children": []}}
+    ]"##;
+
+    const COMMENT_RESPONSE: &str = r##"{"kind": "Listing", "data": {"children": [{"kind": "t1", "data": {
+        "name": "t1_k1",
+        "author": "replier",
+        "author_fullname": "t2_abc",
+        "subreddit": "Solana",
+        "created_utc": 1717243260.0,
+        "score": -2,
+        "body": "gm bonsol",
+        "parent_id": "t3_1abcde"
+    }}]}}"##;
+
+    #[test]
+    fn normalizes_self_post() {
+        let post = Reddit.parse(&mut POST_RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "reddit://t3_1abcde");
+        assert_eq!(post.author_id, "t2_xyz");
+        assert_eq!(post.author_handle, "poster");
+        assert_eq!(post.community.as_deref(), Some("solana"));
+        assert_eq!(
+            post.text,
+            "Shipping on Bonsol\n\nRead the [launch post](https://bonsol.sh/launch)."
+        );
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.media.external_uri, None);
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.parent_uri, None);
+        assert_eq!(post.metrics.like_count, 42);
+        assert_eq!(post.metrics.reply_count, 5);
+        assert_eq!(post.metrics.repost_count, 1);
+    }
+
+    #[test]
+    fn normalizes_comment() {
+        let post = Reddit.parse(&mut COMMENT_RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "reddit://t1_k1");
+        assert_eq!(post.text, "gm bonsol");
+        assert_eq!(post.parent_uri.as_deref(), Some("reddit://t3_1abcde"));
+        assert_eq!(post.community.as_deref(), Some("Solana"));
+        assert_eq!(post.metrics.like_count, 0);
+    }
+
+    #[test]
+    fn link_post_carries_its_url() {
+        let response = POST_RESPONSE
+            .replace(r#""is_self": true"#, r#""is_self": false"#)
+            .replace(r#""over_18": false"#, r#""over_18": true"#)
+            .replace(
+                "https://www.reddit.com/r/solana/comments/1abcde/shipping_on_bonsol/",
+                "https://bonsol.sh/blog",
+            );
+        let post = Reddit.parse(&mut response.as_bytes()).unwrap();
+        assert_eq!(
+            post.media.external_uri.as_deref(),
+            Some("https://bonsol.sh/blog")
+        );
+        assert_eq!(post.labels, ["nsfw"]);
+    }
+
+    #[test]
+    fn empty_listing_has_no_post() {
+        let result =
+            Reddit.parse(&mut r#"{"kind": "Listing", "data": {"children": []}}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
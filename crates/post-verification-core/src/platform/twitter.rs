@@ -0,0 +1,300 @@
+use proof_of_post_common::PostMetrics;
+use serde::Deserialize;
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::media::MediaSummary;
+use crate::time::parse_rfc3339;
+
+/// X (Twitter) API v2 `GET /2/tweets` responses, requested with
+/// `tweet.fields=created_at,public_metrics,author_id,entities,lang,referenced_tweets,attachments`,
+/// `expansions=author_id,attachments.media_keys`,
+/// `user.fields=created_at,public_metrics,description` and `media.fields=type`
+pub struct Twitter;
+
+/// Tweets are identified by their canonical status URL, the same shape for the tweet
+/// itself and the tweet it replies to
+const STATUS_URL_PREFIX: &str = "https://x.com/i/status/";
+
+#[derive(Debug, Deserialize)]
+struct TweetsResponse {
+    #[serde(default, deserialize_with = "super::first")]
+    data: Option<Tweet>,
+    #[serde(default)]
+    includes: Includes,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tweet {
+    id: String,
+    text: String,
+    author_id: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    lang: Option<String>,
+    #[serde(default)]
+    public_metrics: TweetMetrics,
+    #[serde(default)]
+    entities: Entities,
+    #[serde(default)]
+    referenced_tweets: Vec<ReferencedTweet>,
+    #[serde(default)]
+    attachments: Attachments,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TweetMetrics {
+    #[serde(default)]
+    retweet_count: u64,
+    #[serde(default)]
+    reply_count: u64,
+    #[serde(default)]
+    like_count: u64,
+    #[serde(default)]
+    quote_count: u64,
+    /// Only returned to the tweet's author or with elevated access
+    #[serde(default)]
+    impression_count: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Entities {
+    #[serde(default)]
+    hashtags: Vec<Hashtag>,
+    #[serde(default)]
+    mentions: Vec<Mention>,
+    #[serde(default)]
+    urls: Vec<UrlEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hashtag {
+    tag: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Mention {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UrlEntity {
+    url: String,
+    #[serde(default)]
+    expanded_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReferencedTweet {
+    #[serde(rename = "type")]
+    kind: String,
+    id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Attachments {
+    #[serde(default)]
+    media_keys: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Includes {
+    #[serde(default)]
+    users: Vec<User>,
+    #[serde(default)]
+    media: Vec<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: String,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    public_metrics: Option<UserMetrics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserMetrics {
+    followers_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Media {
+    media_key: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+fn status_url(id: &str) -> String {
+    format!("{STATUS_URL_PREFIX}{id}")
+}
+
+impl PlatformAdapter for Twitter {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: TweetsResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let includes = response.includes;
+        let tweet = response.data.ok_or(ParseError::NoPost)?;
+
+        // Profile fields only come with the author_id expansion
+        let author = includes
+            .users
+            .iter()
+            .find(|user| user.id == tweet.author_id);
+        let images = tweet
+            .attachments
+            .media_keys
+            .iter()
+            .filter(|key| {
+                includes
+                    .media
+                    .iter()
+                    .any(|media| &media.media_key == *key && media.kind == "photo")
+            })
+            .count();
+        let parent_uri = tweet
+            .referenced_tweets
+            .iter()
+            .find(|referenced| referenced.kind == "replied_to")
+            .map(|referenced| status_url(&referenced.id));
+
+        Ok(NormalizedPost {
+            uri: status_url(&tweet.id),
+            author_handle: author.map(|user| user.username.clone()).unwrap_or_default(),
+            author_bio: author
+                .and_then(|user| user.description.clone())
+                .unwrap_or_default(),
+            author_created_at: author
+                .and_then(|user| user.created_at.as_deref())
+                .and_then(parse_rfc3339),
+            author_followers: author
+                .and_then(|user| user.public_metrics.as_ref())
+                .map(|metrics| metrics.followers_count),
+            author_id: tweet.author_id,
+            created_at: tweet.created_at.as_deref().and_then(parse_rfc3339),
+            langs: tweet.lang.into_iter().collect(),
+            tags: tweet
+                .entities
+                .hashtags
+                .into_iter()
+                .map(|hashtag| hashtag.tag)
+                .collect(),
+            mentions: tweet
+                .entities
+                .mentions
+                .into_iter()
+                .map(|mention| mention.username)
+                .collect(),
+            // t.co wrappers hide the destination, so match on the expanded URL
+            links: tweet
+                .entities
+                .urls
+                .into_iter()
+                .map(|url| url.expanded_url.unwrap_or(url.url))
+                .collect(),
+            media: MediaSummary {
+                images,
+                external_uri: None,
+            },
+            parent_uri,
+            community: None,
+            labels: Vec::new(),
+            metrics: PostMetrics {
+                like_count: tweet.public_metrics.like_count,
+                repost_count: tweet.public_metrics.retweet_count,
+                reply_count: tweet.public_metrics.reply_count,
+                quote_count: tweet.public_metrics.quote_count,
+                view_count: tweet.public_metrics.impression_count,
+            },
+            text: tweet.text,
+            thread: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{
+        "data": [{
+            "id": "1800000000000000001",
+            "text": "gm #bonsol @bonsol_sh https://t.co/abc",
+            "author_id": "42",
+            "created_at": "2024-06-01T12:00:00.000Z",
+            "lang": "en",
+            "public_metrics": {
+                "retweet_count": 2, "reply_count": 1, "like_count": 7,
+                "quote_count": 0, "bookmark_count": 3, "impression_count": 900
+            },
+            "entities": {
+                "hashtags": [{ "start": 3, "end": 10, "tag": "bonsol" }],
+                "mentions": [{ "start": 11, "end": 21, "username": "bonsol_sh", "id": "7" }],
+                "urls": [{ "start": 22, "end": 38, "url": "https://t.co/abc", "expanded_url": "https://bonsol.sh/launch" }]
+            },
+            "referenced_tweets": [{ "type": "replied_to", "id": "1799999999999999999" }],
+            "attachments": { "media_keys": ["3_1", "7_2"] }
+        }],
+        "includes": {
+            "users": [{
+                "id": "42",
+                "username": "poster",
+                "created_at": "2023-01-01T00:00:00.000Z",
+                "description": "wallet in bio",
+                "public_metrics": { "followers_count": 120, "following_count": 5 }
+            }],
+            "media": [
+                { "media_key": "3_1", "type": "photo" },
+                { "media_key": "7_2", "type": "video" }
+            ]
+        }
+    }"##;
+
+    #[test]
+    fn normalizes_tweets_response() {
+        let post = Twitter.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "https://x.com/i/status/1800000000000000001");
+        assert_eq!(post.author_id, "42");
+        assert_eq!(post.author_handle, "poster");
+        assert_eq!(post.author_bio, "wallet in bio");
+        assert_eq!(post.author_followers, Some(120));
+        assert_eq!(post.author_created_at, Some(1_672_531_200));
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.langs, ["en"]);
+        assert_eq!(post.tags, ["bonsol"]);
+        assert_eq!(post.mentions, ["bonsol_sh"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.media.images, 1);
+        assert_eq!(
+            post.parent_uri.as_deref(),
+            Some("https://x.com/i/status/1799999999999999999")
+        );
+        assert_eq!(post.metrics.like_count, 7);
+        assert_eq!(post.metrics.repost_count, 2);
+    }
+
+    #[test]
+    fn missing_expansions_leave_author_fields_empty() {
+        let post = Twitter
+            .parse(&mut r#"{"data": [{"id": "1", "text": "gm", "author_id": "42"}]}"#.as_bytes())
+            .unwrap();
+        assert_eq!(post.author_followers, None);
+        assert_eq!(post.author_created_at, None);
+        assert!(post.author_bio.is_empty());
+    }
+
+    #[test]
+    fn error_responses_have_no_post() {
+        let result = Twitter.parse(
+            &mut r#"{"errors": [{"title": "Not Found Error", "detail": "Could not find tweet"}]}"#
+                .as_bytes(),
+        );
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
@@ -0,0 +1,161 @@
+use proof_of_post_common::PostMetrics;
+use serde::{Deserialize, Deserializer};
+use std::io::Read;
+
+use super::{NormalizedPost, ParseError, PlatformAdapter};
+use crate::media::MediaSummary;
+use crate::time::parse_rfc3339;
+
+/// YouTube Data API v3 `videos.list` responses with `part=snippet,statistics`. The post
+/// text is the video title and description; the channel is the author.
+pub struct YouTube;
+
+#[derive(Debug, Deserialize)]
+struct VideoListResponse {
+    #[serde(default, deserialize_with = "super::first")]
+    items: Option<Video>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Video {
+    id: String,
+    snippet: Snippet,
+    #[serde(default)]
+    statistics: Statistics,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snippet {
+    published_at: String,
+    channel_id: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    default_language: Option<String>,
+    #[serde(default)]
+    default_audio_language: Option<String>,
+}
+
+/// Counts are JSON strings; hidden counts are left out of the response
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Statistics {
+    #[serde(default, deserialize_with = "count")]
+    view_count: u64,
+    #[serde(default, deserialize_with = "count")]
+    like_count: u64,
+    #[serde(default, deserialize_with = "count")]
+    comment_count: u64,
+}
+
+fn count<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+impl PlatformAdapter for YouTube {
+    fn parse(&self, response: &mut dyn Read) -> Result<NormalizedPost, ParseError> {
+        let response: VideoListResponse =
+            serde_json::from_reader(response).map_err(|_| ParseError::Malformed)?;
+        let video = response.items.ok_or(ParseError::NoPost)?;
+        let snippet = video.snippet;
+
+        // Descriptions carry plain URLs; YouTube adds no link annotations
+        let links = snippet
+            .description
+            .split_whitespace()
+            .filter(|token| token.starts_with("https://") || token.starts_with("http://"))
+            .map(String::from)
+            .collect();
+
+        Ok(NormalizedPost {
+            uri: format!("https://www.youtube.com/watch?v={}", video.id),
+            author_id: snippet.channel_id,
+            author_handle: String::new(),
+            author_bio: String::new(),
+            author_created_at: None,
+            author_followers: None,
+            text: format!("{}\n\n{}", snippet.title, snippet.description),
+            thread: Vec::new(),
+            created_at: parse_rfc3339(&snippet.published_at),
+            langs: snippet
+                .default_language
+                .into_iter()
+                .chain(snippet.default_audio_language)
+                .collect(),
+            tags: snippet.tags,
+            mentions: Vec::new(),
+            links,
+            media: MediaSummary::default(),
+            parent_uri: None,
+            community: None,
+            labels: Vec::new(),
+            metrics: PostMetrics {
+                like_count: video.statistics.like_count,
+                repost_count: 0,
+                reply_count: video.statistics.comment_count,
+                quote_count: 0,
+                view_count: video.statistics.view_count,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r##"{
+        "kind": "youtube#videoListResponse",
+        "items": [{
+            "kind": "youtube#video",
+            "id": "dQw4w9WgXcQ",
+            "snippet": {
+                "publishedAt": "2024-06-01T12:00:00Z",
+                "channelId": "UCbonsol000000000000000",
+                "title": "Building on Bonsol",
+                "description": "Verifiable compute on Solana https://bonsol.sh/launch",
+                "tags": ["bonsol", "zk"],
+                "channelTitle": "Bonsol",
+                "defaultAudioLanguage": "en"
+            },
+            "statistics": { "viewCount": "1500", "likeCount": "42", "favoriteCount": "0", "commentCount": "7" }
+        }]
+    }"##;
+
+    #[test]
+    fn normalizes_video() {
+        let post = YouTube.parse(&mut RESPONSE.as_bytes()).unwrap();
+        assert_eq!(post.uri, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(post.author_id, "UCbonsol000000000000000");
+        assert_eq!(
+            post.text,
+            "Building on Bonsol\n\nVerifiable compute on Solana https://bonsol.sh/launch"
+        );
+        assert_eq!(post.created_at, Some(1_717_243_200));
+        assert_eq!(post.langs, ["en"]);
+        assert_eq!(post.tags, ["bonsol", "zk"]);
+        assert_eq!(post.links, ["https://bonsol.sh/launch"]);
+        assert_eq!(post.metrics.view_count, 1500);
+        assert_eq!(post.metrics.like_count, 42);
+        assert_eq!(post.metrics.reply_count, 7);
+    }
+
+    #[test]
+    fn hidden_likes_default_to_zero() {
+        let response = RESPONSE.replace(r#""likeCount": "42", "#, "");
+        let post = YouTube.parse(&mut response.as_bytes()).unwrap();
+        assert_eq!(post.metrics.like_count, 0);
+    }
+
+    #[test]
+    fn empty_item_list_has_no_post() {
+        let result = YouTube.parse(&mut r#"{"items": []}"#.as_bytes());
+        assert_eq!(result.unwrap_err(), ParseError::NoPost);
+    }
+}
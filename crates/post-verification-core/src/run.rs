@@ -0,0 +1,240 @@
+use proof_of_post_common::{
+    decode_public_input, decode_rules_bundle, InputSource, PostVerificationOutput, RulesBundle,
+    REASON_PARSE_ERROR, REASON_RULES_MISMATCH, REASON_UNSUPPORTED_PLATFORM,
+};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+
+use crate::authenticity::is_authentic_response;
+use crate::platform::{adapter, thread_adapter};
+use crate::stream::{decode_response, DigestReader, HashingInput, HashingReader, InputHasher};
+use crate::verify::evaluate;
+
+/// What the guest commits, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal {
+    /// SHA-256 over every input byte read, the same bytes Bonsol hashes for `input_hash`
+    pub input_digest: [u8; 32],
+    pub output: PostVerificationOutput,
+}
+
+impl Journal {
+    /// Fail before reading the post, committing the digest of the input read so far
+    fn failed(hasher: InputHasher, reason_code: u16) -> Self {
+        Self {
+            input_digest: hasher.finalize().into(),
+            output: PostVerificationOutput::failed(reason_code),
+        }
+    }
+}
+
+/// The whole verification over the guest's input stream: public input, rule bundle,
+/// response signature and URL response, in that order. Input the prover got wrong
+/// (truncated, oversized, not the platform's shape) ends in a failure reason code
+/// rather than a panic, so a bad request still produces a journal the program can
+/// settle.
+pub fn run(mut stdin: impl Read) -> Journal {
+    let mut hasher = InputHasher::new();
+
+    // Read public input: campaign rules and request parameters
+    let decoded = decode_public_input(&mut HashingInput::new(&mut stdin, &mut hasher));
+    let mut public_input = match decoded {
+        Ok(input) => input,
+        Err(_) => return Journal::failed(hasher, REASON_PARSE_ERROR),
+    };
+    let post_size = public_input.post_size;
+
+    // The platform picks the parser for the fetched response
+    let min_thread_posts = public_input.min_thread_posts;
+    let adapter = if min_thread_posts > 0 {
+        thread_adapter(public_input.platform)
+    } else {
+        adapter(public_input.platform)
+    };
+    let Some(adapter) = adapter else {
+        debug_log!("Unsupported platform: {:?}", public_input.platform);
+        return Journal::failed(hasher, REASON_UNSUPPORTED_PLATFORM);
+    };
+
+    // Large rule sets are fetched by the prover from the campaign's metadata_uri and
+    // must hash to the value stored on-chain
+    let rules = match public_input.rules_bundle.take() {
+        Some(bundle_ref) => {
+            let mut bundle = vec![0u8; bundle_ref.size as usize];
+            let read = HashingInput::new(&mut stdin, &mut hasher).read_exact(&mut bundle);
+            if read.is_err() || <[u8; 32]>::from(Sha256::digest(&bundle)) != bundle_ref.hash {
+                debug_log!("Rule bundle hash mismatch");
+                return Journal::failed(hasher, REASON_RULES_MISMATCH);
+            }
+            match decode_rules_bundle(&bundle) {
+                Ok(rules) => rules,
+                Err(_) => return Journal::failed(hasher, REASON_RULES_MISMATCH),
+            }
+        }
+        None => RulesBundle {
+            keywords: std::mem::take(&mut public_input.keywords),
+            blocked_keywords: std::mem::take(&mut public_input.blocked_keywords),
+            required_facets: std::mem::take(&mut public_input.required_facets),
+        },
+    };
+    let response_signer = public_input.response_signer;
+    let response_encoding = public_input.response_encoding;
+    debug_log!("Input: {:?}, keywords: {:?}", post_size, rules.keywords);
+
+    // Campaigns with a response signer get the notary's signature over the response
+    // ahead of the response itself
+    let response_signature = if response_signer != [0u8; 32] {
+        let mut signature = [0u8; 64];
+        let read = HashingInput::new(&mut stdin, &mut hasher).read_exact(&mut signature);
+        if read.is_err() {
+            debug_log!("Response signature missing");
+            return Journal::failed(hasher, REASON_PARSE_ERROR);
+        }
+        Some(signature)
+    } else {
+        None
+    };
+
+    // Read URL response (platform API JSON), parsing and hashing it as it streams in so
+    // large responses are never held in guest memory as a whole. Compressed responses
+    // are inflated on the way, so the prover ships fewer bytes.
+    let mut url_response = HashingReader::new(&mut stdin, &mut hasher, post_size);
+    let mut decoded = DigestReader::new(decode_response(response_encoding, &mut url_response));
+    if response_signature.is_some() {
+        decoded = decoded.with_digest();
+    }
+    let parsed = adapter.parse(&mut decoded);
+    // A body that fails to inflate has no hash to check a signature against
+    let response_hash = decoded.finish().ok().flatten();
+    if url_response.finish().is_err() {
+        debug_log!("URL response shorter than post_size");
+        return Journal::failed(hasher, REASON_PARSE_ERROR);
+    }
+    let input_digest = hasher.finalize().into();
+
+    // A valid signature shows the signer saw these exact bytes at the requested URL,
+    // not a body the prover made up or took from another post
+    let response_authenticated = match (response_signature, response_hash) {
+        (Some(signature), Some(hash)) => is_authentic_response(
+            &response_signer,
+            &public_input.post_url_hash,
+            &hash,
+            &signature,
+        ),
+        _ => false,
+    };
+    debug_log!("Response authenticated: {:?}", response_authenticated);
+
+    Journal {
+        input_digest,
+        output: evaluate(&public_input, &rules, parsed, response_authenticated),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof_of_post_common::{
+        encode_public_input, encode_rules_bundle, AuthorRequirements, ContentRequirements,
+        EngagementThresholds, MatchMode, MediaRequirement, PublicInput, RecencyWindow,
+        RulesBundleRef, ENCODING_IDENTITY, PLATFORM_BLUESKY, REASON_OK,
+    };
+
+    const RESPONSE: &[u8] = br#"{"posts": [{
+        "uri": "at://did:plc:abc/app.bsky.feed.post/1",
+        "author": { "did": "did:plc:abc", "handle": "bonsol.bsky.social" },
+        "record": { "createdAt": "2024-06-01T12:00:00Z", "text": "gm bonsol" }
+    }]}"#;
+
+    fn input(response_len: usize) -> PublicInput {
+        PublicInput {
+            post_size: response_len as u64,
+            keywords: vec![String::from("bonsol")],
+            blocked_keywords: Vec::new(),
+            required_facets: Vec::new(),
+            match_mode: MatchMode::All,
+            match_flags: 0,
+            require_authorship: false,
+            verifier: [0u8; 32],
+            engagement: EngagementThresholds::default(),
+            recency: RecencyWindow::default(),
+            required_langs: Vec::new(),
+            content: ContentRequirements::default(),
+            media: MediaRequirement::default(),
+            allowed_link_domains: Vec::new(),
+            reply_to_uri: String::new(),
+            author: AuthorRequirements::default(),
+            blocked_labels: Vec::new(),
+            platform: PLATFORM_BLUESKY,
+            required_communities: Vec::new(),
+            disclosure_tags: Vec::new(),
+            allowed_author_ids: Vec::new(),
+            segments: Vec::new(),
+            response_signer: [0u8; 32],
+            post_url_hash: [0u8; 32],
+            config: [0u8; 32],
+            response_encoding: ENCODING_IDENTITY,
+            min_thread_posts: 0,
+            rules_bundle: None,
+        }
+    }
+
+    fn sha256(bytes: &[u8]) -> [u8; 32] {
+        Sha256::digest(bytes).into()
+    }
+
+    #[test]
+    fn digest_covers_every_input() {
+        let stdin = [
+            encode_public_input(&input(RESPONSE.len())),
+            RESPONSE.to_vec(),
+        ]
+        .concat();
+        let journal = run(stdin.as_slice());
+        assert_eq!(journal.output.reason_code, REASON_OK);
+        assert_eq!(journal.input_digest, sha256(&stdin));
+    }
+
+    #[test]
+    fn truncated_inputs_fail_with_a_reason() {
+        let public = encode_public_input(&input(RESPONSE.len()));
+        let journal = run(&public[..public.len() - 1]);
+        assert_eq!(journal.output.reason_code, REASON_PARSE_ERROR);
+
+        let stdin = [public.as_slice(), &RESPONSE[..RESPONSE.len() - 1]].concat();
+        let journal = run(stdin.as_slice());
+        assert_eq!(journal.output.reason_code, REASON_PARSE_ERROR);
+        assert_eq!(journal.input_digest, sha256(&stdin));
+
+        let mut signed = input(RESPONSE.len());
+        signed.response_signer = [1u8; 32];
+        let stdin = [encode_public_input(&signed), vec![0u8; 10]].concat();
+        let journal = run(stdin.as_slice());
+        assert_eq!(journal.output.reason_code, REASON_PARSE_ERROR);
+    }
+
+    #[test]
+    fn rules_bundle_is_checked_against_its_hash() {
+        let bundle = encode_rules_bundle(&RulesBundle {
+            keywords: vec![String::from("gm")],
+            ..Default::default()
+        });
+        let mut bundled = input(RESPONSE.len());
+        bundled.keywords = Vec::new();
+        bundled.rules_bundle = Some(RulesBundleRef {
+            hash: sha256(&bundle),
+            size: bundle.len() as u64,
+        });
+        let public = encode_public_input(&bundled);
+        let stdin = [public.as_slice(), &bundle, RESPONSE].concat();
+        assert_eq!(run(stdin.as_slice()).output.reason_code, REASON_OK);
+
+        let mut tampered = bundle.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        let stdin = [public.as_slice(), &tampered, RESPONSE].concat();
+        assert_eq!(
+            run(stdin.as_slice()).output.reason_code,
+            REASON_RULES_MISMATCH
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use proof_of_post_common::{SegmentRule, SEGMENT_HANDLE_SUFFIX, SEGMENT_LANGUAGE, SEGMENT_NONE};
+
+use crate::language::has_accepted_language;
+
+/// Index of the first segment the post falls in, or [`SEGMENT_NONE`]. Language segments
+/// match the post's language tags; handle segments match the end of the author's
+/// handle, case-insensitively. Unknown kinds never match.
+pub fn select_segment(segments: &[SegmentRule], langs: &[String], handle: &str) -> u8 {
+    let handle = handle.to_ascii_lowercase();
+    segments
+        .iter()
+        .position(|segment| match segment.kind {
+            SEGMENT_LANGUAGE => has_accepted_language(langs, std::slice::from_ref(&segment.value)),
+            SEGMENT_HANDLE_SUFFIX => {
+                !segment.value.is_empty() && handle.ends_with(&segment.value.to_ascii_lowercase())
+            }
+            _ => false,
+        })
+        .map_or(SEGMENT_NONE, |index| index as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(kind: u8, value: &str) -> SegmentRule {
+        SegmentRule {
+            kind,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn first_matching_segment_wins() {
+        let segments = [
+            segment(SEGMENT_HANDLE_SUFFIX, ".jp"),
+            segment(SEGMENT_LANGUAGE, "ja"),
+            segment(SEGMENT_LANGUAGE, "pt"),
+        ];
+        let langs = vec![String::from("ja")];
+        assert_eq!(select_segment(&segments, &langs, "poster.BSKY.jp"), 0);
+        assert_eq!(select_segment(&segments, &langs, "poster.bsky.social"), 1);
+        let langs = vec![String::from("pt-BR")];
+        assert_eq!(select_segment(&segments, &langs, "poster.bsky.social"), 2);
+    }
+
+    #[test]
+    fn unmatched_post_has_no_segment() {
+        let segments = [segment(SEGMENT_LANGUAGE, "ja"), segment(9, "")];
+        let langs = vec![String::from("en")];
+        assert_eq!(select_segment(&segments, &langs, "poster"), SEGMENT_NONE);
+        // A language segment is never the "any language" wildcard
+        assert_eq!(select_segment(&segments, &[], "poster"), SEGMENT_NONE);
+        assert_eq!(select_segment(&[], &langs, "poster"), SEGMENT_NONE);
+    }
+}
@@ -0,0 +1,373 @@
+use flate2::read::{GzDecoder, ZlibDecoder};
+use proof_of_post_common::{
+    DecodeError, InputSource, ENCODING_DEFLATE, ENCODING_GZIP, MAX_RESPONSE_LEN,
+};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Take};
+
+/// Size of the scratch buffer used to drain unread input
+const DRAIN_CHUNK_LEN: usize = 4096;
+
+/// Size of the buffer all inputs are pulled from the host through. Decoding the public
+/// input field by field would otherwise cost a read syscall per field.
+pub const INPUT_BUFFER_LEN: usize = 8 * 1024;
+
+/// Running SHA-256 over every input in the order Bonsol supplies them, matching its
+/// `input_hash`
+pub type InputHasher = Sha256;
+
+/// Decodes fixed-size fields from a buffered input stream, feeding them to the input
+/// digest as they are read
+pub struct HashingInput<'a, R> {
+    inner: &'a mut R,
+    hasher: &'a mut InputHasher,
+}
+
+impl<'a, R: Read> HashingInput<'a, R> {
+    pub fn new(inner: &'a mut R, hasher: &'a mut InputHasher) -> Self {
+        Self { inner, hasher }
+    }
+}
+
+impl<R: Read> InputSource for HashingInput<'_, R> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), DecodeError> {
+        self.inner
+            .read_exact(buf)
+            .map_err(|_| DecodeError::UnexpectedEof)?;
+        self.hasher.update(&*buf);
+        Ok(())
+    }
+}
+
+/// Reads exactly `len` bytes of framed input, hashing them as they stream through, so the
+/// response can be parsed incrementally without ever holding a full copy for hashing
+pub struct HashingReader<'a, R> {
+    inner: R,
+    hasher: &'a mut InputHasher,
+    remaining: u64,
+}
+
+impl<'a, R: Read> HashingReader<'a, R> {
+    pub fn new(inner: R, hasher: &'a mut InputHasher, len: u64) -> Self {
+        Self {
+            inner,
+            hasher,
+            remaining: len,
+        }
+    }
+
+    /// Consume whatever the parser left unread, so all `len` bytes reach the hasher
+    pub fn finish(mut self) -> io::Result<()> {
+        let mut chunk = [0u8; DRAIN_CHUNK_LEN];
+        while self.remaining > 0 {
+            let len = chunk.len().min(self.remaining as usize);
+            self.read_exact(&mut chunk[..len])?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.remaining as usize);
+        if len == 0 {
+            return Ok(0);
+        }
+        // Framed input is exactly `len` bytes long, so a short read is an error
+        self.inner.read_exact(&mut buf[..len])?;
+        self.hasher.update(&buf[..len]);
+        self.remaining -= len as u64;
+        Ok(len)
+    }
+}
+
+/// Decodes the response input according to its `ENCODING_*`, inflating compressed
+/// responses as the parser pulls from them. `MAX_RESPONSE_LEN` only bounds the shipped
+/// bytes, so inflated bodies are capped at it too, or a small compression bomb would
+/// have the guest inflate and hash without end.
+pub fn decode_response<'a, R: Read + 'a>(encoding: u8, inner: R) -> Box<dyn Read + 'a> {
+    match encoding {
+        ENCODING_GZIP => Box::new(InflateLimit::new(GzDecoder::new(inner))),
+        ENCODING_DEFLATE => Box::new(InflateLimit::new(ZlibDecoder::new(inner))),
+        _ => Box::new(inner),
+    }
+}
+
+/// Fails the read that takes an inflated response past `MAX_RESPONSE_LEN`
+struct InflateLimit<R> {
+    inner: Take<R>,
+}
+
+impl<R: Read> InflateLimit<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner: inner.take(MAX_RESPONSE_LEN + 1),
+        }
+    }
+}
+
+impl<R: Read> Read for InflateLimit<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if self.inner.limit() == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "inflated response is longer than MAX_RESPONSE_LEN",
+            ));
+        }
+        Ok(len)
+    }
+}
+
+/// Passes the decoded response through, optionally keeping its SHA-256: the response
+/// signer signs the decoded body, whatever encoding it was shipped in
+pub struct DigestReader<R> {
+    inner: R,
+    digest: Option<Sha256>,
+}
+
+impl<R: Read> DigestReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            digest: None,
+        }
+    }
+
+    /// Hash the bytes read, returned by [`Self::finish`]
+    pub fn with_digest(mut self) -> Self {
+        self.digest = Some(Sha256::new());
+        self
+    }
+
+    /// With a digest, reads to the end of the response so it covers what the parser
+    /// skipped, and returns it
+    pub fn finish(mut self) -> io::Result<Option<[u8; 32]>> {
+        if self.digest.is_some() {
+            io::copy(&mut self, &mut io::sink())?;
+        }
+        Ok(self.digest.map(|digest| digest.finalize().into()))
+    }
+}
+
+impl<R: Read> Read for DigestReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if let Some(digest) = &mut self.digest {
+            digest.update(&buf[..len]);
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof_of_post_common::ENCODING_IDENTITY;
+
+    fn sha256(bytes: &[u8]) -> [u8; 32] {
+        Sha256::digest(bytes).into()
+    }
+
+    /// Counts the reads that reach the host, each of which is a syscall in the guest
+    struct CountingReader<'a> {
+        inner: &'a [u8],
+        reads: usize,
+    }
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            self.inner.read(buf)
+        }
+    }
+
+    /// Reads `fields` 8-byte fields the way the public input decoder does, returning
+    /// their digest
+    fn read_fields<R: Read>(reader: &mut R, fields: usize) -> [u8; 32] {
+        let mut hasher = InputHasher::new();
+        let mut input = HashingInput::new(reader, &mut hasher);
+        for _ in 0..fields {
+            input.read_exact(&mut [0u8; 8]).unwrap();
+        }
+        hasher.finalize().into()
+    }
+
+    #[test]
+    fn buffered_input_batches_host_reads() {
+        let bytes: Vec<u8> = (0..800).map(|i| i as u8).collect();
+
+        let mut direct = CountingReader {
+            inner: &bytes,
+            reads: 0,
+        };
+        let direct_digest = read_fields(&mut direct, 100);
+
+        let mut counted = CountingReader {
+            inner: &bytes,
+            reads: 0,
+        };
+        let mut buffered = io::BufReader::with_capacity(INPUT_BUFFER_LEN, &mut counted);
+        let buffered_digest = read_fields(&mut buffered, 100);
+        drop(buffered);
+
+        assert_eq!(direct.reads, 100);
+        assert_eq!(counted.reads, 1);
+        assert_eq!(buffered_digest, direct_digest);
+        assert_eq!(direct_digest, sha256(&bytes));
+    }
+
+    #[test]
+    fn short_input_is_a_decode_error() {
+        let mut hasher = InputHasher::new();
+        let mut bytes: &[u8] = b"abc";
+        let mut input = HashingInput::new(&mut bytes, &mut hasher);
+        assert_eq!(
+            input.read_exact(&mut [0u8; 4]),
+            Err(DecodeError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn hashes_everything_the_parser_reads() {
+        let input = br#"{"posts":[]}"#;
+        let mut hasher = InputHasher::new();
+        let mut reader = HashingReader::new(&input[..], &mut hasher, input.len() as u64);
+        let parsed: serde_json::Value = serde_json::from_reader(&mut reader).unwrap();
+        assert_eq!(parsed["posts"], serde_json::json!([]));
+        reader.finish().unwrap();
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(input));
+    }
+
+    #[test]
+    fn unread_bytes_are_drained_into_the_digest() {
+        let input = vec![b'x'; 10_000];
+        let mut hasher = InputHasher::new();
+        let mut reader = HashingReader::new(input.as_slice(), &mut hasher, input.len() as u64);
+        let mut head = [0u8; 10];
+        reader.read_exact(&mut head).unwrap();
+        reader.finish().unwrap();
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(&input));
+    }
+
+    #[test]
+    fn stops_at_the_frame_length() {
+        let input = b"{}trailing frame";
+        let mut hasher = InputHasher::new();
+        let mut reader = HashingReader::new(&input[..], &mut hasher, 2);
+        let parsed: serde_json::Value = serde_json::from_reader(&mut reader).unwrap();
+        assert_eq!(parsed, serde_json::json!({}));
+        reader.finish().unwrap();
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(b"{}"));
+    }
+
+    #[test]
+    fn digest_covers_earlier_inputs() {
+        let mut hasher = InputHasher::new();
+        hasher.update(b"public");
+        let input = b"{}";
+        HashingReader::new(&input[..], &mut hasher, 2)
+            .finish()
+            .unwrap();
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(b"public{}"));
+    }
+
+    #[test]
+    fn response_digest_excludes_earlier_inputs() {
+        let mut hasher = InputHasher::new();
+        hasher.update(b"public");
+        let input = b"{}";
+        let mut reader = HashingReader::new(&input[..], &mut hasher, 2);
+        let digest = DigestReader::new(decode_response(ENCODING_IDENTITY, &mut reader))
+            .with_digest()
+            .finish()
+            .unwrap();
+        reader.finish().unwrap();
+        assert_eq!(digest, Some(sha256(b"{}")));
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(b"public{}"));
+    }
+
+    fn compressed(encoding: u8, body: &[u8]) -> Vec<u8> {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+        match encoding {
+            ENCODING_GZIP => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body).unwrap();
+                encoder.finish().unwrap()
+            }
+            _ => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body).unwrap();
+                encoder.finish().unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn compressed_responses_are_parsed_and_signed_decoded() {
+        let body = format!(r#"{{"posts":[],"padding":"{}"}}"#, "x".repeat(4000));
+        for encoding in [ENCODING_GZIP, ENCODING_DEFLATE] {
+            let input = compressed(encoding, body.as_bytes());
+            assert!(input.len() < body.len() / 10);
+
+            let mut hasher = InputHasher::new();
+            let mut reader = HashingReader::new(input.as_slice(), &mut hasher, input.len() as u64);
+            let mut decoded =
+                DigestReader::new(decode_response(encoding, &mut reader)).with_digest();
+            let parsed: serde_json::Value = serde_json::from_reader(&mut decoded).unwrap();
+            assert_eq!(parsed["posts"], serde_json::json!([]));
+            let digest = decoded.finish().unwrap();
+            reader.finish().unwrap();
+
+            // The input digest covers the shipped bytes, the response digest the body
+            assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(&input));
+            assert_eq!(digest, Some(sha256(body.as_bytes())));
+        }
+    }
+
+    #[test]
+    fn corrupt_compressed_response_fails_to_parse() {
+        let mut input = compressed(ENCODING_GZIP, br#"{"posts":[]}"#);
+        input.truncate(input.len() / 2);
+        let mut hasher = InputHasher::new();
+        let mut reader = HashingReader::new(input.as_slice(), &mut hasher, input.len() as u64);
+        let mut decoded = DigestReader::new(decode_response(ENCODING_GZIP, &mut reader));
+        assert!(serde_json::from_reader::<_, serde_json::Value>(&mut decoded).is_err());
+        drop(decoded);
+        reader.finish().unwrap();
+        assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(&input));
+    }
+
+    #[test]
+    fn compression_bombs_stop_at_the_response_limit() {
+        let body = vec![0u8; MAX_RESPONSE_LEN as usize + 1];
+        for encoding in [ENCODING_GZIP, ENCODING_DEFLATE] {
+            let input = compressed(encoding, &body);
+            assert!(input.len() < 64 * 1024);
+
+            let mut hasher = InputHasher::new();
+            let mut reader = HashingReader::new(input.as_slice(), &mut hasher, input.len() as u64);
+            let decoded = DigestReader::new(decode_response(encoding, &mut reader)).with_digest();
+            let err = decoded.finish().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            reader.finish().unwrap();
+            assert_eq!(<[u8; 32]>::from(hasher.finalize()), sha256(&input));
+        }
+
+        // A body right at the limit still inflates
+        let body = vec![0u8; MAX_RESPONSE_LEN as usize];
+        let input = compressed(ENCODING_GZIP, &body);
+        let decoded = DigestReader::new(decode_response(ENCODING_GZIP, input.as_slice()));
+        assert!(decoded.with_digest().finish().unwrap().is_some());
+    }
+
+    #[test]
+    fn short_input_is_an_error() {
+        let input = b"{}";
+        let mut hasher = InputHasher::new();
+        let reader = HashingReader::new(&input[..], &mut hasher, 3);
+        assert!(reader.finish().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+/// Parse an RFC 3339 timestamp such as `2024-11-20T12:34:56.789Z` into unix seconds.
+/// Fractional seconds are truncated; numeric offsets are applied.
+pub fn parse_rfc3339(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || !matches!(b[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    if b[13] != b':' || b[16] != b':' {
+        return None;
+    }
+    let year = digits(&b[0..4])?;
+    let month = digits(&b[5..7])?;
+    let day = digits(&b[8..10])?;
+    let hour = digits(&b[11..13])?;
+    let minute = digits(&b[14..16])?;
+    let second = digits(&b[17..19])?;
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut rest = &b[19..];
+    if rest.first() == Some(&b'.') {
+        let frac_len = rest[1..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if frac_len == 0 {
+            return None;
+        }
+        rest = &rest[1 + frac_len..];
+    }
+    let offset = match rest {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let hours = digits(&[*h1, *h2])?;
+            let minutes = digits(&[*m1, *m2])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 3600 + minutes * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    let days = days_from_civil(year, month, day);
+    Some(days * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+fn digits(b: &[u8]) -> Option<i64> {
+    b.iter().try_fold(0i64, |acc, &c| {
+        c.is_ascii_digit().then(|| acc * 10 + (c - b'0') as i64)
+    })
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utc_timestamps() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_rfc3339("2024-11-20T12:34:56Z"), Some(1_732_106_096));
+    }
+
+    #[test]
+    fn truncates_fractional_seconds() {
+        assert_eq!(
+            parse_rfc3339("2024-11-20T12:34:56.789Z"),
+            parse_rfc3339("2024-11-20T12:34:56Z")
+        );
+    }
+
+    #[test]
+    fn applies_offsets() {
+        assert_eq!(
+            parse_rfc3339("2024-11-20T14:34:56+02:00"),
+            parse_rfc3339("2024-11-20T12:34:56Z")
+        );
+        assert_eq!(
+            parse_rfc3339("2024-11-20T07:04:56-05:30"),
+            parse_rfc3339("2024-11-20T12:34:56Z")
+        );
+    }
+
+    #[test]
+    fn handles_leap_days() {
+        assert_eq!(parse_rfc3339("2024-02-29T00:00:00Z"), Some(1_709_164_800));
+        assert_eq!(parse_rfc3339("2023-02-29T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_timestamps() {
+        assert_eq!(parse_rfc3339(""), None);
+        assert_eq!(parse_rfc3339("2024-11-20"), None);
+        assert_eq!(parse_rfc3339("2024-13-20T12:34:56Z"), None);
+        assert_eq!(parse_rfc3339("2024-11-20T12:34:56"), None);
+        assert_eq!(parse_rfc3339("2024-11-20T12:34:56.Z"), None);
+        assert_eq!(parse_rfc3339("2024-11-20T1a:34:56Z"), None);
+    }
+}
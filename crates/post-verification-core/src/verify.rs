@@ -0,0 +1,326 @@
+use proof_of_post_common::{
+    likes_per_follower_bps, reason_bit, PostVerificationOutput, PublicInput, RulesBundle,
+    MATCH_FLAG_REGEX, OUTPUT_VERSION, REASON_AUTHORSHIP_MISMATCH, REASON_AUTHOR_INELIGIBLE,
+    REASON_AUTHOR_NOT_ALLOWED, REASON_BLOCKED_KEYWORD, REASON_INVALID_PATTERN, REASON_LABELED,
+    REASON_LANGUAGE_MISMATCH, REASON_LOW_ENGAGEMENT, REASON_MISSING_DISCLOSURE,
+    REASON_MISSING_FACET, REASON_MISSING_KEYWORD, REASON_MISSING_LINK, REASON_MISSING_MEDIA,
+    REASON_NOT_A_REPLY, REASON_NO_POST, REASON_OK, REASON_OUTSIDE_WINDOW, REASON_PARSE_ERROR,
+    REASON_TOO_SHORT, REASON_WRONG_COMMUNITY,
+};
+use sha2::{Digest, Sha256};
+
+use crate::content::content_stats;
+use crate::disclosure::has_disclosure;
+use crate::facets::has_facet;
+use crate::labels::find_blocked_label;
+use crate::language::has_accepted_language;
+use crate::links::find_allowed_link;
+use crate::matching::{compile_pattern, KeywordMatcher};
+use crate::media::has_required_media;
+use crate::platform::{NormalizedPost, ParseError};
+use crate::segments::select_segment;
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+/// Runs every campaign check against the parsed post and builds the journal output.
+/// `rules` are the keyword lists in effect, from the public input or the rule bundle;
+/// `response_authenticated` is whether the response signer's signature held.
+pub fn evaluate(
+    input: &PublicInput,
+    rules: &RulesBundle,
+    parsed: Result<NormalizedPost, ParseError>,
+    response_authenticated: bool,
+) -> PostVerificationOutput {
+    let keywords = &rules.keywords;
+    let blocked_keywords = &rules.blocked_keywords;
+    let required_facets = &rules.required_facets;
+    let match_mode = input.match_mode;
+    let match_flags = input.match_flags;
+    let require_authorship = input.require_authorship;
+    let engagement = &input.engagement;
+    let recency = &input.recency;
+    let required_langs = &input.required_langs;
+    let content = &input.content;
+    let media = &input.media;
+    let allowed_link_domains = &input.allowed_link_domains;
+    let reply_to_uri = &input.reply_to_uri;
+    let author_requirements = &input.author;
+    let blocked_labels = &input.blocked_labels;
+    let required_communities = &input.required_communities;
+    let disclosure_tags = &input.disclosure_tags;
+    let allowed_author_ids = &input.allowed_author_ids;
+    let segments = &input.segments;
+    let min_thread_posts = input.min_thread_posts;
+    let verifier_address = bs58::encode(input.verifier).into_string();
+
+    let post = match parsed {
+        Ok(post) => post,
+        Err(ParseError::Malformed) => return PostVerificationOutput::failed(REASON_PARSE_ERROR),
+        Err(ParseError::NoPost) => return PostVerificationOutput::failed(REASON_NO_POST),
+    };
+    debug_log!("Post: {:?}", post);
+
+    let post_text = post.text;
+    debug_log!("Post text: {:?}", post_text);
+
+    // Proof of authorship: the verifier's wallet address must appear in the post text or the
+    // author's bio, where the response has one, so rewards can't be claimed for someone
+    // else's post
+    let author_bio = post.author_bio;
+    let authorship_matched =
+        post_text.contains(&verifier_address) || author_bio.contains(&verifier_address);
+    debug_log!(
+        "Authorship matched: {:?} (required: {:?})",
+        authorship_matched,
+        require_authorship
+    );
+
+    // Count matching keywords, then apply the campaign's match mode
+    let matcher = KeywordMatcher::new(&post_text, match_flags);
+    let mut matched_keywords = 0usize;
+    for (keyword, found) in keywords.iter().zip(matcher.find_all(keywords)) {
+        match found {
+            Some(pos) => {
+                matched_keywords += 1;
+                // Keyword found, text before and after keywords are printed
+                debug_log!("Found keyword: {:?}", keyword);
+                debug_log!("Context: {:?}", matcher.context(pos, keyword.len(), 10));
+            }
+            None => debug_log!("Missing keyword: {:?}", keyword),
+        }
+    }
+    let post_satisfied = match_mode.is_satisfied(matched_keywords, keywords.len());
+    debug_log!(
+        "Matched {:?} of {:?} keywords ({:?})",
+        matched_keywords,
+        keywords.len(),
+        match_mode
+    );
+
+    // Thread campaigns instead need enough of the author's posts in the thread to
+    // satisfy the keyword rules on their own
+    let thread_matchers: Vec<KeywordMatcher> = post
+        .thread
+        .iter()
+        .map(|text| KeywordMatcher::new(text, match_flags))
+        .collect();
+    let thread_matching_posts = if min_thread_posts > 0 {
+        let thread_post_satisfied = |matcher: &&KeywordMatcher| {
+            let matched = matcher.find_all(keywords).iter().flatten().count();
+            match_mode.is_satisfied(matched, keywords.len())
+        };
+        let count = usize::from(post_satisfied)
+            + thread_matchers.iter().filter(thread_post_satisfied).count();
+        debug_log!(
+            "Thread posts matching: {:?} of {:?} (required: {:?})",
+            count,
+            thread_matchers.len() + 1,
+            min_thread_posts
+        );
+        count.min(u8::MAX as usize) as u8
+    } else {
+        0
+    };
+    let keywords_satisfied = if min_thread_posts > 0 {
+        thread_matching_posts >= min_thread_posts
+    } else {
+        post_satisfied
+    };
+
+    // Brand safety: any blocked term anywhere in the thread fails the post outright
+    let blocked_keyword = std::iter::once(&matcher)
+        .chain(&thread_matchers)
+        .find_map(|matcher| {
+            blocked_keywords
+                .iter()
+                .zip(matcher.find_all(blocked_keywords))
+                .find_map(|(keyword, found)| found.map(|_| keyword))
+        });
+    if let Some(keyword) = blocked_keyword {
+        debug_log!("Blocked keyword present: {:?}", keyword);
+    }
+
+    // Hashtags and mentions must be real facets, not plain text lookalikes
+    let missing_facet = required_facets
+        .iter()
+        .find(|requirement| !has_facet(&post.tags, &post.mentions, requirement));
+    if let Some(requirement) = missing_facet {
+        debug_log!("Missing facet: {:?}", requirement);
+    }
+
+    // Paid promotions must be disclosed with one of the campaign's hashtags
+    let disclosed = has_disclosure(&post.tags, &post_text, disclosure_tags);
+    debug_log!(
+        "Disclosed: {:?} (accepted: {:?})",
+        disclosed,
+        disclosure_tags
+    );
+
+    // In regex mode every pattern must compile, otherwise a typo silently never matches
+    let invalid_pattern = match_flags & MATCH_FLAG_REGEX != 0
+        && keywords
+            .iter()
+            .chain(blocked_keywords)
+            .any(|pattern| compile_pattern(pattern, match_flags).is_none());
+
+    // Engagement is proven as of when the prover fetched the post
+    let metrics = post.metrics;
+    let engagement_met = engagement.is_met(&metrics);
+    debug_log!(
+        "Engagement met: {:?} (required: {:?})",
+        engagement_met,
+        engagement
+    );
+
+    // The post must have been created inside the campaign window. An unparseable
+    // createdAt only matters when there is a window to check.
+    let created_at = post.created_at;
+    debug_log!("Created at: {:?} (window: {:?})", created_at, recency);
+    let within_window = recency.is_unbounded() || created_at.is_some_and(|ts| recency.contains(ts));
+
+    // Region-targeted campaigns only accept posts tagged with one of their languages
+    let language_matched = has_accepted_language(&post.langs, required_langs);
+    debug_log!(
+        "Post langs: {:?} (accepted: {:?})",
+        post.langs,
+        required_langs
+    );
+
+    // Community campaigns only accept posts made in one of their subreddits
+    let community = post.community.as_deref();
+    let community_matched = required_communities.is_empty()
+        || community.is_some_and(|c| {
+            required_communities
+                .iter()
+                .any(|r| r.eq_ignore_ascii_case(c))
+        });
+    debug_log!(
+        "Community: {:?} (required: {:?})",
+        community,
+        required_communities
+    );
+
+    // Low-effort "keyword only" posts fail the substance requirement
+    let stats = content_stats(&post_text);
+    let substantive =
+        stats.chars >= content.min_chars as usize && stats.words >= content.min_words as usize;
+    debug_log!("Content: {:?} (required: {:?})", stats, content);
+
+    // Campaigns can require a photo or a link card to their site
+    let media_matched = has_required_media(&post.media, media.min_images, &media.external_domain);
+    debug_log!("Media matched: {:?} (required: {:?})", media_matched, media);
+
+    // Links come from rich-text annotations and the link card
+    let matched_link = find_allowed_link(&post.links, allowed_link_domains);
+    let link_matched = allowed_link_domains.is_empty() || matched_link.is_some();
+    debug_log!(
+        "Matched link domain: {:?} (allowed: {:?})",
+        matched_link,
+        allowed_link_domains
+    );
+    let link_domain_hash = matched_link.map_or([0u8; 32], |domain| sha256(domain.as_bytes()));
+
+    // Reply campaigns only accept direct replies to the announcement post
+    let parent_uri = post.parent_uri.as_deref();
+    let reply_matched = reply_to_uri.is_empty() || parent_uri == Some(reply_to_uri.as_str());
+    debug_log!(
+        "Reply parent: {:?} (required: {:?})",
+        parent_uri,
+        reply_to_uri
+    );
+    let parent_uri_hash = parent_uri.map_or([0u8; 32], |uri| sha256(uri.as_bytes()));
+
+    // Sybil resistance: established accounts only, measured at request time
+    let author_eligible = author_requirements.is_met(post.author_created_at, post.author_followers);
+    debug_log!(
+        "Author created at {:?} with {:?} followers (required: {:?})",
+        post.author_created_at,
+        post.author_followers,
+        author_requirements
+    );
+
+    // Campaigns can be limited to specific creators, e.g. partner YouTube channels
+    let author_allowed =
+        allowed_author_ids.is_empty() || allowed_author_ids.contains(&post.author_id);
+    debug_log!(
+        "Author {:?} allowed: {:?} (allowlist: {:?})",
+        post.author_id,
+        author_allowed,
+        allowed_author_ids
+    );
+
+    // Brand safety: labeled posts and labeled authors are never paid
+    let blocked_label = find_blocked_label(&post.labels, blocked_labels);
+    debug_log!(
+        "Blocked label: {:?} (blocked: {:?})",
+        blocked_label,
+        blocked_labels
+    );
+
+    // Committed for the payout path, which can reject implausible engagement
+    let likes_per_follower_bps = likes_per_follower_bps(metrics.like_count, post.author_followers);
+    debug_log!("Likes per follower: {:?} bps", likes_per_follower_bps);
+
+    // Multi-market campaigns pay per segment, from separate budgets
+    let segment = select_segment(segments, &post.langs, &post.author_handle);
+    debug_log!("Segment: {:?} (segments: {:?})", segment, segments);
+
+    // Every failed check is committed as a bit; the reason code is the most important
+    // one, in this order
+    let checks = [
+        (invalid_pattern, REASON_INVALID_PATTERN),
+        (blocked_label.is_some(), REASON_LABELED),
+        (!within_window, REASON_OUTSIDE_WINDOW),
+        (!language_matched, REASON_LANGUAGE_MISMATCH),
+        (!community_matched, REASON_WRONG_COMMUNITY),
+        (!substantive, REASON_TOO_SHORT),
+        (!media_matched, REASON_MISSING_MEDIA),
+        (!link_matched, REASON_MISSING_LINK),
+        (!reply_matched, REASON_NOT_A_REPLY),
+        (!author_eligible, REASON_AUTHOR_INELIGIBLE),
+        (!author_allowed, REASON_AUTHOR_NOT_ALLOWED),
+        (blocked_keyword.is_some(), REASON_BLOCKED_KEYWORD),
+        (!keywords_satisfied, REASON_MISSING_KEYWORD),
+        (missing_facet.is_some(), REASON_MISSING_FACET),
+        (!disclosed, REASON_MISSING_DISCLOSURE),
+        (
+            require_authorship && !authorship_matched,
+            REASON_AUTHORSHIP_MISMATCH,
+        ),
+        (!engagement_met, REASON_LOW_ENGAGEMENT),
+    ];
+    let failed_checks = checks
+        .iter()
+        .filter(|(failed, _)| *failed)
+        .fold(0, |bits, &(_, reason)| bits | reason_bit(reason));
+    let reason_code = checks
+        .iter()
+        .find(|(failed, _)| *failed)
+        .map_or(REASON_OK, |&(_, reason)| reason);
+
+    PostVerificationOutput {
+        version: OUTPUT_VERSION,
+        failed_checks,
+        reason_code,
+        authorship_matched,
+        engagement_met,
+        post_uri_hash: sha256(post.uri.as_bytes()),
+        author_did_hash: sha256(post.author_id.as_bytes()),
+        author_handle_hash: if post.author_handle.is_empty() {
+            [0u8; 32]
+        } else {
+            sha256(post.author_handle.as_bytes())
+        },
+        segment,
+        response_authenticated,
+        post_url_hash: input.post_url_hash,
+        config: input.config,
+        thread_matching_posts,
+        metrics,
+        link_domain_hash,
+        parent_uri_hash,
+        likes_per_follower_bps,
+    }
+}
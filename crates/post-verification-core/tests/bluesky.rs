@@ -0,0 +1,432 @@
+//! Campaign checks run end to end over Bluesky API responses, the way the guest runs
+//! them: parse with the platform adapter, then evaluate. Fixtures are `getPosts` (and
+//! one `getPostThread`) responses in `tests/fixtures/bluesky`.
+
+use post_verification_core::evaluate;
+use post_verification_core::platform::{adapter, thread_adapter};
+use proof_of_post_common::*;
+use sha2::{Digest, Sha256};
+
+const ANNOUNCEMENT: &str = "at://did:plc:bonsolannounce7xq2/app.bsky.feed.post/3lbanncmnt2a";
+/// 2024-11-25T00:00:00Z, when the fixtures are proven
+const AS_OF_TS: i64 = 1_732_492_800;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!(
+        "{}/tests/fixtures/bluesky/{name}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read(&path).unwrap_or_else(|e| panic!("{path}: {e}"))
+}
+
+/// A campaign for `keywords` with every other check switched off
+fn campaign(keywords: &[&str]) -> PublicInput {
+    PublicInput {
+        post_size: 0,
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        blocked_keywords: Vec::new(),
+        required_facets: Vec::new(),
+        match_mode: MatchMode::All,
+        match_flags: 0,
+        require_authorship: false,
+        verifier: [7u8; 32],
+        engagement: EngagementThresholds::default(),
+        recency: RecencyWindow::default(),
+        required_langs: Vec::new(),
+        content: ContentRequirements::default(),
+        media: MediaRequirement::default(),
+        allowed_link_domains: Vec::new(),
+        reply_to_uri: String::new(),
+        author: AuthorRequirements {
+            as_of_ts: AS_OF_TS,
+            ..Default::default()
+        },
+        blocked_labels: Vec::new(),
+        platform: PLATFORM_BLUESKY,
+        required_communities: Vec::new(),
+        disclosure_tags: Vec::new(),
+        allowed_author_ids: Vec::new(),
+        segments: Vec::new(),
+        response_signer: [0u8; 32],
+        post_url_hash: [4u8; 32],
+        config: [5u8; 32],
+        response_encoding: ENCODING_IDENTITY,
+        min_thread_posts: 0,
+        rules_bundle: None,
+    }
+}
+
+fn verify(name: &str, input: &PublicInput) -> PostVerificationOutput {
+    let adapter = if input.min_thread_posts > 0 {
+        thread_adapter(input.platform)
+    } else {
+        adapter(input.platform)
+    }
+    .unwrap();
+    let rules = RulesBundle {
+        keywords: input.keywords.clone(),
+        blocked_keywords: input.blocked_keywords.clone(),
+        required_facets: input.required_facets.clone(),
+    };
+    let parsed = adapter.parse(&mut fixture(name).as_slice());
+    evaluate(input, &rules, parsed, false)
+}
+
+fn reason(name: &str, input: &PublicInput) -> u16 {
+    verify(name, input).reason_code
+}
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+#[test]
+fn every_fixture_mentioning_the_keyword_passes() {
+    let input = campaign(&["bonsol"]);
+    let passing = [
+        "plain_text",
+        "hashtag_facets",
+        "mention_facet",
+        "link_facet",
+        "two_images",
+        "quote_with_images",
+        "quote_only",
+        "reply_to_announcement",
+        "nested_reply",
+        "japanese",
+        "emoji",
+        "labeled_spam",
+        "labeled_author",
+        "retracted_label",
+        "new_account",
+        "established_account",
+        "viral",
+        "disclosure_facet",
+        "disclosure_text",
+        "keyword_only",
+        "wallet_in_text",
+        "blocked_keyword",
+        "multiline",
+        "old_post",
+        "no_langs",
+        "multiple_posts",
+    ];
+    for name in passing {
+        let output = verify(name, &input);
+        assert_eq!(output.reason_code, REASON_OK, "{name}");
+        assert_eq!(output.failed_checks, 0, "{name}");
+        assert!(output.passed(), "{name}");
+    }
+    // No "bonsol" in the text: one is a link card, the other accented
+    assert_eq!(reason("external_card", &input), REASON_MISSING_KEYWORD);
+    assert_eq!(reason("diacritics", &input), REASON_MISSING_KEYWORD);
+}
+
+#[test]
+fn unusable_responses_fail_early() {
+    let input = campaign(&["bonsol"]);
+    assert_eq!(reason("not_found", &input), REASON_NO_POST);
+    assert_eq!(reason("error", &input), REASON_PARSE_ERROR);
+    assert_eq!(reason("thread", &input), REASON_PARSE_ERROR);
+}
+
+#[test]
+fn commits_the_first_post_of_a_batch() {
+    let output = verify("multiple_posts", &campaign(&["bonsol"]));
+    assert_eq!(
+        output.post_uri_hash,
+        sha256(b"at://did:plc:q6gjnaw2blty4crticxkmujt/app.bsky.feed.post/3lbfj2kzq229")
+    );
+    assert_eq!(
+        output.author_did_hash,
+        sha256(b"did:plc:q6gjnaw2blty4crticxkmujt")
+    );
+    assert_eq!(output.author_handle_hash, sha256(b"jay.bsky.team"));
+    assert_eq!(output.post_url_hash, [4u8; 32]);
+    assert_eq!(output.config, [5u8; 32]);
+    assert_eq!(output.version, OUTPUT_VERSION);
+}
+
+#[test]
+fn match_modes_and_flags() {
+    let mut input = campaign(&["bonsol", "#zk", "mainnet"]);
+    assert_eq!(reason("emoji", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_KEYWORD);
+    input.match_mode = MatchMode::AtLeast(1);
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    input.match_mode = MatchMode::AtLeast(2);
+    assert_eq!(reason("keyword_only", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_KEYWORD);
+
+    let mut input = campaign(&["bonsol"]);
+    input.match_flags = MATCH_FLAG_FOLD_DIACRITICS;
+    assert_eq!(reason("diacritics", &input), REASON_OK);
+    input.match_flags = MATCH_FLAG_CASE_SENSITIVE;
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    assert_eq!(reason("diacritics", &input), REASON_MISSING_KEYWORD);
+
+    // "bonsol's" still has "bonsol" on a word boundary
+    let mut input = campaign(&["bonsol"]);
+    input.match_flags = MATCH_FLAG_WHOLE_WORD;
+    assert_eq!(reason("disclosure_facet", &input), REASON_OK);
+    let mut input = campaign(&["bons"]);
+    input.match_flags = MATCH_FLAG_WHOLE_WORD;
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_KEYWORD);
+}
+
+#[test]
+fn whitespace_is_collapsed_unless_exact() {
+    let mut input = campaign(&["zero knowledge proofs"]);
+    assert_eq!(reason("multiline", &input), REASON_OK);
+    input.match_flags = MATCH_FLAG_EXACT_PHRASE;
+    assert_eq!(reason("multiline", &input), REASON_MISSING_KEYWORD);
+}
+
+#[test]
+fn regex_keywords() {
+    let mut input = campaign(&[r"proofs? (are|is) (live|cooking)"]);
+    input.match_flags = MATCH_FLAG_REGEX;
+    assert_eq!(reason("emoji", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    assert_eq!(reason("viral", &input), REASON_MISSING_KEYWORD);
+
+    let mut input = campaign(&["(unclosed"]);
+    input.match_flags = MATCH_FLAG_REGEX;
+    assert_eq!(reason("plain_text", &input), REASON_INVALID_PATTERN);
+}
+
+#[test]
+fn multibyte_text_and_facets() {
+    let mut input = campaign(&["ゼロ知識証明"]);
+    input.required_facets = vec![String::from("#bonsol")];
+    input.required_langs = vec![String::from("ja")];
+    assert_eq!(reason("japanese", &input), REASON_OK);
+
+    let mut input = campaign(&["mainnet"]);
+    input.required_facets = vec![String::from("#zk"), String::from("#bonsol")];
+    assert_eq!(reason("emoji", &input), REASON_OK);
+}
+
+#[test]
+fn required_facets_must_be_annotations() {
+    let mut input = campaign(&[]);
+    input.required_facets = vec![String::from("#bonsol")];
+    assert_eq!(reason("hashtag_facets", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_FACET);
+    // "#sponsored" is typed in the text without a tag facet
+    input.required_facets = vec![String::from("#sponsored")];
+    assert_eq!(reason("disclosure_text", &input), REASON_MISSING_FACET);
+
+    input.required_facets = vec![String::from("@bonsol.bsky.social")];
+    assert_eq!(reason("mention_facet", &input), REASON_OK);
+    assert_eq!(reason("hashtag_facets", &input), REASON_MISSING_FACET);
+}
+
+#[test]
+fn blocked_keywords() {
+    let mut input = campaign(&["bonsol"]);
+    input.blocked_keywords = vec![String::from("scam"), String::from("airdrop")];
+    assert_eq!(reason("blocked_keyword", &input), REASON_BLOCKED_KEYWORD);
+    assert_eq!(reason("labeled_spam", &input), REASON_BLOCKED_KEYWORD);
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+}
+
+#[test]
+fn links_and_link_cards() {
+    let mut input = campaign(&[]);
+    input.allowed_link_domains = vec![String::from("bonsol.sh/launch")];
+    let output = verify("link_facet", &input);
+    assert_eq!(output.reason_code, REASON_OK);
+    assert_eq!(output.link_domain_hash, sha256(b"bonsol.sh/launch"));
+    assert_eq!(reason("external_card", &input), REASON_MISSING_LINK);
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_LINK);
+
+    input.allowed_link_domains = vec![String::from("bonsol.sh")];
+    assert_eq!(reason("external_card", &input), REASON_OK);
+    assert_eq!(
+        verify("plain_text", &campaign(&[])).link_domain_hash,
+        [0u8; 32]
+    );
+}
+
+#[test]
+fn media_requirements() {
+    let mut input = campaign(&[]);
+    input.media.min_images = 2;
+    assert_eq!(reason("two_images", &input), REASON_OK);
+    assert_eq!(reason("quote_with_images", &input), REASON_OK);
+    assert_eq!(reason("quote_only", &input), REASON_MISSING_MEDIA);
+    assert_eq!(reason("external_card", &input), REASON_MISSING_MEDIA);
+
+    let mut input = campaign(&[]);
+    input.media.external_domain = String::from("bonsol.sh");
+    assert_eq!(reason("external_card", &input), REASON_OK);
+    // A link facet in the text is not a link card
+    assert_eq!(reason("link_facet", &input), REASON_MISSING_MEDIA);
+}
+
+#[test]
+fn replies_must_answer_the_announcement() {
+    let mut input = campaign(&[]);
+    input.reply_to_uri = String::from(ANNOUNCEMENT);
+    let output = verify("reply_to_announcement", &input);
+    assert_eq!(output.reason_code, REASON_OK);
+    assert_eq!(output.parent_uri_hash, sha256(ANNOUNCEMENT.as_bytes()));
+    // Replying deeper in the announcement's thread is not a direct reply
+    assert_eq!(reason("nested_reply", &input), REASON_NOT_A_REPLY);
+    assert_eq!(reason("plain_text", &input), REASON_NOT_A_REPLY);
+}
+
+#[test]
+fn languages_and_segments() {
+    let mut input = campaign(&[]);
+    input.required_langs = vec![String::from("en"), String::from("es")];
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    assert_eq!(reason("diacritics", &input), REASON_OK);
+    assert_eq!(reason("japanese", &input), REASON_LANGUAGE_MISMATCH);
+    assert_eq!(reason("no_langs", &input), REASON_LANGUAGE_MISMATCH);
+
+    let mut input = campaign(&[]);
+    input.segments = vec![
+        SegmentRule {
+            kind: SEGMENT_LANGUAGE,
+            value: String::from("ja"),
+        },
+        SegmentRule {
+            kind: SEGMENT_HANDLE_SUFFIX,
+            value: String::from(".bsky.social"),
+        },
+    ];
+    assert_eq!(verify("japanese", &input).segment, 0);
+    assert_eq!(verify("diacritics", &input).segment, 1);
+    assert_eq!(verify("plain_text", &input).segment, SEGMENT_NONE);
+}
+
+#[test]
+fn moderation_labels() {
+    let mut input = campaign(&[]);
+    input.blocked_labels = vec![String::from("spam")];
+    assert_eq!(reason("labeled_spam", &input), REASON_LABELED);
+    assert_eq!(reason("labeled_author", &input), REASON_LABELED);
+    // The labeler negated its own label
+    assert_eq!(reason("retracted_label", &input), REASON_OK);
+}
+
+#[test]
+fn author_requirements() {
+    let mut input = campaign(&[]);
+    input.author.min_account_age_days = 30;
+    assert_eq!(reason("established_account", &input), REASON_OK);
+    assert_eq!(reason("new_account", &input), REASON_AUTHOR_INELIGIBLE);
+    // getPosts has no followersCount, so a follower minimum can't pass
+    input.author.min_followers = 100;
+    assert_eq!(
+        reason("established_account", &input),
+        REASON_AUTHOR_INELIGIBLE
+    );
+
+    let mut input = campaign(&[]);
+    input.allowed_author_ids = vec![String::from("did:plc:kenjix2q7dmz3t")];
+    assert_eq!(reason("japanese", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_AUTHOR_NOT_ALLOWED);
+}
+
+#[test]
+fn engagement_thresholds() {
+    let mut input = campaign(&[]);
+    input.engagement.min_likes = 1000;
+    input.engagement.min_reposts = 100;
+    let output = verify("viral", &input);
+    assert_eq!(output.reason_code, REASON_OK);
+    assert!(output.engagement_met);
+    assert_eq!(output.metrics.like_count, 1520);
+    assert_eq!(output.metrics.quote_count, 41);
+    assert_eq!(output.likes_per_follower_bps, RATIO_UNKNOWN);
+
+    let output = verify("established_account", &input);
+    assert_eq!(output.reason_code, REASON_LOW_ENGAGEMENT);
+    assert!(!output.engagement_met);
+}
+
+#[test]
+fn recency_window() {
+    let mut input = campaign(&[]);
+    input.recency.not_before_ts = 1_704_067_200;
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    assert_eq!(reason("old_post", &input), REASON_OUTSIDE_WINDOW);
+    input.recency = RecencyWindow {
+        not_before_ts: 0,
+        not_after_ts: 1_704_067_200,
+    };
+    assert_eq!(reason("old_post", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_OUTSIDE_WINDOW);
+}
+
+#[test]
+fn disclosure_and_substance() {
+    let mut input = campaign(&[]);
+    input.disclosure_tags = vec![String::from("#ad"), String::from("#sponsored")];
+    assert_eq!(reason("disclosure_facet", &input), REASON_OK);
+    assert_eq!(reason("disclosure_text", &input), REASON_OK);
+    assert_eq!(reason("plain_text", &input), REASON_MISSING_DISCLOSURE);
+
+    let mut input = campaign(&[]);
+    input.content.min_words = 5;
+    assert_eq!(reason("plain_text", &input), REASON_OK);
+    assert_eq!(reason("keyword_only", &input), REASON_TOO_SHORT);
+}
+
+#[test]
+fn authorship_from_post_text() {
+    // getPosts carries no author bio, so on Bluesky only the text binds authorship
+    let mut input = campaign(&[]);
+    input.require_authorship = true;
+    let output = verify("wallet_in_text", &input);
+    assert!(output.authorship_matched);
+    assert_eq!(output.reason_code, REASON_OK);
+
+    let output = verify("plain_text", &input);
+    assert!(!output.authorship_matched);
+    assert_eq!(output.reason_code, REASON_AUTHORSHIP_MISMATCH);
+    // Authorship is still committed when the campaign doesn't require it
+    assert!(verify("wallet_in_text", &campaign(&[])).authorship_matched);
+}
+
+#[test]
+fn every_failed_check_is_committed() {
+    let mut input = campaign(&["mainnet"]);
+    input.blocked_labels = vec![String::from("spam")];
+    input.content.min_words = 20;
+    input.engagement.min_likes = 100;
+    let output = verify("labeled_spam", &input);
+    // Labels come first in the reason order
+    assert_eq!(output.reason_code, REASON_LABELED);
+    assert_eq!(
+        output.failed_checks,
+        reason_bit(REASON_LABELED)
+            | reason_bit(REASON_TOO_SHORT)
+            | reason_bit(REASON_MISSING_KEYWORD)
+            | reason_bit(REASON_LOW_ENGAGEMENT)
+    );
+}
+
+#[test]
+fn thread_mode_counts_the_authors_posts() {
+    let mut input = campaign(&["bonsol"]);
+    input.min_thread_posts = 3;
+    let output = verify("thread", &input);
+    assert_eq!(output.reason_code, REASON_OK);
+    // 1/, 2/ and 3/ mention bonsol; 4/ doesn't, and the reply to a reader isn't part
+    // of the thread
+    assert_eq!(output.thread_matching_posts, 3);
+
+    input.min_thread_posts = 4;
+    let output = verify("thread", &input);
+    assert_eq!(output.reason_code, REASON_MISSING_KEYWORD);
+    assert_eq!(output.thread_matching_posts, 3);
+
+    // A blocked term anywhere in the thread fails it
+    input.min_thread_posts = 1;
+    input.blocked_keywords = vec![String::from("thanks for reading")];
+    assert_eq!(reason("thread", &input), REASON_BLOCKED_KEYWORD);
+}
@@ -0,0 +1,474 @@
+//! LiteSVM environment for the program's integration tests. It loads the program and
+//! bonsol-mock builds, so requests go through the real execute_v1 CPI and results
+//! through the real callback, with journals crafted by the test instead of proven.
+//!
+//! Build both programs first (`anchor build`, then `cargo build-sbf --manifest-path
+//! programs/bonsol-mock/Cargo.toml`). The tests are ignored without them and run with
+//! `cargo test -p program-tests -- --ignored`.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use litesvm::LiteSVM;
+use proof_of_post::{
+    sponsored_verify_message, ChallengeArgs, CircuitBreaker, CreateConfigArgs, JournalBounds,
+    PostProofConfig, PostProofError, PostVerificationLog, ProtocolConfig, SponsoredVerifyArgs,
+    VerifyPostArgs, DEFAULT_EXECUTION_EXPIRY_SLOTS, DEFAULT_SIGNATURE_FEE_LAMPORTS,
+    DEFAULT_VERIFY_COOLDOWN_SECS, DEFAULT_VERIFY_COOLDOWN_SLOTS, POST_VERIFICATION_IMAGE_ID,
+};
+use proof_of_post_client::{instructions, request_id};
+use proof_of_post_common::{PostVerificationOutput, ENCODING_IDENTITY, RATIO_UNKNOWN, REASON_OK};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction_with_signature;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const BONSOL: Pubkey = pubkey!("BoNsHRcyLLNdtnoDf8hiCNZpyehMC4FDMxs6NTxFi3ew");
+pub const POST_URL: &str =
+    "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?uris=at://did:plc:abc/app.bsky.feed.post/1";
+/// Prover tip of the requests the environment makes
+pub const TIP: u64 = 1_000;
+/// Lamports every funded keypair starts with
+pub const AIRDROP: u64 = 10_000_000_000;
+/// Slot the environment starts at
+const START_SLOT: u64 = 1_000;
+/// Nonce of the request IDs the environment derives, unless a retry picks another
+const NONCE: u64 = 7;
+
+/// `error` as the transaction error of the instruction at `index`
+pub fn program_error(index: u8, error: PostProofError) -> TransactionError {
+    TransactionError::InstructionError(
+        index,
+        InstructionError::Custom(ERROR_CODE_OFFSET + error as u32),
+    )
+}
+
+/// A campaign paying `reward` per post containing "bonsol" to up to `max_claimers`
+/// authors, with its platform share going to a real treasury: the default key is the
+/// system program, which the runtime won't lock writable
+pub fn campaign_args(seeds: &str, reward: u64, max_claimers: u64) -> CreateConfigArgs {
+    let mut args = instructions::open_campaign_args(seeds, &["bonsol"], reward, max_claimers);
+    args.platform_treasury = Pubkey::new_unique();
+    args
+}
+
+/// A verify_post request made through [`TestEnv::verify`]
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub verifier: Pubkey,
+    pub campaign: Pubkey,
+    pub author_did_hash: [u8; 32],
+    pub req_id: String,
+    /// Bonsol's requester: the verifier, or the relayer of a sponsored request
+    pub requester: Pubkey,
+    pub execution_request: Pubkey,
+}
+
+impl Request {
+    /// A journal passing every check, for this request's post and campaign
+    pub fn passing_output(&self) -> PostVerificationOutput {
+        PostVerificationOutput {
+            engagement_met: true,
+            author_did_hash: self.author_did_hash,
+            likes_per_follower_bps: RATIO_UNKNOWN,
+            ..self.failed_output(REASON_OK)
+        }
+    }
+
+    /// A journal failing with `reason_code`, bound to this request's post and campaign
+    pub fn failed_output(&self, reason_code: u16) -> PostVerificationOutput {
+        PostVerificationOutput {
+            post_url_hash: hash(POST_URL.as_bytes()).to_bytes(),
+            config: self.campaign.to_bytes(),
+            ..PostVerificationOutput::failed(reason_code)
+        }
+    }
+}
+
+pub struct TestEnv {
+    pub svm: LiteSVM,
+    pub creator: Keypair,
+    /// Pays for deliveries, so they don't show in the other parties' balances
+    pub prover: Keypair,
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(proof_of_post::ID, program("proof_of_post"))
+            .unwrap();
+        svm.add_program_from_file(BONSOL, program("bonsol_mock"))
+            .unwrap();
+        svm.warp_to_slot(START_SLOT);
+        let creator = Keypair::new();
+        let prover = Keypair::new();
+        svm.airdrop(&creator.pubkey(), AIRDROP).unwrap();
+        svm.airdrop(&prover.pubkey(), AIRDROP).unwrap();
+
+        let (_, bump) = Pubkey::find_program_address(
+            &[proof_of_post::PROTOCOL_CONFIG_SEED],
+            &proof_of_post::ID,
+        );
+        let mut env = Self {
+            svm,
+            creator,
+            prover,
+        };
+        env.set_protocol(ProtocolConfig {
+            authority: env.creator.pubkey(),
+            feature_flags: 0,
+            guardian: env.creator.pubkey(),
+            breaker: CircuitBreaker::default(),
+            min_tip: 0,
+            max_tip: 0,
+            max_post_size: 0,
+            journal_bounds: JournalBounds::default(),
+            bump,
+            version: ProtocolConfig::VERSION,
+            verify_cooldown_slots: DEFAULT_VERIFY_COOLDOWN_SLOTS,
+            verify_cooldown_secs: DEFAULT_VERIFY_COOLDOWN_SECS,
+            execution_expiry_slots: DEFAULT_EXECUTION_EXPIRY_SLOTS,
+            signature_fee_lamports: DEFAULT_SIGNATURE_FEE_LAMPORTS,
+            pending_authority: None,
+            paused: false,
+        });
+        env
+    }
+
+    /// Writes the protocol config as is, since init_protocol needs an upgradeable
+    /// deployment
+    pub fn set_protocol(&mut self, protocol_config: ProtocolConfig) {
+        let mut data = Vec::new();
+        protocol_config.try_serialize(&mut data).unwrap();
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: proof_of_post::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let address = instructions::protocol_config_address(&proof_of_post::ID);
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    pub fn protocol(&self) -> ProtocolConfig {
+        self.account(&instructions::protocol_config_address(&proof_of_post::ID))
+    }
+
+    pub fn send(&mut self, payer: &Keypair, ixs: &[Instruction]) -> Result<(), TransactionError> {
+        send(&mut self.svm, payer, ixs)
+    }
+
+    /// A new keypair holding [`AIRDROP`] lamports
+    pub fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm.airdrop(&keypair.pubkey(), AIRDROP).unwrap();
+        keypair
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self.svm.get_account(address).unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or(0)
+    }
+
+    /// Lamports keeping the account at `address` rent exempt
+    pub fn rent(&self, address: &Pubkey) -> u64 {
+        let len = self.svm.get_account(address).unwrap().data.len();
+        self.svm.minimum_balance_for_rent_exemption(len)
+    }
+
+    /// Creates the creator's campaign and funds it with `budget` in the same transaction
+    pub fn create_campaign(
+        &mut self,
+        args: CreateConfigArgs,
+        budget: u64,
+    ) -> Result<Pubkey, TransactionError> {
+        let creator = self.creator.pubkey();
+        let campaign = instructions::config_address(&proof_of_post::ID, &creator, &args.seeds);
+        let create = instructions::create_config(&proof_of_post::ID, &creator, args);
+        send(
+            &mut self.svm,
+            &self.creator,
+            &[create, instructions::top_up(&creator, &campaign, budget)],
+        )?;
+        Ok(campaign)
+    }
+
+    pub fn config(&self, campaign: &Pubkey) -> PostProofConfig {
+        self.account(campaign)
+    }
+
+    pub fn log(&self, verifier: &Pubkey, campaign: &Pubkey) -> PostVerificationLog {
+        self.account(&instructions::verification_log_address(
+            &proof_of_post::ID,
+            verifier,
+            campaign,
+        ))
+    }
+
+    /// Submits `verifier`'s post by the author whose DID hashes to `author_did_hash`
+    pub fn verify(
+        &mut self,
+        verifier: &Keypair,
+        campaign: &Pubkey,
+        author_did_hash: [u8; 32],
+    ) -> Result<Request, TransactionError> {
+        self.verify_with_nonce(verifier, campaign, author_did_hash, NONCE)
+    }
+
+    /// [`TestEnv::verify`] with the request ID derived from `nonce`, as a retry needs a
+    /// new one
+    pub fn verify_with_nonce(
+        &mut self,
+        verifier: &Keypair,
+        campaign: &Pubkey,
+        author_did_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<Request, TransactionError> {
+        let args = verify_args(&verifier.pubkey(), campaign, author_did_hash, nonce);
+        let req_id = args.current_req_id.clone();
+        let ix = instructions::verify_post(
+            &proof_of_post::ID,
+            POST_VERIFICATION_IMAGE_ID,
+            &verifier.pubkey(),
+            campaign,
+            &self.config(campaign),
+            args,
+        );
+        self.send(verifier, &[ix])?;
+        let execution_request = self
+            .log(&verifier.pubkey(), campaign)
+            .current_execution_account
+            .unwrap();
+        Ok(Request {
+            verifier: verifier.pubkey(),
+            campaign: *campaign,
+            author_did_hash,
+            req_id,
+            requester: verifier.pubkey(),
+            execution_request,
+        })
+    }
+
+    /// Has `relayer` submit `verifier`'s post through sponsored_verify, with the intent
+    /// signed by `signer`, normally the verifier
+    pub fn sponsored_verify(
+        &mut self,
+        signer: &Keypair,
+        verifier: &Pubkey,
+        relayer: &Keypair,
+        campaign: &Pubkey,
+        author_did_hash: [u8; 32],
+    ) -> Result<Request, TransactionError> {
+        let args = verify_args(verifier, campaign, author_did_hash, NONCE);
+        let req_id = args.current_req_id.clone();
+        let expires_at = self.svm.get_sysvar::<Clock>().unix_timestamp + 60;
+        let message = sponsored_verify_message(campaign, &args, expires_at);
+        let signature: [u8; 64] = signer.sign_message(&message).into();
+        let intent = new_ed25519_instruction_with_signature(
+            &message,
+            &signature,
+            &signer.pubkey().to_bytes(),
+        );
+        let ix = instructions::sponsored_verify(
+            &proof_of_post::ID,
+            POST_VERIFICATION_IMAGE_ID,
+            &relayer.pubkey(),
+            verifier,
+            campaign,
+            &self.config(campaign),
+            SponsoredVerifyArgs {
+                verify: args,
+                expires_at,
+                intent_signature: signature,
+            },
+        );
+        self.send(relayer, &[intent, ix])?;
+        let execution_request = self
+            .log(verifier, campaign)
+            .current_execution_account
+            .unwrap();
+        Ok(Request {
+            verifier: *verifier,
+            campaign: *campaign,
+            author_did_hash,
+            req_id,
+            requester: relayer.pubkey(),
+            execution_request,
+        })
+    }
+
+    /// Has `challenger` dispute the reward held for `claim`, returning the
+    /// re-verification request
+    pub fn challenge(
+        &mut self,
+        challenger: &Keypair,
+        claim: &Request,
+    ) -> Result<Request, TransactionError> {
+        let log_address = instructions::verification_log_address(
+            &proof_of_post::ID,
+            &claim.verifier,
+            &claim.campaign,
+        );
+        let req_id = request_id(&challenger.pubkey(), &claim.campaign, POST_URL, 7);
+        let ix = instructions::challenge(
+            &proof_of_post::ID,
+            POST_VERIFICATION_IMAGE_ID,
+            &challenger.pubkey(),
+            &self.config(&claim.campaign),
+            &log_address,
+            &self.log(&claim.verifier, &claim.campaign),
+            ChallengeArgs {
+                current_req_id: req_id.clone(),
+                post_size: 2048,
+                tip: TIP,
+                response_signature: None,
+                response_encoding: ENCODING_IDENTITY,
+            },
+        );
+        self.send(challenger, &[ix])?;
+        let execution_request = self
+            .log(&claim.verifier, &claim.campaign)
+            .current_execution_account
+            .unwrap();
+        Ok(Request {
+            req_id,
+            requester: challenger.pubkey(),
+            execution_request,
+            ..claim.clone()
+        })
+    }
+
+    /// Delivers `output` through the mock as the proof of `request`
+    pub fn deliver(
+        &mut self,
+        request: &Request,
+        output: &PostVerificationOutput,
+    ) -> Result<(), TransactionError> {
+        self.deliver_journal(request, &output.encode())
+    }
+
+    /// Delivers `journal` as the committed output of `request`, whatever its layout
+    pub fn deliver_journal(
+        &mut self,
+        request: &Request,
+        journal: &[u8],
+    ) -> Result<(), TransactionError> {
+        let ix = self.deliver_instruction(request, journal, &self.prover.pubkey());
+        send(&mut self.svm, &self.prover, &[ix])
+    }
+
+    /// Delivers `output` as the proof of `request` from `prover`, in a transaction
+    /// [`TestEnv::prover`] pays for
+    pub fn deliver_by(
+        &mut self,
+        request: &Request,
+        output: &PostVerificationOutput,
+        prover: &Keypair,
+    ) -> Result<(), TransactionError> {
+        let ix = self.deliver_instruction(request, &output.encode(), &prover.pubkey());
+        send_signed(&mut self.svm, &self.prover, &[prover], &[ix])
+    }
+
+    fn deliver_instruction(
+        &self,
+        request: &Request,
+        journal: &[u8],
+        prover: &Pubkey,
+    ) -> Instruction {
+        let log_address = instructions::verification_log_address(
+            &proof_of_post::ID,
+            &request.verifier,
+            &request.campaign,
+        );
+        let log = self.log(&request.verifier, &request.campaign);
+        let journal = [[0u8; 32].as_slice(), journal].concat();
+        bonsol_mock::deliver_instruction(
+            &BONSOL,
+            &request.execution_request,
+            &request.requester,
+            prover,
+            &proof_of_post::ID,
+            instructions::callback_accounts(
+                &proof_of_post::ID,
+                &request.campaign,
+                &self.config(&request.campaign),
+                &log_address,
+                &log,
+                &request.req_id,
+            ),
+            &bonsol_mock::callback_payload(&journal),
+        )
+    }
+}
+
+fn verify_args(
+    verifier: &Pubkey,
+    campaign: &Pubkey,
+    author_did_hash: [u8; 32],
+    nonce: u64,
+) -> VerifyPostArgs {
+    VerifyPostArgs {
+        current_req_id: request_id(verifier, campaign, POST_URL, nonce),
+        post_url: POST_URL.to_string(),
+        post_size: 2048,
+        tip: TIP,
+        referrer: None,
+        input_hash: None,
+        payout: None,
+        author_did_hash,
+        terms_signature: None,
+        response_signature: None,
+        response_encoding: ENCODING_IDENTITY,
+        request_nonce: Some(nonce),
+    }
+}
+
+fn program(name: &str) -> String {
+    format!(
+        "{}/../../target/deploy/{name}.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+/// Each transaction gets a fresh blockhash, so repeating one isn't rejected as a
+/// duplicate
+fn send(svm: &mut LiteSVM, payer: &Keypair, ixs: &[Instruction]) -> Result<(), TransactionError> {
+    send_signed(svm, payer, &[], ixs)
+}
+
+fn send_signed(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    cosigners: &[&Keypair],
+    ixs: &[Instruction],
+) -> Result<(), TransactionError> {
+    svm.expire_blockhash();
+    let signers: Vec<&Keypair> = [payer]
+        .into_iter()
+        .chain(cosigners.iter().copied())
+        .collect();
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        &signers,
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+        .map(|_| ())
+        .map_err(|failed| failed.err)
+}
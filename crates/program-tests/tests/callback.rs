@@ -0,0 +1,322 @@
+//! The Bonsol callback: payouts, recorded failures, and requests that expire
+
+use anchor_lang::InstructionData;
+use bonsol_schema::root_as_execution_request_v1;
+use program_tests::{campaign_args, program_error, Request, TestEnv};
+use proof_of_post::{
+    AuthorClaim, PostProofError, RewardSplit, BPS_DENOMINATOR, CALLBACK_VERSION_V2,
+};
+use proof_of_post_client::instructions;
+use proof_of_post_common::*;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+
+const REWARD: u64 = 1_000_000;
+const AUTHOR: [u8; 32] = [9u8; 32];
+
+fn author_claim(env: &TestEnv, campaign: &Pubkey) -> AuthorClaim {
+    env.account(&instructions::author_claim_address(
+        &proof_of_post::ID,
+        campaign,
+        &AUTHOR,
+    ))
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn passing_proof_pays_the_verifier() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("pay", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let before = env.balance(&verifier.pubkey());
+    let budget = env.balance(&campaign);
+    // The mock returns the request's rent to the verifier, as Bonsol does
+    let request_rent = env.balance(&request.execution_request);
+
+    env.deliver(&request, &request.passing_output()).unwrap();
+
+    let log = env.log(&verifier.pubkey(), &campaign);
+    assert!(log.is_verified);
+    assert_eq!(log.current_execution_account, None);
+    assert_eq!(log.failure_reason, REASON_OK);
+    assert_eq!(
+        env.balance(&verifier.pubkey()),
+        before + REWARD + request_rent
+    );
+    assert_eq!(env.balance(&campaign), budget - REWARD);
+    let config = env.config(&campaign);
+    assert_eq!(config.claimers_count, 1);
+    assert_eq!(config.total_paid, REWARD);
+    let claim = author_claim(&env, &campaign);
+    assert!(claim.claimed);
+    assert_eq!(claim.claimer, verifier.pubkey());
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn reward_is_split_between_claimer_and_creator() {
+    let mut env = TestEnv::new();
+    let mut args = campaign_args("split", REWARD, 10);
+    args.reward_split = RewardSplit {
+        claimer_bps: 7_500,
+        creator_bps: 2_500,
+        platform_bps: 0,
+        referrer_bps: 0,
+    };
+    let campaign = env.create_campaign(args, 10 * REWARD).unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let creator = env.creator.pubkey();
+    let (verifier_before, creator_before) =
+        (env.balance(&verifier.pubkey()), env.balance(&creator));
+    let request_rent = env.balance(&request.execution_request);
+
+    env.deliver(&request, &request.passing_output()).unwrap();
+
+    let creator_share = REWARD * 2_500 / BPS_DENOMINATOR;
+    assert_eq!(env.balance(&creator), creator_before + creator_share);
+    assert_eq!(
+        env.balance(&verifier.pubkey()),
+        verifier_before + REWARD - creator_share + request_rent
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn failing_proof_records_the_reason() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("fail", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let budget = env.balance(&campaign);
+
+    env.deliver(&request, &request.failed_output(REASON_MISSING_KEYWORD))
+        .unwrap();
+
+    let log = env.log(&verifier.pubkey(), &campaign);
+    assert!(!log.is_verified);
+    assert_eq!(log.current_execution_account, None);
+    assert_eq!(log.failure_reason, REASON_MISSING_KEYWORD);
+    assert_eq!(log.failed_checks, reason_bit(REASON_MISSING_KEYWORD));
+    assert_eq!(env.balance(&campaign), budget);
+    assert_eq!(env.config(&campaign).claimers_count, 0);
+    assert!(!author_claim(&env, &campaign).claimed);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn expired_request_gets_no_callback() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("expire", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let budget = env.balance(&campaign);
+    let execution_request = env.svm.get_account(&request.execution_request).unwrap();
+    let expiry = root_as_execution_request_v1(&execution_request.data)
+        .unwrap()
+        .max_block_height();
+
+    env.svm.warp_to_slot(expiry + 1);
+    assert_eq!(
+        env.deliver(&request, &request.passing_output()),
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(bonsol_mock::ERROR_EXPIRED)
+        ))
+    );
+
+    // The request stays pending and nothing is paid
+    let log = env.log(&verifier.pubkey(), &campaign);
+    assert!(!log.is_verified);
+    assert_eq!(
+        log.current_execution_account,
+        Some(request.execution_request)
+    );
+    assert_eq!(env.balance(&campaign), budget);
+    assert_eq!(env.config(&campaign).claimers_count, 0);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn payout_never_takes_the_config_below_rent() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("floor", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+
+    // Drained after the request was accepted, one lamport short of rent plus reward
+    let mut account = env.svm.get_account(&campaign).unwrap();
+    account.lamports = env.rent(&campaign) + REWARD - 1;
+    env.svm.set_account(campaign, account).unwrap();
+
+    assert_eq!(
+        env.deliver(&request, &request.passing_output()),
+        Err(program_error(0, PostProofError::WouldBreachRentExemption))
+    );
+    assert!(!env.log(&verifier.pubkey(), &campaign).is_verified);
+    assert_eq!(env.balance(&campaign), env.rent(&campaign) + REWARD - 1);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn callback_only_comes_from_bonsol() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("direct", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let log_address =
+        instructions::verification_log_address(&proof_of_post::ID, &verifier.pubkey(), &campaign);
+    let log = env.log(&verifier.pubkey(), &campaign);
+
+    // Calling the callback directly, as a prover skipping Bonsol would
+    let mut accounts = vec![AccountMeta::new_readonly(request.execution_request, false)];
+    accounts.extend(instructions::callback_accounts(
+        &proof_of_post::ID,
+        &campaign,
+        &env.config(&campaign),
+        &log_address,
+        &log,
+        &request.req_id,
+    ));
+    let ix = Instruction::new_with_bytes(
+        proof_of_post::ID,
+        &proof_of_post::instruction::BonsolCallback {
+            data: request.passing_output().encode(),
+        }
+        .data(),
+        accounts,
+    );
+    let prover = env.funded_keypair();
+    assert_eq!(
+        env.send(&prover, &[ix]),
+        Err(program_error(0, PostProofError::CallbackNotFromBonsol))
+    );
+    assert!(!env.log(&verifier.pubkey(), &campaign).is_verified);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn redelivered_callback_pays_once() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("replay", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    let execution_request = env.svm.get_account(&request.execution_request).unwrap();
+    env.deliver(&request, &request.passing_output()).unwrap();
+    let budget = env.balance(&campaign);
+
+    // The mock closed the request; bring it back as a faulty Bonsol might
+    env.svm
+        .set_account(request.execution_request, execution_request)
+        .unwrap();
+    assert_eq!(
+        env.deliver(&request, &request.passing_output()),
+        Err(program_error(0, PostProofError::CallbackAlreadyProcessed))
+    );
+    assert_eq!(env.balance(&campaign), budget);
+    assert_eq!(env.config(&campaign).claimers_count, 1);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn only_allowed_provers_deliver() {
+    let mut env = TestEnv::new();
+    let mut args = campaign_args("trusted", REWARD, 10);
+    args.allowed_provers = vec![Pubkey::new_unique()];
+    let campaign = env.create_campaign(args, 10 * REWARD).unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+
+    // The environment's prover isn't on the list, so the request stays open
+    assert_eq!(
+        env.deliver(&request, &request.passing_output()),
+        Err(program_error(0, PostProofError::ProverNotAllowed))
+    );
+    let log = env.log(&verifier.pubkey(), &campaign);
+    assert_eq!(
+        log.current_execution_account,
+        Some(request.execution_request)
+    );
+
+    let mut args = campaign_args("ours", REWARD, 10);
+    args.allowed_provers = vec![env.prover.pubkey()];
+    let campaign = env.create_campaign(args, 10 * REWARD).unwrap();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    env.deliver(&request, &request.passing_output()).unwrap();
+    assert!(env.log(&verifier.pubkey(), &campaign).is_verified);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn allowed_provers_are_told_apart_from_the_fee_payer() {
+    let mut env = TestEnv::new();
+    let prover = Keypair::new();
+    let verifier = env.funded_keypair();
+
+    // The fee payer being allowed doesn't let another prover deliver
+    let mut args = campaign_args("payer", REWARD, 10);
+    args.allowed_provers = vec![env.prover.pubkey()];
+    let campaign = env.create_campaign(args, 10 * REWARD).unwrap();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    assert_eq!(
+        env.deliver_by(&request, &request.passing_output(), &prover),
+        Err(program_error(0, PostProofError::ProverNotAllowed))
+    );
+
+    // An allowed prover delivers in a transaction someone else pays for
+    let mut args = campaign_args("prover", REWARD, 10);
+    args.allowed_provers = vec![prover.pubkey()];
+    let campaign = env.create_campaign(args, 10 * REWARD).unwrap();
+    let request = env.verify(&verifier, &campaign, AUTHOR).unwrap();
+    env.deliver_by(&request, &request.passing_output(), &prover)
+        .unwrap();
+    assert!(env.log(&verifier.pubkey(), &campaign).is_verified);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn callback_versions_take_different_journal_formats() {
+    let mut env = TestEnv::new();
+    let verifier = env.funded_keypair();
+    let v1 = env
+        .create_campaign(campaign_args("v1", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let mut args = campaign_args("v2", REWARD, 10);
+    args.callback_version = CALLBACK_VERSION_V2;
+    let v2 = env.create_campaign(args, 10 * REWARD).unwrap();
+
+    // A later guest's journal: a newer version with a field appended
+    let extended = |request: &Request| {
+        let mut journal = request.passing_output().encode();
+        journal[0] = OUTPUT_VERSION + 1;
+        journal.extend_from_slice(&[1u8; 8]);
+        journal
+    };
+
+    // The v1 entrypoint only takes the current layout
+    let request = env.verify(&verifier, &v1, AUTHOR).unwrap();
+    assert_eq!(
+        env.deliver_journal(&request, &extended(&request)),
+        Err(program_error(0, PostProofError::UnsupportedOutputVersion))
+    );
+
+    // The v2 entrypoint skips what it doesn't know yet
+    let request = env.verify(&verifier, &v2, AUTHOR).unwrap();
+    env.deliver_journal(&request, &extended(&request)).unwrap();
+    assert!(env.log(&verifier.pubkey(), &v2).is_verified);
+}
@@ -0,0 +1,394 @@
+//! Campaign setup, funding and the limits verify_post enforces on it
+
+use anchor_lang::AccountSerialize;
+use program_tests::{campaign_args, program_error, TestEnv, TIP};
+use proof_of_post::{PostProofConfig, PostProofError, UpdateConfigArgs};
+use proof_of_post_client::instructions;
+use proof_of_post_common::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const REWARD: u64 = 1_000_000;
+
+fn author(n: u8) -> [u8; 32] {
+    [n; 32]
+}
+
+fn set_active(env: &mut TestEnv, campaign: &Pubkey, active: bool) {
+    let creator = env.creator.insecure_clone();
+    let update = UpdateConfigArgs {
+        active: Some(active),
+        max_claimers: None,
+        reward_amount: None,
+        callback_version: None,
+        budget_alert_bps: None,
+        budget_alert_lamports: None,
+    };
+    let ix = instructions::update_config(&proof_of_post::ID, &creator.pubkey(), campaign, update);
+    env.send(&creator, &[ix]).unwrap();
+}
+
+fn top_up(env: &mut TestEnv, funder: &Keypair, campaign: &Pubkey, lamports: u64) {
+    let ix = instructions::top_up(&funder.pubkey(), campaign, lamports);
+    env.send(funder, &[ix]).unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn create_config_stores_the_campaign() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("store", REWARD, 5), 5 * REWARD)
+        .unwrap();
+
+    let config = env.config(&campaign);
+    assert_eq!(config.creator, env.creator.pubkey());
+    assert_eq!(config.seeds, "store");
+    assert_eq!(config.keywords, vec![String::from("bonsol")]);
+    assert_eq!(config.reward_amount, REWARD);
+    assert_eq!(config.max_claimers, 5);
+    assert_eq!(config.claimers_count, 0);
+    assert!(config.active);
+    // The creator pays the account's rent on top of the budget
+    assert_eq!(env.balance(&campaign), env.rent(&campaign) + 5 * REWARD);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn create_config_rejects_invalid_rules() {
+    let mut env = TestEnv::new();
+    let mut args = campaign_args("atleast", REWARD, 5);
+    (args.match_mode, args.match_threshold) = (MATCH_AT_LEAST, 2);
+    assert_eq!(
+        env.create_campaign(args, 0),
+        Err(program_error(0, PostProofError::InvalidMatchMode))
+    );
+
+    let mut args = campaign_args("comma", REWARD, 5);
+    args.keywords = vec![String::from("bonsol,zk")];
+    assert_eq!(
+        env.create_campaign(args, 0),
+        Err(program_error(0, PostProofError::InvalidKeyword))
+    );
+
+    let mut args = campaign_args("split", REWARD, 5);
+    args.reward_split.claimer_bps -= 1;
+    assert_eq!(
+        env.create_campaign(args, 0),
+        Err(program_error(0, PostProofError::InvalidRewardSplit))
+    );
+
+    // Funding every claim would take more lamports than exist
+    assert_eq!(
+        env.create_campaign(campaign_args("overflow", REWARD, u64::MAX), 0),
+        Err(program_error(0, PostProofError::ArithmeticOverflow))
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn follower_checks_need_a_platform_reporting_followers() {
+    let mut env = TestEnv::new();
+    // Bluesky's getPosts has no follower count, so these checks could never pass
+    let mut args = campaign_args("followers", REWARD, 5);
+    args.min_followers = 100;
+    assert_eq!(
+        env.create_campaign(args, 0),
+        Err(program_error(0, PostProofError::FollowerCountUnavailable))
+    );
+    let mut args = campaign_args("ratio", REWARD, 5);
+    args.max_likes_per_follower_bps = 5_000;
+    assert_eq!(
+        env.create_campaign(args, 0),
+        Err(program_error(0, PostProofError::FollowerCountUnavailable))
+    );
+
+    let mut args = campaign_args("mastodon", REWARD, 5);
+    (args.platform, args.min_followers) = (PLATFORM_MASTODON, 100);
+    let campaign = env.create_campaign(args, 0).unwrap();
+    assert_eq!(env.config(&campaign).min_followers, 100);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn top_ups_add_to_the_budget() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("topup", REWARD, 5), REWARD)
+        .unwrap();
+    let funder = env.funded_keypair();
+    let creator = env.creator.insecure_clone();
+
+    top_up(&mut env, &funder, &campaign, 2 * REWARD);
+    top_up(&mut env, &creator, &campaign, REWARD);
+
+    assert_eq!(env.balance(&campaign), env.rent(&campaign) + 4 * REWARD);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn verify_post_needs_a_reward_above_rent() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("broke", REWARD, 5), REWARD - 1)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(1)).unwrap_err(),
+        program_error(0, PostProofError::InsufficientFunds)
+    );
+
+    let creator = env.creator.insecure_clone();
+    top_up(&mut env, &creator, &campaign, 1);
+    env.verify(&verifier, &campaign, author(1)).unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn sponsor_costs_pays_tip_and_tracker_rent_from_the_vault() {
+    let mut env = TestEnv::new();
+    let mut protocol = env.protocol();
+    protocol.min_tip = TIP;
+    env.set_protocol(protocol.clone());
+    let mut args = campaign_args("costs", REWARD, 5);
+    args.sponsor_costs = true;
+    let campaign = env.create_campaign(args, 5 * REWARD).unwrap();
+    let verifier = env.funded_keypair();
+    let budget = env.balance(&campaign);
+
+    let request = env.verify(&verifier, &campaign, author(1)).unwrap();
+
+    let tracker = instructions::requester_address(&proof_of_post::ID, &request.req_id);
+    assert_eq!(env.balance(&tracker), env.rent(&tracker));
+    assert_eq!(env.balance(&campaign), budget - TIP - env.rent(&tracker));
+
+    // Above the protocol's minimum tip the verifier pays the difference
+    protocol.min_tip = TIP / 4;
+    env.set_protocol(protocol);
+    let budget = env.balance(&campaign);
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, author(2)).unwrap();
+    let tracker = instructions::requester_address(&proof_of_post::ID, &request.req_id);
+    assert_eq!(
+        env.balance(&campaign),
+        budget - TIP / 4 - env.rent(&tracker)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn post_hosts_limit_where_posts_are_fetched() {
+    let mut env = TestEnv::new();
+    let mut args = campaign_args("elsewhere", REWARD, 5);
+    args.allowed_post_hosts = vec![String::from("example.com")];
+    let elsewhere = env.create_campaign(args, 5 * REWARD).unwrap();
+    let mut args = campaign_args("bluesky", REWARD, 5);
+    args.allowed_post_hosts = vec![String::from("public.api.bsky.app")];
+    let bluesky = env.create_campaign(args, 5 * REWARD).unwrap();
+    let verifier = env.funded_keypair();
+
+    assert_eq!(
+        env.verify(&verifier, &elsewhere, author(1)).unwrap_err(),
+        program_error(0, PostProofError::PostHostNotAllowed)
+    );
+    env.verify(&verifier, &bluesky, author(1)).unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn verifier_gets_one_request_per_campaign() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("once", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, author(1)).unwrap();
+
+    // Nor can another request replace the pending one, for another post or later on
+    assert!(env.verify(&verifier, &campaign, author(2)).is_err());
+    env.svm.warp_to_slot(10_000);
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, author(2), 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::VerificationPending)
+    );
+    assert_eq!(
+        env.log(&verifier.pubkey(), &campaign)
+            .current_execution_account,
+        Some(request.execution_request)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn campaign_closes_to_verifiers_at_max_claimers() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("max", REWARD, 2), 5 * REWARD)
+        .unwrap();
+    for n in 1..=2 {
+        let verifier = env.funded_keypair();
+        let request = env.verify(&verifier, &campaign, author(n)).unwrap();
+        env.deliver(&request, &request.passing_output()).unwrap();
+    }
+
+    // The last paid claim deactivates the campaign
+    let config = env.config(&campaign);
+    assert_eq!(config.claimers_count, 2);
+    assert!(!config.active);
+    let verifier = env.funded_keypair();
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(3)).unwrap_err(),
+        program_error(0, PostProofError::ConfigNotActive)
+    );
+
+    // Reactivating doesn't lift the claimer cap
+    set_active(&mut env, &campaign, true);
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(3)).unwrap_err(),
+        program_error(0, PostProofError::MaxClaimersReached)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn paused_campaign_rejects_verifications() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("pause", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    set_active(&mut env, &campaign, false);
+
+    let verifier = env.funded_keypair();
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(1)).unwrap_err(),
+        program_error(0, PostProofError::ConfigNotActive)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn protocol_caps_tips_and_post_sizes() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("caps", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+
+    // The environment's requests tip TIP for a 2 KiB post
+    let mut protocol = env.protocol();
+    protocol.max_tip = TIP - 1;
+    env.set_protocol(protocol.clone());
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(1)).unwrap_err(),
+        program_error(0, PostProofError::TipTooHigh)
+    );
+
+    protocol.max_tip = TIP;
+    protocol.max_post_size = 1_024;
+    env.set_protocol(protocol.clone());
+    assert_eq!(
+        env.verify(&verifier, &campaign, author(1)).unwrap_err(),
+        program_error(0, PostProofError::PostTooLarge)
+    );
+
+    protocol.max_post_size = 2_048;
+    env.set_protocol(protocol);
+    env.verify(&verifier, &campaign, author(1)).unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn campaign_changes_hands_once_accepted() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("handover", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let creator = env.creator.insecure_clone();
+    let dao = env.funded_keypair();
+
+    let propose = instructions::propose_transfer(
+        &proof_of_post::ID,
+        &creator.pubkey(),
+        &campaign,
+        Some(dao.pubkey()),
+    );
+    env.send(&creator, &[propose]).unwrap();
+    assert_eq!(env.config(&campaign).creator, creator.pubkey());
+    assert_eq!(env.config(&campaign).pending_creator, Some(dao.pubkey()));
+
+    let stranger = env.funded_keypair();
+    let accept = instructions::accept_transfer(&proof_of_post::ID, &stranger.pubkey(), &campaign);
+    assert_eq!(
+        env.send(&stranger, &[accept]).unwrap_err(),
+        program_error(0, PostProofError::Unauthorized)
+    );
+    let accept = instructions::accept_transfer(&proof_of_post::ID, &dao.pubkey(), &campaign);
+    env.send(&dao, &[accept]).unwrap();
+    let config = env.config(&campaign);
+    assert_eq!(config.creator, dao.pubkey());
+    assert_eq!(config.pending_creator, None);
+
+    // The new creator manages the campaign, and the old one can't reclaim it by
+    // creating it again at the same address
+    let args = UpdateConfigArgs {
+        active: Some(false),
+        max_claimers: None,
+        reward_amount: None,
+        callback_version: None,
+        budget_alert_bps: None,
+        budget_alert_lamports: None,
+    };
+    let ix = instructions::update_config(&proof_of_post::ID, &dao.pubkey(), &campaign, args);
+    env.send(&dao, &[ix]).unwrap();
+    assert!(!env.config(&campaign).active);
+    assert_eq!(
+        env.create_campaign(campaign_args("handover", REWARD, 5), 0)
+            .unwrap_err(),
+        program_error(0, PostProofError::Unauthorized)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn migrate_config_upgrades_unversioned_configs() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("legacy", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    assert_eq!(env.config(&campaign).version, PostProofConfig::VERSION);
+
+    // Rewrite it as a config from before the version byte, without the version and
+    // the fields that came after it: the pending creator, the dispute settings and the
+    // allowed provers
+    let mut legacy = env.config(&campaign);
+    legacy.version = 0;
+    let mut account = env.svm.get_account(&campaign).unwrap();
+    let space = account.data.len();
+    account.data.clear();
+    legacy.try_serialize(&mut account.data).unwrap();
+    let v0_space = space - (1 + 33 + 8 + 8 + 2 + 8 + (4 + 4 * 32));
+    account.data.resize(v0_space, 0);
+    account.lamports = env.svm.minimum_balance_for_rent_exemption(v0_space) + 5 * REWARD;
+    env.svm.set_account(campaign, account).unwrap();
+
+    // Still usable before it is migrated
+    let verifier = env.funded_keypair();
+    env.verify(&verifier, &campaign, author(1)).unwrap();
+    assert_eq!(env.config(&campaign).version, 0);
+
+    let payer = env.funded_keypair();
+    let migrate = instructions::migrate_config(&proof_of_post::ID, &payer.pubkey(), &campaign);
+    env.send(&payer, &[migrate.clone()]).unwrap();
+    let config = env.config(&campaign);
+    assert_eq!(config.version, PostProofConfig::VERSION);
+    assert_eq!(config.seeds, "legacy");
+    assert_eq!(env.svm.get_account(&campaign).unwrap().data.len(), space);
+    // The payer covered the added rent, leaving the budget whole
+    assert_eq!(env.balance(&campaign), env.rent(&campaign) + 5 * REWARD);
+
+    assert_eq!(
+        env.send(&payer, &[migrate]).unwrap_err(),
+        program_error(0, PostProofError::AccountAlreadyMigrated)
+    );
+}
@@ -0,0 +1,172 @@
+//! Dispute windows: held rewards, the challenges filed against them, and the claims
+//! paid once they close
+
+use program_tests::{campaign_args, program_error, Request, TestEnv};
+use proof_of_post::{AuthorClaim, PostProofError, BPS_DENOMINATOR, FEATURE_DISPUTES};
+use proof_of_post_client::instructions;
+use proof_of_post_common::REASON_MISSING_KEYWORD;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+
+const REWARD: u64 = 1_000_000;
+const WINDOW: u64 = 100;
+const BOND: u64 = 500_000;
+const CHALLENGER_SHARE_BPS: u16 = 5_000;
+const AUTHOR: [u8; 32] = [9u8; 32];
+
+/// A campaign holding rewards for [`WINDOW`] slots, with the feature enabled
+fn disputed_campaign(env: &mut TestEnv) -> Pubkey {
+    let mut protocol = env.protocol();
+    protocol.feature_flags |= FEATURE_DISPUTES;
+    env.set_protocol(protocol);
+    let mut args = campaign_args("disputed", REWARD, 10);
+    args.dispute_window_slots = WINDOW;
+    args.challenge_bond = BOND;
+    args.challenger_share_bps = CHALLENGER_SHARE_BPS;
+    env.create_campaign(args, 10 * REWARD).unwrap()
+}
+
+/// A verified claim whose reward is now held
+fn held_claim(env: &mut TestEnv, campaign: &Pubkey) -> Request {
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, campaign, AUTHOR).unwrap();
+    env.deliver(&request, &request.passing_output()).unwrap();
+    request
+}
+
+/// claim_reward for `request`'s held reward, sent by a bystander
+fn claim(env: &mut TestEnv, request: &Request) -> Result<(), TransactionError> {
+    let log_address = instructions::verification_log_address(
+        &proof_of_post::ID,
+        &request.verifier,
+        &request.campaign,
+    );
+    let ix = instructions::claim_reward(
+        &proof_of_post::ID,
+        &env.config(&request.campaign),
+        &log_address,
+        &env.log(&request.verifier, &request.campaign),
+    );
+    let bystander = env.funded_keypair();
+    env.send(&bystander, &[ix])
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn held_reward_is_paid_once_the_window_closes() {
+    let mut env = TestEnv::new();
+    let campaign = disputed_campaign(&mut env);
+    let budget = env.balance(&campaign);
+    let request = held_claim(&mut env, &campaign);
+    let before = env.balance(&request.verifier);
+
+    let log = env.log(&request.verifier, &campaign);
+    assert!(log.is_verified);
+    assert_eq!(log.held_reward, REWARD);
+    let config = env.config(&campaign);
+    assert_eq!(config.held_rewards, REWARD);
+    assert_eq!(config.claimers_count, 1);
+    assert_eq!(config.total_paid, 0);
+    assert_eq!(env.balance(&campaign), budget);
+
+    assert_eq!(
+        claim(&mut env, &request).unwrap_err(),
+        program_error(0, PostProofError::DisputeWindowOpen)
+    );
+
+    env.svm.warp_to_slot(log.claimable_slot);
+    // Too late to challenge
+    let challenger = env.funded_keypair();
+    assert_eq!(
+        env.challenge(&challenger, &request).unwrap_err(),
+        program_error(0, PostProofError::DisputeWindowClosed)
+    );
+
+    claim(&mut env, &request).unwrap();
+    assert_eq!(env.balance(&request.verifier), before + REWARD);
+    assert_eq!(env.log(&request.verifier, &campaign).held_reward, 0);
+    let config = env.config(&campaign);
+    assert_eq!(config.held_rewards, 0);
+    assert_eq!(config.total_paid, REWARD);
+    assert_eq!(
+        claim(&mut env, &request).unwrap_err(),
+        program_error(0, PostProofError::NoHeldReward)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn failing_re_verification_voids_the_claim() {
+    let mut env = TestEnv::new();
+    let campaign = disputed_campaign(&mut env);
+    let request = held_claim(&mut env, &campaign);
+    let verifier_before = env.balance(&request.verifier);
+    let challenger = env.funded_keypair();
+
+    let recheck = env.challenge(&challenger, &request).unwrap();
+    let log = env.log(&request.verifier, &campaign);
+    assert_eq!(log.challenger, Some(challenger.pubkey()));
+    assert_eq!(log.challenge_bond, BOND);
+    // A claim is challenged by one challenger at a time
+    assert_eq!(
+        env.challenge(&challenger, &request).unwrap_err(),
+        program_error(0, PostProofError::ChallengeOpen)
+    );
+
+    let challenger_before = env.balance(&challenger.pubkey());
+    // The mock returns the request's rent to the challenger, as Bonsol does
+    let request_rent = env.balance(&recheck.execution_request);
+    env.deliver(&recheck, &recheck.failed_output(REASON_MISSING_KEYWORD))
+        .unwrap();
+
+    let award = REWARD * CHALLENGER_SHARE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(
+        env.balance(&challenger.pubkey()),
+        challenger_before + BOND + award + request_rent
+    );
+    assert_eq!(env.balance(&request.verifier), verifier_before);
+    let log = env.log(&request.verifier, &campaign);
+    assert!(!log.is_verified);
+    assert_eq!(log.held_reward, 0);
+    assert_eq!(log.challenger, None);
+    let config = env.config(&campaign);
+    assert_eq!(config.held_rewards, 0);
+    assert_eq!(config.claimers_count, 0);
+    let author_claim = env.account::<AuthorClaim>(&instructions::author_claim_address(
+        &proof_of_post::ID,
+        &campaign,
+        &AUTHOR,
+    ));
+    assert!(!author_claim.claimed);
+
+    env.svm.warp_to_slot(log.claimable_slot);
+    assert_eq!(
+        claim(&mut env, &request).unwrap_err(),
+        program_error(0, PostProofError::NoHeldReward)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn upheld_challenge_pays_the_bond_to_the_claimer() {
+    let mut env = TestEnv::new();
+    let campaign = disputed_campaign(&mut env);
+    let request = held_claim(&mut env, &campaign);
+    let challenger = env.funded_keypair();
+
+    let recheck = env.challenge(&challenger, &request).unwrap();
+    let before = env.balance(&request.verifier);
+    env.deliver(&recheck, &recheck.passing_output()).unwrap();
+
+    assert_eq!(env.balance(&request.verifier), before + BOND);
+    let log = env.log(&request.verifier, &campaign);
+    assert!(log.is_verified);
+    assert_eq!(log.held_reward, REWARD);
+    assert_eq!(log.challenger, None);
+    assert_eq!(env.config(&campaign).claimers_count, 1);
+
+    env.svm.warp_to_slot(log.claimable_slot);
+    claim(&mut env, &request).unwrap();
+    assert_eq!(env.balance(&request.verifier), before + BOND + REWARD);
+}
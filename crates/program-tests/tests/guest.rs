@@ -0,0 +1,37 @@
+//! The guest build the program requests proofs from. `bonsol build` writes the image
+//! ID to the manifest; the program names it in `POST_VERIFICATION_IMAGE_ID`. Whenever
+//! the guest, the core crate or the journal and input formats change, rebuild the
+//! guest and update both together.
+
+use proof_of_post::POST_VERIFICATION_IMAGE_ID;
+use serde_json::Value;
+
+const GUEST_DIR: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../zk-program/post_verification"
+);
+
+fn manifest() -> Value {
+    let manifest = std::fs::read_to_string(format!("{GUEST_DIR}/manifest.json")).unwrap();
+    serde_json::from_str(&manifest).unwrap()
+}
+
+#[test]
+fn program_requests_the_built_image() {
+    assert_eq!(
+        manifest()["imageId"].as_str(),
+        Some(POST_VERIFICATION_IMAGE_ID),
+        "POST_VERIFICATION_IMAGE_ID is not the image in zk-program/post_verification/manifest.json"
+    );
+}
+
+#[test]
+fn manifest_input_order_matches_the_guest_metadata() {
+    let cargo_toml = std::fs::read_to_string(format!("{GUEST_DIR}/Cargo.toml")).unwrap();
+    let input_order = cargo_toml
+        .lines()
+        .find_map(|line| line.strip_prefix("input_order = "))
+        .unwrap();
+    let declared: Value = serde_json::from_str(input_order).unwrap();
+    assert_eq!(manifest()["inputOrder"], declared);
+}
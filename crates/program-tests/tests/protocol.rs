@@ -0,0 +1,277 @@
+//! The protocol config: its authority and the parameters it tunes
+
+use anchor_lang::AccountSerialize;
+use program_tests::{campaign_args, program_error, TestEnv};
+use proof_of_post::{
+    PostProofError, ProtocolConfig, UpdateConfigArgs, UpdateProtocolArgs,
+    DEFAULT_EXECUTION_EXPIRY_SLOTS, DEFAULT_VERIFY_COOLDOWN_SECS, DEFAULT_VERIFY_COOLDOWN_SLOTS,
+    TIME_MODE_TIMESTAMP,
+};
+use proof_of_post_client::instructions;
+use proof_of_post_common::REASON_MISSING_KEYWORD;
+use solana_sdk::clock::Clock;
+use solana_sdk::signature::Signer;
+
+const REWARD: u64 = 1_000_000;
+
+#[test]
+#[ignore = "needs the program builds"]
+fn authority_transfer_takes_two_steps() {
+    let mut env = TestEnv::new();
+    let authority = env.creator.insecure_clone();
+    let successor = env.funded_keypair();
+
+    let propose = instructions::transfer_protocol_authority(
+        &proof_of_post::ID,
+        &authority.pubkey(),
+        Some(successor.pubkey()),
+    );
+    env.send(&authority, &[propose]).unwrap();
+    // Proposing hands nothing over yet
+    assert_eq!(env.protocol().authority, authority.pubkey());
+    assert_eq!(env.protocol().pending_authority, Some(successor.pubkey()));
+
+    // Only the proposed key can accept
+    let stranger = env.funded_keypair();
+    let accept = instructions::accept_protocol_authority(&proof_of_post::ID, &stranger.pubkey());
+    assert_eq!(
+        env.send(&stranger, &[accept]).unwrap_err(),
+        program_error(0, PostProofError::Unauthorized)
+    );
+
+    let accept = instructions::accept_protocol_authority(&proof_of_post::ID, &successor.pubkey());
+    env.send(&successor, &[accept]).unwrap();
+    let protocol = env.protocol();
+    assert_eq!(protocol.authority, successor.pubkey());
+    assert_eq!(protocol.pending_authority, None);
+
+    // The previous authority is out
+    let propose = instructions::transfer_protocol_authority(
+        &proof_of_post::ID,
+        &authority.pubkey(),
+        Some(authority.pubkey()),
+    );
+    assert_eq!(
+        env.send(&authority, &[propose]).unwrap_err(),
+        program_error(0, PostProofError::Unauthorized)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn cooldown_comes_from_the_protocol() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("cooldown", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, [1u8; 32]).unwrap();
+    env.deliver(&request, &request.failed_output(REASON_MISSING_KEYWORD))
+        .unwrap();
+
+    // The retry counts from the slot the failed proof landed in
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::VerificationTooFast)
+    );
+    let delivered = env.log(&verifier.pubkey(), &campaign).slot;
+    env.svm
+        .warp_to_slot(delivered + DEFAULT_VERIFY_COOLDOWN_SLOTS);
+
+    // A longer cooldown from the protocol still holds it back
+    let mut protocol = env.protocol();
+    protocol.verify_cooldown_slots = 1_000_000;
+    env.set_protocol(protocol.clone());
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::VerificationTooFast)
+    );
+
+    protocol.verify_cooldown_slots = DEFAULT_VERIFY_COOLDOWN_SLOTS;
+    env.set_protocol(protocol);
+    env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+        .unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn update_protocol_sets_the_cooldown() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("cooldown-update", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let authority = env.creator.insecure_clone();
+    let update = instructions::update_protocol(
+        &proof_of_post::ID,
+        &authority.pubkey(),
+        UpdateProtocolArgs {
+            feature_flags: None,
+            guardian: None,
+            breaker_window_slots: None,
+            breaker_max_velocity_multiple: None,
+            breaker_min_norm_lamports: None,
+            min_tip: None,
+            max_tip: None,
+            max_post_size: None,
+            journal_bounds: None,
+            verify_cooldown_slots: Some(10),
+            verify_cooldown_secs: None,
+            execution_expiry_slots: None,
+            signature_fee_lamports: None,
+            paused: None,
+        },
+    );
+    env.send(&authority, &[update]).unwrap();
+    assert_eq!(env.protocol().verify_cooldown_slots, 10);
+
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, [1u8; 32]).unwrap();
+    env.deliver(&request, &request.failed_output(REASON_MISSING_KEYWORD))
+        .unwrap();
+    let delivered = env.log(&verifier.pubkey(), &campaign).slot;
+    env.svm.warp_to_slot(delivered + 9);
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::VerificationTooFast)
+    );
+    env.svm.warp_to_slot(delivered + 10);
+    env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+        .unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn timestamp_campaigns_cool_down_in_seconds() {
+    let mut env = TestEnv::new();
+    let mut args = campaign_args("cooldown-secs", REWARD, 5);
+    args.time_mode = TIME_MODE_TIMESTAMP;
+    let campaign = env.create_campaign(args, 5 * REWARD).unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, [1u8; 32]).unwrap();
+    env.deliver(&request, &request.failed_output(REASON_MISSING_KEYWORD))
+        .unwrap();
+
+    // Slots passing don't matter, only the clock
+    let mut clock = env.svm.get_sysvar::<Clock>();
+    clock.slot += DEFAULT_VERIFY_COOLDOWN_SLOTS;
+    env.svm.set_sysvar(&clock);
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::VerificationTooFast)
+    );
+
+    clock.unix_timestamp += DEFAULT_VERIFY_COOLDOWN_SECS;
+    env.svm.set_sysvar(&clock);
+    env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+        .unwrap();
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn a_verified_log_takes_no_more_requests() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("verified", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, [1u8; 32]).unwrap();
+    env.deliver(&request, &request.passing_output()).unwrap();
+
+    env.svm.warp_to_slot(1_000_000);
+    assert_eq!(
+        env.verify_with_nonce(&verifier, &campaign, [1u8; 32], 8)
+            .unwrap_err(),
+        program_error(0, PostProofError::AlreadyVerified)
+    );
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn pause_stops_verifications_and_payouts_but_not_closes() {
+    let mut env = TestEnv::new();
+    let campaign = env
+        .create_campaign(campaign_args("paused", REWARD, 5), 5 * REWARD)
+        .unwrap();
+    let verifier = env.funded_keypair();
+    let request = env.verify(&verifier, &campaign, [1u8; 32]).unwrap();
+
+    let mut protocol = env.protocol();
+    protocol.paused = true;
+    env.set_protocol(protocol);
+
+    // The request already in flight isn't paid, and no new one is accepted
+    assert_eq!(
+        env.deliver(&request, &request.passing_output()),
+        Err(program_error(0, PostProofError::ProtocolPaused))
+    );
+    let other = env.funded_keypair();
+    assert_eq!(
+        env.verify(&other, &campaign, [2u8; 32]).unwrap_err(),
+        program_error(0, PostProofError::ProtocolPaused)
+    );
+
+    // The creator can still wind the campaign down and get the budget back
+    let creator = env.creator.insecure_clone();
+    let pause = UpdateConfigArgs {
+        active: Some(false),
+        max_claimers: None,
+        reward_amount: None,
+        callback_version: None,
+        budget_alert_bps: None,
+        budget_alert_lamports: None,
+    };
+    let before = env.balance(&creator.pubkey());
+    let budget = env.balance(&campaign);
+    let ixs = [
+        instructions::update_config(&proof_of_post::ID, &creator.pubkey(), &campaign, pause),
+        instructions::close_config(&proof_of_post::ID, &creator.pubkey(), &campaign, None),
+    ];
+    env.send(&creator, &ixs).unwrap();
+    assert_eq!(env.balance(&campaign), 0);
+    // Less the fee of its one signature
+    assert_eq!(env.balance(&creator.pubkey()), before + budget - 5_000);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn migrate_protocol_fills_in_the_default_parameters() {
+    let mut env = TestEnv::new();
+    let address = instructions::protocol_config_address(&proof_of_post::ID);
+
+    // Rewrite it as a version 1 config, which ended at the version byte
+    let mut legacy = env.protocol();
+    legacy.version = 1;
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    let space = data.len();
+    let v1_space = space - (8 + 8 + 8 + 8 + 1 + 32 + 1);
+    data.truncate(v1_space);
+    let mut account = env.svm.get_account(&address).unwrap();
+    account.lamports = env.svm.minimum_balance_for_rent_exemption(v1_space);
+    account.data = data;
+    env.svm.set_account(address, account).unwrap();
+
+    let payer = env.funded_keypair();
+    let migrate = instructions::migrate_protocol(&proof_of_post::ID, &payer.pubkey());
+    env.send(&payer, &[migrate.clone()]).unwrap();
+    let protocol = env.protocol();
+    assert_eq!(protocol.version, ProtocolConfig::VERSION);
+    assert_eq!(
+        protocol.verify_cooldown_slots,
+        DEFAULT_VERIFY_COOLDOWN_SLOTS
+    );
+    assert_eq!(
+        protocol.execution_expiry_slots,
+        DEFAULT_EXECUTION_EXPIRY_SLOTS
+    );
+    assert_eq!(env.balance(&address), env.rent(&address));
+
+    assert_eq!(
+        env.send(&payer, &[migrate]).unwrap_err(),
+        program_error(0, PostProofError::AccountAlreadyMigrated)
+    );
+}
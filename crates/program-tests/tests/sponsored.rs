@@ -0,0 +1,100 @@
+//! Verifications a relayer submits and is reimbursed for from the sponsorship budget
+
+use program_tests::{campaign_args, program_error, TestEnv, TIP};
+use proof_of_post::{FundSponsorshipArgs, PostProofError, FEATURE_SPONSORSHIP};
+use proof_of_post_client::instructions;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const REWARD: u64 = 1_000_000;
+const SPONSORSHIP: u64 = 100_000_000;
+const AUTHOR: [u8; 32] = [9u8; 32];
+
+/// A campaign with [`SPONSORSHIP`] lamports set aside for relayers, and the feature
+/// enabled when `enabled`
+fn sponsored_campaign(env: &mut TestEnv, enabled: bool) -> Pubkey {
+    let mut protocol = env.protocol();
+    if enabled {
+        protocol.feature_flags |= FEATURE_SPONSORSHIP;
+    }
+    env.set_protocol(protocol);
+    let campaign = env
+        .create_campaign(campaign_args("sponsored", REWARD, 10), 10 * REWARD)
+        .unwrap();
+    let creator = env.creator.insecure_clone();
+    let ix = instructions::fund_sponsorship(
+        &proof_of_post::ID,
+        &creator.pubkey(),
+        &campaign,
+        FundSponsorshipArgs {
+            deposit: SPONSORSHIP,
+            max_per_request: SPONSORSHIP,
+        },
+    );
+    env.send(&creator, &[ix]).unwrap();
+    campaign
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn relayer_submits_for_a_verifier_without_lamports() {
+    let mut env = TestEnv::new();
+    let campaign = sponsored_campaign(&mut env, true);
+    let verifier = Keypair::new();
+    let relayer = env.funded_keypair();
+    let relayer_before = env.balance(&relayer.pubkey());
+
+    let request = env
+        .sponsored_verify(&verifier, &verifier.pubkey(), &relayer, &campaign, AUTHOR)
+        .unwrap();
+
+    let config = env.config(&campaign);
+    let reimbursed = SPONSORSHIP - config.sponsorship_budget;
+    assert!(reimbursed > 0);
+    // Everything but the execution request's rent, which Bonsol returns to the relayer.
+    // The mock doesn't collect the tip, so the relayer keeps it.
+    let request_rent = env.balance(&request.execution_request);
+    assert_eq!(
+        env.balance(&relayer.pubkey()),
+        relayer_before - request_rent + TIP
+    );
+    assert_eq!(env.balance(&verifier.pubkey()), 0);
+
+    env.deliver(&request, &request.passing_output()).unwrap();
+    assert!(env.log(&verifier.pubkey(), &campaign).is_verified);
+    assert_eq!(env.balance(&verifier.pubkey()), REWARD);
+    assert_eq!(env.balance(&relayer.pubkey()), relayer_before + TIP);
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn sponsorship_needs_the_feature() {
+    let mut env = TestEnv::new();
+    let campaign = sponsored_campaign(&mut env, false);
+    let verifier = Keypair::new();
+    let relayer = env.funded_keypair();
+
+    let err = env
+        .sponsored_verify(&verifier, &verifier.pubkey(), &relayer, &campaign, AUTHOR)
+        .unwrap_err();
+    assert_eq!(err, program_error(1, PostProofError::FeatureDisabled));
+}
+
+#[test]
+#[ignore = "needs the program builds"]
+fn intent_must_be_signed_by_the_verifier() {
+    let mut env = TestEnv::new();
+    let campaign = sponsored_campaign(&mut env, true);
+    let verifier = Keypair::new();
+    let relayer = env.funded_keypair();
+
+    // The relayer can't spend the budget on requests nobody asked for
+    let err = env
+        .sponsored_verify(&relayer, &verifier.pubkey(), &relayer, &campaign, AUTHOR)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        program_error(1, PostProofError::InvalidIntentSignature)
+    );
+    assert_eq!(env.config(&campaign).sponsorship_budget, SPONSORSHIP);
+}
@@ -0,0 +1,62 @@
+//! The error the off-chain services share, so a failure reads the same whether the
+//! SDK, the relayer or the indexer hit it
+
+use anchor_lang::prelude::Pubkey;
+use solana_client::nonblocking::pubsub_client::PubsubClientError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum PopError {
+    /// The RPC request failed
+    Rpc(solana_client::client_error::ClientError),
+    /// A PubSub subscription couldn't be made
+    PubSub(PubsubClientError),
+    /// A PubSub feed ended, e.g. because the connection dropped
+    FeedClosed,
+    /// No account at the address
+    NotFound(Pubkey),
+    /// The account isn't one of the program's accounts of the expected type
+    Decode(Pubkey, anchor_lang::error::Error),
+    /// Every freshly drawn request ID already had a requester account
+    RequestIdsTaken,
+    /// The indexer's database failed
+    Storage(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Rpc(e) => write!(f, "rpc request failed: {e}"),
+            Self::PubSub(e) => write!(f, "subscribing: {e}"),
+            Self::FeedClosed => write!(f, "subscription feed closed"),
+            Self::NotFound(address) => write!(f, "no account at {address}"),
+            Self::Decode(address, e) => write!(f, "decoding {address}: {e}"),
+            Self::RequestIdsTaken => write!(f, "no unused request id found"),
+            Self::Storage(e) => write!(f, "database: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PopError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Rpc(e) => Some(e),
+            Self::PubSub(e) => Some(e),
+            Self::Decode(_, e) => Some(e),
+            Self::Storage(e) => Some(&**e),
+            Self::FeedClosed | Self::NotFound(_) | Self::RequestIdsTaken => None,
+        }
+    }
+}
+
+impl From<solana_client::client_error::ClientError> for PopError {
+    fn from(e: solana_client::client_error::ClientError) -> Self {
+        Self::Rpc(e)
+    }
+}
+
+impl From<PubsubClientError> for PopError {
+    fn from(e: PubsubClientError) -> Self {
+        Self::PubSub(e)
+    }
+}
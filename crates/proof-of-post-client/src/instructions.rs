@@ -0,0 +1,520 @@
+//! Instructions for the program, with the accounts each one takes derived from its
+//! inputs the way the program's constraints expect them
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{bpf_loader_upgradeable, keccak, system_program, sysvar};
+use anchor_lang::{Id, InstructionData, ToAccountMetas};
+use bonsol_anchor_interface::Bonsol;
+use proof_of_post::{
+    accounts, instruction, ChallengeArgs, CreateConfigArgs, FundSponsorshipArgs, InitProtocolArgs,
+    PostProofConfig, PostVerificationLog, RewardSplit, SponsoredVerifyArgs, UpdateConfigArgs,
+    UpdateProtocolArgs, VerifyPostArgs, AUTHOR_CLAIM_SEED, BPS_DENOMINATOR, CALLBACK_VERSION_V1,
+    CONFIG_SEED, PROTOCOL_CONFIG_SEED, TIME_MODE_SLOT, VERIFICATION_LOG_SEED,
+};
+use proof_of_post_common::MATCH_ALL;
+
+/// Bonsol derives execution request and deployment accounts from these seeds
+const EXECUTION_SEED: &[u8] = b"execution";
+const DEPLOYMENT_SEED: &[u8] = b"deployment";
+
+pub fn config_address(program_id: &Pubkey, creator: &Pubkey, seeds: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CONFIG_SEED, creator.as_ref(), seeds.as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn verification_log_address(program_id: &Pubkey, verifier: &Pubkey, config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[VERIFICATION_LOG_SEED, verifier.as_ref(), config.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+pub fn author_claim_address(
+    program_id: &Pubkey,
+    config: &Pubkey,
+    author_did_hash: &[u8; 32],
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[AUTHOR_CLAIM_SEED, config.as_ref(), author_did_hash],
+        program_id,
+    )
+    .0
+}
+
+pub fn protocol_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PROTOCOL_CONFIG_SEED], program_id).0
+}
+
+/// The execution tracker verify_post creates for the request `req_id`
+pub fn requester_address(program_id: &Pubkey, req_id: &str) -> Pubkey {
+    Pubkey::find_program_address(&[req_id.as_bytes()], program_id).0
+}
+
+/// Bonsol's execution request for `req_id` by `requester`
+pub fn execution_request_address(requester: &Pubkey, req_id: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[EXECUTION_SEED, requester.as_ref(), req_id.as_bytes()],
+        &Bonsol::id(),
+    )
+    .0
+}
+
+/// Bonsol's deployment of the guest image `image_id`
+pub fn deployment_address(image_id: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            DEPLOYMENT_SEED,
+            &keccak::hash(image_id.as_bytes()).to_bytes(),
+        ],
+        &Bonsol::id(),
+    )
+    .0
+}
+
+/// A campaign paying `reward_amount` to up to `max_claimers` posts containing all of
+/// `keywords`, with every other check off and the whole reward going to the claimer.
+/// Callers switch on the checks they need.
+pub fn open_campaign_args(
+    seeds: &str,
+    keywords: &[&str],
+    reward_amount: u64,
+    max_claimers: u64,
+) -> CreateConfigArgs {
+    CreateConfigArgs {
+        seeds: seeds.to_string(),
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        reward_amount,
+        max_claimers,
+        require_authorship: false,
+        match_mode: MATCH_ALL,
+        match_threshold: 0,
+        blocked_keywords: Vec::new(),
+        callback_version: CALLBACK_VERSION_V1,
+        match_flags: 0,
+        reward_split: RewardSplit {
+            claimer_bps: BPS_DENOMINATOR as u16,
+            creator_bps: 0,
+            platform_bps: 0,
+            referrer_bps: 0,
+        },
+        platform_treasury: Pubkey::default(),
+        required_facets: Vec::new(),
+        metadata_uri: String::new(),
+        rules_hash: [0u8; 32],
+        rules_bundle_size: 0,
+        min_likes: 0,
+        min_reposts: 0,
+        min_replies: 0,
+        not_before_ts: 0,
+        not_after_ts: 0,
+        required_langs: Vec::new(),
+        min_chars: 0,
+        min_words: 0,
+        min_images: 0,
+        external_domain: String::new(),
+        allowed_link_domains: Vec::new(),
+        time_mode: TIME_MODE_SLOT,
+        budget_alert_bps: 0,
+        budget_alert_lamports: 0,
+        reply_to_uri: String::new(),
+        min_account_age_days: 0,
+        min_followers: 0,
+        blocked_labels: Vec::new(),
+        max_likes_per_follower_bps: 0,
+        platform: proof_of_post_common::PLATFORM_BLUESKY,
+        required_communities: Vec::new(),
+        terms_hash: [0u8; 32],
+        disclosure_tags: Vec::new(),
+        allowed_author_ids: Vec::new(),
+        min_views: 0,
+        segments: Vec::new(),
+        response_signer: None,
+        reject_cpi: false,
+        allowed_callers: Vec::new(),
+        leftover_recipient: None,
+        min_thread_posts: 0,
+        sponsor_costs: false,
+        allowed_post_hosts: Vec::new(),
+        dispute_window_slots: 0,
+        challenge_bond: 0,
+        challenger_share_bps: 0,
+        allowed_provers: Vec::new(),
+    }
+}
+
+pub fn create_config(program_id: &Pubkey, creator: &Pubkey, args: CreateConfigArgs) -> Instruction {
+    let accounts = accounts::CreateConfig {
+        post_proof_config: config_address(program_id, creator, &args.seeds),
+        creator: *creator,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::CreateConfig { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+pub fn update_config(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    config: &Pubkey,
+    args: UpdateConfigArgs,
+) -> Instruction {
+    let accounts = accounts::UpdateConfig {
+        post_proof_config: *config,
+        creator: *creator,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::UpdateConfig { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// `leftover_recipient` must be the campaign's, when it has one
+pub fn close_config(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    config: &Pubkey,
+    leftover_recipient: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::CloseConfig {
+        post_proof_config: *config,
+        creator: *creator,
+        leftover_recipient,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::CloseConfig {}.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Offers the campaign at `config` to `new_creator`, or withdraws the offer with `None`
+pub fn propose_transfer(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    config: &Pubkey,
+    new_creator: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::ProposeTransfer {
+        post_proof_config: *config,
+        creator: *creator,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::ProposeTransfer { new_creator }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+pub fn accept_transfer(program_id: &Pubkey, new_creator: &Pubkey, config: &Pubkey) -> Instruction {
+    let accounts = accounts::AcceptTransfer {
+        post_proof_config: *config,
+        new_creator: *new_creator,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::AcceptTransfer {}.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Rewrites a config from an earlier program version in the current layout, with
+/// `payer` covering the rent of any added space
+pub fn migrate_config(program_id: &Pubkey, payer: &Pubkey, config: &Pubkey) -> Instruction {
+    let accounts = accounts::MigrateConfig {
+        post_proof_config: *config,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::MigrateConfig {}.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// init_protocol, which only the program's upgrade authority `authority` may send
+pub fn init_protocol(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    args: InitProtocolArgs,
+) -> Instruction {
+    let accounts = accounts::InitProtocol {
+        protocol_config: protocol_config_address(program_id),
+        authority: *authority,
+        program: *program_id,
+        program_data: Pubkey::find_program_address(
+            &[program_id.as_ref()],
+            &bpf_loader_upgradeable::ID,
+        )
+        .0,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::InitProtocol { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Sets the protocol parameters given in `args`, leaving the others as they are
+pub fn update_protocol(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    args: UpdateProtocolArgs,
+) -> Instruction {
+    let accounts = accounts::UpdateProtocol {
+        protocol_config: protocol_config_address(program_id),
+        authority: *authority,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::UpdateProtocol { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Proposes `new_authority` to take over the protocol config, or withdraws the pending
+/// proposal with `None`
+pub fn transfer_protocol_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::TransferProtocolAuthority {
+        protocol_config: protocol_config_address(program_id),
+        authority: *authority,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::TransferProtocolAuthority { new_authority }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+pub fn accept_protocol_authority(program_id: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let accounts = accounts::AcceptProtocolAuthority {
+        protocol_config: protocol_config_address(program_id),
+        new_authority: *new_authority,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::AcceptProtocolAuthority {}.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Rewrites the protocol config from an earlier program version in the current layout
+pub fn migrate_protocol(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::MigrateProtocol {
+        protocol_config: protocol_config_address(program_id),
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::MigrateProtocol {}.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// Campaign budgets are the lamports the config account holds, so topping one up is a
+/// plain transfer
+pub fn top_up(funder: &Pubkey, config: &Pubkey, lamports: u64) -> Instruction {
+    solana_system_interface::instruction::transfer(funder, config, lamports)
+}
+
+/// verify_post for `verifier` on the campaign at `config`, proven by the guest image
+/// `image_id` deployed on Bonsol
+pub fn verify_post(
+    program_id: &Pubkey,
+    image_id: &str,
+    verifier: &Pubkey,
+    config: &Pubkey,
+    campaign: &PostProofConfig,
+    args: VerifyPostArgs,
+) -> Instruction {
+    let accounts = accounts::VerifyPost {
+        post_proof_config: *config,
+        post_verification_log: verification_log_address(program_id, verifier, config),
+        verifier: *verifier,
+        bonsol_program: Bonsol::id(),
+        requester: requester_address(program_id, &args.current_req_id),
+        execution_request: execution_request_address(verifier, &args.current_req_id),
+        deployment_account: deployment_address(image_id),
+        creator: campaign.creator,
+        platform_treasury: campaign.platform_treasury,
+        referrer: args.referrer.unwrap_or(*verifier),
+        payout: args.payout.unwrap_or(*verifier),
+        protocol_config: protocol_config_address(program_id),
+        author_claim: author_claim_address(program_id, config, &args.author_did_hash),
+        instructions: sysvar::instructions::ID,
+        post_proof_program: *program_id,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::VerifyPost { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// sponsored_verify of `verifier`'s signed intent, submitted and paid for by `relayer`.
+/// Goes after the Ed25519 instructions checking the intent and, when the campaign has
+/// terms, the terms signature.
+pub fn sponsored_verify(
+    program_id: &Pubkey,
+    image_id: &str,
+    relayer: &Pubkey,
+    verifier: &Pubkey,
+    config: &Pubkey,
+    campaign: &PostProofConfig,
+    args: SponsoredVerifyArgs,
+) -> Instruction {
+    let verify = &args.verify;
+    let accounts = accounts::SponsoredVerify {
+        post_proof_config: *config,
+        post_verification_log: verification_log_address(program_id, verifier, config),
+        verifier: *verifier,
+        relayer: *relayer,
+        bonsol_program: Bonsol::id(),
+        requester: requester_address(program_id, &verify.current_req_id),
+        execution_request: execution_request_address(relayer, &verify.current_req_id),
+        deployment_account: deployment_address(image_id),
+        creator: campaign.creator,
+        platform_treasury: campaign.platform_treasury,
+        referrer: verify.referrer.unwrap_or(*verifier),
+        payout: verify.payout.unwrap_or(*verifier),
+        protocol_config: protocol_config_address(program_id),
+        author_claim: author_claim_address(program_id, config, &verify.author_did_hash),
+        instructions: sysvar::instructions::ID,
+        post_proof_program: *program_id,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::SponsoredVerify { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// challenge by `challenger` of the reward held for `log`, the verification log at
+/// `log_address`, re-proven by the guest image `image_id`
+pub fn challenge(
+    program_id: &Pubkey,
+    image_id: &str,
+    challenger: &Pubkey,
+    campaign: &PostProofConfig,
+    log_address: &Pubkey,
+    log: &PostVerificationLog,
+    args: ChallengeArgs,
+) -> Instruction {
+    let accounts = accounts::Challenge {
+        post_proof_config: log.config,
+        post_verification_log: *log_address,
+        verifier: log.verifier,
+        challenger: *challenger,
+        bonsol_program: Bonsol::id(),
+        requester: requester_address(program_id, &args.current_req_id),
+        execution_request: execution_request_address(challenger, &args.current_req_id),
+        deployment_account: deployment_address(image_id),
+        creator: campaign.creator,
+        platform_treasury: campaign.platform_treasury,
+        referrer: log.referrer.unwrap_or(log.verifier),
+        payout: log.payout.unwrap_or(log.verifier),
+        protocol_config: protocol_config_address(program_id),
+        author_claim: author_claim_address(program_id, &log.config, &log.author_did_hash),
+        instructions: sysvar::instructions::ID,
+        post_proof_program: *program_id,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::Challenge { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// claim_reward of the reward held for `log`, the verification log at `log_address`.
+/// Passes the challenger along when a challenge has lapsed.
+pub fn claim_reward(
+    program_id: &Pubkey,
+    campaign: &PostProofConfig,
+    log_address: &Pubkey,
+    log: &PostVerificationLog,
+) -> Instruction {
+    let accounts = accounts::ClaimReward {
+        post_proof_config: log.config,
+        post_verification_log: *log_address,
+        verifier: log.verifier,
+        creator: campaign.creator,
+        platform_treasury: campaign.platform_treasury,
+        referrer: log.referrer.unwrap_or(log.verifier),
+        payout: log.payout.unwrap_or(log.verifier),
+        protocol_config: protocol_config_address(program_id),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if let Some(challenger) = log.challenger {
+        metas.push(AccountMeta::new(challenger, false));
+    }
+    Instruction::new_with_bytes(*program_id, &instruction::ClaimReward {}.data(), metas)
+}
+
+pub fn fund_sponsorship(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    config: &Pubkey,
+    args: FundSponsorshipArgs,
+) -> Instruction {
+    let accounts = accounts::FundSponsorship {
+        post_proof_config: *config,
+        creator: *creator,
+        system_program: system_program::ID,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction::FundSponsorship { args }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+/// The accounts verify_post registers with Bonsol for the callback of request `req_id`,
+/// which follow the execution request. `log` is the verification log at `log_address`
+/// on the campaign at `config`. A challenge's callback also takes the challenger.
+pub fn callback_accounts(
+    program_id: &Pubkey,
+    config: &Pubkey,
+    campaign: &PostProofConfig,
+    log_address: &Pubkey,
+    log: &PostVerificationLog,
+    req_id: &str,
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(requester_address(program_id, req_id), false),
+        AccountMeta::new(*config, false),
+        AccountMeta::new(*log_address, false),
+        AccountMeta::new(log.verifier, false),
+        AccountMeta::new(campaign.creator, false),
+        AccountMeta::new(campaign.platform_treasury, false),
+        AccountMeta::new(log.referrer.unwrap_or(log.verifier), false),
+        AccountMeta::new(log.payout.unwrap_or(log.verifier), false),
+        AccountMeta::new(protocol_config_address(program_id), false),
+        AccountMeta::new(
+            author_claim_address(program_id, config, &log.author_did_hash),
+            false,
+        ),
+        AccountMeta::new_readonly(sysvar::instructions::ID, false),
+    ];
+    if let Some(challenger) = log.challenger {
+        accounts.push(AccountMeta::new(challenger, false));
+    }
+    accounts
+}
@@ -0,0 +1,273 @@
+//! Async queries over the program's accounts for frontends and bots. Lookups by
+//! campaign, creator or verifier are `getProgramAccounts` calls narrowed on the RPC
+//! node with memcmp filters on the account discriminator and key fields, so only the
+//! matching accounts come back over the wire. [`CampaignClient::watch_verification`]
+//! follows a submitted proof request until its callback lands.
+//!
+//! Calls about a campaign, request or log run in `tracing` spans carrying its `config`,
+//! `request_id` or `log` address, the fields the relayer and indexer use too, so one
+//! claim can be followed across the services' logs.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use proof_of_post::{AuthorClaim, PostProofConfig, PostVerificationLog};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+mod error;
+pub mod instructions;
+mod request_id;
+mod tip;
+mod watch;
+
+pub use error::PopError;
+pub use request_id::request_id;
+pub use tip::{TipMode, TipPolicy};
+pub use watch::VerificationState;
+
+/// Offset of `PostProofConfig::creator`, the first field after the discriminator
+const CONFIG_CREATOR_OFFSET: usize = 8;
+/// Offsets of `PostVerificationLog::verifier` and `PostVerificationLog::config`
+const LOG_VERIFIER_OFFSET: usize = 8;
+const LOG_CONFIG_OFFSET: usize = 40;
+/// Bonsol derives a request's claim account from these seeds and the request address
+const EXECUTION_CLAIM_SEED: &[u8] = b"execution_claim";
+
+/// Which campaigns [`CampaignClient::list_campaigns`] returns. `creator` narrows the
+/// query on the RPC node; the other fields sit behind variable-length fields in the
+/// account and are applied to the decoded campaigns.
+#[derive(Debug, Clone, Default)]
+pub struct CampaignFilter {
+    pub creator: Option<Pubkey>,
+    /// Skip campaigns no longer accepting verifications
+    pub active_only: bool,
+    /// `PLATFORM_*` id the campaign must run on
+    pub platform: Option<u8>,
+}
+
+impl CampaignFilter {
+    fn matches(&self, config: &PostProofConfig) -> bool {
+        (!self.active_only || config.active)
+            && self
+                .platform
+                .is_none_or(|platform| config.platform == platform)
+    }
+}
+
+pub struct CampaignClient {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    /// PubSub endpoint [`CampaignClient::watch_verification`] subscribes to; without
+    /// one it polls
+    websocket_url: Option<String>,
+}
+
+impl CampaignClient {
+    /// A client for the program deployed at [`proof_of_post::ID`]
+    pub fn new(rpc: RpcClient) -> Self {
+        Self::with_program_id(rpc, proof_of_post::ID)
+    }
+
+    /// A client for a deployment at another address, e.g. a localnet build
+    pub fn with_program_id(rpc: RpcClient, program_id: Pubkey) -> Self {
+        Self {
+            rpc,
+            program_id,
+            websocket_url: None,
+        }
+    }
+
+    /// Push log updates to [`CampaignClient::watch_verification`] over the RPC node's
+    /// PubSub endpoint, e.g. `wss://api.devnet.solana.com`
+    pub fn with_websocket_url(mut self, url: impl Into<String>) -> Self {
+        self.websocket_url = Some(url.into());
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub async fn list_campaigns(
+        &self,
+        filter: &CampaignFilter,
+    ) -> Result<Vec<(Pubkey, PostProofConfig)>, PopError> {
+        let mut filters = vec![discriminator_filter::<PostProofConfig>()];
+        if let Some(creator) = &filter.creator {
+            filters.push(key_filter(CONFIG_CREATOR_OFFSET, creator));
+        }
+        let mut campaigns = self.program_accounts::<PostProofConfig>(filters).await?;
+        campaigns.retain(|(_, config)| filter.matches(config));
+        Ok(campaigns)
+    }
+
+    #[tracing::instrument(skip_all, fields(config = %campaign))]
+    pub async fn get_campaign(&self, campaign: &Pubkey) -> Result<PostProofConfig, PopError> {
+        self.account(campaign).await
+    }
+
+    /// The claim record of the author whose DID hashes to `author_did_hash`, which
+    /// exists once one of their posts was submitted to `campaign`
+    pub async fn get_author_claim(
+        &self,
+        campaign: &Pubkey,
+        author_did_hash: &[u8; 32],
+    ) -> Result<AuthorClaim, PopError> {
+        let address =
+            instructions::author_claim_address(&self.program_id, campaign, author_did_hash);
+        self.account(&address).await
+    }
+
+    pub async fn get_verification_log(
+        &self,
+        log: &Pubkey,
+    ) -> Result<PostVerificationLog, PopError> {
+        self.account(log).await
+    }
+
+    /// Verification logs of every verifier that submitted a post to `campaign`
+    pub async fn get_verifications_for_campaign(
+        &self,
+        campaign: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PostVerificationLog)>, PopError> {
+        self.program_accounts(vec![
+            discriminator_filter::<PostVerificationLog>(),
+            key_filter(LOG_CONFIG_OFFSET, campaign),
+        ])
+        .await
+    }
+
+    /// `verifier`'s verification logs across all campaigns, one per campaign
+    pub async fn get_verifier_history(
+        &self,
+        verifier: &Pubkey,
+    ) -> Result<Vec<(Pubkey, PostVerificationLog)>, PopError> {
+        self.program_accounts(vec![
+            discriminator_filter::<PostVerificationLog>(),
+            key_filter(LOG_VERIFIER_OFFSET, verifier),
+        ])
+        .await
+    }
+
+    async fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T, PopError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .await?
+            .value
+            .ok_or(PopError::NotFound(*address))?;
+        if account.owner != self.program_id {
+            return Err(PopError::Decode(
+                *address,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into(),
+            ));
+        }
+        T::try_deserialize(&mut account.data.as_slice()).map_err(|e| PopError::Decode(*address, e))
+    }
+
+    /// Program accounts matching `filters`. Accounts still in a layout from before a
+    /// program upgrade don't decode and are left out rather than failing the query.
+    async fn program_accounts<T: AccountDeserialize>(
+        &self,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>, PopError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                let decoded = T::try_deserialize(&mut account.data.as_slice()).ok()?;
+                Some((address, decoded))
+            })
+            .collect())
+    }
+}
+
+/// The PubSub endpoint next to an RPC endpoint, one port up when the port is explicit
+/// as with a local validator
+pub fn websocket_url(rpc_url: &str) -> String {
+    let url = rpc_url
+        .replacen("https://", "wss://", 1)
+        .replacen("http://", "ws://", 1);
+    match url.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+            format!(
+                "{host}:{}",
+                port.parse::<u16>().unwrap_or(0).wrapping_add(1)
+            )
+        }
+        _ => url,
+    }
+}
+
+fn discriminator_filter<T: Discriminator>() -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, T::DISCRIMINATOR))
+}
+
+/// The account a prover creates when it claims the Bonsol execution request at `request`
+fn execution_claim_address(request: &Pubkey, bonsol_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXECUTION_CLAIM_SEED, request.as_ref()], bonsol_program).0
+}
+
+fn key_filter(offset: usize, key: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+
+    #[test]
+    fn log_filters_match_the_account_layout() {
+        let verifier = Pubkey::new_from_array([1u8; 32]);
+        let config = Pubkey::new_from_array([2u8; 32]);
+        let log = PostVerificationLog {
+            verifier,
+            config,
+            post_url: String::from("https://bsky.app/profile/bonsol.bsky.social/post/1"),
+            slot: 0,
+            is_verified: false,
+            current_execution_account: None,
+            referrer: None,
+            timestamp: 0,
+            input_hash: None,
+            payout: None,
+            author_did_hash: [0u8; 32],
+            terms_signature: None,
+            failure_reason: 0,
+            failed_checks: 0,
+            bump: 255,
+            version: PostVerificationLog::VERSION,
+            held_reward: 0,
+            held_segment: 0,
+            claimable_slot: 0,
+            challenger: None,
+            challenge_bond: 0,
+            requested_slot: 0,
+        };
+        let mut data = Vec::new();
+        log.try_serialize(&mut data).unwrap();
+
+        assert_eq!(&data[..8], PostVerificationLog::DISCRIMINATOR);
+        assert_eq!(&data[LOG_VERIFIER_OFFSET..][..32], verifier.as_ref());
+        assert_eq!(&data[LOG_CONFIG_OFFSET..][..32], config.as_ref());
+    }
+}
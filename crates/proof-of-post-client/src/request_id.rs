@@ -0,0 +1,96 @@
+//! Request IDs for `verify_post`, derived so the program can check them
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash;
+use proof_of_post::{canonical_post_url, derive_request_id};
+
+use crate::instructions::requester_address;
+use crate::{CampaignClient, PopError};
+
+/// Fresh nonces tried before giving up; a used one only turns up by chance
+const MAX_NONCE_ATTEMPTS: usize = 4;
+
+/// The request ID derived for `verifier` proving the post at `post_url` for `campaign`
+/// with `nonce`, the value verify_post expects when given the nonce as `request_nonce`.
+/// The program hashes the canonical form of the URL, and so does this.
+pub fn request_id(verifier: &Pubkey, campaign: &Pubkey, post_url: &str, nonce: u64) -> String {
+    let post_url_hash = hash::hash(canonical_post_url(post_url).as_bytes()).to_bytes();
+    derive_request_id(verifier, campaign, &post_url_hash, nonce)
+}
+
+impl CampaignClient {
+    /// A request ID no earlier request has taken, and the random nonce it was derived
+    /// with. Pass both to verify_post, as `current_req_id` and `request_nonce`.
+    #[tracing::instrument(
+        skip_all,
+        fields(config = %campaign, verifier = %verifier, request_id = tracing::field::Empty)
+    )]
+    pub async fn new_request_id(
+        &self,
+        verifier: &Pubkey,
+        campaign: &Pubkey,
+        post_url: &str,
+    ) -> Result<(String, u64), PopError> {
+        let candidates: Vec<(String, u64)> = (0..MAX_NONCE_ATTEMPTS)
+            .map(|_| {
+                let nonce = rand::random();
+                (request_id(verifier, campaign, post_url, nonce), nonce)
+            })
+            .collect();
+        // Each ID seeds the requester account verify_post creates, so a taken ID has one
+        let requesters: Vec<Pubkey> = candidates
+            .iter()
+            .map(|(id, _)| requester_address(&self.program_id, id))
+            .collect();
+        let taken = self.rpc.get_multiple_accounts(&requesters).await?;
+        let (id, nonce) = candidates
+            .into_iter()
+            .zip(taken)
+            .find_map(|(candidate, requester)| requester.is_none().then_some(candidate))
+            .ok_or(PopError::RequestIdsTaken)?;
+        tracing::Span::current().record("request_id", id.as_str());
+        Ok((id, nonce))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof_of_post::validate_request_id;
+
+    #[test]
+    fn derived_ids_are_valid_and_bound_to_the_request() {
+        let verifier = Pubkey::new_from_array([1u8; 32]);
+        let campaign = Pubkey::new_from_array([2u8; 32]);
+        let url = "https://bsky.app/profile/bonsol.bsky.social/post/1";
+        let id = request_id(&verifier, &campaign, url, 7);
+
+        assert_eq!(id.len(), 32);
+        assert!(validate_request_id(&id).is_ok());
+        assert_eq!(id, request_id(&verifier, &campaign, url, 7));
+        assert_ne!(id, request_id(&verifier, &campaign, url, 8));
+        assert_ne!(id, request_id(&campaign, &verifier, url, 7));
+    }
+
+    #[test]
+    fn equivalent_urls_share_an_id() {
+        let verifier = Pubkey::new_from_array([1u8; 32]);
+        let campaign = Pubkey::new_from_array([2u8; 32]);
+        let url = "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?a=1&uris=at://x";
+        let id = request_id(&verifier, &campaign, url, 7);
+
+        for equivalent in [
+            "https://Public.API.bsky.app/xrpc/app.bsky.feed.getPosts?a=1&uris=at://x",
+            "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?uris=at://x&a=1",
+            "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?a=1&&uris=at://x&a=1#top",
+        ] {
+            assert_eq!(canonical_post_url(equivalent), url);
+            assert_eq!(request_id(&verifier, &campaign, equivalent, 7), id);
+        }
+        // Paths stay case sensitive
+        assert_ne!(
+            canonical_post_url("https://public.api.bsky.app/XRPC/app.bsky.feed.getPosts"),
+            "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Picking a prover tip for `verify_post`
+
+use anchor_lang::prelude::Pubkey;
+use proof_of_post::{PostVerificationLog, ProtocolConfig};
+
+use crate::instructions::protocol_config_address;
+use crate::{discriminator_filter, execution_claim_address, CampaignClient, PopError};
+
+/// Most accounts `getMultipleAccounts` returns per call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipMode {
+    /// Cheapest tip provers are currently taking, at the risk of waiting for a claim
+    Conservative,
+    /// Outbids what provers are currently taking, for a quick claim
+    Aggressive,
+}
+
+/// Tip for a request from its size, used when there is little on-chain history to go
+/// by. Proving cost grows with the bytes the guest hashes and parses, mostly the post's
+/// API response.
+#[derive(Debug, Clone)]
+pub struct TipPolicy {
+    /// Lamports for any request
+    pub base: u64,
+    /// Lamports per KiB of guest input
+    pub per_kib: u64,
+    /// [`TipMode::Aggressive`] pays this multiple of the conservative tip
+    pub aggressive_multiple: u64,
+}
+
+impl Default for TipPolicy {
+    fn default() -> Self {
+        Self {
+            base: 100_000,
+            per_kib: 1_000,
+            aggressive_multiple: 3,
+        }
+    }
+}
+
+impl TipPolicy {
+    pub fn tip(&self, input_len: u64, mode: TipMode) -> u64 {
+        let tip = self
+            .base
+            .saturating_add(self.per_kib.saturating_mul(input_len.div_ceil(1024)));
+        match mode {
+            TipMode::Conservative => tip,
+            TipMode::Aggressive => tip.saturating_mul(self.aggressive_multiple),
+        }
+    }
+}
+
+impl CampaignClient {
+    /// Tip for a request whose guest input is `input_len` bytes: the higher of
+    /// `policy`'s tip and what provers are taking now, kept within the protocol's
+    /// `min_tip` and `max_tip`. Provers' going rate is read from this program's requests that have
+    /// been claimed but not settled yet: their median tip in conservative mode, their
+    /// highest in aggressive mode.
+    pub async fn recommend_tip(
+        &self,
+        input_len: u64,
+        mode: TipMode,
+        policy: &TipPolicy,
+    ) -> Result<u64, PopError> {
+        let protocol = protocol_config_address(&self.program_id);
+        let (min_tip, max_tip) = match self.account::<ProtocolConfig>(&protocol).await {
+            Ok(protocol) if protocol.max_tip > 0 => (protocol.min_tip, protocol.max_tip),
+            Ok(protocol) => (protocol.min_tip, u64::MAX),
+            Err(PopError::NotFound(_)) => (0, u64::MAX),
+            Err(e) => return Err(e),
+        };
+
+        let mut claimed = self.claimed_tips().await?;
+        claimed.sort_unstable();
+        let going_rate = match mode {
+            TipMode::Conservative => claimed.get(claimed.len() / 2),
+            TipMode::Aggressive => claimed.last(),
+        };
+        Ok(policy
+            .tip(input_len, mode)
+            .max(going_rate.copied().unwrap_or(0))
+            .min(max_tip)
+            .max(min_tip))
+    }
+
+    /// Tips of this program's pending requests that a prover has claimed
+    async fn claimed_tips(&self) -> Result<Vec<u64>, PopError> {
+        let logs = vec![discriminator_filter::<PostVerificationLog>()];
+        let pending: Vec<Pubkey> = self
+            .program_accounts::<PostVerificationLog>(logs)
+            .await?
+            .into_iter()
+            .filter_map(|(_, log)| log.current_execution_account)
+            .collect();
+
+        let mut tips = Vec::new();
+        for chunk in pending.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let (claims, chunk_tips): (Vec<Pubkey>, Vec<u64>) = chunk
+                .iter()
+                .zip(self.rpc.get_multiple_accounts(chunk).await?)
+                .filter_map(|(address, request)| {
+                    let request = request?;
+                    let tip = bonsol_schema::root_as_execution_request_v1(&request.data)
+                        .ok()?
+                        .tip();
+                    Some((execution_claim_address(address, &request.owner), tip))
+                })
+                .unzip();
+            let claimed = self.rpc.get_multiple_accounts(&claims).await?;
+            tips.extend(
+                chunk_tips
+                    .into_iter()
+                    .zip(claimed)
+                    .filter_map(|(tip, claim)| claim.map(|_| tip)),
+            );
+        }
+        Ok(tips)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_tip_grows_with_input() {
+        let policy = TipPolicy::default();
+        assert_eq!(policy.tip(0, TipMode::Conservative), 100_000);
+        assert_eq!(policy.tip(1, TipMode::Conservative), 101_000);
+        assert_eq!(policy.tip(10 * 1024, TipMode::Conservative), 110_000);
+        assert_eq!(policy.tip(10 * 1024, TipMode::Aggressive), 330_000);
+        assert_eq!(
+            TipPolicy {
+                per_kib: u64::MAX,
+                ..policy
+            }
+            .tip(4096, TipMode::Aggressive),
+            u64::MAX
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Following a verification request from `verify_post` to the Bonsol callback
+
+use anchor_lang::prelude::Pubkey;
+use futures::stream::{self, Stream, StreamExt};
+use proof_of_post::PostVerificationLog;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+use crate::{execution_claim_address, CampaignClient, PopError};
+
+/// How often request state is re-read. Claims and expiry don't touch the log, so this
+/// runs with a PubSub subscription too.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where the latest request of a verification log stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationState {
+    /// Submitted and waiting for a prover to claim it
+    Requested { execution_account: Pubkey },
+    /// Claimed by a prover, which is fetching the post and proving
+    Proving { execution_account: Pubkey },
+    /// The callback ran and the post passed, at `slot`
+    Verified { slot: u64 },
+    /// The callback ran and the post failed; `reason_code` is the `REASON_*` code of
+    /// its most important failed check and `failed_checks` the bitmap of all of them
+    Failed {
+        reason_code: u16,
+        failed_checks: u32,
+    },
+    /// The request passed its expiry, or was closed, without a callback
+    Expired { execution_account: Pubkey },
+}
+
+impl VerificationState {
+    /// Whether the request is settled and the state won't change until the next
+    /// `verify_post`
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Requested { .. } | Self::Proving { .. })
+    }
+}
+
+impl CampaignClient {
+    /// The state of the latest request made with the verification log at `log`
+    #[tracing::instrument(
+        skip_all,
+        fields(log = %log, config = tracing::field::Empty, execution_account = tracing::field::Empty)
+    )]
+    pub async fn verification_state(&self, log: &Pubkey) -> Result<VerificationState, PopError> {
+        let log: PostVerificationLog = self.account(log).await?;
+        let span = tracing::Span::current();
+        span.record("config", tracing::field::display(log.config));
+        let Some(execution_account) = log.current_execution_account else {
+            return Ok(if log.is_verified {
+                VerificationState::Verified { slot: log.slot }
+            } else {
+                VerificationState::Failed {
+                    reason_code: log.failure_reason,
+                    failed_checks: log.failed_checks,
+                }
+            });
+        };
+        span.record(
+            "execution_account",
+            tracing::field::display(execution_account),
+        );
+
+        let commitment = self.rpc.commitment();
+        let Some(request) = self
+            .rpc
+            .get_account_with_commitment(&execution_account, commitment)
+            .await?
+            .value
+        else {
+            return Ok(VerificationState::Expired { execution_account });
+        };
+        let max_block_height = bonsol_schema::root_as_execution_request_v1(&request.data)
+            .map(|request| request.max_block_height())
+            .ok();
+        let slot = self.rpc.get_slot_with_commitment(commitment).await?;
+        if max_block_height.is_some_and(|expiry| slot > expiry) {
+            return Ok(VerificationState::Expired { execution_account });
+        }
+
+        let claim = execution_claim_address(&execution_account, &request.owner);
+        let claimed = self
+            .rpc
+            .get_account_with_commitment(&claim, commitment)
+            .await?
+            .value
+            .is_some();
+        Ok(if claimed {
+            VerificationState::Proving { execution_account }
+        } else {
+            VerificationState::Requested { execution_account }
+        })
+    }
+
+    /// Follows the latest request of the verification log at `log`, yielding its state
+    /// now and on every change until it is final. Log updates arrive over PubSub when
+    /// the client has a websocket URL, falling back to polling if the subscription
+    /// can't be made or drops. RPC errors are yielded and the watch carries on; drop
+    /// the stream to stop it.
+    ///
+    /// Needs a Tokio runtime, and the stream must be pinned (`Box::pin`) before polling.
+    pub fn watch_verification(
+        &self,
+        log: Pubkey,
+    ) -> impl Stream<Item = Result<VerificationState, PopError>> + '_ {
+        let watch = Watch {
+            log,
+            notifications: self.websocket_url.clone().map(|url| {
+                let config = RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(self.rpc.commitment()),
+                    ..Default::default()
+                };
+                subscribe(url, log, config)
+            }),
+            last: None,
+            first: true,
+            done: false,
+        };
+        let span = tracing::info_span!("watch_verification", log = %log);
+        stream::unfold(watch, move |mut watch| {
+            async move {
+                if watch.done {
+                    return None;
+                }
+                loop {
+                    if !std::mem::take(&mut watch.first) {
+                        watch.wait().await;
+                    }
+                    match self.verification_state(&watch.log).await {
+                        Ok(state) if watch.last.as_ref() == Some(&state) => continue,
+                        Ok(state) => {
+                            tracing::info!(?state, "verification state changed");
+                            watch.done = state.is_final();
+                            watch.last = Some(state.clone());
+                            return Some((Ok(state), watch));
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "reading verification state");
+                            return Some((Err(e), watch));
+                        }
+                    }
+                }
+            }
+            .instrument(span.clone())
+        })
+    }
+}
+
+struct Watch {
+    log: Pubkey,
+    /// Signalled on every log update while the PubSub subscription is up
+    notifications: Option<mpsc::UnboundedReceiver<()>>,
+    last: Option<VerificationState>,
+    first: bool,
+    done: bool,
+}
+
+impl Watch {
+    /// Until the log changes or the next poll is due
+    async fn wait(&mut self) {
+        let Some(notifications) = &mut self.notifications else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            return;
+        };
+        let subscribed = tokio::select! {
+            update = notifications.recv() => update.is_some(),
+            _ = tokio::time::sleep(POLL_INTERVAL) => true,
+        };
+        if !subscribed {
+            self.notifications = None;
+        }
+    }
+}
+
+/// Forwards updates to `account` from a PubSub subscription, which lives on its own
+/// task because the update stream borrows the client. The channel closes if the
+/// subscription can't be made or ends.
+fn subscribe(
+    url: String,
+    account: Pubkey,
+    config: RpcAccountInfoConfig,
+) -> mpsc::UnboundedReceiver<()> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let Ok(pubsub) = PubsubClient::new(&url).await else {
+            return;
+        };
+        let Ok((mut updates, unsubscribe)) = pubsub.account_subscribe(&account, Some(config)).await
+        else {
+            return;
+        };
+        loop {
+            tokio::select! {
+                update = updates.next() => {
+                    if update.is_none() || tx.send(()).is_err() {
+                        break;
+                    }
+                }
+                _ = tx.closed() => break,
+            }
+        }
+        drop(updates);
+        unsubscribe().await;
+    });
+    rx
+}
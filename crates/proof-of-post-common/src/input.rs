@@ -0,0 +1,912 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::PostMetrics;
+
+/// Upper bound on the comma-separated keywords string (20 keywords of up to 50 bytes)
+pub const MAX_KEYWORDS_LEN: usize = 20 * 51;
+/// Upper bound on a domain name in the public input
+pub const MAX_DOMAIN_LEN: usize = 64;
+/// Upper bound on an AT URI in the public input
+pub const MAX_AT_URI_LEN: usize = 128;
+/// Guest memory ceiling for the fetched API response
+pub const MAX_RESPONSE_LEN: u64 = 4 * 1024 * 1024;
+/// Upper bound on a rule bundle fetched from a campaign's `metadata_uri`
+pub const MAX_RULES_BUNDLE_LEN: usize = 3 * (8 + MAX_KEYWORDS_LEN);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    UnexpectedEof,
+    InvalidUtf8,
+    TooLarge,
+    InvalidMatchMode,
+    InvalidEncoding,
+    TrailingBytes,
+}
+
+/// Source of framed input bytes. Implemented for byte slices here and for the zkVM
+/// environment in the guest.
+pub trait InputSource {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), DecodeError>;
+}
+
+impl InputSource for &[u8] {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), DecodeError> {
+        if self.len() < buf.len() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (head, tail) = self.split_at(buf.len());
+        buf.copy_from_slice(head);
+        *self = tail;
+        Ok(())
+    }
+}
+
+pub const MATCH_ALL: u8 = 0;
+pub const MATCH_ANY: u8 = 1;
+pub const MATCH_AT_LEAST: u8 = 2;
+
+/// Keywords only match on word boundaries ("sol" does not match "solution")
+pub const MATCH_FLAG_WHOLE_WORD: u8 = 1 << 0;
+/// Keywords match verbatim, without collapsing runs of whitespace
+pub const MATCH_FLAG_EXACT_PHRASE: u8 = 1 << 1;
+/// Keywords match case-sensitively
+pub const MATCH_FLAG_CASE_SENSITIVE: u8 = 1 << 2;
+/// Keywords are regular expressions evaluated in the guest
+pub const MATCH_FLAG_REGEX: u8 = 1 << 3;
+/// Accents and other diacritics are ignored ("Bónsol" matches "bonsol")
+pub const MATCH_FLAG_FOLD_DIACRITICS: u8 = 1 << 4;
+pub const MATCH_FLAGS_MASK: u8 = MATCH_FLAG_WHOLE_WORD
+    | MATCH_FLAG_EXACT_PHRASE
+    | MATCH_FLAG_CASE_SENSITIVE
+    | MATCH_FLAG_REGEX
+    | MATCH_FLAG_FOLD_DIACRITICS;
+
+/// Social platform whose API response the guest parses
+pub const PLATFORM_BLUESKY: u8 = 0;
+/// X (Twitter) API v2 `GET /2/tweets`
+pub const PLATFORM_TWITTER: u8 = 1;
+/// Mastodon `GET /api/v1/statuses/:id`
+pub const PLATFORM_MASTODON: u8 = 2;
+/// Farcaster casts via Neynar `GET /v2/farcaster/cast`
+pub const PLATFORM_FARCASTER: u8 = 3;
+/// Lens API v2 `publication` query
+pub const PLATFORM_LENS: u8 = 4;
+/// Reddit `.json` post permalinks and `/api/info.json` comment lookups
+pub const PLATFORM_REDDIT: u8 = 5;
+/// YouTube Data API v3 `videos.list` with `part=snippet,statistics`
+pub const PLATFORM_YOUTUBE: u8 = 6;
+
+/// Content encoding of the response input, as the platform API served it
+pub const ENCODING_IDENTITY: u8 = 0;
+/// `Content-Encoding: gzip`
+pub const ENCODING_GZIP: u8 = 1;
+/// `Content-Encoding: deflate`, a zlib stream
+pub const ENCODING_DEFLATE: u8 = 2;
+
+/// Segment matched on the post's language tags, compared like `required_langs`
+pub const SEGMENT_LANGUAGE: u8 = 0;
+/// Segment matched on the end of the author's handle (".jp", ".bsky.social")
+pub const SEGMENT_HANDLE_SUFFIX: u8 = 1;
+/// Most market segments a campaign can define
+pub const MAX_SEGMENTS: usize = 4;
+/// Upper bound on a segment's language tag or handle suffix
+pub const MAX_SEGMENT_VALUE_LEN: usize = 32;
+
+/// Longest regex keyword accepted at config creation, bounding guest cycles
+pub const MAX_REGEX_PATTERN_LEN: usize = 32;
+
+/// Domain separator for a notary's signature over a fetched response
+pub const RESPONSE_SIGNATURE_PREFIX: &[u8] = b"proof-of-post response:";
+
+/// Message a campaign's response signer signs: the prefix, the SHA-256 of the post URL
+/// it fetched and the SHA-256 of the response body exactly as the prover feeds it to
+/// the guest. Binding the URL keeps a signed body from being passed off as the response
+/// of another post.
+pub fn response_signature_message(post_url_hash: &[u8; 32], response_hash: &[u8; 32]) -> Vec<u8> {
+    [
+        RESPONSE_SIGNATURE_PREFIX,
+        post_url_hash.as_slice(),
+        response_hash.as_slice(),
+    ]
+    .concat()
+}
+
+/// How many of the campaign keywords a post must contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    All,
+    Any,
+    AtLeast(u8),
+}
+
+impl MatchMode {
+    /// Build a mode from its `(match_mode, match_threshold)` byte pair. The threshold is
+    /// only meaningful for [`MatchMode::AtLeast`] and must be non-zero there.
+    pub fn from_parts(mode: u8, threshold: u8) -> Option<Self> {
+        match mode {
+            MATCH_ALL => Some(Self::All),
+            MATCH_ANY => Some(Self::Any),
+            MATCH_AT_LEAST if threshold > 0 => Some(Self::AtLeast(threshold)),
+            _ => None,
+        }
+    }
+
+    pub fn to_parts(self) -> (u8, u8) {
+        match self {
+            Self::All => (MATCH_ALL, 0),
+            Self::Any => (MATCH_ANY, 0),
+            Self::AtLeast(n) => (MATCH_AT_LEAST, n),
+        }
+    }
+
+    /// Whether `matched` out of `total` keywords satisfies the mode. A campaign with no
+    /// keywords always passes.
+    pub fn is_satisfied(self, matched: usize, total: usize) -> bool {
+        if total == 0 {
+            return true;
+        }
+        match self {
+            Self::All => matched == total,
+            Self::Any => matched > 0,
+            Self::AtLeast(n) => matched >= n as usize,
+        }
+    }
+}
+
+/// Campaign rules and request parameters passed to the guest as the public input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicInput {
+    pub post_size: u64,
+    pub keywords: Vec<String>,
+    /// Terms that must not appear in the post
+    pub blocked_keywords: Vec<String>,
+    /// `#hashtag` / `@handle` entries that must be present as rich-text facets
+    pub required_facets: Vec<String>,
+    pub match_mode: MatchMode,
+    /// Bitmask of `MATCH_FLAG_*` options
+    pub match_flags: u8,
+    pub require_authorship: bool,
+    pub verifier: [u8; 32],
+    pub engagement: EngagementThresholds,
+    pub recency: RecencyWindow,
+    /// BCP 47 language tags the post's `langs` must include one of; empty accepts any
+    pub required_langs: Vec<String>,
+    pub content: ContentRequirements,
+    pub media: MediaRequirement,
+    /// The post must link to one of these domains, optionally with a path prefix
+    /// ("example.com/launch"); empty disables the check
+    pub allowed_link_domains: Vec<String>,
+    /// AT URI of the post that must be the direct parent of a qualifying reply; empty
+    /// accepts any post
+    pub reply_to_uri: String,
+    pub author: AuthorRequirements,
+    /// Moderation label values ("spam", "porn") that disqualify a post when applied to
+    /// the post or its author
+    pub blocked_labels: Vec<String>,
+    /// `PLATFORM_*` id selecting the guest's response parser
+    pub platform: u8,
+    /// Communities (subreddits) the post must have been made in, compared
+    /// case-insensitively; empty accepts any
+    pub required_communities: Vec<String>,
+    /// Paid-promotion disclosure hashtags ("#ad", "#sponsored"), one of which the post
+    /// must carry; empty disables the check
+    pub disclosure_tags: Vec<String>,
+    /// Stable author ids (channel ids on YouTube) allowed to claim; empty accepts any
+    pub allowed_author_ids: Vec<String>,
+    /// Market segments in priority order; the guest commits the index of the first one
+    /// the post falls in
+    pub segments: Vec<SegmentRule>,
+    /// Ed25519 key of the notary vouching for fetched responses. When non-zero the guest
+    /// reads the notary's signature before the response and commits whether it holds.
+    pub response_signer: [u8; 32],
+    /// SHA-256 of the URL the post response is fetched from, committed back so the
+    /// program can tie the proof to the request's `post_url`
+    pub post_url_hash: [u8; 32],
+    /// Address of the campaign's config account, committed back so a proof made under
+    /// one campaign's rules can't be redeemed against another
+    pub config: [u8; 32],
+    /// `ENCODING_*` of the response input. `post_size` counts the encoded bytes; the
+    /// guest parses, and the response signer signs, the decoded ones.
+    pub response_encoding: u8,
+    /// Thread mode when non-zero: the response is a Bluesky `getPostThread` and at least
+    /// this many of the author's posts in the thread must satisfy the keyword rules
+    pub min_thread_posts: u8,
+    /// When set, the keyword lists above are empty and the guest reads them from a rule
+    /// bundle input instead, checked against this reference
+    pub rules_bundle: Option<RulesBundleRef>,
+}
+
+/// Minimum counts the post must have reached when it is proven. Zero disables a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngagementThresholds {
+    pub min_likes: u64,
+    pub min_reposts: u64,
+    pub min_replies: u64,
+    pub min_views: u64,
+}
+
+impl EngagementThresholds {
+    pub fn is_met(&self, metrics: &PostMetrics) -> bool {
+        metrics.like_count >= self.min_likes
+            && metrics.repost_count >= self.min_reposts
+            && metrics.reply_count >= self.min_replies
+            && metrics.view_count >= self.min_views
+    }
+}
+
+/// Unix-second bounds on the post's `createdAt`, so old posts can't be recycled into new
+/// campaigns. Zero leaves a side unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecencyWindow {
+    pub not_before_ts: i64,
+    pub not_after_ts: i64,
+}
+
+impl RecencyWindow {
+    pub fn is_unbounded(&self) -> bool {
+        self.not_before_ts == 0 && self.not_after_ts == 0
+    }
+
+    pub fn contains(&self, ts: i64) -> bool {
+        (self.not_before_ts == 0 || ts >= self.not_before_ts)
+            && (self.not_after_ts == 0 || ts <= self.not_after_ts)
+    }
+}
+
+/// Minimum substance of a post, counted without hashtags, mentions and URLs. Zero
+/// disables a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentRequirements {
+    pub min_chars: u32,
+    pub min_words: u32,
+}
+
+/// Media the post must embed: at least `min_images` images, or a link card on
+/// `external_domain`. Either satisfies the requirement; with neither set any post passes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MediaRequirement {
+    pub min_images: u8,
+    pub external_domain: String,
+}
+
+/// Anti-sybil checks on the post's author. `as_of_ts` is the on-chain unix time of the
+/// request, which account age is measured against. Zero thresholds disable a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuthorRequirements {
+    pub min_account_age_days: u32,
+    pub min_followers: u64,
+    pub as_of_ts: i64,
+}
+
+impl AuthorRequirements {
+    /// `created_at` is the account's creation time and `followers` its follower count,
+    /// when the API response includes them. Missing data fails an enabled check.
+    pub fn is_met(&self, created_at: Option<i64>, followers: Option<u64>) -> bool {
+        let old_enough = self.min_account_age_days == 0
+            || created_at.is_some_and(|ts| {
+                self.as_of_ts.saturating_sub(ts) >= self.min_account_age_days as i64 * 86_400
+            });
+        let followed =
+            self.min_followers == 0 || followers.is_some_and(|n| n >= self.min_followers);
+        old_enough && followed
+    }
+}
+
+/// How a post is assigned to one of a campaign's market segments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentRule {
+    /// `SEGMENT_*` kind of `value`
+    pub kind: u8,
+    /// Language tag or handle suffix
+    pub value: String,
+}
+
+/// Hash and size of an off-chain rule bundle, so large rule sets don't have to travel
+/// in every request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RulesBundleRef {
+    /// SHA-256 of the encoded bundle
+    pub hash: [u8; 32],
+    pub size: u64,
+}
+
+/// Keyword lists published at a campaign's `metadata_uri`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RulesBundle {
+    pub keywords: Vec<String>,
+    pub blocked_keywords: Vec<String>,
+    pub required_facets: Vec<String>,
+}
+
+/// Public input layout:
+/// [post_size(8)][keywords_size(8)][keywords_string][blocked_size(8)][blocked_string]
+/// [facets_size(8)][facets_string][match_mode(1)][match_threshold(1)][match_flags(1)][require_authorship(1)][verifier(32)]
+/// [min_likes(8)][min_reposts(8)][min_replies(8)][min_views(8)][not_before_ts(8)][not_after_ts(8)]
+/// [langs_size(8)][langs_string][min_chars(4)][min_words(4)]
+/// [min_images(1)][domain_size(8)][external_domain][links_size(8)][links_string]
+/// [reply_to_size(8)][reply_to_uri][min_account_age_days(4)][min_followers(8)][as_of_ts(8)]
+/// [labels_size(8)][labels_string][platform(1)][communities_size(8)][communities_string]
+/// [disclosure_size(8)][disclosure_string][authors_size(8)][authors_string]
+/// [segments_count(1)]([segment_kind(1)][segment_value_size(8)][segment_value])*
+/// [response_signer(32)][post_url_hash(32)][config(32)][response_encoding(1)]
+/// [min_thread_posts(1)][has_rules_bundle(1)][rules_hash(32)][rules_size(8)]
+///
+/// Integers are big-endian and keyword lists are joined with commas. The rules hash and
+/// size are only present when `has_rules_bundle` is 1.
+pub fn encode_public_input(input: &PublicInput) -> Vec<u8> {
+    let (match_mode, match_threshold) = input.match_mode.to_parts();
+    let mut out = Vec::new();
+    out.extend_from_slice(&input.post_size.to_be_bytes());
+    write_keywords(&mut out, &input.keywords);
+    write_keywords(&mut out, &input.blocked_keywords);
+    write_keywords(&mut out, &input.required_facets);
+    out.push(match_mode);
+    out.push(match_threshold);
+    out.push(input.match_flags);
+    out.push(input.require_authorship as u8);
+    out.extend_from_slice(&input.verifier);
+    out.extend_from_slice(&input.engagement.min_likes.to_be_bytes());
+    out.extend_from_slice(&input.engagement.min_reposts.to_be_bytes());
+    out.extend_from_slice(&input.engagement.min_replies.to_be_bytes());
+    out.extend_from_slice(&input.engagement.min_views.to_be_bytes());
+    out.extend_from_slice(&input.recency.not_before_ts.to_be_bytes());
+    out.extend_from_slice(&input.recency.not_after_ts.to_be_bytes());
+    write_keywords(&mut out, &input.required_langs);
+    out.extend_from_slice(&input.content.min_chars.to_be_bytes());
+    out.extend_from_slice(&input.content.min_words.to_be_bytes());
+    out.push(input.media.min_images);
+    write_string(&mut out, &input.media.external_domain);
+    write_keywords(&mut out, &input.allowed_link_domains);
+    write_string(&mut out, &input.reply_to_uri);
+    out.extend_from_slice(&input.author.min_account_age_days.to_be_bytes());
+    out.extend_from_slice(&input.author.min_followers.to_be_bytes());
+    out.extend_from_slice(&input.author.as_of_ts.to_be_bytes());
+    write_keywords(&mut out, &input.blocked_labels);
+    out.push(input.platform);
+    write_keywords(&mut out, &input.required_communities);
+    write_keywords(&mut out, &input.disclosure_tags);
+    write_keywords(&mut out, &input.allowed_author_ids);
+    out.push(input.segments.len() as u8);
+    for segment in &input.segments {
+        out.push(segment.kind);
+        write_string(&mut out, &segment.value);
+    }
+    out.extend_from_slice(&input.response_signer);
+    out.extend_from_slice(&input.post_url_hash);
+    out.extend_from_slice(&input.config);
+    out.push(input.response_encoding);
+    out.push(input.min_thread_posts);
+    match &input.rules_bundle {
+        Some(bundle) => {
+            out.push(1);
+            out.extend_from_slice(&bundle.hash);
+            out.extend_from_slice(&bundle.size.to_be_bytes());
+        }
+        None => out.push(0),
+    }
+    out
+}
+
+pub fn decode_public_input<S: InputSource>(src: &mut S) -> Result<PublicInput, DecodeError> {
+    let post_size = read_u64(src)?;
+    if post_size > MAX_RESPONSE_LEN {
+        return Err(DecodeError::TooLarge);
+    }
+    let keywords = read_keywords(src)?;
+    let blocked_keywords = read_keywords(src)?;
+    let required_facets = read_keywords(src)?;
+
+    let mut mode = [0u8; 3];
+    src.read_exact(&mut mode)?;
+    let match_mode =
+        MatchMode::from_parts(mode[0], mode[1]).ok_or(DecodeError::InvalidMatchMode)?;
+    let match_flags = mode[2];
+    if match_flags & !MATCH_FLAGS_MASK != 0 {
+        return Err(DecodeError::InvalidMatchMode);
+    }
+
+    let mut flag = [0u8; 1];
+    src.read_exact(&mut flag)?;
+    let mut verifier = [0u8; 32];
+    src.read_exact(&mut verifier)?;
+    let engagement = EngagementThresholds {
+        min_likes: read_u64(src)?,
+        min_reposts: read_u64(src)?,
+        min_replies: read_u64(src)?,
+        min_views: read_u64(src)?,
+    };
+    let recency = RecencyWindow {
+        not_before_ts: read_u64(src)? as i64,
+        not_after_ts: read_u64(src)? as i64,
+    };
+    let required_langs = read_keywords(src)?;
+    let content = ContentRequirements {
+        min_chars: read_u32(src)?,
+        min_words: read_u32(src)?,
+    };
+    let mut min_images = [0u8; 1];
+    src.read_exact(&mut min_images)?;
+    let media = MediaRequirement {
+        min_images: min_images[0],
+        external_domain: read_string(src, MAX_DOMAIN_LEN)?,
+    };
+    let allowed_link_domains = read_keywords(src)?;
+    let reply_to_uri = read_string(src, MAX_AT_URI_LEN)?;
+    let author = AuthorRequirements {
+        min_account_age_days: read_u32(src)?,
+        min_followers: read_u64(src)?,
+        as_of_ts: read_u64(src)? as i64,
+    };
+    let blocked_labels = read_keywords(src)?;
+    let mut platform = [0u8; 1];
+    src.read_exact(&mut platform)?;
+    let required_communities = read_keywords(src)?;
+    let disclosure_tags = read_keywords(src)?;
+    let allowed_author_ids = read_keywords(src)?;
+    let mut segments_count = [0u8; 1];
+    src.read_exact(&mut segments_count)?;
+    if segments_count[0] as usize > MAX_SEGMENTS {
+        return Err(DecodeError::TooLarge);
+    }
+    let mut segments = Vec::with_capacity(segments_count[0] as usize);
+    for _ in 0..segments_count[0] {
+        let mut kind = [0u8; 1];
+        src.read_exact(&mut kind)?;
+        segments.push(SegmentRule {
+            kind: kind[0],
+            value: read_string(src, MAX_SEGMENT_VALUE_LEN)?,
+        });
+    }
+    let mut response_signer = [0u8; 32];
+    src.read_exact(&mut response_signer)?;
+    let mut post_url_hash = [0u8; 32];
+    src.read_exact(&mut post_url_hash)?;
+    let mut config = [0u8; 32];
+    src.read_exact(&mut config)?;
+    let mut response_encoding = [0u8; 1];
+    src.read_exact(&mut response_encoding)?;
+    let response_encoding = response_encoding[0];
+    if response_encoding > ENCODING_DEFLATE {
+        return Err(DecodeError::InvalidEncoding);
+    }
+    let mut min_thread_posts = [0u8; 1];
+    src.read_exact(&mut min_thread_posts)?;
+
+    let mut has_bundle = [0u8; 1];
+    src.read_exact(&mut has_bundle)?;
+    let rules_bundle = if has_bundle[0] != 0 {
+        let mut hash = [0u8; 32];
+        src.read_exact(&mut hash)?;
+        let size = read_u64(src)?;
+        if size > MAX_RULES_BUNDLE_LEN as u64 {
+            return Err(DecodeError::TooLarge);
+        }
+        Some(RulesBundleRef { hash, size })
+    } else {
+        None
+    };
+
+    Ok(PublicInput {
+        post_size,
+        keywords,
+        blocked_keywords,
+        required_facets,
+        match_mode,
+        match_flags,
+        require_authorship: flag[0] != 0,
+        verifier,
+        engagement,
+        recency,
+        required_langs,
+        content,
+        media,
+        allowed_link_domains,
+        reply_to_uri,
+        author,
+        blocked_labels,
+        platform: platform[0],
+        required_communities,
+        disclosure_tags,
+        allowed_author_ids,
+        segments,
+        response_signer,
+        post_url_hash,
+        config,
+        response_encoding,
+        min_thread_posts: min_thread_posts[0],
+        rules_bundle,
+    })
+}
+
+/// Rule bundle layout: the three keyword sections of the public input,
+/// [keywords_size(8)][keywords_string][blocked_size(8)][blocked_string][facets_size(8)][facets_string]
+pub fn encode_rules_bundle(bundle: &RulesBundle) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_keywords(&mut out, &bundle.keywords);
+    write_keywords(&mut out, &bundle.blocked_keywords);
+    write_keywords(&mut out, &bundle.required_facets);
+    out
+}
+
+/// Decode a complete rule bundle. The bundle is hashed as a whole, so trailing bytes are
+/// rejected rather than ignored.
+pub fn decode_rules_bundle(mut bytes: &[u8]) -> Result<RulesBundle, DecodeError> {
+    let bundle = RulesBundle {
+        keywords: read_keywords(&mut bytes)?,
+        blocked_keywords: read_keywords(&mut bytes)?,
+        required_facets: read_keywords(&mut bytes)?,
+    };
+    if !bytes.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(bundle)
+}
+
+/// Parse comma-separated keywords, dropping empty entries
+pub fn split_keywords(keywords: &str) -> Vec<String> {
+    keywords
+        .split(',')
+        .map(|s| String::from(s.trim()))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn write_keywords(out: &mut Vec<u8>, keywords: &[String]) {
+    write_string(out, &keywords.join(","));
+}
+
+fn read_keywords<S: InputSource>(src: &mut S) -> Result<Vec<String>, DecodeError> {
+    let joined = read_string(src, MAX_KEYWORDS_LEN)?;
+    Ok(split_keywords(&joined))
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u64).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn read_string<S: InputSource>(src: &mut S, max_len: usize) -> Result<String, DecodeError> {
+    let size = read_u64(src)?;
+    if size > max_len as u64 {
+        return Err(DecodeError::TooLarge);
+    }
+    let mut bytes = vec![0u8; size as usize];
+    src.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+fn read_u32<S: InputSource>(src: &mut S) -> Result<u32, DecodeError> {
+    let mut bytes = [0u8; 4];
+    src.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_u64<S: InputSource>(src: &mut S) -> Result<u64, DecodeError> {
+    let mut bytes = [0u8; 8];
+    src.read_exact(&mut bytes)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PublicInput {
+        PublicInput {
+            post_size: 4096,
+            keywords: vec![String::from("bonsol"), String::from("#zk")],
+            blocked_keywords: vec![String::from("scam")],
+            required_facets: vec![String::from("#bonsol")],
+            match_mode: MatchMode::AtLeast(1),
+            match_flags: MATCH_FLAG_WHOLE_WORD,
+            require_authorship: true,
+            verifier: [7u8; 32],
+            engagement: EngagementThresholds {
+                min_likes: 5,
+                min_reposts: 0,
+                min_replies: 1,
+                min_views: 0,
+            },
+            recency: RecencyWindow {
+                not_before_ts: 1_700_000_000,
+                not_after_ts: 0,
+            },
+            required_langs: vec![String::from("en")],
+            content: ContentRequirements {
+                min_chars: 40,
+                min_words: 8,
+            },
+            media: MediaRequirement {
+                min_images: 1,
+                external_domain: String::from("bonsol.sh"),
+            },
+            allowed_link_domains: vec![String::from("bonsol.sh/launch")],
+            reply_to_uri: String::from("at://did:plc:abc/app.bsky.feed.post/1"),
+            author: AuthorRequirements {
+                min_account_age_days: 30,
+                min_followers: 100,
+                as_of_ts: 1_750_000_000,
+            },
+            blocked_labels: vec![String::from("spam"), String::from("porn")],
+            platform: PLATFORM_BLUESKY,
+            required_communities: vec![String::from("solana")],
+            disclosure_tags: vec![String::from("#ad"), String::from("#sponsored")],
+            allowed_author_ids: vec![String::from("did:plc:abc")],
+            segments: vec![SegmentRule {
+                kind: SEGMENT_LANGUAGE,
+                value: String::from("ja"),
+            }],
+            response_signer: [3u8; 32],
+            post_url_hash: [4u8; 32],
+            config: [5u8; 32],
+            response_encoding: ENCODING_GZIP,
+            min_thread_posts: 3,
+            rules_bundle: None,
+        }
+    }
+
+    #[test]
+    fn public_input_round_trips() {
+        let input = sample();
+        let bytes = encode_public_input(&input);
+        let decoded = decode_public_input(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn public_input_layout_is_big_endian_framed() {
+        let bytes = encode_public_input(&sample());
+        assert_eq!(&bytes[0..8], &4096u64.to_be_bytes());
+        assert_eq!(&bytes[8..16], &10u64.to_be_bytes());
+        assert_eq!(&bytes[16..26], b"bonsol,#zk");
+        assert_eq!(&bytes[26..34], &4u64.to_be_bytes());
+        assert_eq!(&bytes[34..38], b"scam");
+        assert_eq!(&bytes[38..46], &7u64.to_be_bytes());
+        assert_eq!(&bytes[46..53], b"#bonsol");
+        assert_eq!(&bytes[53..56], &[MATCH_AT_LEAST, 1, MATCH_FLAG_WHOLE_WORD]);
+        assert_eq!(bytes[56], 1);
+        assert_eq!(&bytes[57..89], &[7u8; 32]);
+        assert_eq!(&bytes[89..97], &5u64.to_be_bytes());
+        assert_eq!(&bytes[105..113], &1u64.to_be_bytes());
+        assert_eq!(&bytes[113..121], &0u64.to_be_bytes());
+        assert_eq!(&bytes[121..129], &1_700_000_000i64.to_be_bytes());
+        assert_eq!(&bytes[129..137], &0i64.to_be_bytes());
+        assert_eq!(&bytes[137..145], &2u64.to_be_bytes());
+        assert_eq!(&bytes[145..147], b"en");
+        assert_eq!(&bytes[147..151], &40u32.to_be_bytes());
+        assert_eq!(&bytes[151..155], &8u32.to_be_bytes());
+        assert_eq!(bytes[155], 1);
+        assert_eq!(&bytes[156..164], &9u64.to_be_bytes());
+        assert_eq!(&bytes[164..173], b"bonsol.sh");
+        assert_eq!(&bytes[173..181], &16u64.to_be_bytes());
+        assert_eq!(&bytes[181..197], b"bonsol.sh/launch");
+        assert_eq!(&bytes[197..205], &37u64.to_be_bytes());
+        assert_eq!(&bytes[205..242], b"at://did:plc:abc/app.bsky.feed.post/1");
+        assert_eq!(&bytes[242..246], &30u32.to_be_bytes());
+        assert_eq!(&bytes[246..254], &100u64.to_be_bytes());
+        assert_eq!(&bytes[254..262], &1_750_000_000i64.to_be_bytes());
+        assert_eq!(&bytes[262..270], &9u64.to_be_bytes());
+        assert_eq!(&bytes[270..279], b"spam,porn");
+        assert_eq!(bytes[279], PLATFORM_BLUESKY);
+        assert_eq!(&bytes[280..288], &6u64.to_be_bytes());
+        assert_eq!(&bytes[288..294], b"solana");
+        assert_eq!(&bytes[294..302], &14u64.to_be_bytes());
+        assert_eq!(&bytes[302..316], b"#ad,#sponsored");
+        assert_eq!(&bytes[316..324], &11u64.to_be_bytes());
+        assert_eq!(&bytes[324..335], b"did:plc:abc");
+        assert_eq!(&bytes[335..337], &[1, SEGMENT_LANGUAGE]);
+        assert_eq!(&bytes[337..345], &2u64.to_be_bytes());
+        assert_eq!(&bytes[345..347], b"ja");
+        assert_eq!(&bytes[347..379], &[3u8; 32]);
+        assert_eq!(&bytes[379..411], &[4u8; 32]);
+        assert_eq!(&bytes[411..443], &[5u8; 32]);
+        assert_eq!(bytes[443], ENCODING_GZIP);
+        assert_eq!(bytes[444], 3);
+        assert_eq!(&bytes[445..], &[0]);
+    }
+
+    #[test]
+    fn unknown_response_encoding_is_rejected() {
+        let mut bytes = encode_public_input(&sample());
+        bytes[443] = ENCODING_DEFLATE + 1;
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn rules_bundle_reference_round_trips() {
+        let input = PublicInput {
+            keywords: Vec::new(),
+            blocked_keywords: Vec::new(),
+            required_facets: Vec::new(),
+            rules_bundle: Some(RulesBundleRef {
+                hash: [9u8; 32],
+                size: 42,
+            }),
+            ..sample()
+        };
+        let bytes = encode_public_input(&input);
+        assert_eq!(bytes[424], 1);
+        assert_eq!(decode_public_input(&mut bytes.as_slice()).unwrap(), input);
+    }
+
+    #[test]
+    fn response_signature_message_is_domain_separated() {
+        let message = response_signature_message(&[7u8; 32], &[8u8; 32]);
+        assert_eq!(&message[..23], b"proof-of-post response:");
+        assert_eq!(&message[23..55], &[7u8; 32]);
+        assert_eq!(&message[55..], &[8u8; 32]);
+        assert_ne!(response_signature_message(&[6u8; 32], &[8u8; 32]), message);
+    }
+
+    #[test]
+    fn too_many_segments_are_rejected() {
+        let segment = SegmentRule {
+            kind: SEGMENT_HANDLE_SUFFIX,
+            value: String::from(".jp"),
+        };
+        let input = PublicInput {
+            segments: vec![segment; MAX_SEGMENTS + 1],
+            ..sample()
+        };
+        let bytes = encode_public_input(&input);
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn rules_bundle_round_trips() {
+        let bundle = RulesBundle {
+            keywords: vec![String::from("bonsol"), String::from("zk")],
+            blocked_keywords: vec![String::from("scam")],
+            required_facets: vec![String::from("@bonsol.bsky.social")],
+        };
+        let bytes = encode_rules_bundle(&bundle);
+        assert_eq!(decode_rules_bundle(&bytes).unwrap(), bundle);
+    }
+
+    #[test]
+    fn rules_bundle_rejects_trailing_bytes() {
+        let mut bytes = encode_rules_bundle(&RulesBundle::default());
+        bytes.push(0);
+        assert_eq!(decode_rules_bundle(&bytes), Err(DecodeError::TrailingBytes));
+    }
+
+    #[test]
+    fn empty_keywords_round_trip() {
+        let input = PublicInput {
+            keywords: Vec::new(),
+            blocked_keywords: Vec::new(),
+            required_facets: Vec::new(),
+            ..sample()
+        };
+        let bytes = encode_public_input(&input);
+        assert_eq!(decode_public_input(&mut bytes.as_slice()).unwrap(), input);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        let bytes = encode_public_input(&sample());
+        let truncated = &bytes[..bytes.len() - 1];
+        assert_eq!(
+            decode_public_input(&mut &truncated[..]),
+            Err(DecodeError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn invalid_match_mode_is_rejected() {
+        let mut bytes = encode_public_input(&sample());
+        bytes[54] = 0;
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::InvalidMatchMode)
+        );
+    }
+
+    #[test]
+    fn unknown_match_flags_are_rejected() {
+        let mut bytes = encode_public_input(&sample());
+        bytes[55] = 0x80;
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::InvalidMatchMode)
+        );
+    }
+
+    #[test]
+    fn engagement_thresholds_check_each_count() {
+        let thresholds = EngagementThresholds {
+            min_likes: 10,
+            min_reposts: 2,
+            min_replies: 0,
+            min_views: 100,
+        };
+        let mut metrics = PostMetrics {
+            like_count: 10,
+            repost_count: 2,
+            reply_count: 0,
+            quote_count: 0,
+            view_count: 100,
+        };
+        assert!(thresholds.is_met(&metrics));
+        metrics.repost_count = 1;
+        assert!(!thresholds.is_met(&metrics));
+        metrics.repost_count = 2;
+        metrics.view_count = 99;
+        assert!(!thresholds.is_met(&metrics));
+        assert!(EngagementThresholds::default().is_met(&PostMetrics::default()));
+    }
+
+    #[test]
+    fn recency_window_bounds_are_inclusive() {
+        let window = RecencyWindow {
+            not_before_ts: 100,
+            not_after_ts: 200,
+        };
+        assert!(window.contains(100));
+        assert!(window.contains(200));
+        assert!(!window.contains(99));
+        assert!(!window.contains(201));
+
+        let open_ended = RecencyWindow {
+            not_before_ts: 100,
+            not_after_ts: 0,
+        };
+        assert!(open_ended.contains(i64::MAX));
+        assert!(RecencyWindow::default().is_unbounded());
+    }
+
+    #[test]
+    fn author_requirements_need_age_and_followers() {
+        let author = AuthorRequirements {
+            min_account_age_days: 30,
+            min_followers: 100,
+            as_of_ts: 100 * 86_400,
+        };
+        assert!(author.is_met(Some(70 * 86_400), Some(100)));
+        assert!(!author.is_met(Some(71 * 86_400), Some(100)));
+        assert!(!author.is_met(Some(0), Some(99)));
+        assert!(!author.is_met(None, Some(100)));
+        assert!(!author.is_met(Some(0), None));
+        assert!(AuthorRequirements::default().is_met(None, None));
+    }
+
+    #[test]
+    fn match_modes_evaluate_counts() {
+        assert!(MatchMode::All.is_satisfied(3, 3));
+        assert!(!MatchMode::All.is_satisfied(2, 3));
+        assert!(MatchMode::Any.is_satisfied(1, 3));
+        assert!(!MatchMode::Any.is_satisfied(0, 3));
+        assert!(MatchMode::AtLeast(2).is_satisfied(2, 3));
+        assert!(!MatchMode::AtLeast(2).is_satisfied(1, 3));
+        assert!(MatchMode::Any.is_satisfied(0, 0));
+    }
+
+    #[test]
+    fn oversized_response_is_rejected() {
+        let mut bytes = encode_public_input(&sample());
+        bytes[..8].copy_from_slice(&(MAX_RESPONSE_LEN + 1).to_be_bytes());
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn oversized_keywords_are_rejected() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0u64.to_be_bytes());
+        bytes.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(
+            decode_public_input(&mut bytes.as_slice()),
+            Err(DecodeError::TooLarge)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Encoding shared between the proof-of-post Anchor program and the RISC Zero guest.
+//!
+//! The program builds the public input with [`encode_public_input`] and decodes the
+//! committed journal with [`PostVerificationOutput::decode`]; the guest does the reverse.
+#![no_std]
+
+extern crate alloc;
+
+mod input;
+mod output;
+
+pub use input::*;
+pub use output::*;
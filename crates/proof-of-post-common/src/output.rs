@@ -0,0 +1,298 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Version of the committed journal layout
+pub const OUTPUT_VERSION: u8 = 13;
+/// Borsh-encoded size of [`PostVerificationOutput`]
+pub const OUTPUT_LEN: usize = 280;
+
+/// Reason codes committed alongside the result
+pub const REASON_OK: u16 = 0;
+pub const REASON_PARSE_ERROR: u16 = 1;
+pub const REASON_NO_POST: u16 = 2;
+pub const REASON_MISSING_KEYWORD: u16 = 3;
+pub const REASON_AUTHORSHIP_MISMATCH: u16 = 4;
+pub const REASON_BLOCKED_KEYWORD: u16 = 5;
+pub const REASON_MISSING_FACET: u16 = 6;
+pub const REASON_INVALID_PATTERN: u16 = 7;
+pub const REASON_RULES_MISMATCH: u16 = 8;
+pub const REASON_LOW_ENGAGEMENT: u16 = 9;
+pub const REASON_OUTSIDE_WINDOW: u16 = 10;
+pub const REASON_LANGUAGE_MISMATCH: u16 = 11;
+pub const REASON_TOO_SHORT: u16 = 12;
+pub const REASON_MISSING_MEDIA: u16 = 13;
+pub const REASON_MISSING_LINK: u16 = 14;
+pub const REASON_NOT_A_REPLY: u16 = 15;
+pub const REASON_AUTHOR_INELIGIBLE: u16 = 16;
+pub const REASON_LABELED: u16 = 17;
+pub const REASON_UNSUPPORTED_PLATFORM: u16 = 18;
+pub const REASON_WRONG_COMMUNITY: u16 = 19;
+pub const REASON_MISSING_DISCLOSURE: u16 = 20;
+pub const REASON_AUTHOR_NOT_ALLOWED: u16 = 21;
+
+/// Bit for `reason_code` in [`PostVerificationOutput::failed_checks`]: reason `n` is bit
+/// `n - 1`, and [`REASON_OK`] has no bit
+pub const fn reason_bit(reason_code: u16) -> u32 {
+    if reason_code == REASON_OK {
+        return 0;
+    }
+    match 1u32.checked_shl(reason_code as u32 - 1) {
+        Some(bit) => bit,
+        None => 0,
+    }
+}
+
+/// Committed engagement ratio when the response has no follower count
+pub const RATIO_UNKNOWN: u32 = u32::MAX;
+/// Committed segment index when the post falls in none of the campaign's segments
+pub const SEGMENT_NONE: u8 = u8::MAX;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PostMetrics {
+    pub like_count: u64,
+    pub repost_count: u64,
+    pub reply_count: u64,
+    pub quote_count: u64,
+    /// Views or impressions, on platforms that report them
+    pub view_count: u64,
+}
+
+/// Journal output committed by the post verification guest after the input digest
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PostVerificationOutput {
+    pub version: u8,
+    /// [`reason_bit`]s of every check the post failed, zero when it passed
+    pub failed_checks: u32,
+    /// The most important failed check, [`REASON_OK`] when none failed
+    pub reason_code: u16,
+    pub authorship_matched: bool,
+    /// Post met the campaign's minimum engagement thresholds
+    pub engagement_met: bool,
+    pub post_uri_hash: [u8; 32],
+    pub author_did_hash: [u8; 32],
+    pub metrics: PostMetrics,
+    /// SHA-256 of the allowlisted link domain the post matched, zero if none
+    pub link_domain_hash: [u8; 32],
+    /// SHA-256 of the AT URI the post replies to, zero if it isn't a reply
+    pub parent_uri_hash: [u8; 32],
+    /// Likes per author follower in basis points, [`RATIO_UNKNOWN`] without a follower
+    /// count. Bought likes show up as implausibly high ratios.
+    pub likes_per_follower_bps: u32,
+    /// SHA-256 of the author's handle, zero if the platform gave none. Handles can be
+    /// transferred, so this complements rather than replaces `author_did_hash`.
+    pub author_handle_hash: [u8; 32],
+    /// Index of the first campaign segment the post falls in, [`SEGMENT_NONE`] if none
+    pub segment: u8,
+    /// The response carried a valid signature from the campaign's response signer. Always
+    /// false when the campaign has none.
+    pub response_authenticated: bool,
+    /// The public input's `post_url_hash`, echoed so the proof can't be replayed for
+    /// another URL. Zero in outputs that failed before the input was decoded.
+    pub post_url_hash: [u8; 32],
+    /// The public input's campaign config address, zero in outputs that failed before
+    /// the input was decoded
+    pub config: [u8; 32],
+    /// In thread mode, posts by the thread's author that satisfy the keyword rules,
+    /// saturating at 255; zero otherwise
+    pub thread_matching_posts: u8,
+}
+
+/// Likes relative to the author's audience, saturating at [`RATIO_UNKNOWN`]
+pub fn likes_per_follower_bps(likes: u64, followers: Option<u64>) -> u32 {
+    match followers {
+        Some(followers) => {
+            let ratio = likes as u128 * 10_000 / followers.max(1) as u128;
+            ratio.min(RATIO_UNKNOWN as u128) as u32
+        }
+        None => RATIO_UNKNOWN,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputError {
+    UnsupportedVersion(u8),
+    Malformed,
+}
+
+impl PostVerificationOutput {
+    pub fn failed(reason_code: u16) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            failed_checks: reason_bit(reason_code),
+            reason_code,
+            authorship_matched: false,
+            engagement_met: false,
+            post_uri_hash: [0u8; 32],
+            author_did_hash: [0u8; 32],
+            metrics: PostMetrics::default(),
+            link_domain_hash: [0u8; 32],
+            parent_uri_hash: [0u8; 32],
+            likes_per_follower_bps: 0,
+            author_handle_hash: [0u8; 32],
+            segment: SEGMENT_NONE,
+            response_authenticated: false,
+            post_url_hash: [0u8; 32],
+            config: [0u8; 32],
+            thread_matching_posts: 0,
+        }
+    }
+
+    /// Whether the post passed every check
+    pub fn passed(&self) -> bool {
+        self.failed_checks == 0
+    }
+
+    /// Strictly decode a committed journal, rejecting unknown versions and trailing bytes
+    pub fn decode(src: &[u8]) -> Result<Self, OutputError> {
+        match src.first() {
+            Some(&OUTPUT_VERSION) => {}
+            Some(&version) => return Err(OutputError::UnsupportedVersion(version)),
+            None => return Err(OutputError::Malformed),
+        }
+        if src.len() != OUTPUT_LEN {
+            return Err(OutputError::Malformed);
+        }
+        borsh::from_slice(src).map_err(|_| OutputError::Malformed)
+    }
+
+    /// Decode a journal laid out as this version's, followed by whatever fields later
+    /// versions append, which are ignored. Rejects older versions.
+    pub fn decode_prefix(src: &[u8]) -> Result<Self, OutputError> {
+        match src.first() {
+            Some(&version) if version < OUTPUT_VERSION => {
+                return Err(OutputError::UnsupportedVersion(version))
+            }
+            Some(_) => {}
+            None => return Err(OutputError::Malformed),
+        }
+        let known = src.get(..OUTPUT_LEN).ok_or(OutputError::Malformed)?;
+        let mut output: Self = borsh::from_slice(known).map_err(|_| OutputError::Malformed)?;
+        // What was decoded is this version's view of the journal
+        output.version = OUTPUT_VERSION;
+        Ok(output)
+    }
+
+    pub fn encode(&self) -> alloc::vec::Vec<u8> {
+        borsh::to_vec(self).expect("journal output serialization")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PostVerificationOutput {
+        PostVerificationOutput {
+            version: OUTPUT_VERSION,
+            failed_checks: 0,
+            reason_code: REASON_OK,
+            authorship_matched: true,
+            engagement_met: true,
+            post_uri_hash: [1u8; 32],
+            author_did_hash: [2u8; 32],
+            metrics: PostMetrics {
+                like_count: 10,
+                repost_count: 2,
+                reply_count: 3,
+                quote_count: 1,
+                view_count: 500,
+            },
+            link_domain_hash: [3u8; 32],
+            parent_uri_hash: [4u8; 32],
+            likes_per_follower_bps: 250,
+            author_handle_hash: [5u8; 32],
+            segment: 1,
+            response_authenticated: true,
+            post_url_hash: [6u8; 32],
+            config: [7u8; 32],
+            thread_matching_posts: 3,
+        }
+    }
+
+    #[test]
+    fn output_round_trips() {
+        let output = sample();
+        let bytes = output.encode();
+        assert_eq!(bytes.len(), OUTPUT_LEN);
+        assert_eq!(PostVerificationOutput::decode(&bytes).unwrap(), output);
+    }
+
+    #[test]
+    fn failed_output_round_trips() {
+        let output = PostVerificationOutput::failed(REASON_PARSE_ERROR);
+        let bytes = output.encode();
+        assert_eq!(PostVerificationOutput::decode(&bytes).unwrap(), output);
+    }
+
+    #[test]
+    fn failed_output_sets_its_reason_bit() {
+        let output = PostVerificationOutput::failed(REASON_PARSE_ERROR);
+        assert_eq!(output.failed_checks, 1);
+        assert!(!output.passed());
+        assert!(sample().passed());
+    }
+
+    #[test]
+    fn reason_bits() {
+        assert_eq!(reason_bit(REASON_OK), 0);
+        assert_eq!(reason_bit(REASON_MISSING_KEYWORD), 1 << 2);
+        assert_eq!(reason_bit(REASON_AUTHOR_NOT_ALLOWED), 1 << 20);
+        assert_eq!(reason_bit(33), 0);
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut bytes = sample().encode();
+        bytes[0] = OUTPUT_VERSION + 1;
+        assert_eq!(
+            PostVerificationOutput::decode(&bytes),
+            Err(OutputError::UnsupportedVersion(OUTPUT_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn prefix_decoding_skips_appended_fields() {
+        let output = sample();
+        let mut bytes = output.encode();
+        assert_eq!(
+            PostVerificationOutput::decode_prefix(&bytes),
+            Ok(output.clone())
+        );
+
+        bytes[0] = OUTPUT_VERSION + 1;
+        bytes.extend_from_slice(&[8u8; 16]);
+        assert_eq!(
+            PostVerificationOutput::decode(&bytes),
+            Err(OutputError::UnsupportedVersion(OUTPUT_VERSION + 1))
+        );
+        assert_eq!(PostVerificationOutput::decode_prefix(&bytes), Ok(output));
+
+        bytes[0] = OUTPUT_VERSION - 1;
+        assert_eq!(
+            PostVerificationOutput::decode_prefix(&bytes),
+            Err(OutputError::UnsupportedVersion(OUTPUT_VERSION - 1))
+        );
+        assert_eq!(
+            PostVerificationOutput::decode_prefix(&sample().encode()[..OUTPUT_LEN - 1]),
+            Err(OutputError::Malformed)
+        );
+    }
+
+    #[test]
+    fn likes_per_follower_ratio() {
+        assert_eq!(likes_per_follower_bps(50, Some(1_000)), 500);
+        assert_eq!(likes_per_follower_bps(0, Some(0)), 0);
+        assert_eq!(likes_per_follower_bps(3, Some(0)), 30_000);
+        assert_eq!(likes_per_follower_bps(u64::MAX, Some(1)), RATIO_UNKNOWN);
+        assert_eq!(likes_per_follower_bps(50, None), RATIO_UNKNOWN);
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let mut bytes = sample().encode();
+        bytes.push(0);
+        assert_eq!(
+            PostVerificationOutput::decode(&bytes),
+            Err(OutputError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Exports the program's PDA seeds, discriminators and input codec samples as JSON for
+//! the TypeScript tests, so they read them from the Rust source of truth instead of
+//! keeping hand-written copies.
+//!
+//! `cargo run -p proof-of-post-fixtures -- tests/fixtures/proof-of-post.json`
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::Discriminator;
+use proof_of_post::{
+    derive_request_id, instruction, AuthorClaim, CampaignRegistry, CampaignSummary,
+    ExecutionTracker, PostProofConfig, PostVerificationLog, ProtocolConfig, AUTHOR_CLAIM_SEED,
+    CAMPAIGN_REGISTRY_SEED, CAMPAIGN_SUMMARY_SEED, CONFIG_SEED, PROTOCOL_CONFIG_SEED,
+    REQUEST_ID_DOMAIN, TERMS_MESSAGE_PREFIX, VERIFICATION_LOG_SEED,
+};
+use proof_of_post_common::*;
+use serde_json::{json, Value};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn text(bytes: &[u8]) -> &str {
+    std::str::from_utf8(bytes).expect("seeds and prefixes are ASCII")
+}
+
+fn seeds() -> Value {
+    json!({
+        "postProofConfig": text(CONFIG_SEED),
+        "postVerificationLog": text(VERIFICATION_LOG_SEED),
+        "authorClaim": text(AUTHOR_CLAIM_SEED),
+        "protocolConfig": text(PROTOCOL_CONFIG_SEED),
+        "campaignRegistry": text(CAMPAIGN_REGISTRY_SEED),
+        "campaignSummary": text(CAMPAIGN_SUMMARY_SEED),
+    })
+}
+
+fn discriminators() -> Value {
+    json!({
+        "instructions": {
+            "createConfig": instruction::CreateConfig::DISCRIMINATOR,
+            "updateConfig": instruction::UpdateConfig::DISCRIMINATOR,
+            "verifyPost": instruction::VerifyPost::DISCRIMINATOR,
+            "checkEligibility": instruction::CheckEligibility::DISCRIMINATOR,
+            "bonsolCallback": instruction::BonsolCallback::DISCRIMINATOR,
+            "bonsolCallbackV2": instruction::BonsolCallbackV2::DISCRIMINATOR,
+            "initProtocol": instruction::InitProtocol::DISCRIMINATOR,
+            "updateProtocol": instruction::UpdateProtocol::DISCRIMINATOR,
+            "resetCircuitBreaker": instruction::ResetCircuitBreaker::DISCRIMINATOR,
+            "publishCampaignSummary": instruction::PublishCampaignSummary::DISCRIMINATOR,
+            "closeConfig": instruction::CloseConfig::DISCRIMINATOR,
+        },
+        "accounts": {
+            "postProofConfig": PostProofConfig::DISCRIMINATOR,
+            "postVerificationLog": PostVerificationLog::DISCRIMINATOR,
+            "authorClaim": AuthorClaim::DISCRIMINATOR,
+            "protocolConfig": ProtocolConfig::DISCRIMINATOR,
+            "executionTracker": ExecutionTracker::DISCRIMINATOR,
+            "campaignRegistry": CampaignRegistry::DISCRIMINATOR,
+            "campaignSummary": CampaignSummary::DISCRIMINATOR,
+        },
+    })
+}
+
+/// Addresses derived from fixed inputs, which the tests re-derive with their own helpers
+fn pdas() -> Value {
+    let creator = Pubkey::new_from_array([1u8; 32]);
+    let verifier = Pubkey::new_from_array([2u8; 32]);
+    let campaign = "campaign-v1";
+    let author_did = "did:plc:abc";
+    let author_did_hash = hash(author_did.as_bytes()).to_bytes();
+
+    let derive = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &proof_of_post::ID).0;
+    let config = derive(&[CONFIG_SEED, creator.as_ref(), campaign.as_bytes()]);
+    json!({
+        "creator": creator.to_string(),
+        "verifier": verifier.to_string(),
+        "seeds": campaign,
+        "authorDid": author_did,
+        "postProofConfig": config.to_string(),
+        "postVerificationLog":
+            derive(&[VERIFICATION_LOG_SEED, verifier.as_ref(), config.as_ref()]).to_string(),
+        "authorClaim":
+            derive(&[AUTHOR_CLAIM_SEED, config.as_ref(), &author_did_hash]).to_string(),
+        "protocolConfig": derive(&[PROTOCOL_CONFIG_SEED]).to_string(),
+    })
+}
+
+fn codec() -> Value {
+    let verifier = Pubkey::new_from_array([2u8; 32]);
+    let config = Pubkey::new_from_array([3u8; 32]);
+    let post_url = "https://bsky.app/profile/bonsol.bsky.social/post/1";
+    let nonce = 42u64;
+    let bundle = RulesBundle {
+        keywords: vec![String::from("bonsol"), String::from("#zk")],
+        blocked_keywords: vec![String::from("scam")],
+        required_facets: vec![String::from("@bonsol.bsky.social")],
+    };
+    json!({
+        "rulesBundle": {
+            "keywords": bundle.keywords,
+            "blockedKeywords": bundle.blocked_keywords,
+            "requiredFacets": bundle.required_facets,
+            "hex": hex(&encode_rules_bundle(&bundle)),
+        },
+        "requestId": {
+            "verifier": verifier.to_string(),
+            "config": config.to_string(),
+            "postUrl": post_url,
+            "nonce": nonce,
+            "id": derive_request_id(
+                &verifier,
+                &config,
+                &hash(post_url.as_bytes()).to_bytes(),
+                nonce,
+            ),
+        },
+        "termsMessagePrefix": text(TERMS_MESSAGE_PREFIX),
+        "requestIdDomain": text(REQUEST_ID_DOMAIN),
+        "responseSignaturePrefix": text(RESPONSE_SIGNATURE_PREFIX),
+        "outputVersion": OUTPUT_VERSION,
+        "outputLen": OUTPUT_LEN,
+        "platforms": {
+            "bluesky": PLATFORM_BLUESKY,
+            "twitter": PLATFORM_TWITTER,
+            "mastodon": PLATFORM_MASTODON,
+            "farcaster": PLATFORM_FARCASTER,
+            "lens": PLATFORM_LENS,
+            "reddit": PLATFORM_REDDIT,
+            "youtube": PLATFORM_YOUTUBE,
+        },
+        "encodings": {
+            "identity": ENCODING_IDENTITY,
+            "gzip": ENCODING_GZIP,
+            "deflate": ENCODING_DEFLATE,
+        },
+        "matchFlags": {
+            "wholeWord": MATCH_FLAG_WHOLE_WORD,
+            "exactPhrase": MATCH_FLAG_EXACT_PHRASE,
+            "caseSensitive": MATCH_FLAG_CASE_SENSITIVE,
+            "regex": MATCH_FLAG_REGEX,
+            "foldDiacritics": MATCH_FLAG_FOLD_DIACRITICS,
+        },
+    })
+}
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("usage: proof-of-post-fixtures <output.json>");
+    let fixtures = json!({
+        "programId": proof_of_post::ID.to_string(),
+        "seeds": seeds(),
+        "discriminators": discriminators(),
+        "pdas": pdas(),
+        "codec": codec(),
+    });
+    let mut out = serde_json::to_string_pretty(&fixtures).expect("fixtures serialize");
+    out.push('\n');
+    if let Some(dir) = std::path::Path::new(&path).parent() {
+        std::fs::create_dir_all(dir).expect("create fixtures directory");
+    }
+    std::fs::write(&path, out).expect("write fixtures");
+}
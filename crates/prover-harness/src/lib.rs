@@ -0,0 +1,284 @@
+//! Runs the post verification guest on the host in RISC Zero dev mode. Inputs are
+//! framed exactly as Bonsol feeds them, and the journal is decoded the way the program
+//! decodes it, so guest changes can be checked and user-reported false negatives
+//! reproduced without a devnet round trip.
+//!
+//! Build the guest first (`bonsol build --zk-program-path zk-program/post_verification`);
+//! [`Harness::from_env`] picks the image up from there or from `POST_VERIFICATION_ELF`.
+//!
+//! [`compare_cycles`] executes two images on the fixture corpus and reports the cycles
+//! each takes, to measure what a guest change costs or saves.
+
+use proof_of_post_common::{
+    encode_public_input, encode_rules_bundle, AuthorRequirements, ContentRequirements,
+    EngagementThresholds, MatchMode, MediaRequirement, OutputError, PostVerificationOutput,
+    PublicInput, RecencyWindow, RulesBundle, RulesBundleRef, ENCODING_IDENTITY,
+};
+use risc0_zkvm::{default_executor, default_prover, ExecutorEnv};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Where `bonsol build` leaves the guest image, relative to the repository root
+pub const DEFAULT_ELF_PATH: &str =
+    "zk-program/post_verification/target/riscv32im-risc0-zkvm-elf/docker/post_verification.bin";
+/// The Bluesky API responses the core's tests run on, relative to the repository root
+pub const FIXTURES_PATH: &str = "crates/post-verification-core/tests/fixtures/bluesky";
+
+#[derive(Debug)]
+pub enum HarnessError {
+    /// The guest image or a fixture couldn't be read
+    Load(PathBuf, std::io::Error),
+    /// Execution failed, e.g. the guest panicked
+    Prove(String),
+    /// The journal isn't an input digest followed by a decodable output
+    Journal(OutputError),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Load(path, e) => write!(f, "reading {}: {e}", path.display()),
+            Self::Prove(e) => write!(f, "guest execution failed: {e}"),
+            Self::Journal(e) => write!(f, "malformed journal: {e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+/// A campaign on `platform` requiring all of `keywords`, with every other check off.
+/// Tests and repros switch on the checks they need.
+pub fn open_campaign(platform: u8, keywords: &[&str]) -> PublicInput {
+    PublicInput {
+        post_size: 0,
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        blocked_keywords: Vec::new(),
+        required_facets: Vec::new(),
+        match_mode: MatchMode::All,
+        match_flags: 0,
+        require_authorship: false,
+        verifier: [0u8; 32],
+        engagement: EngagementThresholds::default(),
+        recency: RecencyWindow::default(),
+        required_langs: Vec::new(),
+        content: ContentRequirements::default(),
+        media: MediaRequirement::default(),
+        allowed_link_domains: Vec::new(),
+        reply_to_uri: String::new(),
+        author: AuthorRequirements::default(),
+        blocked_labels: Vec::new(),
+        platform,
+        required_communities: Vec::new(),
+        disclosure_tags: Vec::new(),
+        allowed_author_ids: Vec::new(),
+        segments: Vec::new(),
+        response_signer: [0u8; 32],
+        post_url_hash: [0u8; 32],
+        config: [0u8; 32],
+        response_encoding: ENCODING_IDENTITY,
+        min_thread_posts: 0,
+        rules_bundle: None,
+    }
+}
+
+/// One verification request: the campaign's public input plus the private inputs the
+/// prover supplies, in the order the guest reads them
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub input: PublicInput,
+    /// Rule bundle served at the campaign's `metadata_uri`; [`Request::stdin`] points the
+    /// public input at it
+    pub rules_bundle: Option<RulesBundle>,
+    /// The response signer's signature, for campaigns that have one
+    pub response_signature: Option<[u8; 64]>,
+    /// The platform API response as fetched, still encoded per `input.response_encoding`
+    pub response: Vec<u8>,
+}
+
+impl Request {
+    pub fn new(input: PublicInput, response: impl Into<Vec<u8>>) -> Self {
+        Self {
+            input,
+            rules_bundle: None,
+            response_signature: None,
+            response: response.into(),
+        }
+    }
+
+    pub fn with_rules_bundle(mut self, bundle: RulesBundle) -> Self {
+        self.rules_bundle = Some(bundle);
+        self
+    }
+
+    pub fn with_response_signature(mut self, signature: [u8; 64]) -> Self {
+        self.response_signature = Some(signature);
+        self
+    }
+
+    /// The guest's stdin: public input, rule bundle, response signature, response.
+    /// `post_size` and the rule bundle reference are filled in from the inputs, as the
+    /// program does on-chain.
+    pub fn stdin(&self) -> Vec<u8> {
+        let mut input = self.input.clone();
+        input.post_size = self.response.len() as u64;
+        let bundle = self.rules_bundle.as_ref().map(encode_rules_bundle);
+        if let Some(bundle) = &bundle {
+            input.keywords = Vec::new();
+            input.blocked_keywords = Vec::new();
+            input.required_facets = Vec::new();
+            input.rules_bundle = Some(RulesBundleRef {
+                hash: sha256(bundle),
+                size: bundle.len() as u64,
+            });
+        }
+
+        let mut stdin = encode_public_input(&input);
+        if let Some(bundle) = &bundle {
+            stdin.extend_from_slice(bundle);
+        }
+        if let Some(signature) = &self.response_signature {
+            stdin.extend_from_slice(signature);
+        }
+        stdin.extend_from_slice(&self.response);
+        stdin
+    }
+}
+
+/// What the guest committed
+#[derive(Debug, Clone)]
+pub struct Journal {
+    /// SHA-256 over every input byte, which the program compares with Bonsol's
+    /// `input_hash`
+    pub input_digest: [u8; 32],
+    pub output: PostVerificationOutput,
+}
+
+impl Journal {
+    pub fn decode(bytes: &[u8]) -> Result<Self, OutputError> {
+        if bytes.len() < 32 {
+            return Err(OutputError::Malformed);
+        }
+        let (digest, output) = bytes.split_at(32);
+        Ok(Self {
+            input_digest: digest.try_into().expect("split at 32"),
+            output: PostVerificationOutput::decode(output)?,
+        })
+    }
+}
+
+pub struct Harness {
+    elf: Vec<u8>,
+}
+
+impl Harness {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HarnessError> {
+        let path = path.as_ref();
+        let elf = std::fs::read(path).map_err(|e| HarnessError::Load(path.to_owned(), e))?;
+        Ok(Self { elf })
+    }
+
+    /// The image at `POST_VERIFICATION_ELF`, or at [`DEFAULT_ELF_PATH`] in this checkout
+    pub fn from_env() -> Result<Self, HarnessError> {
+        Self::load(elf_path())
+    }
+
+    /// Runs the guest on `request` in dev mode: the guest executes in full, but the
+    /// receipt is a fake one that only dev-mode verifiers accept
+    pub fn run(&self, request: &Request) -> Result<Journal, HarnessError> {
+        // Dev mode is read from the environment by the default prover. Set it here so a
+        // forgotten variable can't turn a quick test run into real proving.
+        std::env::set_var("RISC0_DEV_MODE", "1");
+        let env = ExecutorEnv::builder()
+            .write_slice(&request.stdin())
+            .build()
+            .map_err(|e| HarnessError::Prove(e.to_string()))?;
+        let receipt = default_prover()
+            .prove(env, &self.elf)
+            .map_err(|e| HarnessError::Prove(e.to_string()))?
+            .receipt;
+        Journal::decode(&receipt.journal.bytes).map_err(HarnessError::Journal)
+    }
+
+    /// Executes the guest on `request` without proving, returning the cycles it took
+    pub fn cycles(&self, request: &Request) -> Result<u64, HarnessError> {
+        let env = ExecutorEnv::builder()
+            .write_slice(&request.stdin())
+            .build()
+            .map_err(|e| HarnessError::Prove(e.to_string()))?;
+        let session = default_executor()
+            .execute(env, &self.elf)
+            .map_err(|e| HarnessError::Prove(e.to_string()))?;
+        Ok(session.cycles())
+    }
+}
+
+/// Cycles two guest images took on the same fixture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleCount {
+    pub fixture: String,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+impl CycleCount {
+    /// Cycles `current` saves over `baseline`, negative if it takes more
+    pub fn saved(&self) -> i64 {
+        self.baseline as i64 - self.current as i64
+    }
+}
+
+/// Every fixture in [`FIXTURES_PATH`], by file stem, sorted
+pub fn fixture_corpus() -> Result<Vec<(String, Vec<u8>)>, HarnessError> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(FIXTURES_PATH);
+    let entries = std::fs::read_dir(&dir).map_err(|e| HarnessError::Load(dir.clone(), e))?;
+    let mut corpus = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| HarnessError::Load(dir.clone(), e))?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            let response = std::fs::read(&path).map_err(|e| HarnessError::Load(path.clone(), e))?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            corpus.push((name.into_owned(), response));
+        }
+    }
+    corpus.sort();
+    Ok(corpus)
+}
+
+/// Executes `baseline` and `current` on every fixture of the corpus, each against a
+/// campaign requiring `keywords`
+pub fn compare_cycles(
+    baseline: &Harness,
+    current: &Harness,
+    platform: u8,
+    keywords: &[&str],
+) -> Result<Vec<CycleCount>, HarnessError> {
+    fixture_corpus()?
+        .into_iter()
+        .map(|(fixture, response)| {
+            let request = Request::new(open_campaign(platform, keywords), response);
+            Ok(CycleCount {
+                fixture,
+                baseline: baseline.cycles(&request)?,
+                current: current.cycles(&request)?,
+            })
+        })
+        .collect()
+}
+
+/// `POST_VERIFICATION_ELF` when set, otherwise [`DEFAULT_ELF_PATH`] in this checkout
+pub fn elf_path() -> PathBuf {
+    std::env::var_os("POST_VERIFICATION_ELF")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../..")
+                .join(DEFAULT_ELF_PATH)
+        })
+}
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
@@ -0,0 +1,76 @@
+//! Reproduces a verification locally: runs the guest in dev mode on a saved API
+//! response against a campaign requiring the given keywords, and prints the journal.
+//!
+//! `cargo run -p prover-harness -- post.json bonsol "#zk"`
+//!
+//! With `--compare-cycles`, executes an older guest image and the current one on the
+//! fixture corpus instead and prints the cycles each took, e.g. with the image built
+//! before a guest change as the baseline:
+//!
+//! `cargo run -p prover-harness -- --compare-cycles baseline.bin bonsol`
+
+use proof_of_post_common::PLATFORM_BLUESKY;
+use prover_harness::{compare_cycles, open_campaign, Harness, Request};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().expect(
+        "usage: prover-harness <response.json> [keyword...]\n       \
+         prover-harness --compare-cycles <baseline.bin> [keyword...]",
+    );
+    if path == "--compare-cycles" {
+        let baseline = args
+            .next()
+            .expect("--compare-cycles needs the baseline image");
+        return print_cycles(&baseline, &args.collect::<Vec<_>>());
+    }
+    let keywords: Vec<String> = args.collect();
+    let keywords: Vec<&str> = keywords.iter().map(String::as_str).collect();
+
+    let response = std::fs::read(&path).unwrap_or_else(|e| panic!("reading {path}: {e}"));
+    let request = Request::new(open_campaign(PLATFORM_BLUESKY, &keywords), response);
+    let harness = Harness::from_env().unwrap_or_else(|e| panic!("{e}"));
+    match harness.run(&request) {
+        Ok(journal) => println!("{:#?}", journal.output),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_cycles(baseline: &str, keywords: &[String]) {
+    let keywords: Vec<&str> = keywords.iter().map(String::as_str).collect();
+    let baseline = Harness::load(baseline).unwrap_or_else(|e| panic!("{e}"));
+    let current = Harness::from_env().unwrap_or_else(|e| panic!("{e}"));
+    let counts =
+        compare_cycles(&baseline, &current, PLATFORM_BLUESKY, &keywords).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+    println!(
+        "{:<24} {:>12} {:>12} {:>10}",
+        "fixture", "baseline", "current", "saved"
+    );
+    for count in &counts {
+        println!(
+            "{:<24} {:>12} {:>12} {:>10}",
+            count.fixture,
+            count.baseline,
+            count.current,
+            count.saved()
+        );
+    }
+    let baseline: u64 = counts.iter().map(|count| count.baseline).sum();
+    let current: u64 = counts.iter().map(|count| count.current).sum();
+    let saved = baseline as i64 - current as i64;
+    let percent = if baseline == 0 {
+        0.0
+    } else {
+        saved as f64 * 100.0 / baseline as f64
+    };
+    println!(
+        "{:<24} {baseline:>12} {current:>12} {saved:>10} ({percent:.2}%)",
+        "total"
+    );
+}
@@ -0,0 +1,89 @@
+//! The built guest against the host-side expectations. Needs the guest image (see the
+//! crate docs), so these run with `cargo test -p prover-harness -- --ignored`.
+
+use proof_of_post_common::*;
+use prover_harness::{compare_cycles, fixture_corpus, open_campaign, Harness, Request};
+use sha2::{Digest, Sha256};
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!(
+        "{}/../post-verification-core/tests/fixtures/bluesky/{name}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read(&path).unwrap_or_else(|e| panic!("{path}: {e}"))
+}
+
+fn harness() -> Harness {
+    Harness::from_env().unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[test]
+#[ignore = "needs the guest image"]
+fn matching_post_passes_and_commits_the_input_digest() {
+    let request = Request::new(
+        open_campaign(PLATFORM_BLUESKY, &["bonsol"]),
+        fixture("hashtag_facets"),
+    );
+    let journal = harness().run(&request).unwrap();
+    assert_eq!(journal.output.reason_code, REASON_OK);
+    assert_eq!(journal.output.version, OUTPUT_VERSION);
+    assert_eq!(
+        journal.input_digest,
+        <[u8; 32]>::from(Sha256::digest(request.stdin()))
+    );
+}
+
+#[test]
+#[ignore = "needs the guest image"]
+fn failures_are_committed_not_panicked() {
+    let harness = harness();
+    let input = open_campaign(PLATFORM_BLUESKY, &["mainnet"]);
+    let journal = harness
+        .run(&Request::new(input.clone(), fixture("plain_text")))
+        .unwrap();
+    assert_eq!(journal.output.reason_code, REASON_MISSING_KEYWORD);
+
+    let journal = harness
+        .run(&Request::new(input.clone(), fixture("not_found")))
+        .unwrap();
+    assert_eq!(journal.output.reason_code, REASON_NO_POST);
+
+    let journal = harness
+        .run(&Request::new(input, &b"<html>rate limited</html>"[..]))
+        .unwrap();
+    assert_eq!(journal.output.reason_code, REASON_PARSE_ERROR);
+}
+
+#[test]
+#[ignore = "needs the guest image"]
+fn rules_bundle_replaces_inline_keywords() {
+    let request = Request::new(open_campaign(PLATFORM_BLUESKY, &[]), fixture("emoji"))
+        .with_rules_bundle(RulesBundle {
+            keywords: vec![String::from("mainnet")],
+            blocked_keywords: Vec::new(),
+            required_facets: vec![String::from("#zk")],
+        });
+    let journal = harness().run(&request).unwrap();
+    assert_eq!(journal.output.reason_code, REASON_OK);
+
+    let request = request.with_rules_bundle(RulesBundle {
+        keywords: vec![String::from("testnet")],
+        ..Default::default()
+    });
+    let journal = harness().run(&request).unwrap();
+    assert_eq!(journal.output.reason_code, REASON_MISSING_KEYWORD);
+}
+
+#[test]
+#[ignore = "needs the guest image"]
+fn cycle_comparison_covers_the_corpus() {
+    let harness = harness();
+    let counts = compare_cycles(&harness, &harness, PLATFORM_BLUESKY, &["bonsol"]).unwrap();
+    assert_eq!(counts.len(), fixture_corpus().unwrap().len());
+    assert!(counts.iter().any(|count| count.fixture == "hashtag_facets"));
+    for count in &counts {
+        assert!(count.current > 0, "{} took no cycles", count.fixture);
+        // Execution is deterministic, so the same image takes the same cycles
+        assert_eq!(count.saved(), 0, "{}", count.fixture);
+    }
+}
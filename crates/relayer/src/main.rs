@@ -0,0 +1,365 @@
+//! Submits verify_post requests for verifiers without SOL. The verifier signs an intent
+//! off-chain; the relayer sends it through sponsored_verify, fronting the transaction
+//! fees, the rent and the prover tip, and the program reimburses it from the campaign's
+//! sponsorship budget in the same transaction.
+//!
+//! `relayer --keypair relayer.json --listen 0.0.0.0:8090`
+//!
+//! - `POST /intents` with `{"campaign", "verifier", "args", "expires_at", "signature"}`
+//! - `GET /health`, the relayer's address and balance
+//!
+//! `args` is the base64 Borsh encoding of `VerifyPostArgs` and `signature` the
+//! verifier's base58 signature over `proof_of_post::sponsored_verify_message(campaign,
+//! args, expires_at)`. When the campaign has terms, `args.terms_signature` must be set as
+//! for verify_post. A submitted intent returns the transaction's `signature`.
+//!
+//! Intents are checked before anything is sent, and transactions are simulated first,
+//! so a bad intent costs the relayer nothing.
+//!
+//! Each intent is logged in a span carrying its `request_id`, `config` and, once sent,
+//! `signature`; `RUST_LOG` sets the verbosity, `info` by default.
+
+use anchor_lang::AnchorDeserialize;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::Parser;
+use proof_of_post::{
+    sponsored_verify_message, terms_message, SponsoredVerifyArgs, VerifyPostArgs,
+    POST_VERIFICATION_IMAGE_ID,
+};
+use proof_of_post_client::{instructions, CampaignClient, PopError};
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction_with_signature;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{display, Empty};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(name = "relayer", version, about)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        env = "POP_RPC_URL",
+        default_value = "https://api.devnet.solana.com"
+    )]
+    url: String,
+    /// Keypair that pays for and signs the transactions
+    #[arg(long, env = "POP_RELAYER_KEYPAIR")]
+    keypair: String,
+    #[arg(long, env = "POP_PROGRAM_ID", default_value_t = proof_of_post::ID)]
+    program_id: Pubkey,
+    /// Bonsol image ID of the post verification guest
+    #[arg(long, env = "POP_IMAGE_ID", default_value = POST_VERIFICATION_IMAGE_ID)]
+    image_id: String,
+    #[arg(long, env = "POP_RELAYER_LISTEN", default_value = "127.0.0.1:8090")]
+    listen: String,
+    /// Largest prover tip the relayer fronts, in lamports
+    #[arg(long, env = "POP_RELAYER_MAX_TIP", default_value_t = 10_000_000)]
+    max_tip: u64,
+}
+
+struct Relayer {
+    client: CampaignClient,
+    keypair: Keypair,
+    image_id: String,
+    max_tip: u64,
+}
+
+impl Relayer {
+    async fn send(&self, instructions: &[Instruction]) -> Result<Signature, PopError> {
+        let rpc = self.client.rpc();
+        let blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.keypair.pubkey()),
+            &[&self.keypair],
+            blockhash,
+        );
+        Ok(rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+}
+
+#[derive(Debug)]
+enum RelayError {
+    BadRequest(String),
+    /// The intent's signature isn't the verifier's
+    Unauthorized,
+    /// A valid intent the relayer won't pay for
+    Refused(String),
+    /// The transaction failed, in simulation or on-chain
+    Failed(PopError),
+}
+
+impl From<PopError> for RelayError {
+    fn from(e: PopError) -> Self {
+        Self::Failed(e)
+    }
+}
+
+impl IntoResponse for RelayError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, String::from("bad signature")),
+            Self::Refused(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
+            Self::Failed(e) => {
+                tracing::warn!(error = %e, "relaying failed");
+                (StatusCode::BAD_GATEWAY, e.to_string())
+            }
+        };
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct Intent {
+    campaign: String,
+    verifier: String,
+    args: String,
+    expires_at: i64,
+    signature: String,
+}
+
+/// An intent whose signature checks out
+struct SignedIntent {
+    campaign: Pubkey,
+    verifier: Pubkey,
+    args: VerifyPostArgs,
+    expires_at: i64,
+    message: Vec<u8>,
+    signature: [u8; 64],
+}
+
+impl Intent {
+    /// Decodes the intent and checks it is the verifier's, unexpired at unix time `now`
+    /// and tipping at most `max_tip`, all without touching the chain
+    fn check(self, now: i64, max_tip: u64) -> Result<SignedIntent, RelayError> {
+        let pubkey = |value: &str| {
+            value
+                .parse::<Pubkey>()
+                .map_err(|_| RelayError::BadRequest(format!("invalid address {value:?}")))
+        };
+        let campaign = pubkey(&self.campaign)?;
+        let verifier = pubkey(&self.verifier)?;
+        let args = BASE64
+            .decode(&self.args)
+            .ok()
+            .and_then(|bytes| VerifyPostArgs::try_from_slice(&bytes).ok())
+            .ok_or_else(|| RelayError::BadRequest(String::from("invalid args")))?;
+        let signature: Signature = self
+            .signature
+            .parse()
+            .map_err(|_| RelayError::BadRequest(String::from("invalid signature")))?;
+
+        if self.expires_at < now {
+            return Err(RelayError::BadRequest(String::from("intent expired")));
+        }
+        let message = sponsored_verify_message(&campaign, &args, self.expires_at);
+        if !signature.verify(verifier.as_ref(), &message) {
+            return Err(RelayError::Unauthorized);
+        }
+        if args.tip > max_tip {
+            return Err(RelayError::Refused(format!(
+                "tip above the relayer's maximum of {max_tip} lamports"
+            )));
+        }
+        Ok(SignedIntent {
+            campaign,
+            verifier,
+            args,
+            expires_at: self.expires_at,
+            message,
+            signature: signature
+                .as_ref()
+                .try_into()
+                .expect("signatures are 64 bytes"),
+        })
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+#[tracing::instrument(
+    skip_all,
+    fields(request_id = Empty, config = Empty, verifier = Empty, signature = Empty)
+)]
+async fn submit(
+    State(relayer): State<Arc<Relayer>>,
+    Json(intent): Json<Intent>,
+) -> Result<Json<serde_json::Value>, RelayError> {
+    let intent = intent.check(now(), relayer.max_tip)?;
+    let span = tracing::Span::current();
+    span.record("request_id", intent.args.current_req_id.as_str());
+    span.record("config", display(intent.campaign));
+    span.record("verifier", display(intent.verifier));
+    let config = relayer
+        .client
+        .get_campaign(&intent.campaign)
+        .await
+        .map_err(|e| RelayError::Refused(format!("campaign {}: {e}", intent.campaign)))?;
+    if config.sponsorship_budget == 0 {
+        return Err(RelayError::Refused(String::from(
+            "campaign has no sponsorship budget",
+        )));
+    }
+
+    // The program finds both signature checks among the instructions before its own
+    let mut ixs = vec![new_ed25519_instruction_with_signature(
+        &intent.message,
+        &intent.signature,
+        &intent.verifier.to_bytes(),
+    )];
+    if let Some(terms_signature) = intent.args.terms_signature {
+        ixs.push(new_ed25519_instruction_with_signature(
+            &terms_message(&intent.campaign, &config.terms_hash),
+            &terms_signature,
+            &intent.verifier.to_bytes(),
+        ));
+    }
+    ixs.push(instructions::sponsored_verify(
+        relayer.client.program_id(),
+        &relayer.image_id,
+        &relayer.keypair.pubkey(),
+        &intent.verifier,
+        &intent.campaign,
+        &config,
+        SponsoredVerifyArgs {
+            verify: intent.args,
+            expires_at: intent.expires_at,
+            intent_signature: intent.signature,
+        },
+    ));
+    let signature = relayer.send(&ixs).await?;
+    span.record("signature", display(signature));
+    tracing::info!("relayed");
+    Ok(Json(
+        serde_json::json!({ "signature": signature.to_string() }),
+    ))
+}
+
+async fn health(
+    State(relayer): State<Arc<Relayer>>,
+) -> Result<Json<serde_json::Value>, RelayError> {
+    let address = relayer.keypair.pubkey();
+    let balance = relayer
+        .client
+        .rpc()
+        .get_balance(&address)
+        .await
+        .map_err(PopError::from)?;
+    Ok(Json(serde_json::json!({
+        "relayer": address.to_string(),
+        "balance": balance,
+    })))
+}
+
+fn router(relayer: Arc<Relayer>) -> Router {
+    Router::new()
+        .route("/intents", post(submit))
+        .route("/health", get(health))
+        .with_state(relayer)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    let keypair = read_keypair_file(&cli.keypair)
+        .map_err(|e| format!("reading keypair {}: {e}", cli.keypair))?;
+    let rpc = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
+    let relayer = Relayer {
+        client: CampaignClient::with_program_id(rpc, cli.program_id),
+        keypair,
+        image_id: cli.image_id,
+        max_tip: cli.max_tip,
+    };
+    let listener = tokio::net::TcpListener::bind(&cli.listen).await?;
+    tracing::info!(relayer = %relayer.keypair.pubkey(), listen = %cli.listen, "relaying");
+    axum::serve(listener, router(Arc::new(relayer))).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    fn args(tip: u64) -> VerifyPostArgs {
+        VerifyPostArgs {
+            current_req_id: String::from("aBcDeFgHiJkLmNoP"),
+            post_url: String::from("https://bsky.app/profile/alice.bsky.social/post/3k"),
+            post_size: 2_048,
+            tip,
+            referrer: None,
+            input_hash: None,
+            payout: None,
+            author_did_hash: [7; 32],
+            terms_signature: None,
+            response_signature: None,
+            response_encoding: 0,
+            request_nonce: None,
+        }
+    }
+
+    fn intent(signer: &Keypair, verifier: &Pubkey, args: &VerifyPostArgs) -> Intent {
+        let campaign = Pubkey::new_unique();
+        let message = sponsored_verify_message(&campaign, args, 1_000);
+        let mut encoded = Vec::new();
+        args.serialize(&mut encoded).unwrap();
+        Intent {
+            campaign: campaign.to_string(),
+            verifier: verifier.to_string(),
+            args: BASE64.encode(encoded),
+            expires_at: 1_000,
+            signature: signer.sign_message(&message).to_string(),
+        }
+    }
+
+    #[test]
+    fn intents_must_be_the_verifiers_and_fresh() {
+        let verifier = Keypair::new();
+        let signed = intent(&verifier, &verifier.pubkey(), &args(5_000))
+            .check(900, 10_000)
+            .unwrap();
+        assert_eq!(signed.verifier, verifier.pubkey());
+        assert_eq!(signed.args.tip, 5_000);
+
+        let forged = intent(&Keypair::new(), &verifier.pubkey(), &args(5_000));
+        assert!(matches!(
+            forged.check(900, 10_000),
+            Err(RelayError::Unauthorized)
+        ));
+        let expired = intent(&verifier, &verifier.pubkey(), &args(5_000));
+        assert!(matches!(
+            expired.check(1_001, 10_000),
+            Err(RelayError::BadRequest(_))
+        ));
+        let greedy = intent(&verifier, &verifier.pubkey(), &args(50_000));
+        assert!(matches!(
+            greedy.check(900, 10_000),
+            Err(RelayError::Refused(_))
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+//! Stands in for Bonsol on a local validator, loaded at Bonsol's program ID. It accepts
+//! `execute_v1` requests the way Bonsol does, storing the execution request in the
+//! account Bonsol would, and delivers a result to the request's callback on demand,
+//! signed by the execution request account as Bonsol signs it. No proof is checked:
+//! whoever sends [`DELIVER_TAG`] decides the result, so never deploy this to a shared
+//! cluster. Like Bonsol, it refuses results for requests past their
+//! `max_block_height`.
+//!
+//! `pop-cli dev prove` runs the guest locally and delivers its journal through here;
+//! program-tests drives proof_of_post's callback paths with crafted journals.
+
+use bonsol_schema::{
+    root_as_channel_instruction, root_as_execution_request_v1, ChannelInstructionIxType,
+};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::msg;
+use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::sysvar::Sysvar;
+use solana_system_interface::instruction::create_account;
+
+/// Bonsol derives execution request accounts from these seeds, the requester and the
+/// execution ID
+pub const EXECUTION_SEED: &[u8] = b"execution";
+
+/// Prefixes a delivery: the callback payload follows, and the accounts are laid out as
+/// in Bonsol's status instruction: the requester, the execution request, the callback
+/// program, the delivering prover as signer and the callback's extra accounts in the
+/// order the request lists them
+pub const DELIVER_TAG: [u8; 8] = *b"mockdlvr";
+
+/// Custom error for a delivery after the request's `max_block_height`
+pub const ERROR_EXPIRED: u32 = 1;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.strip_prefix(&DELIVER_TAG) {
+        Some(payload) => deliver(program_id, accounts, payload),
+        None => execute(program_id, accounts, data),
+    }
+}
+
+/// The execution request account for `execution_id` by `requester`, and its bump
+pub fn execution_request_address(
+    program_id: &Pubkey,
+    requester: &Pubkey,
+    execution_id: &str,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[EXECUTION_SEED, requester.as_ref(), execution_id.as_bytes()],
+        program_id,
+    )
+}
+
+/// Instruction delivering `payload` to the callback of the execution request at
+/// `execution_request`, signed by `prover`. `callback_accounts` are the request's extra
+/// accounts.
+pub fn deliver_instruction(
+    program_id: &Pubkey,
+    execution_request: &Pubkey,
+    requester: &Pubkey,
+    prover: &Pubkey,
+    callback_program: &Pubkey,
+    callback_accounts: Vec<AccountMeta>,
+    payload: &[u8],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*requester, false),
+        AccountMeta::new(*execution_request, false),
+        AccountMeta::new_readonly(*callback_program, false),
+        AccountMeta::new_readonly(*prover, true),
+    ];
+    accounts.extend(callback_accounts);
+    Instruction::new_with_bytes(
+        *program_id,
+        &[DELIVER_TAG.as_slice(), payload].concat(),
+        accounts,
+    )
+}
+
+/// A callback payload as Bonsol delivers it to the callback entrypoints' `data`
+/// argument: the committed `journal`, input digest first, Borsh-encoded as a `Vec<u8>`
+pub fn callback_payload(journal: &[u8]) -> Vec<u8> {
+    [(journal.len() as u32).to_le_bytes().as_slice(), journal].concat()
+}
+
+/// Creates the execution request account holding the `ExecutionRequestV1` flatbuffer,
+/// as Bonsol stores it. The first two accounts are the requester and the payer; the
+/// rest are found by address.
+fn execute(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction =
+        root_as_channel_instruction(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    if instruction.ix_type() != ChannelInstructionIxType::ExecuteV1 {
+        msg!("Only execute_v1 is mocked");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let request_bytes = instruction
+        .execute_v1()
+        .ok_or(ProgramError::InvalidInstructionData)?
+        .bytes();
+    let request = root_as_execution_request_v1(request_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let execution_id = request
+        .execution_id()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let accounts_iter = &mut accounts.iter();
+    let requester = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let (address, bump) = execution_request_address(program_id, requester.key, execution_id);
+    let execution_request = find(accounts, &address)?;
+
+    invoke_signed(
+        &create_account(
+            payer.key,
+            &address,
+            Rent::get()?.minimum_balance(request_bytes.len()),
+            request_bytes.len() as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[
+            EXECUTION_SEED,
+            requester.key.as_ref(),
+            execution_id.as_bytes(),
+            &[bump],
+        ]],
+    )?;
+    execution_request
+        .try_borrow_mut_data()?
+        .copy_from_slice(request_bytes);
+    msg!("Mock execution request {} created", execution_id);
+    Ok(())
+}
+
+/// Invokes the request's callback with its instruction prefix followed by `payload`,
+/// the execution request signing, then closes the request to its requester
+fn deliver(program_id: &Pubkey, accounts: &[AccountInfo], payload: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let requester = next_account_info(accounts_iter)?;
+    let execution_request = next_account_info(accounts_iter)?;
+    let callback_program = next_account_info(accounts_iter)?;
+    // Bonsol only takes a result from the prover that claimed the execution
+    let prover = next_account_info(accounts_iter)?;
+    if !prover.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let extra_accounts: Vec<&AccountInfo> = accounts_iter.collect();
+    if execution_request.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let data = execution_request.try_borrow_data()?.to_vec();
+    let request =
+        root_as_execution_request_v1(&data).map_err(|_| ProgramError::InvalidAccountData)?;
+    let execution_id = request
+        .execution_id()
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let (address, bump) = execution_request_address(program_id, requester.key, execution_id);
+    if address != *execution_request.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if Clock::get()?.slot > request.max_block_height() {
+        msg!("Mock execution request {} expired", execution_id);
+        return Err(ProgramError::Custom(ERROR_EXPIRED));
+    }
+    let callback_program_id = request
+        .callback_program_id()
+        .map(|id| id.bytes())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if callback_program_id != callback_program.key.as_ref() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let prefix = request
+        .callback_instruction_prefix()
+        .map(|prefix| prefix.bytes())
+        .unwrap_or_default();
+
+    let mut metas = vec![AccountMeta::new_readonly(*execution_request.key, true)];
+    metas.extend(extra_accounts.iter().map(|account| AccountMeta {
+        pubkey: *account.key,
+        is_signer: false,
+        is_writable: account.is_writable,
+    }));
+    let callback =
+        Instruction::new_with_bytes(*callback_program.key, &[prefix, payload].concat(), metas);
+    invoke_signed(
+        &callback,
+        accounts,
+        &[&[
+            EXECUTION_SEED,
+            requester.key.as_ref(),
+            execution_id.as_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    let lamports = execution_request.lamports();
+    **execution_request.try_borrow_mut_lamports()? = 0;
+    **requester.try_borrow_mut_lamports()? += lamports;
+    execution_request.try_borrow_mut_data()?.fill(0);
+    msg!("Mock execution request {} delivered", execution_id);
+    Ok(())
+}
+
+fn find<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    address: &Pubkey,
+) -> Result<&'a AccountInfo<'info>, ProgramError> {
+    accounts
+        .iter()
+        .find(|account| account.key == address)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
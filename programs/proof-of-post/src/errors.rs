@@ -0,0 +1,167 @@
+//! Errors the program returns
+
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum PostProofError {
+    #[msg("Post verification request failed")]
+    PostVerificationRequestFailed,
+    #[msg("Verification too fast")]
+    VerificationTooFast,
+    #[msg("Invalid callback")]
+    InvalidCallback,
+    #[msg("Invalid output")]
+    InvalidOutput,
+    #[msg("Callback error")]
+    CallbackError,
+    #[msg("Config not active")]
+    ConfigNotActive,
+    #[msg("Max claimers reached")]
+    MaxClaimersReached,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Unsupported output version")]
+    UnsupportedOutputVersion,
+    #[msg("Invalid keyword match mode")]
+    InvalidMatchMode,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Feature disabled")]
+    FeatureDisabled,
+    #[msg("Unsupported callback version")]
+    UnsupportedCallbackVersion,
+    #[msg("Invalid callback data length")]
+    InvalidCallbackDataLength,
+    #[msg("Invalid reward split")]
+    InvalidRewardSplit,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Facet requirements must start with # or @")]
+    InvalidFacetRequirement,
+    #[msg("Keywords must not contain commas")]
+    InvalidKeyword,
+    #[msg("Regex pattern too long")]
+    RegexPatternTooLong,
+    #[msg("Invalid request id")]
+    InvalidRequestId,
+    #[msg("Request id already used")]
+    RequestIdAlreadyUsed,
+    #[msg("Invalid rule bundle")]
+    InvalidRulesBundle,
+    #[msg("Invalid post recency window")]
+    InvalidRecencyWindow,
+    #[msg("Invalid language tag")]
+    InvalidLanguage,
+    #[msg("Committed input digest does not match the requested input hash")]
+    InputHashMismatch,
+    #[msg("Invalid domain")]
+    InvalidDomain,
+    #[msg("Invalid time mode")]
+    InvalidTimeMode,
+    #[msg("Invalid budget alert")]
+    InvalidBudgetAlert,
+    #[msg("Reply target must be a post URI on the campaign's platform")]
+    InvalidReplyTarget,
+    #[msg("Invalid payout address")]
+    InvalidPayoutAddress,
+    #[msg("Circuit breaker tripped, awaiting guardian reset")]
+    CircuitBreakerTripped,
+    #[msg("Post URL must be an https URL of at most 256 bytes")]
+    InvalidPostUrl,
+    #[msg("Invalid moderation label")]
+    InvalidLabel,
+    #[msg("This author has already been rewarded for this campaign")]
+    AuthorAlreadyClaimed,
+    #[msg("Proven post author does not match the declared author")]
+    AuthorHashMismatch,
+    #[msg("Tip is below the protocol minimum")]
+    TipTooLow,
+    #[msg("Unsupported platform")]
+    InvalidPlatform,
+    #[msg("Invalid community name")]
+    InvalidCommunity,
+    #[msg("This campaign requires a signed acceptance of its terms")]
+    TermsNotAccepted,
+    #[msg("Terms signature is not verified by an Ed25519 instruction")]
+    InvalidTermsSignature,
+    #[msg("Disclosure tags must be hashtags of at most 32 bytes")]
+    InvalidDisclosureTag,
+    #[msg("Invalid author id in allowlist")]
+    InvalidAuthorId,
+    #[msg("Invalid market segment")]
+    InvalidSegment,
+    #[msg("This market segment has reached its claim cap")]
+    SegmentFull,
+    #[msg("This campaign requires a signed response from its response signer")]
+    MissingResponseSignature,
+    #[msg("Proven post URL does not match the requested URL")]
+    PostUrlMismatch,
+    #[msg("Proof was made for a different campaign")]
+    ConfigMismatch,
+    #[msg("Only finished campaigns can publish a summary")]
+    CampaignNotFinalized,
+    #[msg("Committed journal values are outside the protocol's sanity bounds")]
+    JournalOutOfBounds,
+    #[msg("Too many allowed callers")]
+    InvalidAllowedCallers,
+    #[msg("verify_post was invoked through CPI by a program this campaign doesn't allow")]
+    UnauthorizedCaller,
+    #[msg("Leftover recipient account does not match the campaign's")]
+    InvalidLeftoverRecipient,
+    #[msg("Unsupported response encoding")]
+    InvalidResponseEncoding,
+    #[msg("Thread mode is only supported for Bluesky campaigns")]
+    InvalidThreadMode,
+    #[msg("The verifier's intent has expired")]
+    IntentExpired,
+    #[msg("Intent signature is not verified by an Ed25519 instruction")]
+    InvalidIntentSignature,
+    #[msg("Sponsored request costs more than the campaign sponsors per request")]
+    SponsorshipLimitExceeded,
+    #[msg("The campaign's sponsorship budget is exhausted")]
+    SponsorshipExhausted,
+    #[msg("Transfer would take the account below its rent-exempt minimum")]
+    WouldBreachRentExemption,
+    #[msg("Arithmetic overflowed")]
+    ArithmeticOverflow,
+    #[msg("Callbacks must come from Bonsol's delivery")]
+    CallbackNotFromBonsol,
+    #[msg("This execution's callback was already processed")]
+    CallbackAlreadyProcessed,
+    #[msg("Post hosts must be at most 4 lowercase hostnames")]
+    InvalidPostHosts,
+    #[msg("Post URL host is not one the campaign allows")]
+    PostHostNotAllowed,
+    #[msg("Tip is above the protocol maximum")]
+    TipTooHigh,
+    #[msg("Post size is above the protocol maximum")]
+    PostTooLarge,
+    #[msg("Account is already on the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Protocol parameters are out of range")]
+    InvalidProtocolParams,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Dispute windows need a challenge bond and a challenger share of at most 100%")]
+    InvalidDisputeSettings,
+    #[msg("No reward is held for this verification")]
+    NoHeldReward,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Held reward is already being challenged")]
+    ChallengeOpen,
+    #[msg("Rewards are still held in a dispute window")]
+    RewardsHeld,
+    #[msg("Too many allowed provers")]
+    InvalidAllowedProvers,
+    #[msg("Prover is not allowed to deliver this campaign's proofs")]
+    ProverNotAllowed,
+    #[msg("The platform's responses carry no follower count to check")]
+    FollowerCountUnavailable,
+    #[msg("This wallet has already been rewarded for this campaign")]
+    AlreadyVerified,
+    #[msg("The previous request is still awaiting its proof")]
+    VerificationPending,
+}
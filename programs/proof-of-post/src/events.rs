@@ -0,0 +1,143 @@
+//! Events the program emits, for indexers and webhooks
+
+use anchor_lang::prelude::*;
+
+/// A verification was requested from Bonsol, which drops it unless a proof lands by
+/// `expiry_slot`
+#[event]
+pub struct VerificationRequested {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub execution_request: Pubkey,
+    pub slot: u64,
+    pub expiry_slot: u64,
+}
+
+/// A relayer was reimbursed for submitting a verifier's sponsored request
+#[event]
+pub struct SponsorReimbursed {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub budget_left: u64,
+}
+
+/// The callback settled a verification request, paid or not. `reason_code` is the
+/// `REASON_*` code of the journal, which passes while the campaign's own checks
+/// (authorship, engagement plausibility, response signer) may still fail it.
+#[event]
+pub struct VerificationCompleted {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub verifier: Pubkey,
+    pub post_url: String,
+    pub passed: bool,
+    pub reason_code: u16,
+    pub failed_checks: u32,
+    pub slot: u64,
+}
+
+/// A campaign stopped being able to pay: it reached its claimer cap
+/// ([`EXHAUSTED_MAX_CLAIMERS`]) or its budget fell below a reward ([`EXHAUSTED_BUDGET`])
+#[event]
+pub struct CampaignExhausted {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub reason: u8,
+    pub claimers_count: u64,
+    /// Budget left above rent
+    pub remaining: u64,
+}
+
+/// A verified post was paid for. `payout` received the claimer's share.
+#[event]
+pub struct RewardPaid {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub payout: Pubkey,
+    pub author_did_hash: [u8; 32],
+    pub amount: u64,
+    pub claimer_share: u64,
+    pub creator_share: u64,
+    pub platform_share: u64,
+    pub referrer: Option<Pubkey>,
+    pub referrer_share: u64,
+    pub slot: u64,
+}
+
+/// A payout took a campaign's remaining budget below its alert threshold
+#[event]
+pub struct BudgetLow {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub remaining: u64,
+    pub threshold: u64,
+}
+
+/// A finished campaign was closed. `donated` is set when the leftover budget went to the
+/// campaign's leftover recipient instead of back to its creator.
+#[event]
+pub struct CampaignFinalized {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub recipient: Pubkey,
+    pub leftover: u64,
+    pub donated: bool,
+}
+
+/// Payout velocity exceeded the circuit breaker limit and verifications are halted
+#[event]
+pub struct CircuitBreakerTripped {
+    pub window_spend: u64,
+    pub norm_spend: u64,
+    pub slot: u64,
+}
+
+/// The creator offered the campaign to `new_creator`, who takes over once it accepts.
+/// `None` withdraws an earlier offer.
+#[event]
+pub struct CampaignTransferProposed {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub new_creator: Option<Pubkey>,
+}
+
+/// `creator` accepted the campaign and now controls it and its leftover budget
+#[event]
+pub struct CampaignTransferred {
+    pub config: Pubkey,
+    pub previous_creator: Pubkey,
+    pub creator: Pubkey,
+}
+
+/// A verified post's reward is held until `claimable_slot`, open to challenges
+#[event]
+pub struct RewardHeld {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub amount: u64,
+    pub claimable_slot: u64,
+}
+
+/// A held reward was challenged and its post sent to Bonsol again
+#[event]
+pub struct ClaimChallenged {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub challenger: Pubkey,
+    pub bond: u64,
+    pub execution_request: Pubkey,
+}
+
+/// The re-verification of a challenged claim came back. `voided` when it disagreed
+/// with the original proof, paying the challenger `challenger_award` on top of its
+/// bond; otherwise the bond went to the claimer.
+#[event]
+pub struct ChallengeResolved {
+    pub config: Pubkey,
+    pub verifier: Pubkey,
+    pub challenger: Pubkey,
+    pub voided: bool,
+    pub challenger_award: u64,
+}
@@ -0,0 +1,31 @@
+//! accept_protocol_authority: the proposed authority takes over the protocol config
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptProtocolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.pending_authority == Some(new_authority.key())
+            @ PostProofError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub new_authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptProtocolAuthority>) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    msg!(
+        "Protocol authority moves from {} to {}",
+        protocol_config.authority,
+        ctx.accounts.new_authority.key()
+    );
+    protocol_config.authority = ctx.accounts.new_authority.key();
+    protocol_config.pending_authority = None;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! accept_transfer: the proposed creator takes over the campaign
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptTransfer<'info> {
+    #[account(
+        mut,
+        constraint = post_proof_config.pending_creator == Some(new_creator.key())
+            @ PostProofError::Unauthorized
+    )]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    pub new_creator: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.post_proof_config;
+    let previous_creator = config.creator;
+    config.creator = ctx.accounts.new_creator.key();
+    config.pending_creator = None;
+
+    emit!(CampaignTransferred {
+        config: config.key(),
+        previous_creator,
+        creator: config.creator,
+    });
+    msg!(
+        "Campaign {:?} moves from {} to {}",
+        config.seeds,
+        previous_creator,
+        config.creator
+    );
+
+    Ok(())
+}
@@ -0,0 +1,636 @@
+//! bonsol_callback: settles a verification with the proven journal
+
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use bonsol_anchor_interface::callback::handle_callback;
+use bonsol_anchor_interface::Bonsol;
+use proof_of_post_common::{OutputError, PostVerificationOutput};
+
+#[derive(Accounts)]
+pub struct BonsolCallback<'info> {
+    /// CHECK: This is the raw ER account, checked in the callback handler
+    pub execution_request: UncheckedAccount<'info>,
+
+    #[account(mut, owner = crate::id() @ PostProofError::InvalidCallback)]
+    /// CHECK: This is the requester PDA that stores ExecutionTracker data, which only
+    /// verify_post writes
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(
+        mut,
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump = post_verification_log.bump,
+        constraint = post_verification_log.verifier == verifier.key() @ PostProofError::InvalidCallback,
+        constraint = post_verification_log.config == post_proof_config.key() @ PostProofError::ConfigMismatch,
+    )]
+    pub post_verification_log: Account<'info, PostVerificationLog>,
+
+    #[account(mut)]
+    /// CHECK: Seeds the verification log PDA
+    pub verifier: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.creator)]
+    /// CHECK: Receives the creator share of the reward
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.platform_treasury)]
+    /// CHECK: Receives the platform share of the reward
+    pub platform_treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Receives the referrer share, checked against the verification log
+    pub referrer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Receives the claimer share, checked against the verification log
+    pub payout: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            AUTHOR_CLAIM_SEED,
+            post_proof_config.key().as_ref(),
+            post_verification_log.author_did_hash.as_ref(),
+        ],
+        bump = author_claim.bump
+    )]
+    pub author_claim: Account<'info, AuthorClaim>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, read to check Bonsol made the call
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub const CALLBACK_VERSION_V1: u8 = 1;
+pub const CALLBACK_VERSION_V2: u8 = 2;
+
+/// Instruction discriminator Bonsol prefixes the callback with, for each registered
+/// callback entrypoint. Derived from the instruction names so renames can't drift.
+pub fn callback_instruction_prefix(callback_version: u8) -> Result<Vec<u8>> {
+    match callback_version {
+        CALLBACK_VERSION_V1 => Ok(instruction::BonsolCallback::DISCRIMINATOR.to_vec()),
+        CALLBACK_VERSION_V2 => Ok(instruction::BonsolCallbackV2::DISCRIMINATOR.to_vec()),
+        _ => Err(PostProofError::UnsupportedCallbackVersion.into()),
+    }
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BonsolCallback<'info>>,
+    data: &[u8],
+    callback_version: u8,
+) -> Result<()> {
+    if data.len() < MIN_CALLBACK_DATA_LEN || data.len() > MAX_CALLBACK_DATA_LEN {
+        msg!("Invalid callback data length: {}", data.len());
+        return Err(PostProofError::InvalidCallbackDataLength.into());
+    }
+
+    // Only Bonsol's delivery, calling this program directly, settles requests
+    if get_stack_height() != TRANSACTION_LEVEL_STACK_HEIGHT + 1
+        || outer_program(&ctx.accounts.instructions)? != Bonsol::id()
+    {
+        msg!("Callback not invoked by Bonsol");
+        return Err(PostProofError::CallbackNotFromBonsol.into());
+    }
+
+    // Bonsol's ExecutionConfig can't name provers, so campaigns restricting them turn
+    // away other provers' deliveries here, leaving the request to an allowed one
+    let allowed_provers = &ctx.accounts.post_proof_config.allowed_provers;
+    if !allowed_provers.is_empty() {
+        let prover = delivering_prover(&ctx.accounts.instructions)?;
+        if !prover.is_some_and(|prover| allowed_provers.contains(&prover)) {
+            msg!("Delivery by prover {:?} is not allowed", prover);
+            return Err(PostProofError::ProverNotAllowed.into());
+        }
+    }
+
+    let slot = current_slot()?;
+    let timestamp = current_timestamp()?;
+
+    // Get execution account from requester data
+    let mut requester_data = ctx.accounts.requester.try_borrow_mut_data()?;
+
+    // Skip discriminator and get ExecutionTracker data
+    if requester_data.len() < 8 + ExecutionTracker::UNVERSIONED_SPACE {
+        msg!("Requester data too short");
+        return Err(PostProofError::InvalidCallback.into());
+    }
+
+    let tracker_len = (requester_data.len() - 8).min(ExecutionTracker::INIT_SPACE);
+    let tracker_data = &mut requester_data[8..8 + tracker_len];
+    let tracker = ExecutionTracker::unpack(tracker_data)?;
+    // Each execution settles once, whatever a redelivery finds in the log
+    if tracker.processed {
+        msg!("Execution {} already processed", tracker.execution_account);
+        return Err(PostProofError::CallbackAlreadyProcessed.into());
+    }
+    ExecutionTracker {
+        processed: true,
+        ..tracker
+    }
+    .pack(tracker_data)?;
+    let execution_account = tracker.execution_account;
+    drop(requester_data);
+
+    if let Some(epub) = ctx.accounts.post_verification_log.current_execution_account {
+        if ctx.accounts.execution_request.key() != epub {
+            msg!("Invalid execution request account");
+            return Err(PostProofError::InvalidCallback.into());
+        }
+
+        let ainfos = ctx.accounts.to_account_infos();
+
+        let output = handle_callback(
+            POST_VERIFICATION_IMAGE_ID,
+            &execution_account,
+            &ainfos,
+            &data,
+        )
+        .map_err(|_| PostProofError::CallbackError)?;
+        msg!("Callback handled, output received");
+
+        // The guest commits the digest of all inputs, which must match the input hash the
+        // request was submitted with
+        if let Some(input_hash) = ctx.accounts.post_verification_log.input_hash {
+            if output.input_digest != input_hash.as_slice() {
+                msg!("Committed input digest does not match the requested input hash");
+                return Err(PostProofError::InputHashMismatch.into());
+            }
+        }
+
+        // Decode the versioned journal output in the entrypoint's format
+        let decode = match callback_version {
+            CALLBACK_VERSION_V1 => PostVerificationOutput::decode,
+            _ => PostVerificationOutput::decode_prefix,
+        };
+        let journal = decode(output.committed_outputs).map_err(|e| match e {
+            OutputError::UnsupportedVersion(_) => PostProofError::UnsupportedOutputVersion,
+            OutputError::Malformed => PostProofError::InvalidOutput,
+        })?;
+
+        // Absurd committed values point at a faulty image; keep them out of payout math
+        if !ctx
+            .accounts
+            .protocol_config
+            .journal_bounds
+            .accepts(&journal)
+        {
+            msg!("Committed journal values are outside the protocol's sanity bounds");
+            return Err(PostProofError::JournalOutOfBounds.into());
+        }
+        let authorship_matched = journal.authorship_matched;
+
+        // Engagement far above what the author's audience could produce suggests bought likes
+        let max_ratio = ctx.accounts.post_proof_config.max_likes_per_follower_bps;
+        let engagement_plausible = max_ratio == 0 || journal.likes_per_follower_bps <= max_ratio;
+
+        // Campaigns with a response signer only trust responses it vouched for
+        let response_trusted = ctx.accounts.post_proof_config.response_signer.is_none()
+            || journal.response_authenticated;
+
+        // Only pay when the post carries the verifier's wallet address, if required, and
+        // met the engagement thresholds with plausible engagement
+        let is_valid_post = journal.passed()
+            && (!ctx.accounts.post_proof_config.require_authorship || authorship_matched)
+            && journal.engagement_met
+            && engagement_plausible
+            && response_trusted;
+
+        msg!(
+            "Post verification result: {} (reason: {}, authorship: {}, engagement: {}, response authenticated: {}, likes per follower: {} bps, likes: {}, reposts: {}, replies: {}, thread posts matching: {})",
+            is_valid_post,
+            journal.reason_code,
+            authorship_matched,
+            journal.engagement_met,
+            journal.response_authenticated,
+            journal.likes_per_follower_bps,
+            journal.metrics.like_count,
+            journal.metrics.repost_count,
+            journal.metrics.reply_count,
+            journal.thread_matching_posts
+        );
+
+        // Update verification log
+        ctx.accounts.post_verification_log.slot = slot;
+        ctx.accounts.post_verification_log.timestamp = timestamp;
+        ctx.accounts.post_verification_log.is_verified = is_valid_post;
+        ctx.accounts.post_verification_log.failure_reason = journal.reason_code;
+        ctx.accounts.post_verification_log.failed_checks = journal.failed_checks;
+        ctx.accounts.post_verification_log.current_execution_account = None;
+        ctx.accounts.post_verification_log.input_hash = None;
+
+        // A challenge's re-verification settles the held claim instead of paying again
+        let passed = if let Some(challenger) = ctx.accounts.post_verification_log.challenger {
+            settle_challenge(
+                ctx.accounts,
+                ctx.remaining_accounts,
+                challenger,
+                &journal,
+                is_valid_post,
+            )?
+        } else if is_valid_post {
+            // Posts in a market segment are paid from that segment's budget
+            let segment_index = journal.segment as usize;
+            let reward_amount = match ctx.accounts.post_proof_config.segments.get(segment_index) {
+                Some(segment) if segment.claimers_count >= segment.max_claimers => {
+                    return Err(PostProofError::SegmentFull.into());
+                }
+                Some(segment) => segment.reward_amount,
+                None => ctx.accounts.post_proof_config.reward_amount,
+            };
+
+            // Split the reward between claimer, creator, platform and referrer
+            let has_referrer = ctx.accounts.post_verification_log.referrer.is_some();
+            if has_referrer
+                && Some(ctx.accounts.referrer.key()) != ctx.accounts.post_verification_log.referrer
+            {
+                return Err(PostProofError::InvalidReferrer.into());
+            }
+            let payout = ctx
+                .accounts
+                .post_verification_log
+                .payout
+                .unwrap_or(ctx.accounts.post_verification_log.verifier);
+            if ctx.accounts.payout.key() != payout {
+                return Err(PostProofError::InvalidPayoutAddress.into());
+            }
+            // The author claim PDA was derived from the DID hash the verifier declared,
+            // so the proven author must match it
+            if journal.author_did_hash != ctx.accounts.post_verification_log.author_did_hash {
+                msg!("Proven author does not match the declared author");
+                return Err(PostProofError::AuthorHashMismatch.into());
+            }
+            check_proof_binding(
+                &ctx.accounts.post_proof_config,
+                &ctx.accounts.post_verification_log,
+                &journal,
+            )?;
+            if ctx.accounts.author_claim.claimed {
+                return Err(PostProofError::AuthorAlreadyClaimed.into());
+            }
+            let remaining_before = check_reward_payable(
+                &ctx.accounts.post_proof_config,
+                &ctx.accounts.protocol_config,
+                reward_amount,
+            )?;
+
+            ctx.accounts.author_claim.claimed = true;
+            ctx.accounts.author_claim.claimer = ctx.accounts.post_verification_log.verifier;
+            ctx.accounts.author_claim.slot = slot;
+
+            // Update claimers count and the campaign's running totals
+            let metrics = &journal.metrics;
+            let config = &mut ctx.accounts.post_proof_config;
+            config.claimers_count = config
+                .claimers_count
+                .checked_add(1)
+                .ok_or(PostProofError::ArithmeticOverflow)?;
+            config.total_views = config.total_views.saturating_add(metrics.view_count);
+            config.total_engagement = config.total_engagement.saturating_add(
+                metrics
+                    .like_count
+                    .saturating_add(metrics.repost_count)
+                    .saturating_add(metrics.reply_count)
+                    .saturating_add(metrics.quote_count),
+            );
+            if let Some(segment) = ctx
+                .accounts
+                .post_proof_config
+                .segments
+                .get_mut(segment_index)
+            {
+                segment.claimers_count = segment
+                    .claimers_count
+                    .checked_add(1)
+                    .ok_or(PostProofError::ArithmeticOverflow)?;
+            }
+
+            // Campaigns with a dispute window hold the reward until the window closes,
+            // leaving time to challenge the claim
+            let dispute_window = ctx.accounts.post_proof_config.dispute_window_slots;
+            if dispute_window > 0
+                && ctx
+                    .accounts
+                    .protocol_config
+                    .is_feature_enabled(FEATURE_DISPUTES)
+            {
+                let claimable_slot = slot.saturating_add(dispute_window);
+                let config = &mut ctx.accounts.post_proof_config;
+                config.held_rewards = config.held_rewards.saturating_add(reward_amount);
+                let log = &mut ctx.accounts.post_verification_log;
+                log.held_reward = reward_amount;
+                log.held_segment = journal.segment;
+                log.claimable_slot = claimable_slot;
+
+                msg!(
+                    "Post verified for campaign {:?}! Holding {} lamports until slot {}",
+                    ctx.accounts.post_proof_config.seeds,
+                    reward_amount,
+                    claimable_slot
+                );
+                emit!(RewardHeld {
+                    config: ctx.accounts.post_proof_config.key(),
+                    verifier: ctx.accounts.post_verification_log.verifier,
+                    amount: reward_amount,
+                    claimable_slot,
+                });
+            } else {
+                pay_reward(
+                    RewardPayment {
+                        post_proof_config: &mut ctx.accounts.post_proof_config,
+                        protocol_config: &mut ctx.accounts.protocol_config,
+                        post_verification_log: &ctx.accounts.post_verification_log,
+                        creator: ctx.accounts.creator.to_account_info(),
+                        platform_treasury: ctx.accounts.platform_treasury.to_account_info(),
+                        referrer: ctx.accounts.referrer.to_account_info(),
+                        payout: ctx.accounts.payout.to_account_info(),
+                    },
+                    reward_amount,
+                    slot,
+                )?;
+            }
+
+            // Warn the creator once, when the claim takes the budget below the alert level
+            let remaining = unreserved_lamports(&ctx.accounts.post_proof_config)?;
+            let threshold = ctx.accounts.post_proof_config.budget_alert_threshold();
+            if remaining < threshold && remaining_before >= threshold {
+                emit!(BudgetLow {
+                    config: ctx.accounts.post_proof_config.key(),
+                    creator: ctx.accounts.post_proof_config.creator,
+                    remaining,
+                    threshold,
+                });
+            }
+
+            // Deactivate config if max claimers reached
+            if ctx.accounts.post_proof_config.claimers_count
+                >= ctx.accounts.post_proof_config.max_claimers
+            {
+                ctx.accounts.post_proof_config.active = false;
+                msg!(
+                    "Config deactivated - max claimers reached for campaign {:?}",
+                    ctx.accounts.post_proof_config.seeds
+                );
+                emit!(CampaignExhausted {
+                    config: ctx.accounts.post_proof_config.key(),
+                    creator: ctx.accounts.post_proof_config.creator,
+                    reason: EXHAUSTED_MAX_CLAIMERS,
+                    claimers_count: ctx.accounts.post_proof_config.claimers_count,
+                    remaining,
+                });
+            } else {
+                // Or, once, when the budget left can't cover another reward
+                let max_reward = ctx.accounts.post_proof_config.max_reward();
+                if remaining < max_reward && remaining_before >= max_reward {
+                    emit!(CampaignExhausted {
+                        config: ctx.accounts.post_proof_config.key(),
+                        creator: ctx.accounts.post_proof_config.creator,
+                        reason: EXHAUSTED_BUDGET,
+                        claimers_count: ctx.accounts.post_proof_config.claimers_count,
+                        remaining,
+                    });
+                }
+            }
+            true
+        } else {
+            msg!(
+                "Post verification failed for campaign {:?}",
+                ctx.accounts.post_proof_config.seeds
+            );
+            false
+        };
+
+        emit!(VerificationCompleted {
+            config: ctx.accounts.post_proof_config.key(),
+            creator: ctx.accounts.post_proof_config.creator,
+            verifier: ctx.accounts.post_verification_log.verifier,
+            post_url: ctx.accounts.post_verification_log.post_url.clone(),
+            passed,
+            reason_code: journal.reason_code,
+            failed_checks: journal.failed_checks,
+            slot,
+        });
+
+        Ok(())
+    } else {
+        Err(PostProofError::InvalidCallback.into())
+    }
+}
+
+// Bonsol's status instruction takes the requester, the execution request, the callback
+// program and then the delivering prover, before the callback's extra accounts
+const STATUS_PROVER_INDEX: usize = 3;
+
+/// Prover delivering the result: the signing prover account of Bonsol's status
+/// instruction, which Bonsol only accepts from the prover that claimed the execution.
+/// The transaction's fee payer may be anyone else.
+fn delivering_prover(instructions: &AccountInfo) -> Result<Option<Pubkey>> {
+    let current = load_current_index_checked(instructions)? as usize;
+    let delivery = load_instruction_at_checked(current, instructions)?;
+    if delivery.program_id != Bonsol::id() {
+        return Ok(None);
+    }
+    Ok(delivery
+        .accounts
+        .get(STATUS_PROVER_INDEX)
+        .filter(|account| account.is_signer)
+        .map(|account| account.pubkey))
+}
+
+/// Checks a proof was made for the post URL the log requested and for this campaign,
+/// not a more lenient one
+fn check_proof_binding(
+    config: &Account<PostProofConfig>,
+    log: &PostVerificationLog,
+    journal: &PostVerificationOutput,
+) -> Result<()> {
+    let post_url_hash = hash::hash(log.post_url.as_bytes()).to_bytes();
+    if journal.post_url_hash != post_url_hash {
+        msg!("Proven post URL does not match the requested URL");
+        return Err(PostProofError::PostUrlMismatch.into());
+    }
+    if journal.config != config.key().to_bytes() {
+        msg!("Proof was made for a different campaign");
+        return Err(PostProofError::ConfigMismatch.into());
+    }
+    Ok(())
+}
+
+/// Lamports of the vault left for rewards, after its reserved lamports
+pub(crate) fn unreserved_lamports(config: &Account<PostProofConfig>) -> Result<u64> {
+    let config_info = config.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(config_info.data_len());
+    Ok(config_info
+        .lamports()
+        .saturating_sub(config.reserved_lamports(rent_floor)))
+}
+
+/// Checks the campaign can pay `reward_amount` now, returning the lamports left for
+/// rewards before paying it
+pub(crate) fn check_reward_payable(
+    config: &Account<PostProofConfig>,
+    protocol_config: &ProtocolConfig,
+    reward_amount: u64,
+) -> Result<u64> {
+    // Requests already in flight when the protocol was paused or the breaker tripped
+    // are not paid either
+    if protocol_config.paused {
+        return Err(PostProofError::ProtocolPaused.into());
+    }
+    if protocol_config.breaker.tripped {
+        return Err(PostProofError::CircuitBreakerTripped.into());
+    }
+    let config_info = config.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(config_info.data_len());
+    if config_info.lamports() < rent_floor.saturating_add(reward_amount) {
+        return Err(PostProofError::WouldBreachRentExemption.into());
+    }
+    let remaining = unreserved_lamports(config)?;
+    if remaining < reward_amount {
+        return Err(PostProofError::InsufficientFunds.into());
+    }
+    Ok(remaining)
+}
+
+/// The accounts a reward is paid from and to, shared by the callback and claim_reward
+pub(crate) struct RewardPayment<'a, 'info> {
+    pub(crate) post_proof_config: &'a mut Account<'info, PostProofConfig>,
+    pub(crate) protocol_config: &'a mut Account<'info, ProtocolConfig>,
+    pub(crate) post_verification_log: &'a PostVerificationLog,
+    pub(crate) creator: AccountInfo<'info>,
+    pub(crate) platform_treasury: AccountInfo<'info>,
+    pub(crate) referrer: AccountInfo<'info>,
+    pub(crate) payout: AccountInfo<'info>,
+}
+
+/// Splits `reward_amount` from the vault between claimer, creator, platform and
+/// referrer. Callers check the campaign can pay it with `check_reward_payable`.
+pub(crate) fn pay_reward(payment: RewardPayment, reward_amount: u64, slot: u64) -> Result<()> {
+    let log = payment.post_verification_log;
+    let shares = payment
+        .post_proof_config
+        .reward_split
+        .apply(reward_amount, log.referrer.is_some());
+
+    let config_info = payment.post_proof_config.to_account_info();
+    transfer_lamports(&config_info, &payment.payout, shares.claimer)?;
+    transfer_lamports(&config_info, &payment.creator, shares.creator)?;
+    transfer_lamports(&config_info, &payment.platform_treasury, shares.platform)?;
+    transfer_lamports(&config_info, &payment.referrer, shares.referrer)?;
+
+    // This payout still goes through; tripping halts everything after it
+    let breaker = &mut payment.protocol_config.breaker;
+    if breaker.record_payout(slot, reward_amount) {
+        msg!("Circuit breaker tripped: payout velocity above the historical norm");
+        emit!(CircuitBreakerTripped {
+            window_spend: breaker.window_spend,
+            norm_spend: breaker.norm_spend,
+            slot,
+        });
+    }
+
+    let config = payment.post_proof_config;
+    config.total_paid = config.total_paid.saturating_add(reward_amount);
+
+    msg!(
+        "Post verified for campaign {:?}! Paid {} lamports (claimer {}, creator {}, platform {}, referrer {}). Total claimers: {}",
+        config.seeds,
+        reward_amount,
+        shares.claimer,
+        shares.creator,
+        shares.platform,
+        shares.referrer,
+        config.claimers_count
+    );
+    emit!(RewardPaid {
+        config: config.key(),
+        verifier: log.verifier,
+        payout: payment.payout.key(),
+        author_did_hash: log.author_did_hash,
+        amount: reward_amount,
+        claimer_share: shares.claimer,
+        creator_share: shares.creator,
+        platform_share: shares.platform,
+        referrer: log.referrer,
+        referrer_share: shares.referrer,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Settles a challenged claim with its re-verification. An upheld claim keeps its held
+/// reward and takes the bond; a voided one frees its slot, and the challenger gets the
+/// bond back with their share of the reward. Returns whether the claim was upheld.
+fn settle_challenge<'info>(
+    accounts: &mut BonsolCallback<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    challenger: Pubkey,
+    journal: &PostVerificationOutput,
+    is_valid_post: bool,
+) -> Result<bool> {
+    // verify_post forwards the challenger after the callback's own accounts
+    let challenger_info = remaining_accounts
+        .first()
+        .filter(|account| account.key() == challenger)
+        .ok_or(PostProofError::InvalidCallback)?;
+    check_proof_binding(
+        &accounts.post_proof_config,
+        &accounts.post_verification_log,
+        journal,
+    )?;
+
+    let log = &mut accounts.post_verification_log;
+    let upheld = is_valid_post && journal.author_did_hash == log.author_did_hash;
+    let log_info = log.to_account_info();
+    let held_reward = log.held_reward;
+    let bond = log.challenge_bond;
+    let mut challenger_award = 0;
+    if upheld {
+        if accounts.payout.key() != log.payout.unwrap_or(log.verifier) {
+            return Err(PostProofError::InvalidPayoutAddress.into());
+        }
+        transfer_lamports(&log_info, &accounts.payout, bond)?;
+    } else {
+        let config = &mut accounts.post_proof_config;
+        challenger_award = config.challenger_award(held_reward);
+        config.held_rewards = config.held_rewards.saturating_sub(held_reward);
+        config.claimers_count = config.claimers_count.saturating_sub(1);
+        if let Some(segment) = config.segments.get_mut(log.held_segment as usize) {
+            segment.claimers_count = segment.claimers_count.saturating_sub(1);
+        }
+        transfer_lamports(&log_info, challenger_info, bond)?;
+        transfer_lamports(&config.to_account_info(), challenger_info, challenger_award)?;
+        accounts.author_claim.claimed = false;
+        log.held_reward = 0;
+    }
+    log.is_verified = upheld;
+    log.challenger = None;
+    log.challenge_bond = 0;
+
+    msg!(
+        "Challenge by {} {} the claim of {}",
+        challenger,
+        if upheld { "upheld" } else { "voided" },
+        log.verifier
+    );
+    emit!(ChallengeResolved {
+        config: accounts.post_proof_config.key(),
+        verifier: log.verifier,
+        challenger,
+        voided: !upheld,
+        challenger_award,
+    });
+
+    Ok(upheld)
+}
@@ -0,0 +1,211 @@
+//! challenge: bonds a dispute of a held reward and sends its post to Bonsol again
+
+use crate::*;
+use anchor_lang::prelude::*;
+use bonsol_anchor_interface::Bonsol;
+use proof_of_post_common::ENCODING_DEFLATE;
+
+#[derive(Accounts)]
+#[instruction(args: ChallengeArgs)]
+pub struct Challenge<'info> {
+    #[account(mut)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(
+        mut,
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump = post_verification_log.bump,
+        constraint = post_verification_log.config == post_proof_config.key() @ PostProofError::ConfigMismatch,
+    )]
+    pub post_verification_log: Account<'info, PostVerificationLog>,
+
+    #[account(mut)]
+    /// CHECK: The claimer whose reward is challenged, seeding the verification log
+    pub verifier: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    pub bonsol_program: Program<'info, Bonsol>,
+
+    #[account(
+        mut,
+        seeds = [args.current_req_id.as_bytes()],
+        bump
+    )]
+    /// CHECK: This account will be initialized in the instruction if needed
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: This is the execution request account
+    pub execution_request: UncheckedAccount<'info>,
+
+    /// CHECK: This is the deployment account
+    pub deployment_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.creator)]
+    /// CHECK: Campaign creator, receives the creator share of rewards
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.platform_treasury)]
+    /// CHECK: Platform treasury, receives the platform share of rewards
+    pub platform_treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Referrer from the verification log, or the verifier when there is none
+    pub referrer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Payout address from the verification log, or the verifier when there is none
+    pub payout: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            AUTHOR_CLAIM_SEED,
+            post_proof_config.key().as_ref(),
+            post_verification_log.author_did_hash.as_ref(),
+        ],
+        bump = author_claim.bump
+    )]
+    pub author_claim: Account<'info, AuthorClaim>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, forwarded to the callback
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(address = crate::id())]
+    /// CHECK: This is the current program address
+    pub post_proof_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ChallengeArgs {
+    pub current_req_id: String,
+    pub post_size: u64,
+    pub tip: u64,
+    /// The response signer's signature over the response, required when the campaign
+    /// has a response signer
+    pub response_signature: Option<[u8; 64]>,
+    /// `ENCODING_*` the response is shipped in; `post_size` counts the encoded bytes
+    pub response_encoding: u8,
+}
+
+pub(crate) fn handler(ctx: Context<Challenge>, args: ChallengeArgs) -> Result<()> {
+    let protocol_config = &ctx.accounts.protocol_config;
+    protocol_config.require_feature(FEATURE_DISPUTES)?;
+    if protocol_config.paused {
+        return Err(PostProofError::ProtocolPaused.into());
+    }
+    let slot = current_slot()?;
+    let timestamp = current_timestamp()?;
+
+    let log = &ctx.accounts.post_verification_log;
+    if log.held_reward == 0 {
+        return Err(PostProofError::NoHeldReward.into());
+    }
+    if log.challenger.is_some() {
+        return Err(PostProofError::ChallengeOpen.into());
+    }
+    if slot >= log.claimable_slot {
+        return Err(PostProofError::DisputeWindowClosed.into());
+    }
+
+    check_request_limits(protocol_config, args.tip, args.post_size)?;
+    validate_request_id(&args.current_req_id)?;
+    if args.response_encoding > ENCODING_DEFLATE {
+        return Err(PostProofError::InvalidResponseEncoding.into());
+    }
+    // The callback settles the challenge with the accounts the claim was made with
+    if ctx.accounts.referrer.key() != log.referrer.unwrap_or(log.verifier) {
+        return Err(PostProofError::InvalidReferrer.into());
+    }
+    if ctx.accounts.payout.key() != log.payout.unwrap_or(log.verifier) {
+        return Err(PostProofError::InvalidPayoutAddress.into());
+    }
+
+    // The bond waits in the verification log for the re-verification to settle it
+    let bond = ctx.accounts.post_proof_config.challenge_bond;
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ctx.accounts.post_verification_log.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    // The same post, proven against the same rules for the claimer, by the challenger
+    let log = &ctx.accounts.post_verification_log;
+    let args = VerifyPostArgs {
+        current_req_id: args.current_req_id,
+        post_url: log.post_url.clone(),
+        post_size: args.post_size,
+        tip: args.tip,
+        referrer: log.referrer,
+        input_hash: None,
+        payout: log.payout,
+        author_did_hash: log.author_did_hash,
+        terms_signature: None,
+        response_signature: args.response_signature,
+        response_encoding: args.response_encoding,
+        request_nonce: None,
+    };
+    let challenger = ctx.accounts.challenger.to_account_info();
+    let log_bump = log.bump;
+    let author_claim_bump = ctx.accounts.author_claim.bump;
+    let mut request = ProofRequest {
+        post_proof_config: &ctx.accounts.post_proof_config,
+        protocol_config: &ctx.accounts.protocol_config,
+        post_verification_log: &mut ctx.accounts.post_verification_log,
+        author_claim: &mut ctx.accounts.author_claim,
+        verifier: ctx.accounts.verifier.to_account_info(),
+        payer: challenger.clone(),
+        bonsol_program: ctx.accounts.bonsol_program.to_account_info(),
+        requester: ctx.accounts.requester.to_account_info(),
+        execution_request: ctx.accounts.execution_request.to_account_info(),
+        deployment_account: ctx.accounts.deployment_account.to_account_info(),
+        creator: ctx.accounts.creator.to_account_info(),
+        platform_treasury: ctx.accounts.platform_treasury.to_account_info(),
+        referrer: ctx.accounts.referrer.to_account_info(),
+        payout: ctx.accounts.payout.to_account_info(),
+        instructions: ctx.accounts.instructions.to_account_info(),
+        post_proof_program: ctx.accounts.post_proof_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        challenger: Some(challenger),
+        requester_bump: ctx.bumps.requester,
+        log_bump,
+        author_claim_bump,
+    };
+    submit_proof(&mut request, &args, timestamp)?;
+
+    // The claim stays locked until the re-verification could have expired, after which
+    // claim_reward treats the challenge as lapsed
+    let log = &mut ctx.accounts.post_verification_log;
+    log.challenger = Some(ctx.accounts.challenger.key());
+    log.challenge_bond = bond;
+    let lapse_slot = ctx
+        .accounts
+        .protocol_config
+        .execution_expiry(slot)?
+        .checked_add(1)
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+    log.claimable_slot = log.claimable_slot.max(lapse_slot);
+
+    emit!(ClaimChallenged {
+        config: ctx.accounts.post_proof_config.key(),
+        verifier: log.verifier,
+        challenger: ctx.accounts.challenger.key(),
+        bond,
+        execution_request: ctx.accounts.execution_request.key(),
+    });
+
+    Ok(())
+}
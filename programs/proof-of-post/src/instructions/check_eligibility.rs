@@ -0,0 +1,95 @@
+//! check_eligibility: the verify_post checks, run without side effects
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(args: VerifyPostArgs)]
+pub struct CheckEligibility<'info> {
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: Deserialized when it exists, for the cooldown check
+    pub post_verification_log: UncheckedAccount<'info>,
+
+    /// CHECK: Only its key is used; signing isn't needed to simulate
+    pub verifier: UncheckedAccount<'info>,
+
+    #[account(seeds = [args.current_req_id.as_bytes()], bump)]
+    /// CHECK: Checked for an existing execution tracker
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [AUTHOR_CLAIM_SEED, post_proof_config.key().as_ref(), args.author_did_hash.as_ref()],
+        bump,
+    )]
+    /// CHECK: Deserialized when it exists, for the one-reward-per-author check
+    pub author_claim: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+/// Return data of check_eligibility. `error_code` is the error verify_post would fail
+/// with, or 0 when eligible.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct EligibilityResult {
+    pub eligible: bool,
+    pub error_code: u32,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CheckEligibility>,
+    mut args: VerifyPostArgs,
+) -> Result<EligibilityResult> {
+    args.post_url = canonical_post_url(&args.post_url);
+    let log_info = ctx.accounts.post_verification_log.to_account_info();
+    let log = if log_info.owner == &crate::id() && !log_info.data_is_empty() {
+        Some(PostVerificationLog::try_deserialize(
+            &mut &log_info.try_borrow_data()?[..],
+        )?)
+    } else {
+        None
+    };
+    let claim_info = ctx.accounts.author_claim.to_account_info();
+    let author_claim = if claim_info.owner == &crate::id() && !claim_info.data_is_empty() {
+        Some(AuthorClaim::try_deserialize(
+            &mut &claim_info.try_borrow_data()?[..],
+        )?)
+    } else {
+        None
+    };
+
+    let result = check_verify_preconditions(
+        &ctx.accounts.post_proof_config,
+        &ctx.accounts.protocol_config,
+        log.as_ref(),
+        author_claim.as_ref(),
+        &ctx.accounts.verifier.key(),
+        &args,
+        current_slot()?,
+        current_timestamp()?,
+    )
+    .and_then(|()| {
+        // An existing tracker means the req_id was already used
+        if ctx.accounts.requester.owner == &crate::id() {
+            return Err(PostProofError::RequestIdAlreadyUsed.into());
+        }
+        Ok(())
+    });
+
+    Ok(match result {
+        Ok(()) => EligibilityResult {
+            eligible: true,
+            error_code: 0,
+        },
+        Err(Error::AnchorError(err)) => EligibilityResult {
+            eligible: false,
+            error_code: err.error_code_number,
+        },
+        Err(err) => return Err(err),
+    })
+}
@@ -0,0 +1,92 @@
+//! claim_reward: pays out a held reward once its dispute window has closed
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    #[account(mut)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(
+        mut,
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump = post_verification_log.bump,
+        constraint = post_verification_log.config == post_proof_config.key() @ PostProofError::ConfigMismatch,
+    )]
+    pub post_verification_log: Account<'info, PostVerificationLog>,
+
+    /// CHECK: Seeds the verification log PDA
+    pub verifier: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.creator)]
+    /// CHECK: Receives the creator share of the reward
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.platform_treasury)]
+    /// CHECK: Receives the platform share of the reward
+    pub platform_treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Receives the referrer share, checked against the verification log
+    pub referrer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Receives the claimer share, checked against the verification log
+    pub payout: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClaimReward<'info>>) -> Result<()> {
+    let slot = current_slot()?;
+    let log = &mut ctx.accounts.post_verification_log;
+    let held_reward = log.held_reward;
+    if held_reward == 0 {
+        return Err(PostProofError::NoHeldReward.into());
+    }
+    if slot < log.claimable_slot {
+        return Err(PostProofError::DisputeWindowOpen.into());
+    }
+    if log.referrer.is_some() && Some(ctx.accounts.referrer.key()) != log.referrer {
+        return Err(PostProofError::InvalidReferrer.into());
+    }
+    if ctx.accounts.payout.key() != log.payout.unwrap_or(log.verifier) {
+        return Err(PostProofError::InvalidPayoutAddress.into());
+    }
+
+    // A challenge whose re-verification expired undelivered lapses, returning the bond
+    // to the challenger, passed after the accounts above
+    if let Some(challenger) = log.challenger {
+        let challenger_info = ctx
+            .remaining_accounts
+            .first()
+            .filter(|account| account.key() == challenger)
+            .ok_or(PostProofError::ChallengeOpen)?;
+        transfer_lamports(&log.to_account_info(), challenger_info, log.challenge_bond)?;
+        log.challenger = None;
+        log.challenge_bond = 0;
+        log.current_execution_account = None;
+    }
+
+    let config = &mut ctx.accounts.post_proof_config;
+    config.held_rewards = config.held_rewards.saturating_sub(held_reward);
+    check_reward_payable(config, &ctx.accounts.protocol_config, held_reward)?;
+    pay_reward(
+        RewardPayment {
+            post_proof_config: &mut ctx.accounts.post_proof_config,
+            protocol_config: &mut ctx.accounts.protocol_config,
+            post_verification_log: &ctx.accounts.post_verification_log,
+            creator: ctx.accounts.creator.to_account_info(),
+            platform_treasury: ctx.accounts.platform_treasury.to_account_info(),
+            referrer: ctx.accounts.referrer.to_account_info(),
+            payout: ctx.accounts.payout.to_account_info(),
+        },
+        held_reward,
+        slot,
+    )?;
+    ctx.accounts.post_verification_log.held_reward = 0;
+
+    Ok(())
+}
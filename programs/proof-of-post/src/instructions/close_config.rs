@@ -0,0 +1,64 @@
+//! close_config: ends a finished campaign and returns what is left of its budget
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseConfig<'info> {
+    #[account(mut, has_one = creator, close = creator)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: Checked against the config's leftover recipient, required when it has one
+    pub leftover_recipient: Option<UncheckedAccount<'info>>,
+}
+
+pub(crate) fn handler(ctx: Context<CloseConfig>) -> Result<()> {
+    let config = &ctx.accounts.post_proof_config;
+    if config.active {
+        return Err(PostProofError::CampaignNotFinalized.into());
+    }
+    // Closing would take the held rewards away from their claimers
+    if config.held_rewards > 0 {
+        return Err(PostProofError::RewardsHeld.into());
+    }
+
+    // Unused sponsorship returns to the creator with the rent
+    let config_info = config.to_account_info();
+    let reserved = Rent::get()?
+        .minimum_balance(config_info.data_len())
+        .saturating_add(config.sponsorship_budget);
+    let leftover = config_info.lamports().saturating_sub(reserved);
+    let recipient = match config.leftover_recipient {
+        Some(recipient) => {
+            let account = ctx
+                .accounts
+                .leftover_recipient
+                .as_ref()
+                .filter(|account| account.key() == recipient)
+                .ok_or(PostProofError::InvalidLeftoverRecipient)?;
+            transfer_lamports(&config_info, account, leftover)?;
+            recipient
+        }
+        None => config.creator,
+    };
+
+    emit!(CampaignFinalized {
+        config: config.key(),
+        creator: config.creator,
+        recipient,
+        leftover,
+        donated: config.leftover_recipient.is_some(),
+    });
+    msg!(
+        "Closed campaign {:?}, {} leftover lamports to {}",
+        config.seeds,
+        leftover,
+        recipient
+    );
+
+    Ok(())
+}
@@ -0,0 +1,483 @@
+//! create_config: a new campaign and the validation of its rules
+
+use crate::*;
+use anchor_lang::prelude::*;
+use proof_of_post_common::{
+    MatchMode, MATCH_FLAGS_MASK, MATCH_FLAG_REGEX, MAX_AT_URI_LEN, MAX_DOMAIN_LEN,
+    MAX_REGEX_PATTERN_LEN, MAX_RULES_BUNDLE_LEN, MAX_SEGMENTS, MAX_SEGMENT_VALUE_LEN,
+    PLATFORM_BLUESKY, PLATFORM_FARCASTER, PLATFORM_LENS, PLATFORM_MASTODON, PLATFORM_REDDIT,
+    PLATFORM_TWITTER, PLATFORM_YOUTUBE, SEGMENT_HANDLE_SUFFIX, SEGMENT_LANGUAGE,
+};
+
+#[derive(Accounts)]
+#[instruction(args: CreateConfigArgs)]
+pub struct CreateConfig<'info> {
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [CONFIG_SEED, creator.key().as_ref(), args.seeds.as_bytes()],
+        bump,
+        space = 8 + PostProofConfig::INIT_SPACE,
+    )]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace)]
+pub struct CreateConfigArgs {
+    #[max_len(10)]
+    pub seeds: String,
+    #[max_len(20, 50)]
+    pub keywords: Vec<String>,
+    pub reward_amount: u64,
+    pub max_claimers: u64,
+    pub require_authorship: bool,
+    pub match_mode: u8,
+    pub match_threshold: u8,
+    #[max_len(10, 50)]
+    pub blocked_keywords: Vec<String>,
+    pub callback_version: u8,
+    pub match_flags: u8,
+    pub reward_split: RewardSplit,
+    pub platform_treasury: Pubkey,
+    #[max_len(5, 64)]
+    pub required_facets: Vec<String>,
+    #[max_len(200)]
+    pub metadata_uri: String,
+    pub rules_hash: [u8; 32],
+    pub rules_bundle_size: u64,
+    pub min_likes: u64,
+    pub min_reposts: u64,
+    pub min_replies: u64,
+    pub not_before_ts: i64,
+    pub not_after_ts: i64,
+    #[max_len(4, 8)]
+    pub required_langs: Vec<String>,
+    pub min_chars: u32,
+    pub min_words: u32,
+    pub min_images: u8,
+    #[max_len(64)]
+    pub external_domain: String,
+    #[max_len(5, 64)]
+    pub allowed_link_domains: Vec<String>,
+    pub time_mode: u8,
+    pub budget_alert_bps: u16,
+    pub budget_alert_lamports: u64,
+    #[max_len(128)]
+    pub reply_to_uri: String,
+    pub min_account_age_days: u32,
+    pub min_followers: u64,
+    #[max_len(8, 32)]
+    pub blocked_labels: Vec<String>,
+    pub max_likes_per_follower_bps: u32,
+    pub platform: u8,
+    #[max_len(4, 21)]
+    pub required_communities: Vec<String>,
+    pub terms_hash: [u8; 32],
+    #[max_len(4, 32)]
+    pub disclosure_tags: Vec<String>,
+    #[max_len(8, 64)]
+    pub allowed_author_ids: Vec<String>,
+    pub min_views: u64,
+    #[max_len(4)]
+    pub segments: Vec<SegmentArgs>,
+    pub response_signer: Option<Pubkey>,
+    pub reject_cpi: bool,
+    #[max_len(4)]
+    pub allowed_callers: Vec<Pubkey>,
+    pub leftover_recipient: Option<Pubkey>,
+    pub min_thread_posts: u8,
+    pub sponsor_costs: bool,
+    #[max_len(4, 64)]
+    pub allowed_post_hosts: Vec<String>,
+    pub dispute_window_slots: u64,
+    pub challenge_bond: u64,
+    pub challenger_share_bps: u16,
+    #[max_len(4)]
+    pub allowed_provers: Vec<Pubkey>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, Debug)]
+pub struct SegmentArgs {
+    pub kind: u8,
+    #[max_len(32)]
+    pub value: String,
+    pub reward_amount: u64,
+    pub max_claimers: u64,
+}
+
+impl SegmentArgs {
+    fn is_valid(&self) -> bool {
+        let value_valid = match self.kind {
+            SEGMENT_LANGUAGE => is_valid_language_tag(&self.value),
+            SEGMENT_HANDLE_SUFFIX => {
+                !self.value.is_empty()
+                    && self.value.len() <= MAX_SEGMENT_VALUE_LEN
+                    && self.value.bytes().all(|b| b.is_ascii_graphic())
+            }
+            _ => false,
+        };
+        value_valid && self.max_claimers > 0
+    }
+}
+
+pub(crate) fn handler(ctx: Context<CreateConfig>, args: CreateConfigArgs) -> Result<()> {
+    msg!("Creating PostProofConfig");
+
+    // With a rule bundle the keyword lists live off-chain, behind metadata_uri
+    let uses_rules_bundle = !args.metadata_uri.is_empty();
+    if uses_rules_bundle
+        && (args.rules_bundle_size == 0
+            || args.rules_bundle_size > MAX_RULES_BUNDLE_LEN as u64
+            || !args.keywords.is_empty()
+            || !args.blocked_keywords.is_empty()
+            || !args.required_facets.is_empty())
+    {
+        return Err(PostProofError::InvalidRulesBundle.into());
+    }
+
+    // Validate keyword match mode against the keyword list
+    match MatchMode::from_parts(args.match_mode, args.match_threshold) {
+        Some(MatchMode::AtLeast(n)) if !uses_rules_bundle && n as usize > args.keywords.len() => {
+            return Err(PostProofError::InvalidMatchMode.into());
+        }
+        Some(_) => {}
+        None => return Err(PostProofError::InvalidMatchMode.into()),
+    }
+    if args.match_flags & !MATCH_FLAGS_MASK != 0 {
+        return Err(PostProofError::InvalidMatchMode.into());
+    }
+
+    // Keywords travel comma-joined, and regex patterns are capped to bound guest cycles
+    let mut terms = args.keywords.iter().chain(&args.blocked_keywords);
+    if terms.clone().any(|k| k.contains(',')) {
+        return Err(PostProofError::InvalidKeyword.into());
+    }
+    if args.match_flags & MATCH_FLAG_REGEX != 0 && terms.any(|k| k.len() > MAX_REGEX_PATTERN_LEN) {
+        return Err(PostProofError::RegexPatternTooLong.into());
+    }
+    args.reward_split.validate(&args.platform_treasury)?;
+
+    // A transferred campaign keeps the address derived from its first creator, who
+    // can't take it back by creating it again
+    let creator = ctx.accounts.post_proof_config.creator;
+    if creator != Pubkey::default() && creator != ctx.accounts.creator.key() {
+        return Err(PostProofError::Unauthorized.into());
+    }
+    ctx.accounts.post_proof_config.creator = ctx.accounts.creator.key();
+    ctx.accounts.post_proof_config.seeds = args.seeds;
+    ctx.accounts.post_proof_config.keywords = args.keywords;
+    ctx.accounts.post_proof_config.blocked_keywords = args.blocked_keywords;
+    if args
+        .required_facets
+        .iter()
+        .any(|f| !(f.starts_with('#') || f.starts_with('@')) || f.len() < 2)
+    {
+        return Err(PostProofError::InvalidFacetRequirement.into());
+    }
+    ctx.accounts.post_proof_config.required_facets = args.required_facets;
+    callback_instruction_prefix(args.callback_version)?;
+    ctx.accounts.post_proof_config.callback_version = args.callback_version;
+    ctx.accounts.post_proof_config.match_mode = args.match_mode;
+    ctx.accounts.post_proof_config.match_threshold = args.match_threshold;
+    ctx.accounts.post_proof_config.match_flags = args.match_flags;
+    ctx.accounts.post_proof_config.reward_split = args.reward_split;
+    ctx.accounts.post_proof_config.platform_treasury = args.platform_treasury;
+    ctx.accounts.post_proof_config.metadata_uri = args.metadata_uri;
+    ctx.accounts.post_proof_config.rules_hash = args.rules_hash;
+    ctx.accounts.post_proof_config.rules_bundle_size = args.rules_bundle_size;
+    ctx.accounts.post_proof_config.min_likes = args.min_likes;
+    ctx.accounts.post_proof_config.min_reposts = args.min_reposts;
+    ctx.accounts.post_proof_config.min_replies = args.min_replies;
+    if args.not_before_ts < 0
+        || args.not_after_ts < 0
+        || (args.not_after_ts != 0 && args.not_after_ts < args.not_before_ts)
+    {
+        return Err(PostProofError::InvalidRecencyWindow.into());
+    }
+    ctx.accounts.post_proof_config.not_before_ts = args.not_before_ts;
+    ctx.accounts.post_proof_config.not_after_ts = args.not_after_ts;
+    if args
+        .required_langs
+        .iter()
+        .any(|lang| !is_valid_language_tag(lang))
+    {
+        return Err(PostProofError::InvalidLanguage.into());
+    }
+    ctx.accounts.post_proof_config.required_langs = args.required_langs;
+    ctx.accounts.post_proof_config.min_chars = args.min_chars;
+    ctx.accounts.post_proof_config.min_words = args.min_words;
+    if !args.external_domain.is_empty() && !is_valid_domain(&args.external_domain) {
+        return Err(PostProofError::InvalidDomain.into());
+    }
+    ctx.accounts.post_proof_config.min_images = args.min_images;
+    ctx.accounts.post_proof_config.external_domain = args.external_domain;
+    if args
+        .allowed_link_domains
+        .iter()
+        .any(|entry| !is_valid_link_allowlist_entry(entry))
+    {
+        return Err(PostProofError::InvalidDomain.into());
+    }
+    ctx.accounts.post_proof_config.allowed_link_domains = args.allowed_link_domains;
+    if !args.reply_to_uri.is_empty() && !is_valid_reply_target(args.platform, &args.reply_to_uri) {
+        return Err(PostProofError::InvalidReplyTarget.into());
+    }
+    ctx.accounts.post_proof_config.reply_to_uri = args.reply_to_uri;
+    ctx.accounts.post_proof_config.min_account_age_days = args.min_account_age_days;
+    ctx.accounts.post_proof_config.min_followers = args.min_followers;
+    if args
+        .blocked_labels
+        .iter()
+        .any(|label| !is_valid_label_value(label))
+    {
+        return Err(PostProofError::InvalidLabel.into());
+    }
+    ctx.accounts.post_proof_config.blocked_labels = args.blocked_labels;
+    ctx.accounts.post_proof_config.max_likes_per_follower_bps = args.max_likes_per_follower_bps;
+    if !matches!(
+        args.platform,
+        PLATFORM_BLUESKY
+            | PLATFORM_TWITTER
+            | PLATFORM_MASTODON
+            | PLATFORM_FARCASTER
+            | PLATFORM_LENS
+            | PLATFORM_REDDIT
+            | PLATFORM_YOUTUBE
+    ) {
+        return Err(PostProofError::InvalidPlatform.into());
+    }
+    ctx.accounts.post_proof_config.platform = args.platform;
+    // Follower checks on a platform without follower counts would fail every claim
+    if (args.min_followers > 0 || args.max_likes_per_follower_bps > 0)
+        && !reports_follower_counts(args.platform)
+    {
+        return Err(PostProofError::FollowerCountUnavailable.into());
+    }
+    if args
+        .required_communities
+        .iter()
+        .any(|name| !is_valid_community_name(name))
+    {
+        return Err(PostProofError::InvalidCommunity.into());
+    }
+    ctx.accounts.post_proof_config.required_communities = args.required_communities;
+    ctx.accounts.post_proof_config.terms_hash = args.terms_hash;
+    if args
+        .disclosure_tags
+        .iter()
+        .any(|tag| !is_valid_disclosure_tag(tag))
+    {
+        return Err(PostProofError::InvalidDisclosureTag.into());
+    }
+    ctx.accounts.post_proof_config.disclosure_tags = args.disclosure_tags;
+    if args
+        .allowed_author_ids
+        .iter()
+        .any(|id| !is_valid_author_id(id))
+    {
+        return Err(PostProofError::InvalidAuthorId.into());
+    }
+    ctx.accounts.post_proof_config.allowed_author_ids = args.allowed_author_ids;
+    ctx.accounts.post_proof_config.min_views = args.min_views;
+    if args.segments.len() > MAX_SEGMENTS || args.segments.iter().any(|segment| !segment.is_valid())
+    {
+        return Err(PostProofError::InvalidSegment.into());
+    }
+    ctx.accounts.post_proof_config.segments = args
+        .segments
+        .iter()
+        .map(|segment| Segment {
+            kind: segment.kind,
+            value: segment.value.clone(),
+            reward_amount: segment.reward_amount,
+            max_claimers: segment.max_claimers,
+            claimers_count: 0,
+        })
+        .collect();
+    ctx.accounts.post_proof_config.response_signer = args.response_signer;
+    if args.allowed_callers.len() > MAX_ALLOWED_CALLERS {
+        return Err(PostProofError::InvalidAllowedCallers.into());
+    }
+    ctx.accounts.post_proof_config.reject_cpi = args.reject_cpi;
+    ctx.accounts.post_proof_config.allowed_callers = args.allowed_callers;
+    ctx.accounts.post_proof_config.leftover_recipient = args.leftover_recipient;
+    if args.min_thread_posts > 0 && args.platform != PLATFORM_BLUESKY {
+        return Err(PostProofError::InvalidThreadMode.into());
+    }
+    ctx.accounts.post_proof_config.min_thread_posts = args.min_thread_posts;
+    ctx.accounts.post_proof_config.sponsor_costs = args.sponsor_costs;
+    if args.allowed_post_hosts.len() > MAX_POST_HOSTS
+        || !args
+            .allowed_post_hosts
+            .iter()
+            .all(|host| is_valid_domain(host))
+    {
+        return Err(PostProofError::InvalidPostHosts.into());
+    }
+    ctx.accounts.post_proof_config.allowed_post_hosts = args.allowed_post_hosts;
+    // A free challenge would let anyone stall every claim for the price of a proof
+    if args.challenger_share_bps as u64 > BPS_DENOMINATOR
+        || (args.dispute_window_slots > 0 && args.challenge_bond == 0)
+    {
+        return Err(PostProofError::InvalidDisputeSettings.into());
+    }
+    ctx.accounts.post_proof_config.dispute_window_slots = args.dispute_window_slots;
+    ctx.accounts.post_proof_config.challenge_bond = args.challenge_bond;
+    ctx.accounts.post_proof_config.challenger_share_bps = args.challenger_share_bps;
+    if args.allowed_provers.len() > MAX_ALLOWED_PROVERS {
+        return Err(PostProofError::InvalidAllowedProvers.into());
+    }
+    ctx.accounts.post_proof_config.allowed_provers = args.allowed_provers;
+    ctx.accounts.post_proof_config.bump = ctx.bumps.post_proof_config;
+    ctx.accounts.post_proof_config.version = PostProofConfig::VERSION;
+    ctx.accounts.post_proof_config.claimers_count = 0;
+    ctx.accounts.post_proof_config.total_paid = 0;
+    ctx.accounts.post_proof_config.total_views = 0;
+    ctx.accounts.post_proof_config.total_engagement = 0;
+    ctx.accounts.post_proof_config.sponsorship_budget = 0;
+    ctx.accounts.post_proof_config.sponsorship_max_per_request = 0;
+    ctx.accounts.post_proof_config.reward_amount = args.reward_amount;
+    ctx.accounts.post_proof_config.max_claimers = args.max_claimers;
+    ctx.accounts.post_proof_config.active = true;
+    ctx.accounts.post_proof_config.require_authorship = args.require_authorship;
+    ctx.accounts.post_proof_config.created_slot = current_slot()?;
+    ctx.accounts.post_proof_config.created_ts = current_timestamp()?;
+    if args.time_mode != TIME_MODE_SLOT && args.time_mode != TIME_MODE_TIMESTAMP {
+        return Err(PostProofError::InvalidTimeMode.into());
+    }
+    ctx.accounts.post_proof_config.time_mode = args.time_mode;
+    if args.budget_alert_bps as u64 > BPS_DENOMINATOR {
+        return Err(PostProofError::InvalidBudgetAlert.into());
+    }
+    ctx.accounts.post_proof_config.budget_alert_bps = args.budget_alert_bps;
+    ctx.accounts.post_proof_config.budget_alert_lamports = args.budget_alert_lamports;
+
+    // transfer initial funds to config account
+    let rent = Rent::get()?;
+    let min_balance =
+        rent.minimum_balance(ctx.accounts.post_proof_config.to_account_info().data_len());
+    // Fund every claim at the largest reward any segment can pay
+    let total_required = ctx
+        .accounts
+        .post_proof_config
+        .max_reward()
+        .checked_mul(args.max_claimers)
+        .and_then(|rewards| rewards.checked_add(min_balance))
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+
+    if total_required > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.post_proof_config.to_account_info(),
+                },
+            ),
+            total_required,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Language tags are a 2-3 letter primary subtag with an optional region ("en", "pt-BR")
+pub(crate) fn is_valid_language_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let region = parts.next();
+    (2..=3).contains(&primary.len())
+        && primary.bytes().all(|b| b.is_ascii_alphabetic())
+        && region.map_or(true, |r| {
+            (2..=4).contains(&r.len()) && r.bytes().all(|b| b.is_ascii_alphanumeric())
+        })
+        && parts.next().is_none()
+}
+
+/// Reply targets use the post identifier the guest derives for the platform: an AT URI
+/// on Bluesky, the status URL on X, the cast hash URI on Farcaster, the parent's
+/// fullname URI on Reddit. Mastodon responses don't identify the parent post, and
+/// YouTube videos are never replies.
+pub(crate) fn is_valid_reply_target(platform: u8, uri: &str) -> bool {
+    let prefix = match platform {
+        PLATFORM_TWITTER => "https://x.com/i/status/",
+        PLATFORM_FARCASTER => "farcaster://casts/",
+        PLATFORM_LENS => "lens://publications/",
+        PLATFORM_REDDIT => "reddit://",
+        PLATFORM_MASTODON | PLATFORM_YOUTUBE => return false,
+        _ => "at://",
+    };
+    uri.len() <= MAX_AT_URI_LEN && uri.starts_with(prefix)
+}
+
+/// Whether the guest gets the author's follower count from the platform's response.
+/// Bluesky's getPosts only embeds a basic profile without one, and Reddit and YouTube
+/// responses have none either.
+pub(crate) fn reports_follower_counts(platform: u8) -> bool {
+    !matches!(
+        platform,
+        PLATFORM_BLUESKY | PLATFORM_REDDIT | PLATFORM_YOUTUBE
+    )
+}
+
+/// Label values as Bluesky writes them: lowercase letters, digits and dashes, with a
+/// leading "!" for system labels ("spam", "!hide")
+pub(crate) fn is_valid_label_value(label: &str) -> bool {
+    let value = label.strip_prefix('!').unwrap_or(label);
+    !value.is_empty()
+        && label.len() <= 32
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// Subreddit name without the "r/" prefix: 2 to 21 letters, digits or underscores
+pub(crate) fn is_valid_community_name(name: &str) -> bool {
+    (2..=21).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// A single hashtag such as "#ad", which the guest compares as a whole tag
+pub(crate) fn is_valid_disclosure_tag(tag: &str) -> bool {
+    tag.len() <= 32
+        && tag.strip_prefix('#').is_some_and(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+/// Author ids as the guest derives them (DIDs, numeric ids, channel ids): printable
+/// ASCII without the comma that separates list entries
+pub(crate) fn is_valid_author_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.bytes().all(|b| b.is_ascii_graphic() && b != b',')
+}
+
+/// Plain lowercase hostname such as "example.com", as compared by the guest
+pub(crate) fn is_valid_domain(domain: &str) -> bool {
+    domain.len() <= MAX_DOMAIN_LEN
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        })
+}
+
+/// "domain" or "domain/path-prefix", with a path that can't break comma framing
+pub(crate) fn is_valid_link_allowlist_entry(entry: &str) -> bool {
+    let (domain, path) = match entry.find('/') {
+        Some(i) => entry.split_at(i),
+        None => (entry, ""),
+    };
+    entry.len() <= MAX_DOMAIN_LEN
+        && is_valid_domain(domain)
+        && path
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && b != b',' && b != b'?' && b != b'#')
+}
@@ -0,0 +1,48 @@
+//! fund_sponsorship: sets aside part of a campaign's vault for relayers
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct FundSponsorship<'info> {
+    #[account(mut, has_one = creator)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct FundSponsorshipArgs {
+    /// Lamports moved from the creator into the sponsorship budget, 0 to only change
+    /// the cap
+    pub deposit: u64,
+    pub max_per_request: u64,
+}
+
+pub(crate) fn handler(ctx: Context<FundSponsorship>, args: FundSponsorshipArgs) -> Result<()> {
+    msg!("Funding sponsorship with {} lamports", args.deposit);
+
+    if args.deposit > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.post_proof_config.to_account_info(),
+                },
+            ),
+            args.deposit,
+        )?;
+    }
+    let config = &mut ctx.accounts.post_proof_config;
+    config.sponsorship_budget = config
+        .sponsorship_budget
+        .checked_add(args.deposit)
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+    config.sponsorship_max_per_request = args.max_per_request;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! init_protocol: the global protocol config, set up once by the upgrade authority
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitProtocol<'info> {
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::ProofOfPost>,
+
+    // Only the program's upgrade authority may initialize the protocol config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ PostProofError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct InitProtocolArgs {
+    pub feature_flags: u64,
+    pub guardian: Pubkey,
+    pub breaker_window_slots: u64,
+    pub breaker_max_velocity_multiple: u16,
+    pub breaker_min_norm_lamports: u64,
+    pub min_tip: u64,
+    pub max_tip: u64,
+    pub max_post_size: u64,
+    pub journal_bounds: JournalBounds,
+    pub verify_cooldown_slots: u64,
+    pub verify_cooldown_secs: i64,
+    pub execution_expiry_slots: u64,
+    pub signature_fee_lamports: u64,
+}
+
+pub(crate) fn handler(ctx: Context<InitProtocol>, args: InitProtocolArgs) -> Result<()> {
+    msg!("Initializing ProtocolConfig");
+
+    ctx.accounts.protocol_config.authority = ctx.accounts.authority.key();
+    ctx.accounts.protocol_config.feature_flags = args.feature_flags;
+    ctx.accounts.protocol_config.guardian = args.guardian;
+    ctx.accounts.protocol_config.breaker = CircuitBreaker {
+        window_slots: args.breaker_window_slots,
+        max_velocity_multiple: args.breaker_max_velocity_multiple,
+        min_norm_lamports: args.breaker_min_norm_lamports,
+        window_start_slot: current_slot()?,
+        ..Default::default()
+    };
+    ctx.accounts.protocol_config.min_tip = args.min_tip;
+    ctx.accounts.protocol_config.max_tip = args.max_tip;
+    ctx.accounts.protocol_config.max_post_size = args.max_post_size;
+    ctx.accounts.protocol_config.journal_bounds = args.journal_bounds;
+    ctx.accounts.protocol_config.verify_cooldown_slots = args.verify_cooldown_slots;
+    ctx.accounts.protocol_config.verify_cooldown_secs = args.verify_cooldown_secs;
+    ctx.accounts.protocol_config.execution_expiry_slots = args.execution_expiry_slots;
+    ctx.accounts.protocol_config.signature_fee_lamports = args.signature_fee_lamports;
+    ctx.accounts.protocol_config.validate_params()?;
+    ctx.accounts.protocol_config.bump = ctx.bumps.protocol_config;
+    ctx.accounts.protocol_config.version = ProtocolConfig::VERSION;
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! migrate_config: rewrites a campaign config in the current layout
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut, owner = crate::id())]
+    /// CHECK: Decoded by hand, since a config in an older layout may not fit the
+    /// current one until it is resized
+    pub post_proof_config: UncheckedAccount<'info>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.post_proof_config.to_account_info();
+    resize_for_migration::<PostProofConfig>(
+        &config_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + PostProofConfig::INIT_SPACE,
+    )?;
+
+    let mut data = config_info.try_borrow_mut_data()?;
+    let mut config = PostProofConfig::try_deserialize(&mut &data[..])?;
+    if config.version >= PostProofConfig::VERSION {
+        return Err(PostProofError::AccountAlreadyMigrated.into());
+    }
+    msg!(
+        "Migrating config {} from version {} to {}",
+        config_info.key(),
+        config.version,
+        PostProofConfig::VERSION
+    );
+    config.version = PostProofConfig::VERSION;
+    config.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// Grows an account holding a `T` in an older layout to `space` bytes. Every layout so
+/// far only appended fields, which then decode as zero from the new tail. The payer
+/// covers the added rent so it doesn't come out of the account's own lamports.
+pub(crate) fn resize_for_migration<'info, T: Discriminator>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    if !account.try_borrow_data()?.starts_with(T::DISCRIMINATOR) {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    if account.data_len() >= space {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let added_rent = rent
+        .minimum_balance(space)
+        .saturating_sub(rent.minimum_balance(account.data_len()));
+    if added_rent > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            added_rent,
+        )?;
+    }
+    account.resize(space)?;
+    Ok(())
+}
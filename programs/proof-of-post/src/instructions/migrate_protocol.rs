@@ -0,0 +1,51 @@
+//! migrate_protocol: rewrites the protocol config in the current layout
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateProtocol<'info> {
+    #[account(mut, owner = crate::id(), seeds = [PROTOCOL_CONFIG_SEED], bump)]
+    /// CHECK: Decoded by hand, since a protocol config in an older layout doesn't fit
+    /// the current one until it is resized
+    pub protocol_config: UncheckedAccount<'info>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateProtocol>) -> Result<()> {
+    let protocol_info = ctx.accounts.protocol_config.to_account_info();
+    resize_for_migration::<ProtocolConfig>(
+        &protocol_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + ProtocolConfig::INIT_SPACE,
+    )?;
+
+    let mut data = protocol_info.try_borrow_mut_data()?;
+    let mut protocol_config = ProtocolConfig::try_deserialize(&mut &data[..])?;
+    if protocol_config.version >= ProtocolConfig::VERSION {
+        return Err(PostProofError::AccountAlreadyMigrated.into());
+    }
+    msg!(
+        "Migrating protocol config from version {} to {}",
+        protocol_config.version,
+        ProtocolConfig::VERSION
+    );
+    // Version 2 made the cooldowns, the execution expiry and the signature fee tunable
+    if protocol_config.version < 2 {
+        protocol_config.verify_cooldown_slots = DEFAULT_VERIFY_COOLDOWN_SLOTS;
+        protocol_config.verify_cooldown_secs = DEFAULT_VERIFY_COOLDOWN_SECS;
+        protocol_config.execution_expiry_slots = DEFAULT_EXECUTION_EXPIRY_SLOTS;
+        protocol_config.signature_fee_lamports = DEFAULT_SIGNATURE_FEE_LAMPORTS;
+    }
+    // Version 3 added the pause switch, which reads as off from the zeroed tail
+    protocol_config.version = ProtocolConfig::VERSION;
+    protocol_config.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+pub mod accept_protocol_authority;
+pub mod accept_transfer;
+pub mod bonsol_callback;
+pub mod challenge;
+pub mod check_eligibility;
+pub mod claim_reward;
+pub mod close_config;
+pub mod create_config;
+pub mod fund_sponsorship;
+pub mod init_protocol;
+pub mod migrate_config;
+pub mod migrate_protocol;
+pub mod propose_transfer;
+pub mod publish_campaign_summary;
+pub mod reset_circuit_breaker;
+pub mod sponsored_verify;
+pub mod transfer_protocol_authority;
+pub mod update_config;
+pub mod update_protocol;
+pub mod verify_post;
+
+pub use accept_protocol_authority::*;
+pub use accept_transfer::*;
+pub use bonsol_callback::*;
+pub use challenge::*;
+pub use check_eligibility::*;
+pub use claim_reward::*;
+pub use close_config::*;
+pub use create_config::*;
+pub use fund_sponsorship::*;
+pub use init_protocol::*;
+pub use migrate_config::*;
+pub use migrate_protocol::*;
+pub use propose_transfer::*;
+pub use publish_campaign_summary::*;
+pub use reset_circuit_breaker::*;
+pub use sponsored_verify::*;
+pub use transfer_protocol_authority::*;
+pub use update_config::*;
+pub use update_protocol::*;
+pub use verify_post::*;
@@ -0,0 +1,25 @@
+//! propose_transfer: the creator offers the campaign to a new creator
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeTransfer<'info> {
+    #[account(mut, has_one = creator)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    pub creator: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ProposeTransfer>, new_creator: Option<Pubkey>) -> Result<()> {
+    let config = &mut ctx.accounts.post_proof_config;
+    config.pending_creator = new_creator;
+
+    emit!(CampaignTransferProposed {
+        config: config.key(),
+        creator: config.creator,
+        new_creator,
+    });
+
+    Ok(())
+}
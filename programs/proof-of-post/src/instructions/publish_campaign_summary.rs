@@ -0,0 +1,77 @@
+//! publish_campaign_summary: a finished campaign's results, on-chain for good
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PublishCampaignSummary<'info> {
+    #[account(has_one = creator)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [CAMPAIGN_REGISTRY_SEED],
+        bump,
+        space = 8 + CampaignRegistry::INIT_SPACE,
+    )]
+    pub campaign_registry: Account<'info, CampaignRegistry>,
+
+    // One summary per campaign; publishing again fails on the existing account
+    #[account(
+        init,
+        payer = creator,
+        seeds = [CAMPAIGN_SUMMARY_SEED, post_proof_config.key().as_ref()],
+        bump,
+        space = 8 + CampaignSummary::INIT_SPACE,
+    )]
+    pub campaign_summary: Account<'info, CampaignSummary>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<PublishCampaignSummary>) -> Result<()> {
+    let config = &ctx.accounts.post_proof_config;
+    // Campaigns finish when they hit max claimers or the creator deactivates them
+    if config.active {
+        return Err(PostProofError::CampaignNotFinalized.into());
+    }
+
+    let registry = &mut ctx.accounts.campaign_registry;
+    let summary = &mut ctx.accounts.campaign_summary;
+    summary.config = config.key();
+    summary.creator = config.creator;
+    summary.index = registry.campaigns_count;
+    summary.platform = config.platform;
+    summary.claimers_count = config.claimers_count;
+    summary.max_claimers = config.max_claimers;
+    summary.total_paid = config.total_paid;
+    summary.total_views = config.total_views;
+    summary.total_engagement = config.total_engagement;
+    summary.created_ts = config.created_ts;
+    summary.published_ts = current_timestamp()?;
+    summary.bump = ctx.bumps.campaign_summary;
+    summary.version = CampaignSummary::VERSION;
+    registry.bump = ctx.bumps.campaign_registry;
+    registry.version = CampaignRegistry::VERSION;
+
+    registry.campaigns_count = registry
+        .campaigns_count
+        .checked_add(1)
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+    registry.total_claims = registry.total_claims.saturating_add(config.claimers_count);
+    registry.total_paid = registry.total_paid.saturating_add(config.total_paid);
+
+    msg!(
+        "Published summary #{} for campaign {:?}: {} claims, {} lamports paid",
+        summary.index,
+        config.seeds,
+        summary.claimers_count,
+        summary.total_paid
+    );
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! reset_circuit_breaker: the guardian resumes verifications after a trip
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ResetCircuitBreaker<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = guardian @ PostProofError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ResetCircuitBreaker>) -> Result<()> {
+    msg!("Resetting circuit breaker");
+
+    let breaker = &mut ctx.accounts.protocol_config.breaker;
+    breaker.tripped = false;
+    breaker.window_start_slot = current_slot()?;
+    breaker.window_spend = 0;
+
+    Ok(())
+}
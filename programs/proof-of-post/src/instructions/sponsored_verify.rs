@@ -0,0 +1,240 @@
+//! sponsored_verify: verify_post submitted by a relayer on a verifier's signed intent
+
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+use bonsol_anchor_interface::Bonsol;
+
+#[derive(Accounts)]
+#[instruction(args: SponsoredVerifyArgs)]
+pub struct SponsoredVerify<'info> {
+    #[account(mut)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    // Retries reuse the log, so the cooldown and pending checks see the last request
+    #[account(
+        init_if_needed,
+        space = 8 + PostVerificationLog::INIT_SPACE,
+        payer = relayer,
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump,
+    )]
+    pub post_verification_log: Account<'info, PostVerificationLog>,
+
+    #[account(mut)]
+    /// CHECK: Authorizes the request through the Ed25519-verified intent
+    pub verifier: UncheckedAccount<'info>,
+
+    /// Pays for the request and is reimbursed from the sponsorship budget
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub bonsol_program: Program<'info, Bonsol>,
+
+    #[account(
+        mut,
+        seeds = [args.verify.current_req_id.as_bytes()],
+        bump
+    )]
+    /// CHECK: This account will be initialized in the instruction if needed
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: This is the execution request account, derived from the relayer
+    pub execution_request: UncheckedAccount<'info>,
+
+    /// CHECK: This is the deployment account
+    pub deployment_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.creator)]
+    /// CHECK: Campaign creator, receives the creator share of rewards
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.platform_treasury)]
+    /// CHECK: Platform treasury, receives the platform share of rewards
+    pub platform_treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Referrer from the args, or the verifier when there is none
+    pub referrer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Payout address from the args, or the verifier when there is none
+    pub payout: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + AuthorClaim::INIT_SPACE,
+        payer = relayer,
+        seeds = [AUTHOR_CLAIM_SEED, post_proof_config.key().as_ref(), args.verify.author_did_hash.as_ref()],
+        bump,
+    )]
+    pub author_claim: Account<'info, AuthorClaim>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, read for the intent and terms signatures and CPI
+    /// caller checks
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(address = crate::id())]
+    /// CHECK: This is the current program address
+    pub post_proof_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SponsoredVerifyArgs {
+    pub verify: VerifyPostArgs,
+    /// Unix time after which the intent can't be submitted
+    pub expires_at: i64,
+    /// The verifier's signature over `sponsored_verify_message`
+    pub intent_signature: [u8; 64],
+}
+
+pub(crate) fn handler(ctx: Context<SponsoredVerify>, args: SponsoredVerifyArgs) -> Result<()> {
+    msg!(
+        "Processing sponsored_verify for post_url: {}",
+        args.verify.post_url
+    );
+    ctx.accounts
+        .protocol_config
+        .require_feature(FEATURE_SPONSORSHIP)?;
+
+    if current_timestamp()? > args.expires_at {
+        return Err(PostProofError::IntentExpired.into());
+    }
+    let message = sponsored_verify_message(
+        &ctx.accounts.post_proof_config.key(),
+        &args.verify,
+        args.expires_at,
+    );
+    if !has_ed25519_verification(
+        &ctx.accounts.instructions,
+        &ctx.accounts.verifier.key(),
+        &message,
+        &args.intent_signature,
+    )? {
+        return Err(PostProofError::InvalidIntentSignature.into());
+    }
+
+    // What the relayer fronts that it never gets back, measured before the request
+    // creates the accounts
+    let rent = Rent::get()?;
+    let log_rent = if ctx.accounts.post_verification_log.version == 0 {
+        rent.minimum_balance(8 + PostVerificationLog::INIT_SPACE)
+    } else {
+        0
+    };
+    let claim_rent = if ctx.accounts.author_claim.config == Pubkey::default() {
+        rent.minimum_balance(8 + AuthorClaim::INIT_SPACE)
+    } else {
+        0
+    };
+    // Campaigns sponsoring costs pay the tracker's rent and the sponsored part of the
+    // tip themselves
+    let sponsor_costs = ctx.accounts.post_proof_config.sponsor_costs;
+    let tracker_rent = if sponsor_costs {
+        0
+    } else {
+        rent.minimum_balance(8 + ExecutionTracker::INIT_SPACE)
+            .saturating_sub(ctx.accounts.requester.lamports())
+    };
+    let tip = args.verify.tip
+        - sponsored_tip(
+            &ctx.accounts.post_proof_config,
+            &ctx.accounts.protocol_config,
+            args.verify.tip,
+        );
+    let signatures = if args.verify.terms_signature.is_some() {
+        3
+    } else {
+        2
+    };
+    let reimbursement = tip
+        .saturating_add(log_rent)
+        .saturating_add(claim_rent)
+        .saturating_add(tracker_rent)
+        .saturating_add(
+            signatures.saturating_mul(ctx.accounts.protocol_config.signature_fee_lamports),
+        );
+    let config = &ctx.accounts.post_proof_config;
+    if reimbursement > config.sponsorship_max_per_request {
+        return Err(PostProofError::SponsorshipLimitExceeded.into());
+    }
+    if reimbursement > config.sponsorship_budget {
+        return Err(PostProofError::SponsorshipExhausted.into());
+    }
+
+    let relayer = ctx.accounts.relayer.to_account_info();
+    request_proof(
+        ProofRequest {
+            post_proof_config: &ctx.accounts.post_proof_config,
+            protocol_config: &ctx.accounts.protocol_config,
+            post_verification_log: &mut ctx.accounts.post_verification_log,
+            author_claim: &mut ctx.accounts.author_claim,
+            verifier: ctx.accounts.verifier.to_account_info(),
+            payer: relayer.clone(),
+            bonsol_program: ctx.accounts.bonsol_program.to_account_info(),
+            requester: ctx.accounts.requester.to_account_info(),
+            execution_request: ctx.accounts.execution_request.to_account_info(),
+            deployment_account: ctx.accounts.deployment_account.to_account_info(),
+            creator: ctx.accounts.creator.to_account_info(),
+            platform_treasury: ctx.accounts.platform_treasury.to_account_info(),
+            referrer: ctx.accounts.referrer.to_account_info(),
+            payout: ctx.accounts.payout.to_account_info(),
+            instructions: ctx.accounts.instructions.to_account_info(),
+            post_proof_program: ctx.accounts.post_proof_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            challenger: None,
+            requester_bump: ctx.bumps.requester,
+            log_bump: ctx.bumps.post_verification_log,
+            author_claim_bump: ctx.bumps.author_claim,
+        },
+        args.verify,
+    )?;
+
+    let config = &mut ctx.accounts.post_proof_config;
+    config.sponsorship_budget = config
+        .sponsorship_budget
+        .checked_sub(reimbursement)
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+    transfer_lamports(&config.to_account_info(), &relayer, reimbursement)?;
+    msg!(
+        "Reimbursed relayer {} with {} lamports, {} left for sponsorship",
+        relayer.key(),
+        reimbursement,
+        config.sponsorship_budget
+    );
+    emit!(SponsorReimbursed {
+        config: config.key(),
+        verifier: ctx.accounts.verifier.key(),
+        relayer: relayer.key(),
+        amount: reimbursement,
+        budget_left: config.sponsorship_budget,
+    });
+
+    Ok(())
+}
+
+/// The off-chain message a verifier signs to have a relayer submit `args` on the
+/// campaign at `config` through sponsored_verify, valid until unix time `expires_at`
+pub fn sponsored_verify_message(
+    config: &Pubkey,
+    args: &VerifyPostArgs,
+    expires_at: i64,
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    args.serialize(&mut data).expect("args serialize");
+    let args_hash = hash::hash(&data).to_bytes();
+    [
+        SPONSORED_INTENT_PREFIX,
+        config.as_ref(),
+        &args_hash,
+        &expires_at.to_le_bytes(),
+    ]
+    .concat()
+}
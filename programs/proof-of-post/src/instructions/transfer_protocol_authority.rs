@@ -0,0 +1,30 @@
+//! transfer_protocol_authority: the authority proposes its successor
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferProtocolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = authority @ PostProofError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<TransferProtocolAuthority>,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    match new_authority {
+        Some(new_authority) => msg!("Proposing {} as protocol authority", new_authority),
+        None => msg!("Withdrawing the pending protocol authority transfer"),
+    }
+    ctx.accounts.protocol_config.pending_authority = new_authority;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! update_config: the settings a creator can change on a live campaign
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        has_one = creator
+    )]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateConfigArgs {
+    pub active: Option<bool>,
+    pub max_claimers: Option<u64>,
+    pub reward_amount: Option<u64>,
+    pub callback_version: Option<u8>,
+    pub budget_alert_bps: Option<u16>,
+    pub budget_alert_lamports: Option<u64>,
+}
+
+pub(crate) fn handler(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
+    msg!("Updating PostProofConfig");
+
+    if let Some(active) = args.active {
+        ctx.accounts.post_proof_config.active = active;
+    }
+    if let Some(max_claimers) = args.max_claimers {
+        ctx.accounts.post_proof_config.max_claimers = max_claimers;
+    }
+    if let Some(reward_amount) = args.reward_amount {
+        ctx.accounts.post_proof_config.reward_amount = reward_amount;
+    }
+    if let Some(callback_version) = args.callback_version {
+        callback_instruction_prefix(callback_version)?;
+        ctx.accounts.post_proof_config.callback_version = callback_version;
+    }
+    if let Some(budget_alert_bps) = args.budget_alert_bps {
+        if budget_alert_bps as u64 > BPS_DENOMINATOR {
+            return Err(PostProofError::InvalidBudgetAlert.into());
+        }
+        ctx.accounts.post_proof_config.budget_alert_bps = budget_alert_bps;
+    }
+    if let Some(budget_alert_lamports) = args.budget_alert_lamports {
+        ctx.accounts.post_proof_config.budget_alert_lamports = budget_alert_lamports;
+    }
+
+    Ok(())
+}
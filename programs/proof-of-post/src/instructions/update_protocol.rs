@@ -0,0 +1,87 @@
+//! update_protocol: the authority's changes to the protocol config
+
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateProtocol<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = authority @ PostProofError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateProtocolArgs {
+    pub feature_flags: Option<u64>,
+    pub guardian: Option<Pubkey>,
+    pub breaker_window_slots: Option<u64>,
+    pub breaker_max_velocity_multiple: Option<u16>,
+    pub breaker_min_norm_lamports: Option<u64>,
+    pub min_tip: Option<u64>,
+    pub max_tip: Option<u64>,
+    pub max_post_size: Option<u64>,
+    pub journal_bounds: Option<JournalBounds>,
+    pub verify_cooldown_slots: Option<u64>,
+    pub verify_cooldown_secs: Option<i64>,
+    pub execution_expiry_slots: Option<u64>,
+    pub signature_fee_lamports: Option<u64>,
+    pub paused: Option<bool>,
+}
+
+pub(crate) fn handler(ctx: Context<UpdateProtocol>, args: UpdateProtocolArgs) -> Result<()> {
+    msg!("Updating ProtocolConfig");
+
+    if let Some(feature_flags) = args.feature_flags {
+        ctx.accounts.protocol_config.feature_flags = feature_flags;
+        msg!("Feature flags set to {:#b}", feature_flags);
+    }
+    if let Some(guardian) = args.guardian {
+        ctx.accounts.protocol_config.guardian = guardian;
+    }
+    if let Some(window_slots) = args.breaker_window_slots {
+        ctx.accounts.protocol_config.breaker.window_slots = window_slots;
+    }
+    if let Some(multiple) = args.breaker_max_velocity_multiple {
+        ctx.accounts.protocol_config.breaker.max_velocity_multiple = multiple;
+    }
+    if let Some(min_norm) = args.breaker_min_norm_lamports {
+        ctx.accounts.protocol_config.breaker.min_norm_lamports = min_norm;
+    }
+    if let Some(min_tip) = args.min_tip {
+        ctx.accounts.protocol_config.min_tip = min_tip;
+    }
+    if let Some(max_tip) = args.max_tip {
+        ctx.accounts.protocol_config.max_tip = max_tip;
+    }
+    if let Some(max_post_size) = args.max_post_size {
+        ctx.accounts.protocol_config.max_post_size = max_post_size;
+    }
+    if let Some(journal_bounds) = args.journal_bounds {
+        ctx.accounts.protocol_config.journal_bounds = journal_bounds;
+    }
+    if let Some(cooldown_slots) = args.verify_cooldown_slots {
+        ctx.accounts.protocol_config.verify_cooldown_slots = cooldown_slots;
+    }
+    if let Some(cooldown_secs) = args.verify_cooldown_secs {
+        ctx.accounts.protocol_config.verify_cooldown_secs = cooldown_secs;
+    }
+    if let Some(expiry_slots) = args.execution_expiry_slots {
+        ctx.accounts.protocol_config.execution_expiry_slots = expiry_slots;
+    }
+    if let Some(signature_fee) = args.signature_fee_lamports {
+        ctx.accounts.protocol_config.signature_fee_lamports = signature_fee;
+    }
+    if let Some(paused) = args.paused {
+        ctx.accounts.protocol_config.paused = paused;
+        msg!("Protocol {}", if paused { "paused" } else { "resumed" });
+    }
+    ctx.accounts.protocol_config.validate_params()?;
+
+    Ok(())
+}
@@ -0,0 +1,844 @@
+//! verify_post: checks a verification request and submits it to Bonsol. The
+//! request logic is shared with sponsored_verify.
+
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::{ed25519_program, hash};
+use bonsol_anchor_interface::instructions::{
+    execute_v1, CallbackConfig, ExecutionConfig, InputRef,
+};
+use bonsol_anchor_interface::Bonsol;
+use proof_of_post_common::{
+    encode_public_input, AuthorRequirements, ContentRequirements, EngagementThresholds, MatchMode,
+    MediaRequirement, PublicInput, RecencyWindow, SegmentRule, ENCODING_DEFLATE,
+};
+
+#[derive(Accounts)]
+#[instruction(args: VerifyPostArgs)]
+pub struct VerifyPost<'info> {
+    #[account(mut)]
+    pub post_proof_config: Account<'info, PostProofConfig>,
+
+    // Retries reuse the log, so the cooldown and pending checks see the last request
+    #[account(
+        init_if_needed,
+        space = 8 + PostVerificationLog::INIT_SPACE,
+        payer = verifier,
+        seeds = [VERIFICATION_LOG_SEED, verifier.key().as_ref(), post_proof_config.key().as_ref()],
+        bump,
+    )]
+    pub post_verification_log: Account<'info, PostVerificationLog>,
+
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+
+    pub bonsol_program: Program<'info, Bonsol>,
+
+    #[account(
+        mut,
+        seeds = [args.current_req_id.as_bytes()],
+        bump
+    )]
+    /// CHECK: This account will be initialized in the instruction if needed
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: This is the execution request account
+    pub execution_request: UncheckedAccount<'info>,
+
+    /// CHECK: This is the deployment account
+    pub deployment_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.creator)]
+    /// CHECK: Campaign creator, receives the creator share of rewards
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut, address = post_proof_config.platform_treasury)]
+    /// CHECK: Platform treasury, receives the platform share of rewards
+    pub platform_treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Referrer from the args, or the verifier when there is none
+    pub referrer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Payout address from the args, or the verifier when there is none
+    pub payout: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + AuthorClaim::INIT_SPACE,
+        payer = verifier,
+        seeds = [AUTHOR_CLAIM_SEED, post_proof_config.key().as_ref(), args.author_did_hash.as_ref()],
+        bump,
+    )]
+    pub author_claim: Account<'info, AuthorClaim>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, read for the terms signature and CPI caller checks
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(address = crate::id())]
+    /// CHECK: This is the current program address
+    pub post_proof_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct VerifyPostArgs {
+    pub current_req_id: String,
+    pub post_url: String,
+    pub post_size: u64,
+    pub tip: u64,
+    pub referrer: Option<Pubkey>,
+    /// SHA-256 over all inputs in order (public input, rule bundle, response signature,
+    /// URL response). When set, Bonsol verifies it and the callback cross-checks the
+    /// committed digest.
+    pub input_hash: Option<[u8; 32]>,
+    /// Pay the claimer share here instead of to the verifier
+    pub payout: Option<Pubkey>,
+    /// sha256 of the post author's DID, which the guest proves
+    pub author_did_hash: [u8; 32],
+    /// Signature over `terms_message`, required when the campaign has terms
+    pub terms_signature: Option<[u8; 64]>,
+    /// The response signer's signature over `response_signature_message` for the
+    /// response at `post_url`, required when the campaign has a response signer
+    pub response_signature: Option<[u8; 64]>,
+    /// `ENCODING_*` the response is shipped in; `post_size` counts the encoded bytes
+    pub response_encoding: u8,
+    /// Nonce `current_req_id` was derived with by `derive_request_id`, checked when set
+    pub request_nonce: Option<u64>,
+}
+
+pub(crate) fn handler(ctx: Context<VerifyPost>, args: VerifyPostArgs) -> Result<()> {
+    msg!("Processing verify_post for post_url: {}", args.post_url);
+
+    let verifier = ctx.accounts.verifier.to_account_info();
+    request_proof(
+        ProofRequest {
+            post_proof_config: &ctx.accounts.post_proof_config,
+            protocol_config: &ctx.accounts.protocol_config,
+            post_verification_log: &mut ctx.accounts.post_verification_log,
+            author_claim: &mut ctx.accounts.author_claim,
+            verifier: verifier.clone(),
+            payer: verifier,
+            bonsol_program: ctx.accounts.bonsol_program.to_account_info(),
+            requester: ctx.accounts.requester.to_account_info(),
+            execution_request: ctx.accounts.execution_request.to_account_info(),
+            deployment_account: ctx.accounts.deployment_account.to_account_info(),
+            creator: ctx.accounts.creator.to_account_info(),
+            platform_treasury: ctx.accounts.platform_treasury.to_account_info(),
+            referrer: ctx.accounts.referrer.to_account_info(),
+            payout: ctx.accounts.payout.to_account_info(),
+            instructions: ctx.accounts.instructions.to_account_info(),
+            post_proof_program: ctx.accounts.post_proof_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            challenger: None,
+            requester_bump: ctx.bumps.requester,
+            log_bump: ctx.bumps.post_verification_log,
+            author_claim_bump: ctx.bumps.author_claim,
+        },
+        args,
+    )
+}
+
+/// Request IDs seed the requester PDA, so they must fit in a seed and be hard to guess
+/// ahead of time, otherwise others can squat on them
+pub fn validate_request_id(req_id: &str) -> Result<()> {
+    if req_id.len() < MIN_REQUEST_ID_LEN || req_id.len() > MAX_REQUEST_ID_LEN {
+        return Err(PostProofError::InvalidRequestId.into());
+    }
+    if !req_id
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return Err(PostProofError::InvalidRequestId.into());
+    }
+    let mut seen = [false; 128];
+    let distinct = req_id
+        .bytes()
+        .filter(|&b| !std::mem::replace(&mut seen[b as usize], true))
+        .count();
+    if distinct < MIN_REQUEST_ID_DISTINCT_CHARS {
+        return Err(PostProofError::InvalidRequestId.into());
+    }
+    Ok(())
+}
+
+/// Request ID for `verifier` proving the post at the URL hashing to `post_url_hash` for
+/// `config`, as 32 base64url characters. With a random nonce it is as hard to guess as
+/// any other ID, while the verifier can re-derive it and verify_post can check it was
+/// made for this request when given the nonce.
+pub fn derive_request_id(
+    verifier: &Pubkey,
+    config: &Pubkey,
+    post_url_hash: &[u8; 32],
+    nonce: u64,
+) -> String {
+    const BASE64URL: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let digest = hash::hashv(&[
+        REQUEST_ID_DOMAIN,
+        verifier.as_ref(),
+        config.as_ref(),
+        post_url_hash,
+        &nonce.to_le_bytes(),
+    ])
+    .to_bytes();
+    // 24 digest bytes make exactly MAX_REQUEST_ID_LEN characters
+    digest[..24]
+        .chunks(3)
+        .flat_map(|chunk| {
+            let bits = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+            [18, 12, 6, 0].map(|shift| BASE64URL[((bits >> shift) & 63) as usize] as char)
+        })
+        .collect()
+}
+
+/// Post URLs must fit in the verification log and be fetched over TLS from a plain
+/// hostname, with no port or credentials
+pub fn validate_post_url(post_url: &str) -> Result<()> {
+    if post_url.len() > MAX_POST_URL_LEN
+        || post_url.bytes().any(|b| !b.is_ascii_graphic())
+        || !post_url_host(post_url).is_some_and(is_valid_domain)
+    {
+        return Err(PostProofError::InvalidPostUrl.into());
+    }
+    Ok(())
+}
+
+/// Host of an https URL
+pub(crate) fn post_url_host(post_url: &str) -> Option<&str> {
+    let rest = post_url.strip_prefix("https://")?;
+    Some(&rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())])
+}
+
+/// The form of `post_url` verify_post stores and hashes, so trivially different URLs
+/// for the same post are one request: a lowercase host, no fragment, and the query's
+/// non-empty parameters sorted without repeats. Clients deriving request IDs hash
+/// this form too.
+pub fn canonical_post_url(post_url: &str) -> String {
+    let Some(rest) = post_url.strip_prefix("https://") else {
+        return post_url.to_string();
+    };
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = location.split_at(location.find('/').unwrap_or(location.len()));
+    let mut params: Vec<&str> = query.split('&').filter(|param| !param.is_empty()).collect();
+    params.sort_unstable();
+    params.dedup();
+    let mut canonical = format!("https://{}{}", host.to_ascii_lowercase(), path);
+    if !params.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&params.join("&"));
+    }
+    canonical
+}
+
+/// The off-chain message a claimer signs to accept a campaign's terms
+pub fn terms_message(config: &Pubkey, terms_hash: &[u8; 32]) -> Vec<u8> {
+    [TERMS_MESSAGE_PREFIX, config.as_ref(), terms_hash].concat()
+}
+
+/// Program of the transaction-level instruction being executed: this program when
+/// called directly, the outermost wrapper when reached through CPI
+pub(crate) fn outer_program(instructions: &AccountInfo) -> Result<Pubkey> {
+    let current = load_current_index_checked(instructions)? as usize;
+    Ok(load_instruction_at_checked(current, instructions)?.program_id)
+}
+
+/// Whether an instruction before the current one is an Ed25519 program instruction
+/// checking exactly `signature` by `signer` over `message`. The runtime rejects the
+/// transaction if that signature is invalid, so finding it is enough. Sponsored
+/// requests carry two, for the intent and the terms.
+pub(crate) fn has_ed25519_verification(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<bool> {
+    let current = load_current_index_checked(instructions)? as usize;
+    for index in (0..current).rev() {
+        let ix = load_instruction_at_checked(index, instructions)?;
+        if ix.program_id == ed25519_program::ID
+            && verifies_signature(&ix.data, signer, message, signature)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether Ed25519 program instruction data checks exactly `signature` by `signer`
+/// over `message`
+pub(crate) fn verifies_signature(
+    data: &[u8],
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> bool {
+    // A single signature: count and padding, then seven u16 offsets
+    if data.len() < 16 || data[0] != 1 {
+        return false;
+    }
+    let offset = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let (signature_at, key_at, message_at, message_len) =
+        (offset(2), offset(6), offset(10), offset(12));
+    // Every offset must point into this instruction's own data
+    if [offset(4), offset(8), offset(14)]
+        .iter()
+        .any(|&ix_index| ix_index != u16::MAX as usize)
+    {
+        return false;
+    }
+    data.get(signature_at..signature_at + 64) == Some(signature.as_slice())
+        && data.get(key_at..key_at + 32) == Some(signer.as_ref())
+        && data.get(message_at..message_at + message_len) == Some(message)
+}
+
+/// The accounts verify_post, sponsored_verify and challenge take, and who pays for the
+/// request. The payer funds the execution tracker and the Bonsol request, and is its
+/// requester on Bonsol.
+pub(crate) struct ProofRequest<'a, 'info> {
+    pub(crate) post_proof_config: &'a Account<'info, PostProofConfig>,
+    pub(crate) protocol_config: &'a Account<'info, ProtocolConfig>,
+    pub(crate) post_verification_log: &'a mut Account<'info, PostVerificationLog>,
+    pub(crate) author_claim: &'a mut Account<'info, AuthorClaim>,
+    pub(crate) verifier: AccountInfo<'info>,
+    pub(crate) payer: AccountInfo<'info>,
+    pub(crate) bonsol_program: AccountInfo<'info>,
+    pub(crate) requester: AccountInfo<'info>,
+    pub(crate) execution_request: AccountInfo<'info>,
+    pub(crate) deployment_account: AccountInfo<'info>,
+    pub(crate) creator: AccountInfo<'info>,
+    pub(crate) platform_treasury: AccountInfo<'info>,
+    pub(crate) referrer: AccountInfo<'info>,
+    pub(crate) payout: AccountInfo<'info>,
+    pub(crate) instructions: AccountInfo<'info>,
+    pub(crate) post_proof_program: AccountInfo<'info>,
+    pub(crate) system_program: AccountInfo<'info>,
+    /// Challenger of a re-verification, passed to the callback after its accounts
+    pub(crate) challenger: Option<AccountInfo<'info>>,
+    pub(crate) requester_bump: u8,
+    pub(crate) log_bump: u8,
+    pub(crate) author_claim_bump: u8,
+}
+
+/// The part of `tip` the vault of a campaign with `sponsor_costs` pays back: up to the
+/// protocol's minimum tip, which provers take requests for. A verifier tipping more for
+/// a faster proof pays the rest, so the tip can't drain the budget.
+pub(crate) fn sponsored_tip(
+    config: &PostProofConfig,
+    protocol_config: &ProtocolConfig,
+    tip: u64,
+) -> u64 {
+    if !config.sponsor_costs {
+        return 0;
+    }
+    tip.min(protocol_config.min_tip)
+}
+
+/// What a request costs the vault of a campaign with `sponsor_costs`: the sponsored part
+/// of the tip and the execution tracker's rent
+pub(crate) fn sponsored_costs(
+    config: &PostProofConfig,
+    protocol_config: &ProtocolConfig,
+    args: &VerifyPostArgs,
+) -> Result<u64> {
+    if !config.sponsor_costs {
+        return Ok(0);
+    }
+    let tracker_rent = Rent::get()?.minimum_balance(8 + ExecutionTracker::INIT_SPACE);
+    Ok(sponsored_tip(config, protocol_config, args.tip).saturating_add(tracker_rent))
+}
+
+/// Checks an authorized verification request and submits it to Bonsol
+pub(crate) fn request_proof(mut request: ProofRequest, mut args: VerifyPostArgs) -> Result<()> {
+    args.post_url = canonical_post_url(&args.post_url);
+    let slot = current_slot()?;
+    let timestamp = current_timestamp()?;
+
+    // A log created by this instruction has no earlier request to check against
+    let existing_log =
+        (request.post_verification_log.version != 0).then_some(&**request.post_verification_log);
+    check_verify_preconditions(
+        request.post_proof_config,
+        request.protocol_config,
+        existing_log,
+        Some(&*request.author_claim),
+        &request.verifier.key(),
+        &args,
+        slot,
+        timestamp,
+    )?;
+    request.author_claim.config = request.post_proof_config.key();
+    request.author_claim.author_did_hash = args.author_did_hash;
+    request.author_claim.bump = request.author_claim_bump;
+    request.author_claim.version = AuthorClaim::VERSION;
+
+    // A wrapper program can sandwich the claim with its own fee extraction, so
+    // campaigns that opt in only accept CPI from the programs they list
+    let caller = outer_program(&request.instructions)?;
+    if caller != crate::id() {
+        msg!("verify_post invoked through CPI by {}", caller);
+        let config = request.post_proof_config;
+        if config.reject_cpi && !config.allowed_callers.contains(&caller) {
+            return Err(PostProofError::UnauthorizedCaller.into());
+        }
+    }
+
+    // The terms signature must have been checked by the Ed25519 program earlier in
+    // this transaction; the log keeps it as the claimer's acceptance record
+    let terms_signature = match args.terms_signature {
+        Some(signature) if request.post_proof_config.requires_terms() => {
+            let message = terms_message(
+                &request.post_proof_config.key(),
+                &request.post_proof_config.terms_hash,
+            );
+            if !has_ed25519_verification(
+                &request.instructions,
+                &request.verifier.key(),
+                &message,
+                &signature,
+            )? {
+                return Err(PostProofError::InvalidTermsSignature.into());
+            }
+            Some(signature)
+        }
+        _ => None,
+    };
+
+    // Referrer and payout must match the passed accounts
+    if request.referrer.key() != args.referrer.unwrap_or(request.verifier.key()) {
+        return Err(PostProofError::InvalidReferrer.into());
+    }
+
+    // The verifier's signature on this instruction authorizes paying the claimer
+    // share to a different wallet
+    if request.payout.key() != args.payout.unwrap_or(request.verifier.key()) {
+        return Err(PostProofError::InvalidPayoutAddress.into());
+    }
+
+    // Campaigns sponsoring costs pre-fund the tracker from the vault, which then
+    // takes the pre-funded path when the tracker is created
+    if request.post_proof_config.sponsor_costs && request.requester.owner != &crate::id() {
+        let required_lamports = Rent::get()?.minimum_balance(8 + ExecutionTracker::INIT_SPACE);
+        let shortfall = required_lamports.saturating_sub(request.requester.lamports());
+        transfer_lamports(
+            &request.post_proof_config.to_account_info(),
+            &request.requester,
+            shortfall,
+        )?;
+    }
+
+    submit_proof(&mut request, &args, timestamp)?;
+
+    // Bonsol takes the tip from its payer, so the vault pays back its sponsored part
+    if request.post_proof_config.sponsor_costs {
+        transfer_lamports(
+            &request.post_proof_config.to_account_info(),
+            &request.payer,
+            sponsored_tip(request.post_proof_config, request.protocol_config, args.tip),
+        )?;
+    }
+
+    request.post_verification_log.verifier = request.verifier.key();
+    request.post_verification_log.post_url = args.post_url.clone();
+    request.post_verification_log.config = request.post_proof_config.key();
+    request.post_verification_log.referrer = args.referrer;
+    request.post_verification_log.payout = args.payout;
+    request.post_verification_log.bump = request.log_bump;
+    request.post_verification_log.version = PostVerificationLog::VERSION;
+    request.post_verification_log.input_hash = args.input_hash;
+    request.post_verification_log.author_did_hash = args.author_did_hash;
+    request.post_verification_log.terms_signature = terms_signature;
+    request.post_verification_log.requested_slot = slot;
+
+    emit!(VerificationRequested {
+        config: request.post_proof_config.key(),
+        verifier: request.verifier.key(),
+        execution_request: request.execution_request.key(),
+        slot,
+        expiry_slot: request.protocol_config.execution_expiry(slot)?,
+    });
+
+    Ok(())
+}
+
+/// Creates the execution tracker and submits the request to Bonsol, proving the post
+/// at `args.post_url` against the campaign's rules as of `timestamp`
+pub(crate) fn submit_proof(
+    request: &mut ProofRequest,
+    args: &VerifyPostArgs,
+    timestamp: i64,
+) -> Result<()> {
+    let slot = current_slot()?;
+    // Initialize requester account, signing with the canonical bump the account
+    // constraint already found. An existing program-owned tracker means the
+    // req_id was already used; a pre-funded system account is adopted instead of
+    // letting it block create_account.
+    let space = ExecutionTracker::INIT_SPACE + 8;
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let requester_seeds: &[&[u8]] = &[args.current_req_id.as_bytes(), &[request.requester_bump]];
+    if request.requester.lamports() == 0 {
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                request.system_program.to_account_info(),
+                anchor_lang::system_program::CreateAccount {
+                    from: request.payer.to_account_info(),
+                    to: request.requester.to_account_info(),
+                },
+                &[requester_seeds],
+            ),
+            required_lamports,
+            space as u64,
+            &crate::id(),
+        )?;
+    } else if request.requester.owner == &crate::id() {
+        return Err(PostProofError::RequestIdAlreadyUsed.into());
+    } else {
+        let shortfall = required_lamports.saturating_sub(request.requester.lamports());
+        if shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    request.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: request.payer.to_account_info(),
+                        to: request.requester.to_account_info(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        anchor_lang::system_program::allocate(
+            CpiContext::new_with_signer(
+                request.system_program.to_account_info(),
+                anchor_lang::system_program::Allocate {
+                    account_to_allocate: request.requester.to_account_info(),
+                },
+                &[requester_seeds],
+            ),
+            space as u64,
+        )?;
+        anchor_lang::system_program::assign(
+            CpiContext::new_with_signer(
+                request.system_program.to_account_info(),
+                anchor_lang::system_program::Assign {
+                    account_to_assign: request.requester.to_account_info(),
+                },
+                &[requester_seeds],
+            ),
+            &crate::id(),
+        )?;
+    }
+
+    msg!(
+        "Requesting proof of {} ({} bytes)",
+        args.post_url,
+        args.post_size
+    );
+
+    let public_input = encode_public_input(&PublicInput {
+        post_size: args.post_size,
+        keywords: request.post_proof_config.keywords.clone(),
+        blocked_keywords: request.post_proof_config.blocked_keywords.clone(),
+        required_facets: request.post_proof_config.required_facets.clone(),
+        match_mode: MatchMode::from_parts(
+            request.post_proof_config.match_mode,
+            request.post_proof_config.match_threshold,
+        )
+        .ok_or(PostProofError::InvalidMatchMode)?,
+        match_flags: request.post_proof_config.match_flags,
+        require_authorship: request.post_proof_config.require_authorship,
+        verifier: request.verifier.key().to_bytes(),
+        engagement: EngagementThresholds {
+            min_likes: request.post_proof_config.min_likes,
+            min_reposts: request.post_proof_config.min_reposts,
+            min_replies: request.post_proof_config.min_replies,
+            min_views: request.post_proof_config.min_views,
+        },
+        recency: RecencyWindow {
+            not_before_ts: request.post_proof_config.not_before_ts,
+            not_after_ts: request.post_proof_config.not_after_ts,
+        },
+        required_langs: request.post_proof_config.required_langs.clone(),
+        content: ContentRequirements {
+            min_chars: request.post_proof_config.min_chars,
+            min_words: request.post_proof_config.min_words,
+        },
+        media: MediaRequirement {
+            min_images: request.post_proof_config.min_images,
+            external_domain: request.post_proof_config.external_domain.clone(),
+        },
+        allowed_link_domains: request.post_proof_config.allowed_link_domains.clone(),
+        reply_to_uri: request.post_proof_config.reply_to_uri.clone(),
+        author: AuthorRequirements {
+            min_account_age_days: request.post_proof_config.min_account_age_days,
+            min_followers: request.post_proof_config.min_followers,
+            as_of_ts: timestamp,
+        },
+        blocked_labels: request.post_proof_config.blocked_labels.clone(),
+        platform: request.post_proof_config.platform,
+        required_communities: request.post_proof_config.required_communities.clone(),
+        disclosure_tags: request.post_proof_config.disclosure_tags.clone(),
+        allowed_author_ids: request.post_proof_config.allowed_author_ids.clone(),
+        segments: request
+            .post_proof_config
+            .segments
+            .iter()
+            .map(|segment| SegmentRule {
+                kind: segment.kind,
+                value: segment.value.clone(),
+            })
+            .collect(),
+        response_signer: request
+            .post_proof_config
+            .response_signer
+            .map(|signer| signer.to_bytes())
+            .unwrap_or_default(),
+        post_url_hash: hash::hash(args.post_url.as_bytes()).to_bytes(),
+        config: request.post_proof_config.key().to_bytes(),
+        response_encoding: args.response_encoding,
+        min_thread_posts: request.post_proof_config.min_thread_posts,
+        rules_bundle: request.post_proof_config.rules_bundle_ref(),
+    });
+
+    // The guest reads the rule bundle and the response signature, when there are
+    // any, before the post
+    let mut inputs = vec![InputRef::public(&public_input)];
+    if request.post_proof_config.rules_bundle_ref().is_some() {
+        inputs.push(InputRef::url(
+            request.post_proof_config.metadata_uri.as_bytes(),
+        ));
+    }
+    if request.post_proof_config.response_signer.is_some() {
+        let signature = args
+            .response_signature
+            .as_ref()
+            .ok_or(PostProofError::MissingResponseSignature)?;
+        inputs.push(InputRef::public(signature));
+    }
+    inputs.push(InputRef::url(args.post_url.as_bytes()));
+
+    let mut extra_accounts = vec![
+        AccountMeta::new(request.requester.key(), false),
+        AccountMeta::new(request.post_proof_config.key(), false),
+        AccountMeta::new(request.post_verification_log.key(), false),
+        AccountMeta::new(request.verifier.key(), false),
+        AccountMeta::new(request.creator.key(), false),
+        AccountMeta::new(request.platform_treasury.key(), false),
+        AccountMeta::new(request.referrer.key(), false),
+        AccountMeta::new(request.payout.key(), false),
+        AccountMeta::new(request.protocol_config.key(), false),
+        AccountMeta::new(request.author_claim.key(), false),
+        AccountMeta::new_readonly(request.instructions.key(), false),
+    ];
+    let mut account_infos = vec![
+        request.payer.to_account_info().clone(),
+        request.system_program.to_account_info().clone(),
+        request.execution_request.to_account_info().clone(),
+        request.bonsol_program.to_account_info().clone(),
+        request.deployment_account.to_account_info().clone(),
+        request.requester.to_account_info().clone(),
+        request.post_proof_config.to_account_info().clone(),
+        request.post_verification_log.to_account_info().clone(),
+        request.verifier.to_account_info().clone(),
+        request.creator.to_account_info().clone(),
+        request.platform_treasury.to_account_info().clone(),
+        request.referrer.to_account_info().clone(),
+        request.payout.to_account_info().clone(),
+        request.protocol_config.to_account_info().clone(),
+        request.author_claim.to_account_info().clone(),
+        request.instructions.to_account_info().clone(),
+        request.post_proof_program.to_account_info().clone(),
+    ];
+    // A challenge's callback settles the bond, so it needs the challenger too
+    if let Some(challenger) = &request.challenger {
+        extra_accounts.push(AccountMeta::new(challenger.key(), false));
+        account_infos.push(challenger.clone());
+    }
+
+    // Prepare Bonsol execution
+    let bonsol_ix = execute_v1(
+        &request.payer.key(),
+        &request.payer.key(),
+        POST_VERIFICATION_IMAGE_ID,
+        &args.current_req_id,
+        inputs,
+        args.tip,
+        request.protocol_config.execution_expiry(slot)?,
+        ExecutionConfig {
+            verify_input_hash: args.input_hash.is_some(),
+            input_hash: args.input_hash.as_ref().map(|hash| hash.as_slice()),
+            forward_output: true,
+        },
+        Some(CallbackConfig {
+            program_id: crate::id(),
+            instruction_prefix: callback_instruction_prefix(
+                request.post_proof_config.callback_version,
+            )?,
+            extra_accounts,
+        }),
+        None,
+    )
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    msg!("Invoking Bonsol execute_v1 CPI");
+    invoke(&bonsol_ix, &account_infos)?;
+    msg!("Bonsol execute_v1 CPI invoked");
+
+    // Store execution account reference in requester
+    let mut requester_data = request.requester.try_borrow_mut_data()?;
+    let tracker = ExecutionTracker {
+        execution_account: request.execution_request.key(),
+        processed: false,
+        version: ExecutionTracker::VERSION,
+    };
+
+    // Pack the ExecutionTracker data
+    let mut data = [0u8; ExecutionTracker::INIT_SPACE];
+    tracker.pack(&mut data)?;
+
+    // Write discriminator + data
+    requester_data[0..8].copy_from_slice(&ExecutionTracker::DISCRIMINATOR);
+    requester_data[8..8 + ExecutionTracker::INIT_SPACE].copy_from_slice(&data);
+
+    request.post_verification_log.current_execution_account = Some(request.execution_request.key());
+
+    Ok(())
+}
+
+/// Protocol bounds on a Bonsol request's tip and the size of the post it fetches
+pub(crate) fn check_request_limits(
+    protocol_config: &ProtocolConfig,
+    tip: u64,
+    post_size: u64,
+) -> Result<()> {
+    // Dust tips are never picked up by provers and leave requests to expire
+    if tip < protocol_config.min_tip {
+        return Err(PostProofError::TipTooLow.into());
+    }
+    if protocol_config.max_tip > 0 && tip > protocol_config.max_tip {
+        return Err(PostProofError::TipTooHigh.into());
+    }
+    if protocol_config.max_post_size > 0 && post_size > protocol_config.max_post_size {
+        return Err(PostProofError::PostTooLarge.into());
+    }
+    Ok(())
+}
+
+/// verify_post checks that don't depend on the accounts it creates or forwards to
+/// Bonsol, shared with check_eligibility
+pub(crate) fn check_verify_preconditions(
+    config: &Account<PostProofConfig>,
+    protocol_config: &ProtocolConfig,
+    log: Option<&PostVerificationLog>,
+    author_claim: Option<&AuthorClaim>,
+    verifier: &Pubkey,
+    args: &VerifyPostArgs,
+    slot: u64,
+    timestamp: i64,
+) -> Result<()> {
+    if protocol_config.paused {
+        return Err(PostProofError::ProtocolPaused.into());
+    }
+    // A tripped circuit breaker halts new verifications until the guardian resets it
+    if protocol_config.breaker.tripped {
+        return Err(PostProofError::CircuitBreakerTripped.into());
+    }
+
+    // Check if config is active
+    if !config.active {
+        return Err(PostProofError::ConfigNotActive.into());
+    }
+
+    // Check if max claimers reached
+    if config.claimers_count >= config.max_claimers {
+        return Err(PostProofError::MaxClaimersReached.into());
+    }
+
+    // Check if config has sufficient funds for reward. Payouts can't touch the lamports
+    // keeping the config rent exempt, the sponsorship budget or held rewards, so those
+    // aren't budget.
+    let config_info = config.to_account_info();
+    let reserved = config.reserved_lamports(Rent::get()?.minimum_balance(config_info.data_len()));
+    if config_info.lamports().saturating_sub(reserved)
+        < config
+            .max_reward()
+            .saturating_add(sponsored_costs(config, protocol_config, args)?)
+    {
+        return Err(PostProofError::InsufficientFunds.into());
+    }
+
+    check_request_limits(protocol_config, args.tip, args.post_size)?;
+
+    // Referrer can't be the verifier
+    if args.referrer == Some(*verifier) {
+        return Err(PostProofError::InvalidReferrer.into());
+    }
+
+    validate_request_id(&args.current_req_id)?;
+    validate_post_url(&args.post_url)?;
+    if !config.allowed_post_hosts.is_empty()
+        && !config
+            .allowed_post_hosts
+            .iter()
+            .any(|host| Some(host.as_str()) == post_url_host(&args.post_url))
+    {
+        return Err(PostProofError::PostHostNotAllowed.into());
+    }
+
+    // Derived request IDs are checked against the request they were made for
+    if let Some(nonce) = args.request_nonce {
+        let post_url_hash = hash::hash(args.post_url.as_bytes()).to_bytes();
+        if args.current_req_id != derive_request_id(verifier, &config.key(), &post_url_hash, nonce)
+        {
+            return Err(PostProofError::InvalidRequestId.into());
+        }
+    }
+
+    if config.requires_terms() && args.terms_signature.is_none() {
+        return Err(PostProofError::TermsNotAccepted.into());
+    }
+
+    if config.response_signer.is_some() && args.response_signature.is_none() {
+        return Err(PostProofError::MissingResponseSignature.into());
+    }
+
+    if args.response_encoding > ENCODING_DEFLATE {
+        return Err(PostProofError::InvalidResponseEncoding.into());
+    }
+
+    // Each Bluesky account earns at most one reward per campaign, whichever wallet
+    // submits its posts
+    if author_claim.is_some_and(|claim| claim.claimed) {
+        return Err(PostProofError::AuthorAlreadyClaimed.into());
+    }
+
+    // Rate limiting: prevent spam verifications
+    if let Some(log) = log {
+        // A held reward is settled against this log's request, so it can't be replaced
+        if log.held_reward > 0 {
+            return Err(PostProofError::RewardsHeld.into());
+        }
+        if log.is_verified {
+            return Err(PostProofError::AlreadyVerified.into());
+        }
+        if log.is_pending(protocol_config, slot) {
+            return Err(PostProofError::VerificationPending.into());
+        }
+        if !log.cooldown_elapsed(protocol_config, config.time_mode, slot, timestamp) {
+            return Err(PostProofError::VerificationTooFast.into());
+        }
+    }
+
+    Ok(())
+}
@@ -1,505 +1,258 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::sysvar;
-use anchor_lang::solana_program::sysvar::Sysvar;
-use bonsol_anchor_interface::instructions::{
-    execute_v1, CallbackConfig, ExecutionConfig, InputRef,
-};
-use bonsol_anchor_interface::Bonsol;
+use proof_of_post_common::OUTPUT_LEN;
 
-use anchor_lang::solana_program::program::invoke;
-use bonsol_anchor_interface::callback::handle_callback;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod state;
+
+pub use errors::*;
+pub use events::*;
+pub use instructions::*;
+pub use state::*;
 
 // Change this ID and make your own if you want to deploy to devnet
 declare_id!("5MQLTq2D5ZhUAc6TDoAMXfnMeA32bo5DUxYco5LDMKAA");
-const POST_VERIFICATION_IMAGE_ID: &str =
+/// Bonsol image ID of the post verification guest that verify_post requests proofs from
+pub const POST_VERIFICATION_IMAGE_ID: &str =
     "4de2a43da6e788efef9837b71e055b2bfd83d18ca1c32b93cf5bfff58662aaa5";
 
-#[error_code]
-pub enum PostProofError {
-    #[msg("Post verification request failed")]
-    PostVerificationRequestFailed,
-    #[msg("Verification too fast")]
-    VerificationTooFast,
-    #[msg("Invalid callback")]
-    InvalidCallback,
-    #[msg("Invalid output")]
-    InvalidOutput,
-    #[msg("Callback error")]
-    CallbackError,
-    #[msg("Config not active")]
-    ConfigNotActive,
-    #[msg("Max claimers reached")]
-    MaxClaimersReached,
-    #[msg("Insufficient funds")]
-    InsufficientFunds,
-}
+// Request IDs are PDA seeds (max 32 bytes) and need some minimum entropy
+const MIN_REQUEST_ID_LEN: usize = 16;
+const MAX_REQUEST_ID_LEN: usize = 32;
+const MIN_REQUEST_ID_DISTINCT_CHARS: usize = 8;
+// Post URLs are stored in the verification log
+const MAX_POST_URL_LEN: usize = 256;
+/// Outer programs a campaign can allow to invoke verify_post through CPI
+const MAX_ALLOWED_CALLERS: usize = 4;
+/// Hosts a campaign can restrict post URLs to
+const MAX_POST_HOSTS: usize = 4;
+/// Provers a campaign can restrict deliveries to
+const MAX_ALLOWED_PROVERS: usize = 4;
+
+// PDA seed prefixes
+pub const CONFIG_SEED: &[u8] = b"postproofconfig";
+pub const VERIFICATION_LOG_SEED: &[u8] = b"postverificationlog";
+pub const AUTHOR_CLAIM_SEED: &[u8] = b"authorclaim";
+pub const PROTOCOL_CONFIG_SEED: &[u8] = b"protocolconfig";
+pub const CAMPAIGN_REGISTRY_SEED: &[u8] = b"campaignregistry";
+pub const CAMPAIGN_SUMMARY_SEED: &[u8] = b"campaignsummary";
+
+/// Start of the off-chain message claimers sign to accept a campaign's terms, followed
+/// by the config address and the terms hash
+pub const TERMS_MESSAGE_PREFIX: &[u8] = b"proof-of-post terms acceptance:";
+/// Start of the off-chain message verifiers sign to have a relayer submit their request,
+/// followed by the config address, the hash of the request's args and the expiry
+pub const SPONSORED_INTENT_PREFIX: &[u8] = b"proof-of-post sponsored verification:";
+/// Domain separator hashed ahead of the inputs of `derive_request_id`
+pub const REQUEST_ID_DOMAIN: &[u8] = b"proof-of-post request id:";
+
+// Configs schedule windows either in slots or in wall-clock seconds
+pub const TIME_MODE_SLOT: u8 = 0;
+pub const TIME_MODE_TIMESTAMP: u8 = 1;
+// Defaults of the ProtocolConfig parameters below, filled in by migrate_protocol on
+// configs from before they were tunable.
+// Minimum gap between verifications by the same verifier, in each time mode (~40s)
+pub const DEFAULT_VERIFY_COOLDOWN_SLOTS: u64 = 100;
+pub const DEFAULT_VERIFY_COOLDOWN_SECS: i64 = 40;
+// Bonsol execution expiry is always slot-based
+pub const DEFAULT_EXECUTION_EXPIRY_SLOTS: u64 = 50_000;
+// Fee per transaction signature, Ed25519 program signatures included, which relayers
+// are reimbursed for on sponsored requests
+pub const DEFAULT_SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+// Reward splits are expressed in basis points of the reward amount
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Feature flags in ProtocolConfig, letting new subsystems ship dark
+pub const FEATURE_HOOKS: u64 = 1 << 0;
+pub const FEATURE_TOKEN_REWARDS: u64 = 1 << 1;
+pub const FEATURE_DISPUTES: u64 = 1 << 2;
+pub const FEATURE_SPONSORSHIP: u64 = 1 << 3;
+
+// Why a CampaignExhausted event was emitted
+pub const EXHAUSTED_MAX_CLAIMERS: u8 = 0;
+pub const EXHAUSTED_BUDGET: u8 = 1;
+
+// Callback data is the 32-byte input digest followed by the committed journal
+const INPUT_DIGEST_LEN: usize = 32;
+const MIN_CALLBACK_DATA_LEN: usize = INPUT_DIGEST_LEN + OUTPUT_LEN;
+const MAX_CALLBACK_DATA_LEN: usize = 1024;
 
 #[program]
 pub mod proof_of_post {
     use super::*;
 
     pub fn create_config(ctx: Context<CreateConfig>, args: CreateConfigArgs) -> Result<()> {
-        msg!("Creating PostProofConfig");
-
-        ctx.accounts.post_proof_config.creator = ctx.accounts.creator.key();
-        ctx.accounts.post_proof_config.seeds = args.seeds;
-        ctx.accounts.post_proof_config.keywords = args.keywords;
-        ctx.accounts.post_proof_config.claimers_count = 0;
-        ctx.accounts.post_proof_config.reward_amount = args.reward_amount;
-        ctx.accounts.post_proof_config.max_claimers = args.max_claimers;
-        ctx.accounts.post_proof_config.active = true;
-        ctx.accounts.post_proof_config.created_slot = sysvar::clock::Clock::get()?.slot;
-
-        // transfer initial funds to config account
-        let rent = Rent::get()?;
-        let min_balance =
-            rent.minimum_balance(ctx.accounts.post_proof_config.to_account_info().data_len());
-        let total_required = min_balance + args.reward_amount * args.max_claimers;
-
-        if total_required > 0 {
-            anchor_lang::system_program::transfer(
-                CpiContext::new(
-                    ctx.accounts.system_program.to_account_info(),
-                    anchor_lang::system_program::Transfer {
-                        from: ctx.accounts.creator.to_account_info(),
-                        to: ctx.accounts.post_proof_config.to_account_info(),
-                    },
-                ),
-                total_required,
-            )?;
-        }
-
-        Ok(())
+        create_config::handler(ctx, args)
     }
 
     pub fn update_config(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
-        msg!("Updating PostProofConfig");
-
-        if let Some(active) = args.active {
-            ctx.accounts.post_proof_config.active = active;
-        }
-        if let Some(max_claimers) = args.max_claimers {
-            ctx.accounts.post_proof_config.max_claimers = max_claimers;
-        }
-        if let Some(reward_amount) = args.reward_amount {
-            ctx.accounts.post_proof_config.reward_amount = reward_amount;
-        }
-
-        Ok(())
+        update_config::handler(ctx, args)
     }
 
     pub fn verify_post(ctx: Context<VerifyPost>, args: VerifyPostArgs) -> Result<()> {
-        msg!("Processing verify_post for post_url: {}", args.post_url);
-
-        // Check if config is active
-        if !ctx.accounts.post_proof_config.active {
-            return Err(PostProofError::ConfigNotActive.into());
-        }
-
-        // Check if max claimers reached
-        if ctx.accounts.post_proof_config.claimers_count
-            >= ctx.accounts.post_proof_config.max_claimers
-        {
-            return Err(PostProofError::MaxClaimersReached.into());
-        }
-
-        // Check if config has sufficient funds for reward
-        if ctx.accounts.post_proof_config.to_account_info().lamports()
-            < ctx.accounts.post_proof_config.reward_amount
-        {
-            return Err(PostProofError::InsufficientFunds.into());
-        }
-
-        // Expected requester PDA
-        let (expected_requester, _bump) =
-            Pubkey::find_program_address(&[args.current_req_id.as_bytes()], &crate::id());
-        if ctx.accounts.requester.key() != expected_requester {
-            return Err(PostProofError::PostVerificationRequestFailed.into());
-        }
-
-        let slot = sysvar::clock::Clock::get()?.slot;
-
-        // Rate limiting: prevent spam verifications
-        if slot - ctx.accounts.post_verification_log.slot < 100 {
-            return Err(PostProofError::VerificationTooFast.into());
-        }
-
-        // Initialize requester account if it doesn't exist
-        if ctx.accounts.requester.lamports() == 0 {
-            let space = ExecutionTracker::INIT_SPACE + 8;
-            let rent = Rent::get()?;
-            let lamports = rent.minimum_balance(space);
-
-            anchor_lang::system_program::create_account(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    anchor_lang::system_program::CreateAccount {
-                        from: ctx.accounts.verifier.to_account_info(),
-                        to: ctx.accounts.requester.to_account_info(),
-                    },
-                    &[&[args.current_req_id.as_bytes(), &[ctx.bumps.requester]]],
-                ),
-                lamports,
-                space as u64,
-                &crate::id(),
-            )?;
-        }
-
-        // Create comma-separated keywords string
-        let keywords_string = ctx.accounts.post_proof_config.keywords.join(",");
-        let keywords_bytes = keywords_string.as_bytes();
-
-        msg!(
-            "satyam123, post_size: {}, post_url: {}, keyword_string: {:?}",
-            args.post_size,
-            args.post_url,
-            keywords_string
-        );
-
-        // Build public input: [post_size(8)][keywords_size(8)][keywords_string]
-        let mut public_input = Vec::new();
-        public_input.extend_from_slice(&args.post_size.to_be_bytes());
-        public_input.extend_from_slice(&(keywords_bytes.len() as u64).to_be_bytes());
-        public_input.extend_from_slice(keywords_bytes);
-
-        // Prepare Bonsol execution
-        let bonsol_ix = execute_v1(
-            &ctx.accounts.verifier.key(),
-            &ctx.accounts.verifier.key(),
-            POST_VERIFICATION_IMAGE_ID,
-            &args.current_req_id,
-            vec![
-                InputRef::public(&public_input),
-                InputRef::url(args.post_url.as_bytes()),
-            ],
-            args.tip,
-            slot + 50000,
-            ExecutionConfig {
-                verify_input_hash: false,
-                input_hash: None,
-                forward_output: true,
-            },
-            Some(CallbackConfig {
-                program_id: crate::id(),
-                instruction_prefix: vec![181, 16, 138, 77, 227, 78, 167, 151], // bonsol_callback instruction discriminator
-                extra_accounts: vec![
-                    AccountMeta::new_readonly(ctx.accounts.requester.key(), false),
-                    AccountMeta::new(ctx.accounts.post_proof_config.key(), false),
-                    AccountMeta::new(ctx.accounts.post_verification_log.key(), false),
-                    AccountMeta::new(ctx.accounts.verifier.key(), false),
-                ],
-            }),
-            None,
-        )
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
-
-        msg!("Invoking Bonsol execute_v1 CPI");
-        invoke(
-            &bonsol_ix,
-            &[
-                ctx.accounts.verifier.to_account_info().clone(),
-                ctx.accounts.system_program.to_account_info().clone(),
-                ctx.accounts.execution_request.to_account_info().clone(),
-                ctx.accounts.bonsol_program.to_account_info().clone(),
-                ctx.accounts.deployment_account.to_account_info().clone(),
-                ctx.accounts.requester.to_account_info().clone(),
-                ctx.accounts.post_proof_config.to_account_info().clone(),
-                ctx.accounts.post_verification_log.to_account_info().clone(),
-                ctx.accounts.verifier.to_account_info().clone(),
-                ctx.accounts.post_proof_program.to_account_info().clone(),
-            ],
-        )?;
-        msg!("Bonsol execute_v1 CPI invoked");
-
-        // Store execution account reference in requester
-        let mut requester_data = ctx.accounts.requester.try_borrow_mut_data()?;
-        let tracker = ExecutionTracker {
-            execution_account: ctx.accounts.execution_request.key(),
-        };
-
-        // Pack the ExecutionTracker data
-        let mut data = [0u8; ExecutionTracker::INIT_SPACE];
-        tracker.pack(&mut data)?;
-
-        // Write discriminator + data
-        requester_data[0..8].copy_from_slice(&ExecutionTracker::DISCRIMINATOR);
-        requester_data[8..8 + ExecutionTracker::INIT_SPACE].copy_from_slice(&data);
-
-        ctx.accounts.post_verification_log.current_execution_account =
-            Some(ctx.accounts.execution_request.key());
-        ctx.accounts.post_verification_log.verifier = ctx.accounts.verifier.key();
-        ctx.accounts.post_verification_log.post_url = args.post_url.clone();
-        ctx.accounts.post_verification_log.config = ctx.accounts.post_proof_config.key();
-
-        Ok(())
+        verify_post::handler(ctx, args)
     }
 
-    pub fn bonsol_callback(ctx: Context<BonsolCallback>, data: [u8; 33]) -> Result<()> {
-        msg!("Processing bonsol_callback");
-        let slot = sysvar::clock::Clock::get()?.slot;
-
-        if let Some(epub) = ctx.accounts.post_verification_log.current_execution_account {
-            if ctx.accounts.execution_request.key() != epub {
-                msg!("Invalid execution request account");
-                return Err(PostProofError::InvalidCallback.into());
-            }
-
-            // Get execution account from requester data
-            let requester_data = ctx.accounts.requester.try_borrow_data()?;
-
-            // Skip discriminator and get ExecutionTracker data
-            if requester_data.len() < 8 + ExecutionTracker::INIT_SPACE {
-                msg!("Requester data too short");
-                return Err(PostProofError::InvalidCallback.into());
-            }
-
-            let tracker_data = &requester_data[8..8 + ExecutionTracker::INIT_SPACE];
-            let tracker = ExecutionTracker::unpack(tracker_data)?;
-            let execution_account = tracker.execution_account;
-            drop(requester_data);
-
-            let ainfos = ctx.accounts.to_account_infos();
-
-            let output = handle_callback(
-                POST_VERIFICATION_IMAGE_ID,
-                &execution_account,
-                &ainfos,
-                &data,
-            )
-            .map_err(|_| PostProofError::CallbackError)?;
-            msg!("Callback handled, output received");
-
-            // Extract boolean result from ZK proof output
-            let is_valid_post = if output.committed_outputs.len() > 0 {
-                output.committed_outputs[0] != 0
-            } else {
-                false
-            };
-
-            msg!("Post verification result: {}", is_valid_post);
-
-            // Update verification log
-            ctx.accounts.post_verification_log.slot = slot;
-            ctx.accounts.post_verification_log.is_verified = is_valid_post;
-            ctx.accounts.post_verification_log.current_execution_account = None;
-
-            // If post is valid, transfer reward and update stats
-            if is_valid_post {
-                // Transfer SOL reward to verifier
-                let reward_amount = ctx.accounts.post_proof_config.reward_amount;
-
-                **ctx
-                    .accounts
-                    .post_proof_config
-                    .to_account_info()
-                    .try_borrow_mut_lamports()? -= reward_amount;
-                **ctx
-                    .accounts
-                    .verifier
-                    .to_account_info()
-                    .try_borrow_mut_lamports()? += reward_amount;
-
-                // Update claimers count
-                ctx.accounts.post_proof_config.claimers_count += 1;
-
-                msg!(
-                    "Post verified for campaign {:?}! Transferred {} lamports to verifier. Total claimers: {}",
-                    ctx.accounts.post_proof_config.seeds,
-                    reward_amount,
-                    ctx.accounts.post_proof_config.claimers_count
-                );
-
-                // Deactivate config if max claimers reached
-                if ctx.accounts.post_proof_config.claimers_count
-                    >= ctx.accounts.post_proof_config.max_claimers
-                {
-                    ctx.accounts.post_proof_config.active = false;
-                    msg!("Config deactivated - max claimers reached for campaign {:?}", ctx.accounts.post_proof_config.seeds);
-                }
-            } else {
-                msg!("Post verification failed for campaign {:?}", ctx.accounts.post_proof_config.seeds);
-            }
-
-            Ok(())
-        } else {
-            Err(PostProofError::InvalidCallback.into())
-        }
+    /// verify_post submitted and paid for by a relayer, for verifiers without SOL. The
+    /// verifier authorizes the request by signing `sponsored_verify_message` off-chain,
+    /// checked by an Ed25519 instruction earlier in the transaction, and the relayer is
+    /// reimbursed from the campaign's sponsorship budget for the tip, the rent of the
+    /// accounts this program creates and the signature fees. Bonsol returns the
+    /// execution request's rent to the relayer as its requester.
+    pub fn sponsored_verify(
+        ctx: Context<SponsoredVerify>,
+        args: SponsoredVerifyArgs,
+    ) -> Result<()> {
+        sponsored_verify::handler(ctx, args)
     }
-}
 
-#[account]
-#[derive(InitSpace)]
-pub struct PostProofConfig {
-    pub creator: Pubkey,
-    #[max_len(10)]
-    pub seeds: String,
-    #[max_len(20, 50)]
-    pub keywords: Vec<String>,
-    pub claimers_count: u64,
-    pub reward_amount: u64,
-    pub max_claimers: u64,
-    pub active: bool,
-    pub created_slot: u64,
-}
+    /// Sets aside `deposit` more lamports of the campaign's vault for reimbursing
+    /// relayers, and caps what a single sponsored request may cost. The sponsorship
+    /// budget is kept out of the reward budget, and what is left of it returns to the
+    /// creator when the campaign closes.
+    pub fn fund_sponsorship(
+        ctx: Context<FundSponsorship>,
+        args: FundSponsorshipArgs,
+    ) -> Result<()> {
+        fund_sponsorship::handler(ctx, args)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct PostVerificationLog {
-    pub verifier: Pubkey,
-    pub config: Pubkey,
-    #[max_len(256)]
-    pub post_url: String,
-    pub slot: u64,
-    pub is_verified: bool,
-    pub current_execution_account: Option<Pubkey>,
-}
+    /// Runs the verify_post checks without creating accounts or calling Bonsol, so
+    /// frontends can simulate it before paying for a proof. Failures are returned as an
+    /// error code rather than failing the instruction.
+    pub fn check_eligibility(
+        ctx: Context<CheckEligibility>,
+        args: VerifyPostArgs,
+    ) -> Result<EligibilityResult> {
+        check_eligibility::handler(ctx, args)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct ExecutionTracker {
-    pub execution_account: Pubkey,
-}
+    /// Takes the journal exactly as the current output version lays it out
+    pub fn bonsol_callback(ctx: Context<BonsolCallback>, data: Vec<u8>) -> Result<()> {
+        msg!("Processing bonsol_callback");
+        bonsol_callback::handler(ctx, &data, CALLBACK_VERSION_V1)
+    }
 
-impl ExecutionTracker {
-    pub const INIT_SPACE: usize = 32; // Just the Pubkey size
+    /// Second registered callback entrypoint, selected per config via `callback_version`.
+    /// Takes journals of the current output version or later ones, which append fields
+    /// it skips, so campaigns can move to a new guest before the program learns its
+    /// fields.
+    pub fn bonsol_callback_v2(ctx: Context<BonsolCallback>, data: Vec<u8>) -> Result<()> {
+        msg!("Processing bonsol_callback_v2");
+        bonsol_callback::handler(ctx, &data, CALLBACK_VERSION_V2)
+    }
 
-    pub fn pack(&self, dst: &mut [u8]) -> Result<()> {
-        if dst.len() < Self::INIT_SPACE {
-            return Err(ProgramError::AccountDataTooSmall.into());
-        }
-        dst[0..32].copy_from_slice(&self.execution_account.to_bytes());
-        Ok(())
+    /// Disputes a reward held in its campaign's dispute window. The challenger posts the
+    /// campaign's bond and pays for a fresh Bonsol execution against the same post; if
+    /// it disagrees, the claim is voided and the challenger gets the bond back with a
+    /// share of the reward, otherwise the bond goes to the claimer.
+    pub fn challenge(ctx: Context<Challenge>, args: ChallengeArgs) -> Result<()> {
+        challenge::handler(ctx, args)
     }
 
-    pub fn unpack(src: &[u8]) -> Result<Self> {
-        if src.len() < Self::INIT_SPACE {
-            return Err(ProgramError::AccountDataTooSmall.into());
-        }
-        let execution_account = Pubkey::new_from_array([
-            src[0], src[1], src[2], src[3], src[4], src[5], src[6], src[7], src[8], src[9],
-            src[10], src[11], src[12], src[13], src[14], src[15], src[16], src[17], src[18],
-            src[19], src[20], src[21], src[22], src[23], src[24], src[25], src[26], src[27],
-            src[28], src[29], src[30], src[31],
-        ]);
-        Ok(Self { execution_account })
+    /// Pays a held reward once its dispute window has closed unchallenged. Anyone may
+    /// call it, since the reward only goes where the verification said.
+    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
+        claim_reward::handler(ctx)
     }
-}
 
-#[derive(AnchorDeserialize, AnchorSerialize, InitSpace)]
-pub struct CreateConfigArgs {
-    #[max_len(10)]
-    pub seeds: String,
-    #[max_len(20, 50)]
-    pub keywords: Vec<String>,
-    pub reward_amount: u64,
-    pub max_claimers: u64,
-}
+    pub fn init_protocol(ctx: Context<InitProtocol>, args: InitProtocolArgs) -> Result<()> {
+        init_protocol::handler(ctx, args)
+    }
 
-#[derive(AnchorDeserialize, AnchorSerialize)]
-pub struct UpdateConfigArgs {
-    pub active: Option<bool>,
-    pub max_claimers: Option<u64>,
-    pub reward_amount: Option<u64>,
-}
+    pub fn update_protocol(ctx: Context<UpdateProtocol>, args: UpdateProtocolArgs) -> Result<()> {
+        update_protocol::handler(ctx, args)
+    }
 
-#[derive(AnchorDeserialize, AnchorSerialize)]
-pub struct VerifyPostArgs {
-    pub current_req_id: String,
-    pub post_url: String,
-    pub post_size: u64,
-    pub tip: u64,
-}
+    /// First step of handing the protocol config to a new authority, which takes over
+    /// once it accepts. `None` withdraws a pending transfer.
+    pub fn transfer_protocol_authority(
+        ctx: Context<TransferProtocolAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        transfer_protocol_authority::handler(ctx, new_authority)
+    }
 
-#[derive(Accounts)]
-#[instruction(args: CreateConfigArgs)]
-pub struct CreateConfig<'info> {
-    #[account(
-        init_if_needed,
-        payer = creator,
-        seeds = [b"postproofconfig", creator.key().as_ref(), args.seeds.as_bytes()],
-        bump,
-        space = 8 + PostProofConfig::INIT_SPACE,
-    )]
-    pub post_proof_config: Account<'info, PostProofConfig>,
-
-    #[account(mut)]
-    pub creator: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    pub fn accept_protocol_authority(ctx: Context<AcceptProtocolAuthority>) -> Result<()> {
+        accept_protocol_authority::handler(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct UpdateConfig<'info> {
-    #[account(
-        mut,
-        has_one = creator
-    )]
-    pub post_proof_config: Account<'info, PostProofConfig>,
+    /// Upgrades the protocol config to the current layout, with the parameters it
+    /// didn't have yet at their defaults
+    pub fn migrate_protocol(ctx: Context<MigrateProtocol>) -> Result<()> {
+        migrate_protocol::handler(ctx)
+    }
 
-    pub creator: Signer<'info>,
-}
+    /// Guardian-only reset of a tripped circuit breaker, starting a fresh window
+    pub fn reset_circuit_breaker(ctx: Context<ResetCircuitBreaker>) -> Result<()> {
+        reset_circuit_breaker::handler(ctx)
+    }
 
-#[derive(Accounts)]
-#[instruction(args: VerifyPostArgs)]
-pub struct VerifyPost<'info> {
-    #[account(mut)]
-    pub post_proof_config: Account<'info, PostProofConfig>,
-
-    #[account(
-        init,
-        space = 8 + PostVerificationLog::INIT_SPACE,
-        payer = verifier,
-        seeds = [b"postverificationlog", verifier.key().as_ref(), post_proof_config.key().as_ref()],
-        bump,
-    )]
-    pub post_verification_log: Account<'info, PostVerificationLog>,
-
-    #[account(mut)]
-    pub verifier: Signer<'info>,
-
-    pub bonsol_program: Program<'info, Bonsol>,
-
-    #[account(
-        mut,
-        seeds = [args.current_req_id.as_bytes()],
-        bump
-    )]
-    /// CHECK: This account will be initialized in the instruction if needed
-    pub requester: UncheckedAccount<'info>,
-
-    #[account(mut)]
-    /// CHECK: This is the execution request account
-    pub execution_request: UncheckedAccount<'info>,
-
-    /// CHECK: This is the deployment account
-    pub deployment_account: UncheckedAccount<'info>,
-
-    #[account(address = crate::id())]
-    /// CHECK: This is the current program address
-    pub post_proof_program: UncheckedAccount<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Opt-in: the creator of a finished campaign publishes its results to the global
+    /// campaign registry, building a public track record. Each campaign publishes once.
+    pub fn publish_campaign_summary(ctx: Context<PublishCampaignSummary>) -> Result<()> {
+        publish_campaign_summary::handler(ctx)
+    }
+
+    /// Closes a finished campaign. The unclaimed budget goes to the campaign's leftover
+    /// recipient if it has one, otherwise back to the creator with the rent. Publish the
+    /// summary first, since it is built from the config.
+    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
+        close_config::handler(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct BonsolCallback<'info> {
-    /// CHECK: This is the raw ER account, checked in the callback handler
-    pub execution_request: UncheckedAccount<'info>,
+    /// First step of handing a campaign to another creator, such as a team or a DAO,
+    /// which takes over once it accepts. `None` withdraws a pending offer.
+    pub fn propose_transfer(
+        ctx: Context<ProposeTransfer>,
+        new_creator: Option<Pubkey>,
+    ) -> Result<()> {
+        propose_transfer::handler(ctx, new_creator)
+    }
 
-    /// CHECK: This is the requester PDA that stores ExecutionTracker data
-    pub requester: UncheckedAccount<'info>,
+    pub fn accept_transfer(ctx: Context<AcceptTransfer>) -> Result<()> {
+        accept_transfer::handler(ctx)
+    }
 
-    #[account(mut)]
-    pub post_proof_config: Account<'info, PostProofConfig>,
+    /// Upgrades a config written by an earlier version of the program to the current
+    /// layout, reallocating it with the payer covering the extra rent. Anyone may
+    /// migrate any config, since only the layout changes.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        migrate_config::handler(ctx)
+    }
+}
 
-    #[account(
-        mut,
-        seeds = [b"postverificationlog", verifier.key().as_ref(), post_proof_config.key().as_ref()],
-        bump
-    )]
-    pub post_verification_log: Account<'info, PostVerificationLog>,
+/// Current slot. Every instruction reads time through here rather than calling
+/// `Clock::get()` directly, so integration tests can warp the Clock sysvar (e.g. LiteSVM's
+/// `warp_to_slot`) and exercise cooldowns and expiries deterministically.
+pub fn current_slot() -> Result<u64> {
+    Ok(Clock::get()?.slot)
+}
+
+/// Current unix timestamp, for configs using `TIME_MODE_TIMESTAMP`
+pub fn current_timestamp() -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp)
+}
 
-    #[account(mut)]
-    /// CHECK: Will receive SOL reward if verification succeeds
-    pub verifier: UncheckedAccount<'info>,
+/// Move lamports out of a program-owned account, never below its rent-exempt minimum:
+/// an account under it is purged, taking the campaign with it
+pub(crate) fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let floor = Rent::get()?.minimum_balance(from.data_len());
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .filter(|&balance| balance >= floor)
+        .ok_or(PostProofError::WouldBreachRentExemption)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(PostProofError::ArithmeticOverflow)?;
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
 }
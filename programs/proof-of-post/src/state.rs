@@ -0,0 +1,595 @@
+//! Accounts the program owns and the types stored in them
+
+use crate::*;
+use anchor_lang::prelude::*;
+use proof_of_post_common::{PostVerificationOutput, RulesBundleRef, RATIO_UNKNOWN};
+
+#[account]
+#[derive(InitSpace)]
+pub struct PostProofConfig {
+    pub creator: Pubkey,
+    #[max_len(10)]
+    pub seeds: String,
+    #[max_len(20, 50)]
+    pub keywords: Vec<String>,
+    pub claimers_count: u64,
+    pub reward_amount: u64,
+    pub max_claimers: u64,
+    pub active: bool,
+    pub created_slot: u64,
+    /// Post must contain the verifier's base58 wallet address, in its text or in the
+    /// author's bio on platforms whose response carries one (not Bluesky's getPosts)
+    pub require_authorship: bool,
+    /// Keyword match mode: 0 = all, 1 = any, 2 = at least `match_threshold`
+    pub match_mode: u8,
+    pub match_threshold: u8,
+    /// Posts containing any of these terms fail verification
+    #[max_len(10, 50)]
+    pub blocked_keywords: Vec<String>,
+    /// Which registered callback entrypoint Bonsol invokes
+    pub callback_version: u8,
+    /// Whole-word, exact-phrase and case-sensitive matching options
+    pub match_flags: u8,
+    pub reward_split: RewardSplit,
+    pub platform_treasury: Pubkey,
+    /// `#hashtag` / `@handle` entries that must be present as Bluesky facets
+    #[max_len(5, 64)]
+    pub required_facets: Vec<String>,
+    /// Where the prover fetches the rule bundle; empty when the keyword lists are on-chain
+    #[max_len(200)]
+    pub metadata_uri: String,
+    /// SHA-256 of the rule bundle at `metadata_uri`
+    pub rules_hash: [u8; 32],
+    pub rules_bundle_size: u64,
+    /// Minimum engagement the post must have when proven, 0 to disable
+    pub min_likes: u64,
+    pub min_reposts: u64,
+    pub min_replies: u64,
+    /// Unix-second window the post's `createdAt` must fall in, 0 for no bound
+    pub not_before_ts: i64,
+    pub not_after_ts: i64,
+    /// Language tags ("en", "pt-BR") the post must be tagged with one of; empty for any
+    #[max_len(4, 8)]
+    pub required_langs: Vec<String>,
+    /// Minimum characters and words, not counting hashtags, mentions and URLs
+    pub min_chars: u32,
+    pub min_words: u32,
+    /// Post must embed at least this many images, or a link card on `external_domain`
+    pub min_images: u8,
+    #[max_len(64)]
+    pub external_domain: String,
+    /// Post must link to one of these, as "domain" or "domain/path-prefix"
+    #[max_len(5, 64)]
+    pub allowed_link_domains: Vec<String>,
+    /// Whether cooldowns and windows are measured in slots or unix seconds
+    pub time_mode: u8,
+    pub created_ts: i64,
+    /// Emit `BudgetLow` when the remaining budget drops below this share of the full
+    /// budget (reward x max claimers) or below `budget_alert_lamports`, whichever is higher
+    pub budget_alert_bps: u16,
+    pub budget_alert_lamports: u64,
+    /// AT URI of the announcement that qualifying posts must directly reply to
+    #[max_len(128)]
+    pub reply_to_uri: String,
+    /// Author account must be at least this old at request time
+    pub min_account_age_days: u32,
+    /// Minimum author followers, on platforms whose responses include them
+    pub min_followers: u64,
+    /// Moderation labels on the post or author that disqualify it
+    #[max_len(8, 32)]
+    pub blocked_labels: Vec<String>,
+    /// Reject payouts whose likes exceed this share of the author's followers, in basis
+    /// points; 0 disables the check
+    pub max_likes_per_follower_bps: u32,
+    /// `PLATFORM_*` id of the social platform the campaign runs on
+    pub platform: u8,
+    /// Subreddits a post must be made in; empty accepts any
+    #[max_len(4, 21)]
+    pub required_communities: Vec<String>,
+    /// sha256 of the campaign's terms and conditions, which claimers must sign before
+    /// verifying; zero when there are none
+    pub terms_hash: [u8; 32],
+    /// Paid-promotion hashtags ("#ad", "#sponsored"), one of which posts must carry
+    #[max_len(4, 32)]
+    pub disclosure_tags: Vec<String>,
+    /// Author ids (YouTube channel ids, DIDs) allowed to claim; empty accepts any
+    #[max_len(8, 64)]
+    pub allowed_author_ids: Vec<String>,
+    /// Minimum views, on platforms that report them; 0 to disable
+    pub min_views: u64,
+    /// Market segments with their own reward and claim cap, in the priority order the
+    /// guest assigns posts to them. Posts in no segment get the base reward.
+    #[max_len(4)]
+    pub segments: Vec<Segment>,
+    /// Ed25519 key of a notary that fetches post URLs itself and signs the responses;
+    /// when set, only responses carrying its signature are paid
+    pub response_signer: Option<Pubkey>,
+    /// Lamports paid out across all claims, including creator, platform and referrer
+    /// shares
+    pub total_paid: u64,
+    /// Views of rewarded posts, on platforms that report them
+    pub total_views: u64,
+    /// Likes, reposts, replies and quotes of rewarded posts
+    pub total_engagement: u64,
+    /// Reject verify_post calls made through CPI by programs outside `allowed_callers`
+    pub reject_cpi: bool,
+    #[max_len(4)]
+    pub allowed_callers: Vec<Pubkey>,
+    /// Charity or community treasury receiving the unclaimed budget when the campaign
+    /// closes; `None` returns it to the creator
+    pub leftover_recipient: Option<Pubkey>,
+    /// Thread campaigns: how many posts of the author's thread, the anchor post
+    /// included, must satisfy the keyword rules. 0 checks the single post.
+    pub min_thread_posts: u8,
+    /// Lamports of the vault set aside for reimbursing relayers of sponsored requests,
+    /// which rewards can't touch
+    pub sponsorship_budget: u64,
+    /// Most a single sponsored request may be reimbursed; 0 sponsors none
+    pub sponsorship_max_per_request: u64,
+    /// Pay the prover tip, up to the protocol's `min_tip`, and the execution tracker's
+    /// rent of verify_post requests from the vault, so claimers only need the fees and
+    /// the log's rent
+    pub sponsor_costs: bool,
+    /// Hosts post URLs must be on, such as "public.api.bsky.app"; empty accepts any
+    #[max_len(4, 64)]
+    pub allowed_post_hosts: Vec<String>,
+    /// Canonical bump of this PDA, stored at creation so later instructions verify the
+    /// address without searching for it again
+    pub bump: u8,
+    /// Layout version, see [`PostProofConfig::VERSION`]. It comes last so configs
+    /// written before it read as version 0 from the zeroed tail of their allocation;
+    /// fields added later go after it, bumping the version, and `migrate_config`
+    /// reallocs older configs to the current layout.
+    pub version: u8,
+    /// Creator proposed by `propose_transfer`, until it accepts. The config keeps its
+    /// address, derived from the creator that opened it.
+    pub pending_creator: Option<Pubkey>,
+    /// Slots a reward is held after its proof lands, open to challenges, before it can
+    /// be claimed; 0 pays rewards right away
+    pub dispute_window_slots: u64,
+    /// Lamports a challenger stakes, going to the claimer if the re-verification agrees
+    pub challenge_bond: u64,
+    /// Share of a voided reward paid to its challenger on top of the bond, in basis
+    /// points
+    pub challenger_share_bps: u16,
+    /// Rewards held in dispute windows, which new verifications can't count on
+    pub held_rewards: u64,
+    /// Provers whose deliveries the callback accepts, for campaigns whose URLs only
+    /// trusted provers should fetch; empty accepts any
+    #[max_len(4)]
+    pub allowed_provers: Vec<Pubkey>,
+}
+
+impl PostProofConfig {
+    pub const VERSION: u8 = 4;
+
+    /// Lamports of the vault rewards can't be paid from: the rent-exempt minimum, the
+    /// sponsorship budget and the rewards held in dispute windows
+    pub fn reserved_lamports(&self, rent_floor: u64) -> u64 {
+        rent_floor
+            .saturating_add(self.sponsorship_budget)
+            .saturating_add(self.held_rewards)
+    }
+
+    /// Share of a voided claim's `held_reward` paid to the challenger who disproved it
+    pub fn challenger_award(&self, held_reward: u64) -> u64 {
+        (held_reward as u128 * self.challenger_share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Remaining budget below which `BudgetLow` fires, 0 when alerts are off
+    pub fn budget_alert_threshold(&self) -> u64 {
+        let full_budget = self.reward_amount as u128 * self.max_claimers as u128;
+        let relative = full_budget * self.budget_alert_bps as u128 / BPS_DENOMINATOR as u128;
+        self.budget_alert_lamports
+            .max(relative.min(u64::MAX as u128) as u64)
+    }
+
+    /// Rule bundle the guest must fetch and check, if the rules live off-chain
+    pub fn rules_bundle_ref(&self) -> Option<RulesBundleRef> {
+        (!self.metadata_uri.is_empty()).then_some(RulesBundleRef {
+            hash: self.rules_hash,
+            size: self.rules_bundle_size,
+        })
+    }
+
+    /// Largest reward a single claim can pay, across the base reward and segments
+    pub fn max_reward(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(|segment| segment.reward_amount)
+            .fold(self.reward_amount, u64::max)
+    }
+
+    /// Whether claimers must sign the campaign's terms to verify
+    pub fn requires_terms(&self) -> bool {
+        self.terms_hash != [0u8; 32]
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PostVerificationLog {
+    pub verifier: Pubkey,
+    pub config: Pubkey,
+    #[max_len(256)]
+    pub post_url: String,
+    pub slot: u64,
+    pub is_verified: bool,
+    pub current_execution_account: Option<Pubkey>,
+    pub referrer: Option<Pubkey>,
+    /// Unix timestamp of the last verification, alongside `slot`
+    pub timestamp: i64,
+    /// Input hash the request was submitted with, checked against the committed digest
+    pub input_hash: Option<[u8; 32]>,
+    /// Wallet receiving the claimer share instead of the verifier, e.g. a cold wallet
+    pub payout: Option<Pubkey>,
+    /// sha256 of the post author's DID, declared at request time and seeding the
+    /// author claim
+    pub author_did_hash: [u8; 32],
+    /// The verifier's signature over the campaign's terms message, kept as its record
+    /// of accepting them
+    pub terms_signature: Option<[u8; 64]>,
+    /// `REASON_*` code of the most important check the last proven post failed, 0 if it
+    /// passed them all
+    pub failure_reason: u16,
+    /// Bitmap of every check the last proven post failed, bit `n - 1` for reason `n`
+    pub failed_checks: u32,
+    pub bump: u8,
+    /// Layout version, last like [`PostProofConfig::version`]
+    pub version: u8,
+    /// Reward held in the campaign's dispute window, 0 when there is none
+    pub held_reward: u64,
+    /// Segment the held reward counts against, as the journal assigned it
+    pub held_segment: u8,
+    /// First slot the held reward can be claimed, unless it is being challenged
+    pub claimable_slot: u64,
+    /// Who challenged the held reward; its bond is kept in this account until the
+    /// re-verification settles the challenge
+    pub challenger: Option<Pubkey>,
+    pub challenge_bond: u64,
+    /// Slot the latest request was submitted in, from which it expires
+    pub requested_slot: u64,
+}
+
+/// One per Bluesky author and campaign, so an account is rewarded at most once no
+/// matter how many wallets submit its posts
+#[account]
+#[derive(InitSpace)]
+pub struct AuthorClaim {
+    pub config: Pubkey,
+    /// sha256 of the author's DID
+    pub author_did_hash: [u8; 32],
+    pub claimed: bool,
+    /// Verifier whose request was rewarded
+    pub claimer: Pubkey,
+    pub slot: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl AuthorClaim {
+    pub const VERSION: u8 = 1;
+}
+
+impl PostVerificationLog {
+    pub const VERSION: u8 = 3;
+
+    /// Whether the latest request may still be delivered, so a new one can't replace it
+    pub fn is_pending(&self, protocol: &ProtocolConfig, slot: u64) -> bool {
+        self.current_execution_account.is_some()
+            && slot
+                <= self
+                    .requested_slot
+                    .saturating_add(protocol.execution_expiry_slots)
+    }
+
+    /// Whether the protocol's cooldown has passed since the last proof was settled,
+    /// measured in the config's time mode
+    pub fn cooldown_elapsed(
+        &self,
+        protocol: &ProtocolConfig,
+        time_mode: u8,
+        slot: u64,
+        timestamp: i64,
+    ) -> bool {
+        match time_mode {
+            TIME_MODE_TIMESTAMP => {
+                timestamp.saturating_sub(self.timestamp) >= protocol.verify_cooldown_secs
+            }
+            _ => slot.saturating_sub(self.slot) >= protocol.verify_cooldown_slots,
+        }
+    }
+}
+
+/// A market within a campaign, e.g. Japanese-language posts, with a separate budget
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, Debug)]
+pub struct Segment {
+    /// `SEGMENT_*` kind of `value`
+    pub kind: u8,
+    /// Language tag or author handle suffix
+    #[max_len(32)]
+    pub value: String,
+    pub reward_amount: u64,
+    pub max_claimers: u64,
+    pub claimers_count: u64,
+}
+
+/// Basis-point split of each reward. Shares must sum to `BPS_DENOMINATOR`.
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, Copy, Debug)]
+pub struct RewardSplit {
+    pub claimer_bps: u16,
+    pub creator_bps: u16,
+    pub platform_bps: u16,
+    pub referrer_bps: u16,
+}
+
+pub struct RewardShares {
+    pub claimer: u64,
+    pub creator: u64,
+    pub platform: u64,
+    pub referrer: u64,
+}
+
+impl RewardSplit {
+    pub fn validate(&self, platform_treasury: &Pubkey) -> Result<()> {
+        let total = self.claimer_bps as u64
+            + self.creator_bps as u64
+            + self.platform_bps as u64
+            + self.referrer_bps as u64;
+        if total != BPS_DENOMINATOR {
+            return Err(PostProofError::InvalidRewardSplit.into());
+        }
+        if self.platform_bps > 0 && *platform_treasury == Pubkey::default() {
+            return Err(PostProofError::InvalidRewardSplit.into());
+        }
+        Ok(())
+    }
+
+    /// Split `amount` into shares. Rounding dust and the referrer share (when there is no
+    /// referrer) go to the claimer.
+    pub fn apply(&self, amount: u64, has_referrer: bool) -> RewardShares {
+        let share = |bps: u16| (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let creator = share(self.creator_bps);
+        let platform = share(self.platform_bps);
+        let referrer = if has_referrer {
+            share(self.referrer_bps)
+        } else {
+            0
+        };
+        RewardShares {
+            claimer: amount - creator - platform - referrer,
+            creator,
+            platform,
+            referrer,
+        }
+    }
+}
+
+/// Global index of published campaign summaries
+#[account]
+#[derive(InitSpace)]
+pub struct CampaignRegistry {
+    pub campaigns_count: u64,
+    pub total_claims: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl CampaignRegistry {
+    pub const VERSION: u8 = 1;
+}
+
+/// Final results of a finished campaign, published by its creator so claimers can judge
+/// the creator's track record
+#[account]
+#[derive(InitSpace)]
+pub struct CampaignSummary {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    /// Position in the registry, in publishing order
+    pub index: u64,
+    pub platform: u8,
+    pub claimers_count: u64,
+    pub max_claimers: u64,
+    pub total_paid: u64,
+    pub total_views: u64,
+    pub total_engagement: u64,
+    pub created_ts: i64,
+    pub published_ts: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl CampaignSummary {
+    pub const VERSION: u8 = 1;
+}
+
+/// Global protocol configuration, one per deployment
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub authority: Pubkey,
+    pub feature_flags: u64,
+    /// May reset the circuit breaker once it trips
+    pub guardian: Pubkey,
+    /// Protocol-wide payout velocity tracking
+    pub breaker: CircuitBreaker,
+    /// Smallest prover tip verify_post accepts, in lamports
+    pub min_tip: u64,
+    /// Largest prover tip verify_post accepts, in lamports; 0 for no limit
+    pub max_tip: u64,
+    /// Largest `post_size` verify_post accepts, so provers aren't sent to fetch and
+    /// prove multi-megabyte responses; 0 for no limit
+    pub max_post_size: u64,
+    /// Plausibility limits the callback applies to every committed journal
+    pub journal_bounds: JournalBounds,
+    pub bump: u8,
+    pub version: u8,
+    /// Minimum gap between a verifier's last settled proof on a campaign and their next
+    /// request on it, for configs measuring time in slots and in seconds respectively
+    pub verify_cooldown_slots: u64,
+    pub verify_cooldown_secs: i64,
+    /// Slots a Bonsol execution has to be proven before it expires
+    pub execution_expiry_slots: u64,
+    /// Fee per signature relayers are reimbursed for on sponsored requests
+    pub signature_fee_lamports: u64,
+    /// Authority proposed by `transfer_protocol_authority`, until it accepts
+    pub pending_authority: Option<Pubkey>,
+    /// Emergency stop set by the authority, e.g. when the guest image or Bonsol is
+    /// found vulnerable: no new verifications and no payouts, while campaigns can
+    /// still be funded, closed and refunded
+    pub paused: bool,
+}
+
+/// Upper limits on committed engagement, so a buggy or malicious image version can't
+/// feed absurd numbers into payouts. 0 disables a limit.
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, Copy, Debug, Default)]
+pub struct JournalBounds {
+    /// Largest like, repost, reply, quote or view count accepted
+    pub max_metric_count: u64,
+    /// Largest likes per follower accepted, in basis points, when the follower count
+    /// is known
+    pub max_likes_per_follower_bps: u32,
+}
+
+impl JournalBounds {
+    pub fn accepts(&self, journal: &PostVerificationOutput) -> bool {
+        let metrics = &journal.metrics;
+        let counts_plausible = self.max_metric_count == 0
+            || [
+                metrics.like_count,
+                metrics.repost_count,
+                metrics.reply_count,
+                metrics.quote_count,
+                metrics.view_count,
+            ]
+            .iter()
+            .all(|&count| count <= self.max_metric_count);
+        let ratio_plausible = self.max_likes_per_follower_bps == 0
+            || journal.likes_per_follower_bps == RATIO_UNKNOWN
+            || journal.likes_per_follower_bps <= self.max_likes_per_follower_bps;
+        counts_plausible && ratio_plausible
+    }
+}
+
+/// Trips when payouts in the current window exceed `max_velocity_multiple` times the
+/// norm, an exponential moving average of completed windows
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, Copy, Debug, Default)]
+pub struct CircuitBreaker {
+    pub window_slots: u64,
+    /// 0 disables the breaker
+    pub max_velocity_multiple: u16,
+    /// Floor for the norm, so the first windows of a quiet protocol don't trip it
+    pub min_norm_lamports: u64,
+    pub window_start_slot: u64,
+    pub window_spend: u64,
+    pub norm_spend: u64,
+    pub tripped: bool,
+}
+
+impl CircuitBreaker {
+    /// Weight of a completed window in the moving average, as 1 / NORM_SMOOTHING
+    const NORM_SMOOTHING: u128 = 8;
+
+    /// Record a payout at `slot`. Returns true when this payout trips the breaker.
+    pub fn record_payout(&mut self, slot: u64, amount: u64) -> bool {
+        if self.window_slots > 0 && slot >= self.window_start_slot.saturating_add(self.window_slots)
+        {
+            self.norm_spend = if self.norm_spend == 0 {
+                self.window_spend
+            } else {
+                ((self.norm_spend as u128 * (Self::NORM_SMOOTHING - 1) + self.window_spend as u128)
+                    / Self::NORM_SMOOTHING) as u64
+            };
+            self.window_start_slot = slot;
+            self.window_spend = 0;
+        }
+        self.window_spend = self.window_spend.saturating_add(amount);
+
+        if self.max_velocity_multiple == 0 || self.tripped {
+            return false;
+        }
+        let limit = self.norm_spend.max(self.min_norm_lamports) as u128
+            * self.max_velocity_multiple as u128;
+        self.tripped = self.window_spend as u128 > limit;
+        self.tripped
+    }
+}
+
+impl ProtocolConfig {
+    pub const VERSION: u8 = 3;
+
+    pub fn is_feature_enabled(&self, feature: u64) -> bool {
+        self.feature_flags & feature == feature
+    }
+
+    pub fn require_feature(&self, feature: u64) -> Result<()> {
+        if !self.is_feature_enabled(feature) {
+            return Err(PostProofError::FeatureDisabled.into());
+        }
+        Ok(())
+    }
+
+    /// Rejects tunable parameters that would stop verifications from working, such as
+    /// executions that expire as soon as they are requested
+    pub fn validate_params(&self) -> Result<()> {
+        if self.verify_cooldown_secs < 0 || self.execution_expiry_slots == 0 {
+            return Err(PostProofError::InvalidProtocolParams.into());
+        }
+        Ok(())
+    }
+
+    /// Last slot a Bonsol execution requested at `slot` can be delivered in
+    pub fn execution_expiry(&self, slot: u64) -> Result<u64> {
+        slot.checked_add(self.execution_expiry_slots)
+            .ok_or_else(|| PostProofError::ArithmeticOverflow.into())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExecutionTracker {
+    pub execution_account: Pubkey,
+    /// Set by the first callback for the execution, so a redelivery can't pay twice
+    pub processed: bool,
+    pub version: u8,
+}
+
+impl ExecutionTracker {
+    pub const VERSION: u8 = 1;
+    pub const INIT_SPACE: usize = 34; // The Pubkey, the processed flag and the version
+    /// Trackers created before the version byte, still in flight until their
+    /// executions expire
+    pub const UNVERSIONED_SPACE: usize = 33;
+
+    pub fn pack(&self, dst: &mut [u8]) -> Result<()> {
+        if dst.len() < Self::UNVERSIONED_SPACE {
+            return Err(ProgramError::AccountDataTooSmall.into());
+        }
+        dst[0..32].copy_from_slice(&self.execution_account.to_bytes());
+        dst[32] = self.processed as u8;
+        if let Some(version) = dst.get_mut(33) {
+            *version = self.version;
+        }
+        Ok(())
+    }
+
+    /// Reads version 0 from trackers without the version byte
+    pub fn unpack(src: &[u8]) -> Result<Self> {
+        if src.len() < Self::UNVERSIONED_SPACE {
+            return Err(ProgramError::AccountDataTooSmall.into());
+        }
+        let execution_account = Pubkey::new_from_array([
+            src[0], src[1], src[2], src[3], src[4], src[5], src[6], src[7], src[8], src[9],
+            src[10], src[11], src[12], src[13], src[14], src[15], src[16], src[17], src[18],
+            src[19], src[20], src[21], src[22], src[23], src[24], src[25], src[26], src[27],
+            src[28], src[29], src[30], src[31],
+        ]);
+        Ok(Self {
+            execution_account,
+            processed: src[32] != 0,
+            version: src.get(33).copied().unwrap_or(0),
+        })
+    }
+}
@@ -1,6 +1,14 @@
-import { Connection, PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  Connection,
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Ed25519Program,
+  TransactionInstruction,
+} from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
+import { createHash } from "crypto";
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { ProofOfPost } from "../target/types/proof_of_post";
@@ -8,15 +16,40 @@ import { assert } from "chai";
 import keccak256 from "keccak256";
 import fetch from "node-fetch";
 
+// Deployment settings. Defaults target the devnet deployment; override them per
+// environment so devnet/mainnet/test deployments can share this tooling.
 const PROGRAM_ID = new PublicKey(
-  "5MQLTq2D5ZhUAc6TDoAMXfnMeA32bo5DUxYco5LDMKAA"
+  process.env.POP_PROGRAM_ID || "5MQLTq2D5ZhUAc6TDoAMXfnMeA32bo5DUxYco5LDMKAA"
 );
 const POST_VERIFICATION_IMAGE_ID =
+  process.env.POP_IMAGE_ID ||
   "4de2a43da6e788efef9837b71e055b2bfd83d18ca1c32b93cf5bfff58662aaa5";
 const BONSOL_PROGRAM_ID = new PublicKey(
-  "BoNsHRcyLLNdtnoDf8hiCNZpyehMC4FDMxs6NTxFi3ew"
+  process.env.BONSOL_PROGRAM_ID || "BoNsHRcyLLNdtnoDf8hiCNZpyehMC4FDMxs6NTxFi3ew"
 );
 
+// PDA seeds, discriminators and codec samples exported from the Rust source; regenerate
+// with `cargo run -p proof-of-post-fixtures -- tests/fixtures/proof-of-post.json`
+const FIXTURES = JSON.parse(
+  fs.readFileSync(path.join(__dirname, "fixtures", "proof-of-post.json"), "utf8")
+);
+
+// Rule bundle layout read by the guest: three u64 big-endian length-prefixed,
+// comma-joined lists (keywords, blocked keywords, required facets)
+const encodeRulesBundle = (
+  keywords: string[],
+  blockedKeywords: string[] = [],
+  requiredFacets: string[] = []
+): Buffer =>
+  Buffer.concat(
+    [keywords, blockedKeywords, requiredFacets].map((list) => {
+      const joined = Buffer.from(list.join(","), "utf8");
+      const size = Buffer.alloc(8);
+      size.writeBigUInt64BE(BigInt(joined.length));
+      return Buffer.concat([size, joined]);
+    })
+  );
+
 class ProofOfPostClient {
   private connection: Connection;
   private payer: Keypair;
@@ -30,13 +63,18 @@ class ProofOfPostClient {
     anchor.setProvider(anchor.AnchorProvider.env());
     this.provider = anchor.AnchorProvider.env();
     this.wallet = anchor.AnchorProvider.env().wallet;
-    this.program = anchor.workspace.ProofOfPost as Program<ProofOfPost>;
+    // Bind the IDL to the configured program ID rather than the one it was built with
+    const workspaceProgram = anchor.workspace.ProofOfPost as Program<ProofOfPost>;
+    this.program = new Program<ProofOfPost>(
+      { ...workspaceProgram.idl, address: PROGRAM_ID.toBase58() },
+      this.provider
+    );
   }
 
   // Get PDA addresses
   getPostProofConfigPDA(creator: PublicKey, seeds: string): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
-      [Buffer.from("postproofconfig"), creator.toBuffer(), Buffer.from(seeds)],
+      [Buffer.from(FIXTURES.seeds.postProofConfig), creator.toBuffer(), Buffer.from(seeds)],
       this.program.programId
     );
   }
@@ -44,18 +82,87 @@ class ProofOfPostClient {
   getPostVerificationLogPDA(verifier: PublicKey, configPDA: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
       // [Buffer.from("postverificationlog"), verifier.toBuffer(), Buffer.from(postUrl)],
-      [Buffer.from("postverificationlog"), verifier.toBuffer(), configPDA.toBuffer()],
+      [Buffer.from(FIXTURES.seeds.postVerificationLog), verifier.toBuffer(), configPDA.toBuffer()],
+      this.program.programId
+    );
+  }
+
+  getAuthorClaimPDA(configPDA: PublicKey, authorDidHash: Buffer): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from(FIXTURES.seeds.authorClaim), configPDA.toBuffer(), authorDidHash],
       this.program.programId
     );
   }
 
+  // Check the hand-written encoders and PDA helpers against the Rust-generated fixtures
+  checkFixtures(): void {
+    const bundle = FIXTURES.codec.rulesBundle;
+    assert.equal(
+      encodeRulesBundle(bundle.keywords, bundle.blockedKeywords, bundle.requiredFacets).toString("hex"),
+      bundle.hex,
+      "rule bundle encoding drifted from the Rust codec"
+    );
+    const sample = FIXTURES.codec.requestId;
+    assert.equal(
+      this.deriveRequestId(
+        new PublicKey(sample.verifier),
+        new PublicKey(sample.config),
+        sample.postUrl,
+        new anchor.BN(sample.nonce)
+      ),
+      sample.id,
+      "request ID derivation drifted from the program's"
+    );
+    if (this.program.programId.toBase58() !== FIXTURES.programId) {
+      return; // sample PDAs are derived for the built-in program ID
+    }
+    const pdas = FIXTURES.pdas;
+    const [configPDA] = this.getPostProofConfigPDA(new PublicKey(pdas.creator), pdas.seeds);
+    assert.equal(configPDA.toBase58(), pdas.postProofConfig);
+    const [logPDA] = this.getPostVerificationLogPDA(new PublicKey(pdas.verifier), configPDA);
+    assert.equal(logPDA.toBase58(), pdas.postVerificationLog);
+    const didHash = createHash("sha256").update(pdas.authorDid).digest();
+    const [claimPDA] = this.getAuthorClaimPDA(configPDA, didHash);
+    assert.equal(claimPDA.toBase58(), pdas.authorClaim);
+  }
+
+  // sha256 of the post author's DID, taken from the AT-URI in the API URL
+  getAuthorDidHash(apiUrl: string): Buffer {
+    const params = new URL(apiUrl).searchParams;
+    const atUri = params.get("uris") ?? params.get("uri") ?? "";
+    const did = atUri.replace("at://", "").split("/")[0];
+    return createHash("sha256").update(did).digest();
+  }
+
+  // Request ID verify_post re-derives when given the nonce: the first 24 bytes of
+  // sha256(domain, verifier, config, sha256(post URL), nonce as u64 LE), base64url
+  deriveRequestId(
+    verifier: PublicKey,
+    configPDA: PublicKey,
+    postUrl: string,
+    nonce: anchor.BN
+  ): string {
+    const digest = createHash("sha256")
+      .update(Buffer.from(FIXTURES.codec.requestIdDomain))
+      .update(verifier.toBuffer())
+      .update(configPDA.toBuffer())
+      .update(createHash("sha256").update(postUrl).digest())
+      .update(nonce.toArrayLike(Buffer, "le", 8))
+      .digest();
+    return digest.subarray(0, 24).toString("base64url");
+  }
+
   // Get PDA for execution tracker (Bonsol specific)
   getExecutionTrackerPDA(executionIdBuffer: Buffer): [PublicKey, number] {
-    return PublicKey.findProgramAddressSync([executionIdBuffer], PROGRAM_ID);
+    return PublicKey.findProgramAddressSync([executionIdBuffer], this.program.programId);
   }
 
-  // Convert web URL or AT-URI to Bluesky API URL
-  async convertToApiUrl(postId: string): Promise<string> {
+  // Convert web URL or AT-URI to Bluesky API URL. Thread campaigns fetch the post with
+  // its replies through getPostThread instead of getPosts.
+  async convertToApiUrl(postId: string, thread = false): Promise<string> {
+    const bskyEndpoint = thread
+      ? "https://public.api.bsky.app/xrpc/app.bsky.feed.getPostThread?uri="
+      : "https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?uris=";
     // If already an API URL, return as-is
     if (postId.startsWith("https://public.api.bsky.app/") || 
         postId.startsWith("https://api.bsky.app/")) {
@@ -64,7 +171,7 @@ class ProofOfPostClient {
 
     // If it's an AT-URI, convert directly
     if (postId.startsWith("at://")) {
-      return `https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?uris=${postId}`;
+      return `${bskyEndpoint}${postId}`;
     }
 
     // If it's a web URL, we need to resolve handle to DID
@@ -82,10 +189,54 @@ class ProofOfPostClient {
 
         // Construct AT-URI and then API URL
         const atUri = `at://${did}/app.bsky.feed.post/${rkey}`;
-        return `https://public.api.bsky.app/xrpc/app.bsky.feed.getPosts?uris=${atUri}`;
+        return `${bskyEndpoint}${atUri}`;
       }
     }
 
+    // X status URLs map to the v2 tweet lookup with the fields the guest reads. The
+    // prover needs an API bearer token to fetch it.
+    const status = postId.match(/^https:\/\/(?:x|twitter)\.com\/[^/]+\/status\/(\d+)/);
+    if (status) {
+      const params = new URLSearchParams({
+        ids: status[1],
+        "tweet.fields": "created_at,public_metrics,author_id,entities,lang,referenced_tweets,attachments",
+        expansions: "author_id,attachments.media_keys",
+        "user.fields": "created_at,public_metrics,description",
+        "media.fields": "type",
+      });
+      return `https://api.x.com/2/tweets?${params}`;
+    }
+
+    // Mastodon status URLs (https://instance/@user/id) map to the instance's status API
+    const toot = postId.match(/^https:\/\/([^/]+)\/@[^/]+\/(\d+)$/);
+    if (toot) {
+      return `https://${toot[1]}/api/v1/statuses/${toot[2]}`;
+    }
+
+    // Reddit post permalinks map to the thread's .json view; comment permalinks to an
+    // info lookup of the comment alone
+    const reddit = postId.match(
+      /^https:\/\/(?:www\.|old\.)?reddit\.com\/r\/[^/]+\/comments\/(\w+)(?:\/[^/]*\/(\w+))?/
+    );
+    if (reddit) {
+      return reddit[2]
+        ? `https://www.reddit.com/api/info.json?id=t1_${reddit[2]}`
+        : `https://www.reddit.com/comments/${reddit[1]}.json`;
+    }
+
+    // YouTube videos map to videos.list; the prover appends its API key
+    const video = postId.match(
+      /^https:\/\/(?:www\.youtube\.com\/watch\?v=|youtu\.be\/)([\w-]{11})/
+    );
+    if (video) {
+      return `https://www.googleapis.com/youtube/v3/videos?part=snippet,statistics&id=${video[1]}`;
+    }
+
+    // Farcaster cast hashes are looked up through Neynar, which needs an API key header
+    if (/^0x[0-9a-f]{40}$/i.test(postId)) {
+      return `https://api.neynar.com/v2/farcaster/cast?identifier=${postId}&type=hash`;
+    }
+
     throw new Error("Invalid post ID format");
   }
 
@@ -108,7 +259,45 @@ class ProofOfPostClient {
     seeds: string,
     keywords: string[],
     rewardAmount: number,
-    maxClaimers: number
+    maxClaimers: number,
+    requireAuthorship: boolean = false,
+    matchMode: number = 0, // 0 = all, 1 = any, 2 = at least matchThreshold
+    matchThreshold: number = 0,
+    blockedKeywords: string[] = [],
+    callbackVersion: number = 1,
+    matchFlags: number = 0, // 1 = whole word, 2 = exact phrase, 4 = case sensitive, 8 = regex, 16 = fold diacritics
+    rewardSplit = { claimerBps: 10000, creatorBps: 0, platformBps: 0, referrerBps: 0 },
+    platformTreasury: PublicKey = PublicKey.default,
+    requiredFacets: string[] = [], // e.g. ["#bonsol", "@bonsol.bsky.social"]
+    // Off-chain rules: the bytes published at metadataUri (see encodeRulesBundle). The
+    // keyword, blocked and facet lists must then be empty.
+    rulesBundle: { metadataUri: string; bytes: Buffer } | null = null,
+    engagement = { minLikes: 0, minReposts: 0, minReplies: 0, minViews: 0 },
+    recency = { notBeforeTs: 0, notAfterTs: 0 }, // unix seconds, 0 = unbounded
+    requiredLangs: string[] = [], // e.g. ["en", "pt"]
+    content = { minChars: 0, minWords: 0 }, // excluding hashtags, mentions and URLs
+    media = { minImages: 0, externalDomain: "" }, // images, or a link card on the domain
+    allowedLinkDomains: string[] = [], // e.g. ["example.com/launch"]
+    timeMode: number = 0, // 0 = slots, 1 = unix timestamps
+    budgetAlert = { bps: 0, lamports: 0 }, // emit BudgetLow below either threshold
+    replyToUri: string = "", // at:// URI of the announcement post for reply campaigns
+    authorRequirements = { minAccountAgeDays: 0, minFollowers: 0 },
+    blockedLabels: string[] = [], // moderation labels, e.g. ["spam", "porn"]
+    maxLikesPerFollowerBps: number = 0, // reject bought likes, e.g. 5000 = 0.5 likes per follower
+    platform: number = 0, // 0 = Bluesky, 1 = X (Twitter), 2 = Mastodon, 3 = Farcaster, 4 = Lens, 5 = Reddit, 6 = YouTube
+    requiredCommunities: string[] = [], // subreddits without "r/", e.g. ["solana"]
+    terms: string = "", // T&Cs text claimers must sign, e.g. a paid-promotion disclosure
+    disclosureTags: string[] = [], // posts must carry one of these, e.g. ["#ad", "#sponsored"]
+    allowedAuthorIds: string[] = [], // e.g. partner YouTube channel ids
+    // Markets with their own budget, by language (kind 0, "ja") or handle suffix (kind 1, ".jp")
+    segments: { kind: number; value: string; rewardAmount: number; maxClaimers: number }[] = [],
+    responseSigner: PublicKey | null = null, // notary whose signed responses are the only ones paid
+    // Reject verify_post through CPI unless the outer program is one of allowedCallers
+    cpiGuard = { rejectCpi: false, allowedCallers: [] as PublicKey[] },
+    leftoverRecipient: PublicKey | null = null, // charity or treasury receiving the unclaimed budget at close
+    // Bluesky thread campaigns: posts of the author's thread, the first included, that
+    // must match the keywords; 0 checks only the post itself
+    minThreadPosts: number = 0
   ): Promise<void> {
     console.log("🔧 Creating PostProofConfig...");
 
@@ -121,6 +310,64 @@ class ProofOfPostClient {
           keywords,
           rewardAmount: new anchor.BN(rewardAmount),
           maxClaimers: new anchor.BN(maxClaimers),
+          requireAuthorship,
+          matchMode,
+          matchThreshold,
+          blockedKeywords,
+          callbackVersion,
+          matchFlags,
+          rewardSplit,
+          platformTreasury,
+          requiredFacets,
+          metadataUri: rulesBundle?.metadataUri ?? "",
+          rulesHash: rulesBundle
+            ? Array.from(createHash("sha256").update(rulesBundle.bytes).digest())
+            : new Array(32).fill(0),
+          rulesBundleSize: new anchor.BN(rulesBundle?.bytes.length ?? 0),
+          minLikes: new anchor.BN(engagement.minLikes),
+          minReposts: new anchor.BN(engagement.minReposts),
+          minReplies: new anchor.BN(engagement.minReplies),
+          notBeforeTs: new anchor.BN(recency.notBeforeTs),
+          notAfterTs: new anchor.BN(recency.notAfterTs),
+          requiredLangs,
+          minChars: content.minChars,
+          minWords: content.minWords,
+          minImages: media.minImages,
+          externalDomain: media.externalDomain,
+          allowedLinkDomains,
+          timeMode,
+          budgetAlertBps: budgetAlert.bps,
+          budgetAlertLamports: new anchor.BN(budgetAlert.lamports),
+          replyToUri,
+          minAccountAgeDays: authorRequirements.minAccountAgeDays,
+          minFollowers: new anchor.BN(authorRequirements.minFollowers),
+          blockedLabels,
+          maxLikesPerFollowerBps,
+          platform,
+          requiredCommunities,
+          termsHash: terms
+            ? Array.from(createHash("sha256").update(terms).digest())
+            : new Array(32).fill(0),
+          disclosureTags,
+          allowedAuthorIds,
+          minViews: new anchor.BN(engagement.minViews),
+          segments: segments.map((segment) => ({
+            kind: segment.kind,
+            value: segment.value,
+            rewardAmount: new anchor.BN(segment.rewardAmount),
+            maxClaimers: new anchor.BN(segment.maxClaimers),
+          })),
+          responseSigner,
+          rejectCpi: cpiGuard.rejectCpi,
+          allowedCallers: cpiGuard.allowedCallers,
+          leftoverRecipient,
+          minThreadPosts,
+          sponsorCosts: false,
+          allowedPostHosts: [],
+          disputeWindowSlots: new anchor.BN(0),
+          challengeBond: new anchor.BN(0),
+          challengerShareBps: 0,
+          allowedProvers: [],
         })
         .accounts({
           // postProofConfig: configPDA,
@@ -154,6 +401,9 @@ class ProofOfPostClient {
       active?: boolean;
       maxClaimers?: number;
       rewardAmount?: number;
+      callbackVersion?: number;
+      budgetAlertBps?: number;
+      budgetAlertLamports?: number;
     }
   ): Promise<void> {
     console.log("🔄 Updating PostProofConfig...");
@@ -166,6 +416,10 @@ class ProofOfPostClient {
           active: updates.active !== undefined ? updates.active : null,
           maxClaimers: updates.maxClaimers !== undefined ? new anchor.BN(updates.maxClaimers) : null,
           rewardAmount: updates.rewardAmount !== undefined ? new anchor.BN(updates.rewardAmount) : null,
+          callbackVersion: updates.callbackVersion !== undefined ? updates.callbackVersion : null,
+          budgetAlertBps: updates.budgetAlertBps !== undefined ? updates.budgetAlertBps : null,
+          budgetAlertLamports:
+            updates.budgetAlertLamports !== undefined ? new anchor.BN(updates.budgetAlertLamports) : null,
         })
         .accounts({
           postProofConfig: configPDA,
@@ -181,23 +435,93 @@ class ProofOfPostClient {
     }
   }
 
+  // Publish a finished campaign's results to the global campaign registry
+  async publishCampaignSummary(seeds: string): Promise<void> {
+    console.log("📣 Publishing campaign summary...");
+
+    const [configPDA] = this.getPostProofConfigPDA(this.payer.publicKey, seeds);
+
+    try {
+      const tx = await this.program.methods
+        .publishCampaignSummary()
+        .accounts({
+          postProofConfig: configPDA,
+          // campaignRegistry, campaignSummary: derived from seeds
+          creator: this.payer.publicKey,
+        })
+        .signers([this.payer])
+        .rpc();
+
+      console.log("✅ Campaign summary published. Transaction:", tx);
+    } catch (error) {
+      console.error("❌ Publish campaign summary failed:", error);
+      throw error;
+    }
+  }
+
+  // Close a finished campaign, sending the unclaimed budget to its leftover recipient
+  // if it has one
+  async closeConfig(seeds: string, leftoverRecipient: PublicKey | null = null): Promise<void> {
+    console.log("🏁 Closing campaign...");
+
+    const [configPDA] = this.getPostProofConfigPDA(this.payer.publicKey, seeds);
+
+    try {
+      const tx = await this.program.methods
+        .closeConfig()
+        .accounts({
+          postProofConfig: configPDA,
+          creator: this.payer.publicKey,
+          leftoverRecipient,
+        })
+        .signers([this.payer])
+        .rpc();
+
+      console.log("✅ Campaign closed. Transaction:", tx);
+    } catch (error) {
+      console.error("❌ Close campaign failed:", error);
+      throw error;
+    }
+  }
+
   // Verify a post
   async verifyPost(
     configPDA: PublicKey,
-    postId: string
+    postId: string,
+    referrer: PublicKey | null = null,
+    // sha256 over all inputs in order (public input, rule bundle, response signature, URL
+    // response), enabling Bonsol's input hash verification
+    inputHash: number[] | null = null,
+    // Receive the claimer share in another wallet, e.g. a cold wallet
+    payout: PublicKey | null = null,
+    // The campaign's response signer's signature over "proof-of-post response:" followed
+    // by sha256 of the response, required when the campaign has a response signer
+    responseSignature: number[] | null = null,
+    // 1 = gzip, 2 = deflate when the prover ships the response compressed; the size
+    // measured below is of the uncompressed body, so only 0 works without a prover change
+    responseEncoding: number = 0
   ): Promise<void> {
     console.log("🔍 Verifying post...");
     console.log("📝 Post ID:", postId);
 
+    const config = await this.program.account.postProofConfig.fetch(configPDA);
+
     // Convert post ID to API URL
-    const apiUrl = await this.convertToApiUrl(postId);
+    const apiUrl = await this.convertToApiUrl(postId, config.minThreadPosts > 0);
     console.log("🌐 API URL:", apiUrl);
 
     // Get response size
     const postSize = await this.getUrlResponseSize(apiUrl);
 
-    // Create unique request ID
-    const currentReqId = `verify-${Date.now()}`;
+    // Derive a hard-to-guess request ID from a random nonce, which the program checks
+    const nonceBytes = Keypair.generate().publicKey.toBytes().slice(0, 8);
+    const requestNonce = new anchor.BN(nonceBytes, undefined, "le");
+    const currentReqId = this.deriveRequestId(
+      this.payer.publicKey,
+      configPDA,
+      apiUrl,
+      requestNonce
+    );
     const executionIdBuffer = Buffer.from(currentReqId);
 
     // Get PDAs (Bonsol specific)
@@ -228,14 +552,57 @@ class ProofOfPostClient {
     console.log("🔑 Image ID Account:", imageIdAccount.toBase58());
     console.log("🔑 Verification Log PDA:", verificationLogPDA.toBase58());
 
+    const authorDidHash = this.getAuthorDidHash(apiUrl);
+    const [authorClaimPDA] = this.getAuthorClaimPDA(configPDA, authorDidHash);
+    console.log("🔑 Author Claim PDA:", authorClaimPDA.toBase58());
+
+    const args = {
+      currentReqId,
+      postUrl: apiUrl,
+      postSize: new anchor.BN(postSize),
+      tip: new anchor.BN(100000), // 0.0001 SOL tip
+      referrer,
+      inputHash,
+      payout,
+      authorDidHash: Array.from(authorDidHash),
+      termsSignature: null as number[] | null,
+      responseSignature,
+      responseEncoding,
+      requestNonce,
+    };
+
+    // Campaigns with terms need the claimer's signed acceptance, checked by an Ed25519
+    // instruction placed right before verify_post
+    const preInstructions: TransactionInstruction[] = [];
+    if (config.termsHash.some((b: number) => b !== 0)) {
+      const termsIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: this.payer.secretKey,
+        message: Buffer.concat([
+          Buffer.from(FIXTURES.codec.termsMessagePrefix),
+          configPDA.toBuffer(),
+          Buffer.from(config.termsHash),
+        ]),
+      });
+      // The instruction data holds the 64-byte signature after the header and public key
+      args.termsSignature = Array.from(termsIx.data.subarray(48, 112));
+      preInstructions.push(termsIx);
+    }
+
+    // Simulate the cheap eligibility check before paying for a proof
+    const eligibility = await this.program.methods
+      .checkEligibility(args)
+      .accounts({
+        postProofConfig: configPDA,
+        verifier: this.payer.publicKey,
+      })
+      .view();
+    if (!eligibility.eligible) {
+      throw new Error(`Not eligible to verify, error code ${eligibility.errorCode}`);
+    }
+
     try {
       const tx = await this.program.methods
-        .verifyPost({
-          currentReqId,
-          postUrl: apiUrl,
-          postSize: new anchor.BN(postSize),
-          tip: new anchor.BN(100000), // 0.0001 SOL tip
-        })
+        .verifyPost(args)
         .accounts({
           postProofConfig: configPDA,
           // postVerificationLog: verificationLogPDA,
@@ -244,9 +611,14 @@ class ProofOfPostClient {
           // requester: requesterAccount,
           executionRequest: executionAccount,
           deploymentAccount: imageIdAccount,
+          creator: config.creator,
+          platformTreasury: config.platformTreasury,
+          referrer: referrer ?? this.payer.publicKey,
+          payout: payout ?? this.payer.publicKey,
           // postProofProgram: PROGRAM_ID,
           // systemProgram: SystemProgram.programId,
         })
+        .preInstructions(preInstructions)
         .signers([this.payer])
         .rpc();
 
@@ -276,6 +648,9 @@ class ProofOfPostClient {
       console.log("   Max Claimers:", config.maxClaimers.toString());
       console.log("   Active:", config.active);
       console.log("   Created Slot:", config.createdSlot.toString());
+      console.log("   Total Paid:", config.totalPaid.toString(), "lamports");
+      console.log("   Total Views:", config.totalViews.toString());
+      console.log("   Total Engagement:", config.totalEngagement.toString());
     } catch (error) {
       console.error("❌ Config not found:", error);
     }
@@ -335,6 +710,7 @@ const setup = async (payer: Keypair): Promise<void> => {
   }
 
   client = new ProofOfPostClient(connection, payer);
+  client.checkFixtures();
 
   console.log("💼 Payer public key:", payer.publicKey.toString());
 
@@ -0,0 +1,65 @@
+//! The two zkVM calls the guest makes, so the rule evaluation builds for another proving
+//! backend by switching a cargo feature. RISC Zero (`risc0`, the default) is what Bonsol
+//! proves with; `sp1` builds the same guest for SP1.
+//!
+//! Exactly one backend feature must be enabled.
+
+#[cfg(all(feature = "risc0", feature = "sp1"))]
+compile_error!("enable only one of the `risc0` and `sp1` features");
+#[cfg(not(any(feature = "risc0", feature = "sp1")))]
+compile_error!("enable one of the `risc0` and `sp1` features");
+
+#[cfg(feature = "risc0")]
+mod backend {
+    use risc0_zkvm::guest::env;
+    use std::io::Read;
+
+    pub fn stdin() -> impl Read {
+        env::stdin()
+    }
+
+    pub fn commit(bytes: &[u8]) {
+        env::commit_slice(bytes);
+    }
+}
+
+#[cfg(feature = "sp1")]
+mod backend {
+    use std::io::{self, Read};
+
+    /// SP1 hands the guest each host `write_slice` as one buffer rather than a byte
+    /// stream, so the inputs are chained back together here. The next buffer is only
+    /// requested once the current one is used up, and the guest never reads past its
+    /// last input.
+    #[derive(Default)]
+    struct Stdin {
+        buffer: Vec<u8>,
+        pos: usize,
+    }
+
+    impl Read for Stdin {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if buf.is_empty() {
+                return Ok(0);
+            }
+            while self.pos == self.buffer.len() {
+                self.buffer = sp1_zkvm::io::read_vec();
+                self.pos = 0;
+            }
+            let n = buf.len().min(self.buffer.len() - self.pos);
+            buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    pub fn stdin() -> impl Read {
+        Stdin::default()
+    }
+
+    pub fn commit(bytes: &[u8]) {
+        sp1_zkvm::io::commit_slice(bytes);
+    }
+}
+
+pub use backend::{commit, stdin};